@@ -0,0 +1,106 @@
+//! Benchmarks for loading and saving large registries.
+//!
+//! `Registry::load()` parses `registry.json` as it is read and `Registry::save()`
+//! streams the file as it is serialized. Each benchmark compares them with the
+//! buffered approach of holding the whole file in memory, through a `MemorySink`, at
+//! increasing registry sizes.
+//!
+//! Run with `cargo bench -p link-bridge --bench registry`.
+
+use std::fs;
+use std::hint::black_box;
+use std::path::{Path, PathBuf};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use link_bridge::{FsSink, MemorySink, OutputSink, Registry};
+
+/// The registry sizes to benchmark.
+const SIZES: [usize; 3] = [1_000, 10_000, 100_000];
+
+/// Writes a `registry.json` with `size` entries to a fresh directory.
+fn registry_dir(size: usize) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("link_bridge_bench_registry_{size}"));
+    fs::create_dir_all(&dir).unwrap();
+
+    let entries: Vec<String> = (0..size)
+        .map(|i| {
+            format!(
+                "  \"/docs/section-{}/page-{i}/\": {{\"short_file\": \"page-{i}.html\", \
+                 \"path\": \"s/page-{i}.html\", \"created_at\": \"2024-01-01T00:00:00Z\"}}",
+                i % 100
+            )
+        })
+        .collect();
+    fs::write(
+        dir.join("registry.json"),
+        format!("{{\n{}\n}}\n", entries.join(",\n")),
+    )
+    .unwrap();
+
+    dir
+}
+
+/// Loads a registry by reading the whole file into memory first.
+fn load_buffered(dir: &Path) -> Registry {
+    let mut sink = MemorySink::new();
+    let registry_path = dir.join("registry.json");
+    sink.insert(&registry_path, fs::read(&registry_path).unwrap());
+    Registry::load_from(&mut sink, dir).unwrap()
+}
+
+/// Saves a registry by serializing it into memory before writing the file.
+fn save_buffered(registry: &Registry) {
+    let mut sink = MemorySink::new();
+    registry.save_to(&mut sink).unwrap();
+    for (path, contents) in sink.files() {
+        FsSink.write_file(path, contents).unwrap();
+    }
+}
+
+fn bench_load(c: &mut Criterion) {
+    let mut group = c.benchmark_group("registry_load");
+    group.sample_size(20);
+
+    for size in SIZES {
+        let dir = registry_dir(size);
+        group.bench_with_input(BenchmarkId::new("streamed", size), &dir, |b, dir| {
+            b.iter(|| black_box(Registry::load(dir).unwrap()));
+        });
+        group.bench_with_input(BenchmarkId::new("buffered", size), &dir, |b, dir| {
+            b.iter(|| black_box(load_buffered(dir)));
+        });
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    group.finish();
+}
+
+fn bench_save(c: &mut Criterion) {
+    let mut group = c.benchmark_group("registry_save");
+    group.sample_size(20);
+
+    for size in SIZES {
+        let dir = registry_dir(size);
+        let registry = Registry::load(&dir).unwrap();
+        group.bench_with_input(
+            BenchmarkId::new("streamed", size),
+            &registry,
+            |b, registry| {
+                b.iter(|| registry.save().unwrap());
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("buffered", size),
+            &registry,
+            |b, registry| {
+                b.iter(|| save_buffered(registry));
+            },
+        );
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_load, bench_save);
+criterion_main!(benches);
@@ -0,0 +1,63 @@
+//! mdBook preprocessor that writes link-bridge redirect pages.
+//!
+//! Built when the `mdbook` feature is enabled. Add the preprocessor to `book.toml`
+//! and list the redirects to generate:
+//!
+//! ```toml
+//! [preprocessor.link-bridge]
+//!
+//! [redirects]
+//! install = "guide/installation.html"
+//! ```
+//!
+//! mdBook invokes `mdbook-link-bridge supports <renderer>` to check renderer support,
+//! then runs `mdbook-link-bridge` with the book as JSON on standard input.
+
+use std::io::{self, Read};
+use std::process::ExitCode;
+
+use link_bridge::MdBookPreprocessor;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    if let [command, renderer] = args.as_slice() {
+        if command == "supports" {
+            return if MdBookPreprocessor.supports_renderer(renderer) {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            };
+        }
+    }
+
+    let mut input = String::new();
+    if let Err(e) = io::stdin().read_to_string(&mut input) {
+        eprintln!("error: failed to read book from standard input: {e}");
+        return ExitCode::FAILURE;
+    }
+
+    match MdBookPreprocessor.run(&input) {
+        Ok((book, outcomes)) => {
+            for outcome in outcomes.iter().filter(|outcome| outcome.is_created()) {
+                eprintln!(
+                    "[{}] created {} -> {}",
+                    MdBookPreprocessor::NAME,
+                    outcome.file_path().display(),
+                    outcome.target()
+                );
+            }
+            println!("{book}");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("error: {e}");
+            let mut source = std::error::Error::source(&e);
+            while let Some(cause) = source {
+                eprintln!("  caused by: {cause}");
+                source = cause.source();
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
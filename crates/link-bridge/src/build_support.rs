@@ -0,0 +1,171 @@
+//! Helpers for generating redirects from a `build.rs` script.
+//!
+//! Rust projects that build a static site can declare their redirects in a manifest
+//! and generate the pages during `cargo build`. [`generate_from_manifest()`] writes every
+//! redirect in the manifest and tells Cargo to rerun the build script when the manifest
+//! changes, so the redirects are always in step with it.
+//!
+//! The manifest is a TOML file with one `[[redirect]]` table per redirect, using the
+//! same options as [`RedirectorBuilder`](crate::RedirectorBuilder):
+//!
+//! ```toml
+//! [[redirect]]
+//! target = "docs/installation"
+//! alias = "install"
+//!
+//! [[redirect]]
+//! target = "https://docs.example.com/guide"
+//! allow_hosts = ["docs.example.com"]
+//! out_dir = "go"
+//! ```
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::{RedirectOutcome, Redirector, RedirectorError};
+
+/// The contents of a redirect manifest.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Manifest {
+    /// The redirects to generate.
+    #[serde(default)]
+    redirect: Vec<Redirector>,
+}
+
+/// Generates the redirects declared in a manifest, for use from `build.rs`.
+///
+/// Each redirect is written below `out_dir`: its own `out_dir` option (which defaults
+/// to `s`) is taken as relative to `out_dir`, so `out_dir` is usually the root of the
+/// site's static files. All redirects are written with [`Redirector::write_all()`], so
+/// each registry is updated once and nothing is written if any redirect fails.
+///
+/// A `cargo:rerun-if-changed` line is printed for the manifest, so Cargo reruns the
+/// build script when the manifest changes.
+///
+/// # Arguments
+///
+/// * `manifest` - The path of the TOML manifest, relative to the package root
+/// * `out_dir` - The directory the redirect directories are written below
+///
+/// # Returns
+///
+/// * `Ok(Vec<RedirectOutcome>)` - The outcome for each redirect, in manifest order
+/// * `Err(RedirectorError::FailedToReadManifest)` - If the manifest cannot be read, is
+///   not valid TOML, or contains an invalid redirect
+/// * `Err(RedirectorError)` - The errors of [`Redirector::write_all()`]
+///
+/// # Examples
+///
+/// In the `main` function of `build.rs`:
+///
+/// ```rust,no_run
+/// link_bridge::build_support::generate_from_manifest("redirects.toml", "static")
+///     .expect("failed to generate redirects");
+/// ```
+pub fn generate_from_manifest<P: AsRef<Path>, Q: AsRef<Path>>(
+    manifest: P,
+    out_dir: Q,
+) -> Result<Vec<RedirectOutcome>, RedirectorError> {
+    let manifest = manifest.as_ref();
+    println!("cargo:rerun-if-changed={}", manifest.display());
+
+    let redirectors = load_manifest(manifest, out_dir.as_ref())?;
+    Redirector::write_all(&redirectors)
+}
+
+/// Reads a manifest and places each redirect below `out_dir`.
+fn load_manifest(manifest: &Path, out_dir: &Path) -> Result<Vec<Redirector>, RedirectorError> {
+    let Manifest { redirect } = std::fs::read_to_string(manifest)
+        .and_then(|contents| {
+            toml::from_str(&contents).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        })
+        .map_err(|source| RedirectorError::FailedToReadManifest {
+            path: manifest.to_path_buf(),
+            source,
+        })?;
+
+    Ok(redirect
+        .into_iter()
+        .map(|mut redirector| {
+            let path: PathBuf = out_dir.join(redirector.path());
+            redirector.set_path(path);
+            redirector
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use std::fs;
+
+    fn test_dir(name: &str) -> PathBuf {
+        PathBuf::from(format!(
+            "{name}_{}",
+            Utc::now().timestamp_nanos_opt().unwrap_or(0)
+        ))
+    }
+
+    #[test]
+    fn test_generate_from_manifest() {
+        let dir = test_dir("test_generate_from_manifest");
+        let manifest = dir.join("redirects.toml");
+        let out_dir = dir.join("static");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            &manifest,
+            r#"
+            [[redirect]]
+            target = "docs/installation"
+            alias = "install"
+
+            [[redirect]]
+            target = "docs/usage"
+            alias = "usage"
+            out_dir = "go"
+            "#,
+        )
+        .unwrap();
+
+        let outcomes = generate_from_manifest(&manifest, &out_dir).unwrap();
+
+        assert_eq!(outcomes.len(), 2);
+        assert_eq!(outcomes[0].file_path(), out_dir.join("s/install.html"));
+        assert_eq!(outcomes[1].file_path(), out_dir.join("go/usage.html"));
+        assert!(out_dir.join("s/registry.json").exists());
+
+        // Generating again reuses the existing redirects
+        let outcomes = generate_from_manifest(&manifest, &out_dir).unwrap();
+        assert!(outcomes.iter().all(|outcome| !outcome.is_created()));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_generate_from_manifest_errors() {
+        let dir = test_dir("test_generate_from_manifest_errors");
+        let manifest = dir.join("redirects.toml");
+
+        let missing = generate_from_manifest(&manifest, &dir);
+        assert!(matches!(
+            missing,
+            Err(RedirectorError::FailedToReadManifest { .. })
+        ));
+
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&manifest, "[[redirect]]\ntarget = \"a?b\"\n").unwrap();
+        match generate_from_manifest(&manifest, &dir) {
+            Err(RedirectorError::FailedToReadManifest { path, .. }) => assert_eq!(path, manifest),
+            other => panic!("expected FailedToReadManifest, got {other:?}"),
+        }
+
+        fs::write(&manifest, "[[redirects]]\ntarget = \"a\"\n").unwrap();
+        assert!(generate_from_manifest(&manifest, &dir).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
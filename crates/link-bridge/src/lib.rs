@@ -40,7 +40,7 @@
 //! redirector.set_path("redirects");
 //!
 //! // Generate the redirect HTML file
-//! let redirect_path = redirector.write_redirect().unwrap();
+//! let outcome = redirector.write_redirect().unwrap();
 //!
 //! // Clean up for example
 //! fs::remove_dir_all("redirects").ok();
@@ -49,6 +49,109 @@
 //! This creates an HTML file that automatically redirects visitors from your short URL
 //! to the longer target path using multiple redirect methods for maximum compatibility.
 //!
+//! ## Builder
+//!
+//! Use [`Redirector::builder()`] to configure several options at once; everything is
+//! validated when [`RedirectorBuilder::build()`] is called:
+//!
+//! ```rust
+//! use link_bridge::Redirector;
+//! use std::fs;
+//!
+//! let redirector = Redirector::builder()
+//!     .target("api/v1/users")
+//!     .out_dir("doc_test_builder")
+//!     .alias("users")   // writes doc_test_builder/users.html
+//!     .delay(3)         // wait three seconds before redirecting
+//!     .build()
+//!     .unwrap();
+//!
+//! let outcome = redirector.write_redirect().unwrap();
+//!
+//! // Clean up for example
+//! fs::remove_dir_all("doc_test_builder").ok();
+//! ```
+//!
+//! ## In-Memory Output
+//!
+//! [`Redirector::write_redirect_to()`] writes through an [`OutputSink`] instead of the
+//! filesystem. A [`MemorySink`] collects the generated page and registry in memory,
+//! which lets the crate run on `wasm32` targets or in serverless functions:
+//!
+//! ```rust
+//! use link_bridge::{MemorySink, Redirector};
+//!
+//! let redirector = Redirector::new("api/v1/users").unwrap();
+//! let mut sink = MemorySink::new();
+//! let outcome = redirector.write_redirect_to(&mut sink).unwrap();
+//!
+//! for (path, contents) in sink.files() {
+//!     println!("{}: {} bytes", path.display(), contents.len());
+//! }
+//! # assert!(sink.get(outcome.file_path()).is_some());
+//! ```
+//!
+//! ## Feature Flags
+//!
+//! - `actix-web` - Adds `Registry::scope()`, which builds an `actix-web` scope answering
+//!   short-link requests with 301 redirects straight from the registry
+//! - `axum` - Adds `Registry::router()`, which builds an `axum` router answering
+//!   short-link requests with 301 or 302 redirects straight from the registry
+//! - `build-support` - Adds `build_support::generate_from_manifest()`, which generates
+//!   the redirects declared in a TOML manifest from `build.rs`
+//! - `cli` - Builds the `link-bridge` binary, with `add`, `list`, `rm`, `export`, and
+//!   `import` subcommands for managing redirects from the terminal
+//! - `config` - Adds `ProjectConfig`, which reads project-wide defaults (output
+//!   directory, base URL, delay, allowed hosts, and validation policy) from
+//!   `link-bridge.toml`
+//! - `fetch-title` - Adds `RedirectorBuilder::fetch_title()`, which requests the target
+//!   when a redirect is built and uses its page's `<title>` as the redirect page's title
+//!   and Open Graph title
+//! - `fluent` - Adds `Locale` and `RedirectorBuilder::locale()`, which write the text of
+//!   redirect, confirmation, expired, and retired pages in one of the languages bundled
+//!   as Fluent translations, and the `locale` setting of `link-bridge.toml`
+//! - `link-check` - Adds `Registry::check_targets()`, which sends a `HEAD` request to every
+//!   target and reports the ones answering `404 Not Found` or a server error
+//! - `mdbook` - Adds `MdBookPreprocessor` and builds the `mdbook-link-bridge`
+//!   preprocessor, which writes redirect pages for the `[redirects]` table of `book.toml`
+//! - `object-store` - Adds `ObjectStoreSink`, an `OutputSink` that writes pages and
+//!   registries to an S3, Google Cloud Storage, Azure, or other bucket through the
+//!   `object_store` crate
+//! - `passphrase` - Adds `RedirectorBuilder::passphrase()`, which makes a redirect page
+//!   ask for a passphrase, checked in the browser against its SHA-256 hash, before
+//!   redirecting
+//! - `preview` - Adds `Registry::serve()`, a local HTTP server that serves the output
+//!   directory and resolves short names, for checking redirects before deploying
+//! - `qr` - Adds `Registry::export_qr_codes()`, which writes an SVG QR code of every
+//!   short URL into the `qr/` folder of the output directory for print campaigns, and
+//!   the `qr` subcommand of the `link-bridge` binary
+//! - `rayon` - Adds `Redirector::write_all_parallel()`, which writes a batch of redirect
+//!   pages on the `rayon` thread pool for large migrations
+//! - `rocket` - Adds `RedirectFairing`, a Rocket fairing that mounts the registry as a
+//!   catch-all route answering short-link requests with 301 redirects
+//! - `serde` - Implements `Serialize` and `Deserialize` for [`Redirector`] so redirect
+//!   definitions can be declared in configuration files, and `Serialize` for
+//!   [`RedirectOutcome`] so the results of writes can be reported as JSON
+//! - `signed-names` - Adds `RedirectorBuilder::signing_key()`, which names redirects after
+//!   an HMAC-SHA256 of their target so short names cannot be guessed, and
+//!   `Registry::verify_signed()`, which checks a short name against the key
+//! - `social-preview` - Adds `SocialPreview` and `RedirectorBuilder::social_preview()`,
+//!   which write an SVG preview card next to each redirect page and reference it from
+//!   the page's Open Graph and Twitter card tags
+//! - `tokio` - Adds `Redirector::write_redirect_async()`, `Registry::load_async()`, and
+//!   `Registry::save_async()`, which perform disk I/O with `tokio::fs` so async
+//!   services do not block their executor threads
+//! - `tower` - Adds `RedirectLayer`, a `tower` layer answering requests for registered
+//!   short names with `301 Moved Permanently` and passing every other request on
+//! - `tracing` - Emits `tracing` spans and events for path validation, short-name
+//!   generation, registry loads and saves, and file writes
+//! - `url` - Implements `TryFrom<url::Url>` for [`Redirector`], which redirects to an
+//!   absolute `Url` on its own host, so applications using the `url` crate need not
+//!   format their links first
+//! - `webhook` - Adds `Webhook`, a hook that POSTs a JSON notification, or a Slack or
+//!   Discord message, to a URL whenever a redirect is created, and the `webhook` and
+//!   `webhook_format` settings of `link-bridge.toml`
+//!
 //! ## How It Works
 //!
 //! 1. **URL Validation**: Input paths are validated to ensure they contain only safe characters
@@ -105,7 +208,70 @@
 #![cfg_attr(docsrs, feature(rustdoc_missing_doc_code_examples))]
 #![cfg_attr(docsrs, warn(rustdoc::invalid_codeblock_attributes))]
 
+#[cfg(feature = "build-support")]
+pub mod build_support;
 mod redirector;
 
+pub use redirector::slugify;
+pub use redirector::AuditLog;
+#[cfg(feature = "link-check")]
+pub use redirector::BrokenLink;
+#[cfg(feature = "chrono")]
+pub use redirector::Clock;
+pub use redirector::ContentAlias;
+pub use redirector::DuplicatePolicy;
+pub use redirector::ExistingFilePolicy;
+pub use redirector::FsSink;
+pub use redirector::HostAllowlist;
+#[cfg(feature = "fluent")]
+pub use redirector::Locale;
+pub use redirector::LockedRegistry;
+#[cfg(feature = "mdbook")]
+pub use redirector::MdBookPreprocessor;
+pub use redirector::MemorySink;
+#[cfg(feature = "object-store")]
+pub use redirector::ObjectStoreSink;
+pub use redirector::OutputLayout;
+pub use redirector::OutputSink;
+pub use redirector::PathPolicy;
+pub use redirector::Provenance;
+#[cfg(feature = "rocket")]
+pub use redirector::RedirectFairing;
+pub use redirector::RedirectHooks;
+#[cfg(feature = "tower")]
+pub use redirector::RedirectLayer;
+pub use redirector::RedirectOutcome;
+#[cfg(feature = "tower")]
+pub use redirector::RedirectService;
+#[cfg(feature = "axum")]
+pub use redirector::RedirectStatus;
 pub use redirector::Redirector;
+pub use redirector::RedirectorBuilder;
 pub use redirector::RedirectorError;
+pub use redirector::Registry;
+pub use redirector::RegistryEntry;
+pub use redirector::RegistryIter;
+pub use redirector::ShortNameCollision;
+pub use redirector::SiteGenerator;
+#[cfg(feature = "social-preview")]
+pub use redirector::SocialPreview;
+#[cfg(feature = "chrono")]
+pub use redirector::SystemClock;
+pub use redirector::TargetCase;
+pub use redirector::TargetChange;
+pub use redirector::TargetError;
+pub use redirector::TargetFilter;
+pub use redirector::TrailingSlash;
+#[cfg(feature = "webhook")]
+pub use redirector::Webhook;
+#[cfg(feature = "webhook")]
+pub use redirector::WebhookFormat;
+pub use redirector::{ImportFormat, ImportedRedirect};
+#[cfg(feature = "link-check")]
+pub use redirector::{LinkProblem, LinkReport};
+#[cfg(feature = "config")]
+pub use redirector::{ProjectConfig, CONFIG_FILE_NAME};
+pub use redirector::{UrlPath, UrlPathError};
+pub use redirector::{
+    DEFAULT_EXPIRED_PAGE, DEFAULT_MAX_DEPTH, DEFAULT_MAX_LENGTH, DEFAULT_RETIRED_PAGE,
+};
@@ -0,0 +1,952 @@
+//! Command-line interface for managing link-bridge redirects.
+//!
+//! The `link-bridge` binary wraps the library so redirects can be added, listed,
+//! removed, and exported from the terminal. It is built when the `cli` feature is
+//! enabled:
+//!
+//! ```text
+//! cargo install link-bridge --features cli
+//!
+//! link-bridge add api/v1/users --alias users
+//! link-bridge add summer/sale --alias promo --campaign summer-2025 --tag marketing
+//! link-bridge list
+//! link-bridge list --tag marketing
+//! link-bridge rm users
+//! link-bridge export --output redirects.json
+//! link-bridge export --format bookmarks --site-url https://example.com/s
+//! link-bridge export --format atom --site-url https://example.com/s --output feed.xml
+//! link-bridge export --format next --base-path /s --output redirects.json
+//! link-bridge export --format astro --base-path /s --output redirects.json
+//! link-bridge export --format eleventy --base-path /s --output _data/redirects.json
+//! link-bridge export --format sveltekit --base-path /s --output src/hooks.server.js
+//! link-bridge export --format http-route --gateway edge --base-path /s --output routes.yaml
+//! link-bridge export --format haproxy --base-path /s --output short-links.map
+//! link-bridge export --format varnish --base-path /s --output short-links.vcl
+//! link-bridge export --format rewrite-map --base-path /s --output short-links.map
+//! link-bridge import --format netlify _redirects
+//! link-bridge qr --site-url https://example.com/s --tag flyers
+//! ```
+//!
+//! The `qr` subcommand also needs the `qr` feature.
+//! With the `miette` feature, errors are printed as `miette` reports that point at the
+//! offending part of a rejected path or URL.
+//!
+//! Defaults such as the output directory and base URL are read from a
+//! `link-bridge.toml` in the current directory when present, with settings it leaves
+//! out taken from the user's own `link-bridge.toml` in the platform config directory
+//! (such as `~/.config/link-bridge/link-bridge.toml`). When they set
+//! `audit_log`, every redirect added, found existing, or removed is appended to that
+//! file.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use link_bridge::{
+    HostAllowlist, ImportFormat, ProjectConfig, RedirectOutcome, Redirector, RedirectorError,
+    Registry,
+};
+
+/// Manage short-link redirect pages and their registry.
+#[derive(Debug, Parser)]
+#[command(name = "link-bridge", version, about)]
+struct Cli {
+    /// The output directory holding the redirect pages and registry.json
+    /// [default: out_dir from the config file, or "s"]
+    #[arg(short, long, global = true)]
+    dir: Option<PathBuf>,
+
+    /// The project configuration file [default: ./link-bridge.toml if present]
+    #[arg(short, long, global = true)]
+    config: Option<PathBuf>,
+
+    /// Print the redirects written by add and import as JSON
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Create a redirect to a target, or report the existing one
+    Add {
+        /// The URL path (or allowlisted absolute URL) to redirect to
+        target: String,
+        /// Use a custom short name instead of a generated one
+        #[arg(short, long)]
+        alias: Option<String>,
+        /// Seconds to wait before redirecting
+        #[arg(long)]
+        delay: Option<u32>,
+        /// Site base URL used to emit absolute target URLs
+        #[arg(long)]
+        base_url: Option<String>,
+        /// Host that absolute URL targets may point at (repeatable)
+        #[arg(long = "allow-host")]
+        allow_hosts: Vec<String>,
+        /// Campaign recorded with the redirect
+        #[arg(long)]
+        campaign: Option<String>,
+        /// Tag recorded with the redirect (repeatable)
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+    },
+    /// List the registered redirects
+    List {
+        /// Only list the redirects filed under this tag
+        #[arg(long)]
+        tag: Option<String>,
+    },
+    /// Remove a redirect and its page
+    Rm {
+        /// The short name of the redirect to remove
+        short_name: String,
+    },
+    /// Export the registered redirects as JSON, browser bookmarks, an Atom feed,
+    /// Next.js, Astro, Eleventy, or SvelteKit redirects, Kubernetes routes, HAProxy or
+    /// Apache maps, or Varnish configuration
+    Export {
+        /// Write the export to a file instead of standard output
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// The format of the export
+        #[arg(short, long, value_enum, default_value = "json")]
+        format: ExportFormat,
+        /// The URL the output directory is served from, used for bookmark and feed links
+        #[arg(long, required_if_eq_any([("format", "bookmarks"), ("format", "atom")]))]
+        site_url: Option<String>,
+        /// The path the output directory is served from, used for Next.js, Astro,
+        /// Eleventy, SvelteKit, Kubernetes, HAProxy, Varnish, and Apache redirect
+        /// sources
+        #[arg(long, default_value = "/")]
+        base_path: String,
+        /// The Kubernetes `Gateway` that exported routes attach to
+        #[arg(long, required_if_eq("format", "http-route"))]
+        gateway: Option<String>,
+        /// The name of the exported Kubernetes `HTTPRoute`
+        #[arg(long, default_value = "link-bridge")]
+        route_name: String,
+        /// The maximum number of redirects listed in an Atom feed
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+        /// Only export the redirects filed under this tag
+        #[arg(long)]
+        tag: Option<String>,
+    },
+    /// Create redirects for every redirect defined in another platform's file
+    Import {
+        /// The file to import
+        file: PathBuf,
+        /// The format of the file
+        #[arg(short, long, value_enum)]
+        format: ImportFormatArg,
+        /// Host that absolute URL targets may point at (repeatable)
+        #[arg(long = "allow-host")]
+        allow_hosts: Vec<String>,
+    },
+    /// Write an SVG QR code of every short URL into the qr/ folder of the output directory
+    #[cfg(feature = "qr")]
+    Qr {
+        /// The URL the output directory is served from
+        /// [default: site_url from the config file]
+        #[arg(long)]
+        site_url: Option<String>,
+        /// Only write the codes of the redirects filed under this tag
+        #[arg(long)]
+        tag: Option<String>,
+    },
+}
+
+/// The formats produced by the `export` subcommand.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum ExportFormat {
+    /// A JSON array of short names, targets, and files
+    Json,
+    /// A Netscape bookmarks HTML file, importable into any browser
+    Bookmarks,
+    /// An Atom feed of the newest redirects
+    Atom,
+    /// The array returned by `redirects()` in `next.config.js`
+    Next,
+    /// The `redirects` object of `astro.config.mjs`
+    Astro,
+    /// The `_data/redirects.json` data file of an Eleventy site
+    Eleventy,
+    /// A SvelteKit `hooks.server.js` module answering short links with redirects
+    Sveltekit,
+    /// Kubernetes Gateway API `HTTPRoute` manifests redirecting short links
+    HttpRoute,
+    /// An HAProxy `map` file of paths and the targets they redirect to
+    Haproxy,
+    /// A Varnish VCL snippet answering short links with redirects
+    Varnish,
+    /// The text file of an Apache `RewriteMap` of paths and their targets
+    RewriteMap,
+}
+
+/// The file formats accepted by the `import` subcommand.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum ImportFormatArg {
+    /// A Netlify `_redirects` file
+    Netlify,
+    /// `map` entries and `rewrite` directives from an nginx configuration
+    Nginx,
+    /// A Bitly link export in CSV
+    Bitly,
+    /// A YOURLS link export in CSV
+    Yourls,
+}
+
+impl From<ImportFormatArg> for ImportFormat {
+    fn from(format: ImportFormatArg) -> Self {
+        match format {
+            ImportFormatArg::Netlify => ImportFormat::Netlify,
+            ImportFormatArg::Nginx => ImportFormat::Nginx,
+            ImportFormatArg::Bitly => ImportFormat::Bitly,
+            ImportFormatArg::Yourls => ImportFormat::Yourls,
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    match run(cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        #[cfg(feature = "miette")]
+        Err(e) => {
+            eprintln!("{:?}", miette::Report::new(e));
+            ExitCode::FAILURE
+        }
+        #[cfg(not(feature = "miette"))]
+        Err(e) => {
+            eprintln!("error: {e}");
+            let mut source = std::error::Error::source(&e);
+            while let Some(cause) = source {
+                eprintln!("  caused by: {cause}");
+                source = cause.source();
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Executes a parsed command against the output directory.
+fn run(cli: Cli) -> Result<(), RedirectorError> {
+    let config = match &cli.config {
+        Some(path) => ProjectConfig::load(path)?,
+        None => ProjectConfig::from_project_root(".")?,
+    }
+    .with_defaults(ProjectConfig::user()?);
+    let dir = cli
+        .dir
+        .or_else(|| config.out_dir().map(PathBuf::from))
+        .unwrap_or_else(|| PathBuf::from("s"));
+
+    match cli.command {
+        Command::Add {
+            target,
+            alias,
+            delay,
+            base_url,
+            allow_hosts,
+            campaign,
+            tags,
+        } => {
+            let mut builder = config.builder().target(target).out_dir(&dir);
+            if let Some(delay) = delay {
+                builder = builder.delay(delay);
+            }
+            if let Some(alias) = alias {
+                builder = builder.alias(alias);
+            }
+            if let Some(base_url) = base_url {
+                builder = builder.base_url(base_url);
+            }
+            if !allow_hosts.is_empty() {
+                builder = builder.allow_hosts(HostAllowlist::new(allow_hosts));
+            }
+            if let Some(campaign) = campaign {
+                builder = builder.campaign(campaign);
+            }
+            for tag in tags {
+                builder = builder.tag(tag);
+            }
+
+            let outcome = builder.build()?.write_redirect()?;
+            if cli.json {
+                println!("{}", outcomes_json(&[outcome], config.site_url()));
+            } else {
+                print_outcome(&outcome, config.site_url());
+            }
+        }
+        Command::List { tag } => {
+            let registry = load_tagged(&dir, tag.as_deref())?;
+            for (short_name, target) in sorted_entries(&registry) {
+                println!("{short_name}\t{target}");
+            }
+        }
+        Command::Rm { short_name } => {
+            let mut registry = Registry::load(&dir)?;
+            let target = registry.remove(&short_name)?;
+            registry.save()?;
+            if let Some(audit) = config.audit_log() {
+                audit.record_deleted(&short_name, &target)?;
+            }
+            println!("removed\t{short_name}\t{target}");
+        }
+        Command::Export {
+            output,
+            format,
+            site_url,
+            base_path,
+            gateway,
+            route_name,
+            limit,
+            tag,
+        } => {
+            let registry = load_tagged(&dir, tag.as_deref())?;
+            let export = match format {
+                ExportFormat::Json => export_json(&registry) + "\n",
+                ExportFormat::Bookmarks => {
+                    registry.to_bookmarks_html(site_url.unwrap_or_default())?
+                }
+                ExportFormat::Atom => registry.to_atom_feed(site_url.unwrap_or_default(), limit)?,
+                ExportFormat::Next => registry.to_next_redirects(base_path) + "\n",
+                ExportFormat::Astro => registry.to_astro_redirects(base_path) + "\n",
+                ExportFormat::Eleventy => registry.to_eleventy_redirects(base_path) + "\n",
+                ExportFormat::Sveltekit => registry.to_sveltekit_hooks(base_path),
+                ExportFormat::HttpRoute => {
+                    registry.to_http_routes(route_name, gateway.unwrap_or_default(), base_path)
+                }
+                ExportFormat::Haproxy => registry.to_haproxy_map(base_path),
+                ExportFormat::Varnish => registry.to_varnish_vcl(base_path),
+                ExportFormat::RewriteMap => registry.to_rewrite_map(base_path),
+            };
+            match output {
+                Some(path) => fs::write(&path, export)
+                    .map_err(|source| RedirectorError::FileWriteError { path, source })?,
+                None => print!("{export}"),
+            }
+        }
+        Command::Import {
+            file,
+            format,
+            allow_hosts,
+        } => {
+            let redirectors = ImportFormat::from(format)
+                .read(&file)?
+                .iter()
+                .map(|redirect| {
+                    let mut builder = config
+                        .builder()
+                        .target(redirect.target())
+                        .alias(redirect.short_name())
+                        .out_dir(&dir);
+                    if !allow_hosts.is_empty() {
+                        builder = builder.allow_hosts(HostAllowlist::new(&allow_hosts));
+                    }
+                    builder.build()
+                })
+                .collect::<Result<Vec<Redirector>, RedirectorError>>()?;
+
+            let outcomes = Redirector::write_all(&redirectors)?;
+            if cli.json {
+                println!("{}", outcomes_json(&outcomes, config.site_url()));
+            } else {
+                for outcome in &outcomes {
+                    print_outcome(outcome, config.site_url());
+                }
+            }
+        }
+        #[cfg(feature = "qr")]
+        Command::Qr { site_url, tag } => {
+            let site_url = site_url
+                .as_deref()
+                .or(config.site_url())
+                .unwrap_or_default();
+            let registry = load_tagged(&dir, tag.as_deref())?;
+            for file_path in registry.export_qr_codes(site_url)? {
+                println!("{}", file_path.display());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Prints whether a redirect was created or already existed, with its short name and
+/// its short URL, or its file when no site URL is configured.
+fn print_outcome(outcome: &RedirectOutcome, site_url: Option<&str>) {
+    let status = if outcome.is_created() {
+        "created"
+    } else {
+        "exists"
+    };
+    let location = match site_url {
+        Some(site_url) => outcome.short_url(site_url),
+        None => outcome.file_path().display().to_string(),
+    };
+    println!("{status}\t{}\t{location}", outcome.short_name());
+}
+
+/// Renders written redirects as a pretty-printed JSON array, with each short URL when
+/// a site URL is configured.
+fn outcomes_json(outcomes: &[RedirectOutcome], site_url: Option<&str>) -> String {
+    let outcomes: Vec<serde_json::Value> = outcomes
+        .iter()
+        .map(|outcome| {
+            let mut json = serde_json::to_value(outcome).unwrap_or_default();
+            if let Some(site_url) = site_url {
+                json["url"] = outcome.short_url(site_url).into();
+            }
+            json
+        })
+        .collect();
+
+    serde_json::to_string_pretty(&outcomes).unwrap_or_default()
+}
+
+/// Loads the registry, keeping only the redirects filed under a tag if one is given.
+fn load_tagged(dir: &Path, tag: Option<&str>) -> Result<Registry, RedirectorError> {
+    let registry = Registry::load(dir)?;
+    Ok(match tag {
+        Some(tag) => registry.tagged(tag),
+        None => registry,
+    })
+}
+
+/// Returns the `(short_name, target)` pairs of a registry, ordered by short name.
+fn sorted_entries(registry: &Registry) -> Vec<(&str, &str)> {
+    let mut entries: Vec<(&str, &str)> = registry
+        .iter()
+        .map(|(short_name, target, _)| (short_name, target))
+        .collect();
+    entries.sort_unstable();
+    entries
+}
+
+/// Renders the registry as a pretty-printed JSON array ordered by short name.
+///
+/// Campaigns and tags are included for the redirects that have them.
+fn export_json(registry: &Registry) -> String {
+    let mut redirects: Vec<serde_json::Value> = registry
+        .iter()
+        .map(|(short_name, target, file_path)| {
+            let mut redirect = serde_json::json!({
+                "short_name": short_name,
+                "target": target,
+                "file": file_path,
+            });
+            if let Some(entry) = registry.entry(target) {
+                if let Some(campaign) = entry.campaign() {
+                    redirect["campaign"] = campaign.into();
+                }
+                if !entry.tags().is_empty() {
+                    redirect["tags"] = entry.tags().into();
+                }
+            }
+            redirect
+        })
+        .collect();
+    redirects.sort_unstable_by(|a, b| a["short_name"].as_str().cmp(&b["short_name"].as_str()));
+
+    serde_json::to_string_pretty(&redirects).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn test_dir(name: &str) -> PathBuf {
+        PathBuf::from(format!(
+            "{name}_{}",
+            Utc::now().timestamp_nanos_opt().unwrap_or(0)
+        ))
+    }
+
+    fn cli(dir: &std::path::Path, args: &[&str]) -> Cli {
+        let dir = dir.to_string_lossy();
+        let args = ["link-bridge", "--dir", &dir]
+            .into_iter()
+            .chain(args.iter().copied());
+        Cli::try_parse_from(args).unwrap()
+    }
+
+    #[test]
+    fn test_cli_add_list_rm() {
+        let dir = test_dir("test_cli_add_list_rm");
+
+        run(cli(&dir, &["add", "docs/install", "--alias", "install"])).unwrap();
+        run(cli(&dir, &["add", "docs/usage", "--alias", "usage"])).unwrap();
+
+        let registry = Registry::load(&dir).unwrap();
+        assert_eq!(
+            sorted_entries(&registry),
+            [("install", "/docs/install/"), ("usage", "/docs/usage/")]
+        );
+
+        run(cli(&dir, &["rm", "install"])).unwrap();
+
+        let registry = Registry::load(&dir).unwrap();
+        assert_eq!(sorted_entries(&registry), [("usage", "/docs/usage/")]);
+        assert!(!dir.join("install.html").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_cli_rm_unknown() {
+        let dir = test_dir("test_cli_rm_unknown");
+
+        let result = run(cli(&dir, &["rm", "missing"]));
+        assert!(matches!(result, Err(RedirectorError::ShortLinkNotFound)));
+    }
+
+    #[test]
+    fn test_cli_add_external_requires_allow_host() {
+        let dir = test_dir("test_cli_add_external_requires_allow_host");
+
+        assert!(run(cli(&dir, &["add", "https://example.com/docs"])).is_err());
+        run(cli(
+            &dir,
+            &[
+                "add",
+                "https://example.com/docs",
+                "--allow-host",
+                "example.com",
+            ],
+        ))
+        .unwrap();
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_cli_export() {
+        let dir = test_dir("test_cli_export");
+        let output = dir.join("export.json");
+
+        run(cli(&dir, &["add", "b/path", "--alias", "b"])).unwrap();
+        run(cli(&dir, &["add", "a/path", "--alias", "a"])).unwrap();
+        run(cli(
+            &dir,
+            &["export", "--output", &output.to_string_lossy()],
+        ))
+        .unwrap();
+
+        let exported: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+        assert_eq!(exported[0]["short_name"], "a");
+        assert_eq!(exported[0]["target"], "/a/path/");
+        assert_eq!(exported[1]["short_name"], "b");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_cli_export_frameworks() {
+        let dir = test_dir("test_cli_export_frameworks");
+        let output = dir.join("redirects.json");
+
+        run(cli(&dir, &["add", "a/path", "--alias", "a"])).unwrap();
+        run(cli(
+            &dir,
+            &[
+                "export",
+                "--format",
+                "next",
+                "--base-path",
+                "/s",
+                "--output",
+                &output.to_string_lossy(),
+            ],
+        ))
+        .unwrap();
+
+        let exported: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+        assert_eq!(exported[0]["source"], "/s/a");
+        assert_eq!(exported[0]["destination"], "/a/path/");
+        assert_eq!(exported[0]["permanent"], true);
+
+        run(cli(
+            &dir,
+            &[
+                "export",
+                "--format",
+                "astro",
+                "--base-path",
+                "/s",
+                "--output",
+                &output.to_string_lossy(),
+            ],
+        ))
+        .unwrap();
+        let exported: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+        assert_eq!(exported["/s/a"]["destination"], "/a/path/");
+
+        run(cli(
+            &dir,
+            &[
+                "export",
+                "--format",
+                "eleventy",
+                "--base-path",
+                "/s",
+                "--output",
+                &output.to_string_lossy(),
+            ],
+        ))
+        .unwrap();
+        let exported: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+        assert_eq!(exported[0]["from"], "/s/a");
+        assert_eq!(exported[0]["to"], "/a/path/");
+
+        run(cli(
+            &dir,
+            &[
+                "export",
+                "--format",
+                "sveltekit",
+                "--base-path",
+                "/s",
+                "--output",
+                &output.to_string_lossy(),
+            ],
+        ))
+        .unwrap();
+        assert!(fs::read_to_string(&output)
+            .unwrap()
+            .contains(r#"["/s/a", "/a/path/"],"#));
+
+        assert!(Cli::try_parse_from(["link-bridge", "export", "--format", "http-route"]).is_err());
+        run(cli(
+            &dir,
+            &[
+                "export",
+                "--format",
+                "http-route",
+                "--gateway",
+                "edge",
+                "--base-path",
+                "/s",
+                "--output",
+                &output.to_string_lossy(),
+            ],
+        ))
+        .unwrap();
+        let exported = fs::read_to_string(&output).unwrap();
+        assert!(exported.contains("  name: \"link-bridge\"\n"));
+        assert!(exported.contains("    - name: \"edge\"\n"));
+        assert!(exported.contains("value: \"/s/a\"\n"));
+
+        run(cli(
+            &dir,
+            &[
+                "export",
+                "--format",
+                "haproxy",
+                "--base-path",
+                "/s",
+                "--output",
+                &output.to_string_lossy(),
+            ],
+        ))
+        .unwrap();
+        assert!(fs::read_to_string(&output)
+            .unwrap()
+            .contains("\n/s/a /a/path/\n"));
+
+        run(cli(
+            &dir,
+            &[
+                "export",
+                "--format",
+                "varnish",
+                "--base-path",
+                "/s",
+                "--output",
+                &output.to_string_lossy(),
+            ],
+        ))
+        .unwrap();
+        assert!(fs::read_to_string(&output)
+            .unwrap()
+            .contains(r#"if (req.http.X-Link-Bridge-Path == "/s/a") {"#));
+
+        run(cli(
+            &dir,
+            &[
+                "export",
+                "--format",
+                "rewrite-map",
+                "--base-path",
+                "/s",
+                "--output",
+                &output.to_string_lossy(),
+            ],
+        ))
+        .unwrap();
+        assert!(fs::read_to_string(&output)
+            .unwrap()
+            .contains("\n/s/a.html /a/path/\n"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "qr")]
+    #[test]
+    fn test_cli_qr() {
+        let dir = test_dir("test_cli_qr");
+
+        run(cli(
+            &dir,
+            &["add", "a/path", "--alias", "a", "--tag", "flyers"],
+        ))
+        .unwrap();
+        run(cli(&dir, &["add", "b/path", "--alias", "b"])).unwrap();
+        run(cli(
+            &dir,
+            &[
+                "qr",
+                "--site-url",
+                "https://example.com/s",
+                "--tag",
+                "flyers",
+            ],
+        ))
+        .unwrap();
+
+        assert!(dir.join("qr/a.svg").exists());
+        assert!(!dir.join("qr/b.svg").exists());
+        assert!(matches!(
+            run(cli(&dir, &["qr"])),
+            Err(RedirectorError::InvalidTarget(_))
+        ));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_cli_json() {
+        let dir = test_dir("test_cli_json");
+
+        run(cli(&dir, &["add", "docs", "--alias", "docs", "--json"])).unwrap();
+        let outcome = Redirector::builder()
+            .target("docs")
+            .out_dir(&dir)
+            .build()
+            .unwrap()
+            .write_redirect()
+            .unwrap();
+
+        let json: serde_json::Value =
+            serde_json::from_str(&outcomes_json(&[outcome], Some("https://example.com/s")))
+                .unwrap();
+        assert_eq!(json[0]["short_name"], "docs");
+        assert_eq!(json[0]["target"], "/docs/");
+        assert_eq!(json[0]["created"], false);
+        assert_eq!(json[0]["url"], "https://example.com/s/docs.html");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_cli_audit_log() {
+        let dir = test_dir("test_cli_audit_log");
+        let audit = dir.join("audit.jsonl");
+        let config = dir.join("link-bridge.toml");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            &config,
+            format!("audit_log = {:?}\n", audit.to_string_lossy()),
+        )
+        .unwrap();
+        let config = config.to_string_lossy();
+
+        run(cli(
+            &dir,
+            &["--config", &config, "add", "docs", "--alias", "docs"],
+        ))
+        .unwrap();
+        run(cli(&dir, &["--config", &config, "add", "docs"])).unwrap();
+        run(cli(&dir, &["--config", &config, "rm", "docs"])).unwrap();
+
+        let outcomes: Vec<String> = fs::read_to_string(&audit)
+            .unwrap()
+            .lines()
+            .map(|line| {
+                let line: serde_json::Value = serde_json::from_str(line).unwrap();
+                format!(
+                    "{} {} {}",
+                    line["outcome"], line["short_name"], line["target"]
+                )
+            })
+            .collect();
+        assert_eq!(
+            outcomes,
+            [
+                r#""created" "docs" "/docs/""#,
+                r#""reused" "docs" "/docs/""#,
+                r#""deleted" "docs" "/docs/""#,
+            ]
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_cli_tags() {
+        let dir = test_dir("test_cli_tags");
+        let output = dir.join("export.json");
+
+        run(cli(
+            &dir,
+            &[
+                "add",
+                "summer/sale",
+                "--alias",
+                "promo",
+                "--campaign",
+                "summer-2025",
+                "--tag",
+                "marketing",
+            ],
+        ))
+        .unwrap();
+        run(cli(&dir, &["add", "docs/install", "--alias", "install"])).unwrap();
+        run(cli(
+            &dir,
+            &[
+                "export",
+                "--tag",
+                "marketing",
+                "--output",
+                &output.to_string_lossy(),
+            ],
+        ))
+        .unwrap();
+
+        let exported: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+        assert_eq!(exported.as_array().unwrap().len(), 1);
+        assert_eq!(exported[0]["short_name"], "promo");
+        assert_eq!(exported[0]["campaign"], "summer-2025");
+        assert_eq!(exported[0]["tags"], serde_json::json!(["marketing"]));
+        assert!(run(cli(&dir, &["list", "--tag", "marketing"])).is_ok());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_cli_export_bookmarks_and_atom() {
+        let dir = test_dir("test_cli_export_bookmarks_and_atom");
+        let output = dir.join("bookmarks.html");
+        let output = output.to_string_lossy();
+
+        run(cli(&dir, &["add", "docs/install", "--alias", "install"])).unwrap();
+        assert!(Cli::try_parse_from(["link-bridge", "export", "--format", "bookmarks"]).is_err());
+        run(cli(
+            &dir,
+            &[
+                "export",
+                "--format",
+                "bookmarks",
+                "--site-url",
+                "https://example.com/s",
+                "--output",
+                &output,
+            ],
+        ))
+        .unwrap();
+
+        let bookmarks = fs::read_to_string(&*output).unwrap();
+        assert!(bookmarks.starts_with("<!DOCTYPE NETSCAPE-Bookmark-file-1>"));
+        assert!(bookmarks.contains("<A HREF=\"https://example.com/s/install.html\">install</A>"));
+
+        assert!(Cli::try_parse_from(["link-bridge", "export", "--format", "atom"]).is_err());
+        let args = [
+            "export",
+            "--format",
+            "atom",
+            "--site-url",
+            "https://example.com/s",
+        ];
+        let args = [&args[..], &["--output", &output]].concat();
+        run(cli(&dir, &args)).unwrap();
+
+        let feed = fs::read_to_string(&*output).unwrap();
+        assert!(feed.contains("<link href=\"https://example.com/s/install.html\"/>"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_cli_import_netlify() {
+        let dir = test_dir("test_cli_import_netlify");
+        let file = dir.join("_redirects");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            &file,
+            "/install /docs/install/ 301\n/app/* /index.html 200\n/guide https://guide.example.com/\n",
+        )
+        .unwrap();
+        let file = file.to_string_lossy();
+
+        let args = ["import", "--format", "netlify", &file];
+        assert!(run(cli(&dir, &args)).is_err());
+
+        let args = [&args[..], &["--allow-host", "guide.example.com"]].concat();
+        run(cli(&dir, &args)).unwrap();
+
+        let registry = Registry::load(&dir).unwrap();
+        assert_eq!(
+            sorted_entries(&registry),
+            [
+                ("guide", "https://guide.example.com/"),
+                ("install", "/docs/install/")
+            ]
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_cli_uses_config_file() {
+        let dir = test_dir("test_cli_uses_config_file");
+        let out_dir = dir.join("out");
+        let config = dir.join("link-bridge.toml");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            &config,
+            format!(
+                "out_dir = {:?}\nbase_url = \"https://example.com\"\n",
+                out_dir.to_string_lossy()
+            ),
+        )
+        .unwrap();
+
+        let args = [
+            "link-bridge",
+            "--config",
+            &config.to_string_lossy(),
+            "add",
+            "docs",
+            "--alias",
+            "docs",
+        ];
+        run(Cli::try_parse_from(args).unwrap()).unwrap();
+
+        let content = fs::read_to_string(out_dir.join("docs.html")).unwrap();
+        assert!(content.contains("url=https://example.com/docs/"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -20,55 +20,420 @@
 //! redirector.set_path("doc_test_output");
 //!
 //! // Write the redirect HTML file
-//! let redirect_path = redirector.write_redirect().unwrap();
+//! let outcome = redirector.write_redirect().unwrap();
+//! assert!(outcome.file_path().exists());
 //!
 //! // Clean up test files
 //! fs::remove_dir_all("doc_test_output").ok();
 //! ```
 
+#[cfg(feature = "actix-web")]
+mod actix;
+mod alias;
+mod amp;
+mod apache;
+mod astro;
+#[cfg(feature = "tokio")]
+mod async_io;
+mod audit;
+mod beacon;
+mod bloom;
+mod bookmarks;
+#[cfg(feature = "object-store")]
+mod bucket;
+mod builder;
+#[cfg(feature = "chrono")]
+mod clock;
+mod collisions;
+#[cfg(feature = "config")]
+mod config;
+#[cfg(feature = "serde")]
+mod definition;
+#[cfg(feature = "miette")]
+mod diagnostic;
+mod duplicate;
+mod eleventy;
+mod existing;
+mod expiry;
+#[cfg(feature = "rocket")]
+mod fairing;
+mod feed;
+mod gate;
+mod gateway;
+mod haproxy;
+mod history;
+mod hooks;
+mod import;
+mod json;
+mod layout;
+#[cfg(feature = "link-check")]
+mod link_check;
+#[cfg(feature = "fluent")]
+mod locale;
+mod lock;
+#[cfg(feature = "mdbook")]
+mod mdbook;
+mod metrics;
+#[cfg(feature = "tower")]
+mod middleware;
+mod mirror;
+mod nextjs;
+mod orphans;
+mod outcome;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod path_policy;
+#[cfg(feature = "preview")]
+mod preview;
+mod provenance;
+#[cfg(feature = "qr")]
+mod qr;
+mod registry;
+mod relocate;
+mod retire;
+#[cfg(feature = "axum")]
+mod server;
+mod sidecar;
+#[cfg(feature = "signed-names")]
+mod signed;
+mod sink;
+#[cfg(feature = "social-preview")]
+mod social;
+mod split;
+mod ssg;
+mod stub;
+mod sveltekit;
+mod table;
+mod target;
+mod time;
+#[cfg(feature = "fetch-title")]
+mod title;
+mod trace;
+#[cfg(feature = "url")]
+mod url_interop;
 mod url_path;
+mod varnish;
+mod verify;
+#[cfg(feature = "webhook")]
+mod webhook;
 
+use std::collections::hash_map::{Entry, RandomState};
 use std::collections::HashMap;
 use std::ffi::OsString;
-use std::fs::File;
-use std::io::Write;
+use std::hash::BuildHasher;
+use std::io;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::{fmt, fs};
 use thiserror::Error;
 
-use chrono::Utc;
-
-use crate::redirector::url_path::UrlPath;
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Utc};
+
+use crate::redirector::alias::is_reserved_name;
+use crate::redirector::beacon::js_string;
+use crate::redirector::bookmarks::escape;
+#[cfg(feature = "chrono")]
+use crate::redirector::clock::stable_hash;
+use crate::redirector::duplicate::AdditionalPage;
+use crate::redirector::gate::Gate;
+use crate::redirector::hooks::Hooks;
+use crate::redirector::target::{ExternalUrl, Target};
+use crate::redirector::time::Timestamp;
+
+pub use alias::slugify;
+pub use audit::AuditLog;
+pub use bloom::TargetFilter;
+#[cfg(feature = "object-store")]
+pub use bucket::ObjectStoreSink;
+pub use builder::RedirectorBuilder;
+#[cfg(feature = "chrono")]
+pub use clock::{Clock, SystemClock};
+pub use collisions::ShortNameCollision;
+#[cfg(feature = "config")]
+pub use config::{ProjectConfig, CONFIG_FILE_NAME};
+pub use duplicate::DuplicatePolicy;
+pub use existing::ExistingFilePolicy;
+pub use expiry::DEFAULT_EXPIRED_PAGE;
+#[cfg(feature = "rocket")]
+pub use fairing::RedirectFairing;
+pub use history::TargetChange;
+pub use hooks::RedirectHooks;
+pub use import::{ImportFormat, ImportedRedirect};
+pub use layout::OutputLayout;
+#[cfg(feature = "link-check")]
+pub use link_check::{BrokenLink, LinkProblem, LinkReport};
+#[cfg(feature = "fluent")]
+pub use locale::Locale;
+pub use lock::LockedRegistry;
+#[cfg(feature = "mdbook")]
+pub use mdbook::MdBookPreprocessor;
+#[cfg(feature = "tower")]
+pub use middleware::{RedirectLayer, RedirectService};
+pub use outcome::RedirectOutcome;
+pub use path_policy::{
+    PathPolicy, TargetCase, TrailingSlash, DEFAULT_MAX_DEPTH, DEFAULT_MAX_LENGTH,
+};
+pub use provenance::Provenance;
+pub use registry::{Registry, RegistryEntry, RegistryIter};
+pub use retire::DEFAULT_RETIRED_PAGE;
+#[cfg(feature = "axum")]
+pub use server::RedirectStatus;
+pub use sink::{FsSink, MemorySink, OutputSink};
+#[cfg(feature = "social-preview")]
+pub use social::SocialPreview;
+pub use ssg::{ContentAlias, SiteGenerator};
+pub use target::{HostAllowlist, TargetError};
+pub use url_path::{UrlPath, UrlPathError};
+#[cfg(feature = "webhook")]
+pub use webhook::{Webhook, WebhookFormat};
+
+/// Counts the short names generated by this process, so names generated in the same
+/// millisecond differ.
+static NAME_COUNTER: AtomicU64 = AtomicU64::new(0);
 
 /// Errors that can occur during redirect operations.
+///
+/// Errors raised by filesystem operations carry the path that could not be read or
+/// written, and the underlying I/O error is available through
+/// [`std::error::Error::source()`].
 #[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum RedirectorError {
-    /// An I/O error occurred while creating or writing redirect files.
+    /// The output directory could not be created.
     ///
-    /// This includes errors like permission denied, disk full, or invalid file paths.
-    #[error("Failed to create redirect file")]
-    FileCreationError(#[from] std::io::Error),
-
-    /// The short link has not been generated (should not occur in normal usage).
+    /// This includes errors like permission denied, or a file existing where a parent
+    /// directory is expected.
+    #[error("Failed to create directory {}", path.display())]
+    DirectoryCreationError {
+        /// The directory that could not be created.
+        path: PathBuf,
+        /// The underlying I/O error.
+        source: io::Error,
+    },
+
+    /// The output directory could not be listed.
+    ///
+    /// This occurs when looking for orphaned pages with `Registry::find_orphans()` or
+    /// `Registry::clean_orphans()` and the directory cannot be read.
+    #[error("Failed to read directory {}", path.display())]
+    DirectoryReadError {
+        /// The directory that could not be read.
+        path: PathBuf,
+        /// The underlying I/O error.
+        source: io::Error,
+    },
+
+    /// A redirect HTML file could not be written.
     ///
-    /// This error is included for completeness but should not happen since
-    /// short links are automatically generated during `Redirector::new()`.
+    /// This includes errors like permission denied, disk full, or invalid file names.
+    #[error("Failed to write redirect file {}", path.display())]
+    FileWriteError {
+        /// The redirect file that could not be written.
+        path: PathBuf,
+        /// The underlying I/O error.
+        source: io::Error,
+    },
+
+    /// The requested short link does not exist.
+    ///
+    /// This occurs when a registry operation names a short link that is not recorded
+    /// in the registry. Short links for new redirects are always generated during
+    /// `Redirector::new()`, so this does not occur when writing redirects.
     #[error("Short link not found")]
     ShortLinkNotFound,
 
     /// The provided URL path is invalid.
     ///
     /// This occurs when the path contains invalid characters like query parameters (?),
-    /// semicolons (;), or other forbidden characters.
+    /// semicolons (;), or other forbidden characters, or when it exceeds the length or
+    /// depth limits of the `PathPolicy`.
     #[error("Invalid URL path: {0}")]
-    InvalidUrlPath(#[from] url_path::UrlPathError),
+    InvalidUrlPath(#[from] UrlPathError),
+
+    /// A redirect HTML file could not be deleted.
+    #[error("Failed to remove redirect file {}", path.display())]
+    FileRemovalError {
+        /// The redirect file that could not be deleted.
+        path: PathBuf,
+        /// The underlying I/O error.
+        source: io::Error,
+    },
+
+    /// The redirect registry could not be read.
+    ///
+    /// This occurs when the `registry.json` file cannot be opened or parsed. Common
+    /// causes include corrupted JSON or permission issues.
+    #[error("Failed to read redirect registry {}", path.display())]
+    FailedToReadRegistry {
+        /// The registry file that could not be read.
+        path: PathBuf,
+        /// The underlying I/O or JSON error.
+        source: io::Error,
+    },
+
+    /// The redirect registry could not be written.
+    ///
+    /// This occurs when the `registry.json` file, or the temporary file it is staged
+    /// in, cannot be created, serialized, or moved into place.
+    #[error("Failed to write redirect registry {}", path.display())]
+    FailedToWriteRegistry {
+        /// The registry file that could not be written.
+        path: PathBuf,
+        /// The underlying I/O or JSON error.
+        source: io::Error,
+    },
+
+    /// The project configuration file could not be read.
+    ///
+    /// This occurs when `link-bridge.toml` cannot be opened, is not valid TOML, or
+    /// contains unknown or mistyped settings.
+    #[error("Failed to read configuration {}", path.display())]
+    FailedToReadConfig {
+        /// The configuration file that could not be read.
+        path: PathBuf,
+        /// The underlying I/O or parse error.
+        source: io::Error,
+    },
+
+    /// The redirect manifest could not be read.
+    ///
+    /// This occurs when the manifest passed to
+    /// `build_support::generate_from_manifest()` cannot be opened, is not valid TOML,
+    /// or declares a redirect that fails validation.
+    #[error("Failed to read redirect manifest {}", path.display())]
+    FailedToReadManifest {
+        /// The manifest file that could not be read.
+        path: PathBuf,
+        /// The underlying I/O or parse error.
+        source: io::Error,
+    },
+
+    /// A content directory or file could not be read while scanning for aliases.
+    #[error("Failed to read content {}", path.display())]
+    FailedToReadContent {
+        /// The directory or file that could not be read.
+        path: PathBuf,
+        /// The underlying I/O error.
+        source: io::Error,
+    },
+
+    /// A file of redirects to import could not be read.
+    ///
+    /// This occurs when the file passed to `ImportFormat::read()` cannot be opened, or
+    /// contains a malformed line; the line number is included in the source error.
+    #[error("Failed to read import file {}", path.display())]
+    FailedToReadImport {
+        /// The file that could not be read.
+        path: PathBuf,
+        /// The underlying I/O or parse error.
+        source: io::Error,
+    },
+
+    /// The input passed to the mdBook preprocessor is malformed.
+    ///
+    /// This occurs when the JSON sent by mdBook cannot be parsed, or when the
+    /// `[redirects]` table of `book.toml` is not a table of strings. The reason is
+    /// included.
+    #[error("Invalid mdBook input: {0}")]
+    InvalidBookContext(String),
+
+    /// The provided external target URL is invalid.
+    ///
+    /// This occurs when an external target is not a well-formed `http` or `https` URL,
+    /// or uses a scheme such as `javascript:` that could run a script in the browser.
+    #[error("Invalid target: {0}")]
+    InvalidTarget(#[from] TargetError),
+
+    /// The external target points at a host that is not on the allowlist.
+    ///
+    /// External targets are only permitted for hosts named in the [`HostAllowlist`]
+    /// supplied to [`Redirector::new_external()`]. The offending host is included.
+    #[error("Target host not permitted: {0}")]
+    DisallowedTarget(String),
+
+    /// No target was provided to the [`RedirectorBuilder`].
+    #[error("No redirect target was provided")]
+    MissingTarget,
 
-    /// An error occurred while reading or writing the redirect registry.
+    /// The provided alias is not a valid short name.
     ///
-    /// This occurs when the `registry.json` file cannot be read, parsed, or written.
-    /// Common causes include corrupted JSON, permission issues, or filesystem errors.
-    #[error("Failed to read redirect registry")]
-    FailedToReadRegistry(#[from] serde_json::Error),
+    /// Aliases may only contain ASCII letters, digits, dashes, and underscores, must
+    /// start with a letter or digit, and are limited to 64 characters. Use
+    /// [`slugify()`](crate::slugify) to derive a valid alias from a title.
+    #[error("Invalid alias: {0}")]
+    InvalidAlias(String),
+
+    /// The provided beacon endpoint is not a valid path.
+    ///
+    /// Beacon endpoints are absolute URLs or site-relative paths starting with `/`;
+    /// paths may not contain whitespace, quotes, backslashes, or angle brackets.
+    #[error("Invalid beacon endpoint: {0}")]
+    InvalidBeacon(String),
+
+    /// A target of a split redirect has a weight of zero.
+    ///
+    /// Every target a redirect is split between must be picked some of the time. The
+    /// target is included.
+    #[error("Split target has zero weight: {0}")]
+    ZeroWeight(String),
+
+    /// No translation of the page text is bundled for the language tag.
+    ///
+    /// The language tag is included; it may also be malformed.
+    #[error("Unsupported locale: {0}")]
+    UnsupportedLocale(String),
+
+    /// Another short link already redirects to the target.
+    ///
+    /// The registry holds one short link per target, so an existing short link cannot
+    /// be repointed at a target that is already registered. A redirector built with
+    /// [`DuplicatePolicy::Error`] also reports a write for a registered target this
+    /// way. The target is included.
+    #[error("Target already registered: {0}")]
+    DuplicateTarget(String),
+
+    /// Another target already uses the short name.
+    ///
+    /// Writing the redirect would overwrite the page of an existing short link, so
+    /// links already shared would silently start leading somewhere else.
+    #[error("Short name already in use: {0}")]
+    DuplicateShortName(String),
+    /// A mirror directory already has the target under a different short name.
+    ///
+    /// Writing to mirror directories keeps one short name for the redirect in every
+    /// directory, so a mirror that already redirects to the target under another name
+    /// cannot be brought into line. The mirror directory and its short name are
+    /// included.
+    #[error("Mirror {} already uses short name {short_name}", path.display())]
+    OutOfSync {
+        /// The mirror directory.
+        path: PathBuf,
+        /// The short name the mirror already uses for the target.
+        short_name: String,
+    },
+
+    /// The short name is a device name reserved by Windows.
+    ///
+    /// Windows cannot create files named `CON`, `PRN`, `AUX`, `NUL`, `COM1` to `COM9`,
+    /// or `LPT1` to `LPT9` in any letter case, whatever their extension, so such names
+    /// are rejected on every platform to keep output directories portable.
+    #[error("Short name is reserved on Windows: {0}")]
+    ReservedName(String),
+
+    /// A redirect page just written does not lead to its target consistently.
+    ///
+    /// Redirectors built with `verify_output()` read each new page back after writing
+    /// it, and report this error if its meta refresh, script, or fallback link is
+    /// missing or leads somewhere other than the target. The page is not recorded in
+    /// the registry.
+    #[error("Redirect page {} is inconsistent: {reason}", path.display())]
+    InconsistentPage {
+        /// The page that was written.
+        path: PathBuf,
+        /// What was found wrong with it.
+        reason: String,
+    },
 }
 
 /// Manages URL redirection by generating short links and HTML redirect pages.
@@ -88,10 +453,13 @@ pub enum RedirectorError {
 ///
 /// Short file names are generated using:
 /// - Current timestamp in milliseconds
-/// - Sum of UTF-16 code units from the URL path
-/// - Base62 encoding for compact, URL-safe names
+/// - 16 bits of a randomly keyed hash of the target
+/// - 16 bits of a process-wide counter
+/// - Base62 encoding for compact, URL-safe names, about 13 characters long
 /// - `.html` extension for web server compatibility
 ///
+/// Use [`RedirectorBuilder::alias()`] for a shorter, memorable name.
+///
 /// # Registry System
 ///
 /// The redirector maintains a `registry.json` file in each output directory that tracks:
@@ -107,14 +475,75 @@ pub enum RedirectorError {
 /// - JavaScript fallback for better compatibility
 /// - User-friendly link for manual navigation
 /// - Proper HTML5 structure and encoding
+///
+/// # Serialization
+///
+/// With the `serde` feature enabled, `Redirector` implements `Serialize` and
+/// `Deserialize` using the same options as [`RedirectorBuilder`] (`target`, `out_dir`,
+/// `alias`, `delay`, `base_url`, `trailing_slash`, `target_case`, `allow_hosts`,
+/// `beacon`, `campaign`, `tags`, `layout`, `on_duplicate`, and `on_existing_file`).
+/// Deserialized redirectors are validated exactly as [`RedirectorBuilder::build()`]
+/// would.
 #[derive(Debug, Clone, PartialEq, Default)]
 pub struct Redirector {
-    /// The validated and normalized URL path to redirect to.
-    long_path: UrlPath,
+    /// The validated and normalized target to redirect to.
+    target: Target,
     /// The generated short file name (including .html extension).
     short_file_name: OsString,
     /// The directory path where redirect HTML files will be stored.
     path: PathBuf,
+    /// The optional site base URL used to make path targets absolute.
+    base_url: Option<ExternalUrl>,
+    /// The number of seconds the page waits before redirecting.
+    delay: u32,
+    /// The callbacks invoked when the redirect is written.
+    hooks: Hooks,
+    /// Whether a target registered in another letter case is reused.
+    target_case: TargetCase,
+    /// When the redirect stops working, if it expires.
+    expires_at: Option<Timestamp>,
+    /// The page written instead of the redirect once it has expired.
+    expired_page: Option<String>,
+    /// The endpoint notified of each visit before redirecting, if any.
+    beacon: Option<String>,
+    /// Whether the page waits for the visitor to confirm before redirecting.
+    gate: Gate,
+    /// Whether the page is written as AMP HTML.
+    amp: bool,
+    /// Whether a plain-text file naming the target is written next to the page.
+    text_sidecar: bool,
+    /// Whether a JSON file of the redirect's metadata is written next to the page.
+    json_sidecar: bool,
+    /// Whether a comment recording where the page came from is written into it.
+    provenance: bool,
+    /// The relative weight of the target when the redirect is split.
+    weight: u32,
+    /// The other targets the redirect is split between, with their weights.
+    variants: Vec<(Target, u32)>,
+    /// The campaign recorded with the redirect in the registry, if any.
+    campaign: Option<String>,
+    /// The tags recorded with the redirect in the registry, sorted and without
+    /// duplicates.
+    tags: Vec<String>,
+    /// Whether the page is written as a file or as a directory's index.
+    layout: OutputLayout,
+    /// The other output directories the redirect is written to.
+    mirrors: Vec<PathBuf>,
+    /// What a write does when the target is already registered.
+    duplicate_policy: DuplicatePolicy,
+    /// What a write does when a new page would replace a file not in the registry.
+    existing_file_policy: ExistingFilePolicy,
+    /// The language of the page text, if not English.
+    #[cfg(feature = "fluent")]
+    locale: Option<Locale>,
+    /// The preview image written next to the page, if any.
+    #[cfg(feature = "social-preview")]
+    social_preview: Option<SocialPreview>,
+    /// The title of the page the redirect leads to, if it was fetched.
+    #[cfg(feature = "fetch-title")]
+    title: Option<String>,
+    /// Whether each new page is read back and checked after it is written.
+    verify_output: bool,
 }
 
 impl Redirector {
@@ -148,39 +577,276 @@ impl Redirector {
     /// assert!(Redirector::new("").is_err());                 // Empty string
     /// ```
     pub fn new<S: ToString>(long_path: S) -> Result<Self, RedirectorError> {
-        let long_path = UrlPath::new(long_path.to_string())?;
+        let target = Target::Path(UrlPath::new(long_path.to_string())?);
+
+        Ok(Redirector::with_target(target))
+    }
+
+    /// Creates a new `Redirector` instance, validating the path with a custom policy.
+    ///
+    /// Behaves like [`Redirector::new()`] but uses the supplied [`PathPolicy`] to decide
+    /// which path segments are acceptable, so sites can opt into stricter or more
+    /// permissive validation.
+    ///
+    /// # Arguments
+    ///
+    /// * `long_path` - The URL path to create a redirect for (e.g., "api/v1/users")
+    /// * `policy` - The validation policy to apply to the path
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Redirector)` - A configured redirector ready to generate redirect files
+    /// * `Err(RedirectorError::InvalidUrlPath)` - If the path is rejected by the policy
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::{PathPolicy, Redirector};
+    ///
+    /// let policy = PathPolicy::strict();
+    ///
+    /// assert!(Redirector::with_policy("api/v1", &policy).is_ok());
+    /// assert!(Redirector::with_policy("api/v1.0", &policy).is_err());
+    /// ```
+    pub fn with_policy<S: ToString>(
+        long_path: S,
+        policy: &PathPolicy,
+    ) -> Result<Self, RedirectorError> {
+        let target = Target::Path(UrlPath::with_policy(long_path.to_string(), policy)?);
+
+        let mut redirector = Redirector::with_target(target);
+        redirector.target_case = policy.case();
+        Ok(redirector)
+    }
+
+    /// Creates a new `Redirector` instance for an absolute URL on an allowlisted host.
+    ///
+    /// External targets are disabled unless enabled through this constructor, which
+    /// requires a [`HostAllowlist`] naming the hosts short links may point at. This
+    /// prevents the redirect generator from being abused as an open redirect to
+    /// arbitrary third-party domains.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The absolute `http` or `https` URL to redirect to
+    /// * `allowlist` - The hosts that external targets are permitted to point at
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Redirector)` - A configured redirector ready to generate redirect files
+    /// * `Err(RedirectorError::InvalidTarget)` - If the URL is malformed or uses a
+    ///   scheme such as `javascript:`
+    /// * `Err(RedirectorError::DisallowedTarget)` - If the host is not on the allowlist
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::{HostAllowlist, Redirector, RedirectorError};
+    ///
+    /// let allowlist = HostAllowlist::new(["docs.example.com"]);
+    ///
+    /// let redirector = Redirector::new_external("https://docs.example.com/guide", &allowlist);
+    /// assert!(redirector.is_ok());
+    ///
+    /// let result = Redirector::new_external("https://evil.example.net/", &allowlist);
+    /// assert!(matches!(result, Err(RedirectorError::DisallowedTarget(_))));
+    /// ```
+    pub fn new_external<S: ToString>(
+        url: S,
+        allowlist: &HostAllowlist,
+    ) -> Result<Self, RedirectorError> {
+        let url = ExternalUrl::new(url.to_string())?;
+
+        if !allowlist.permits(url.host()) {
+            return Err(RedirectorError::DisallowedTarget(url.host().to_string()));
+        }
+
+        Ok(Redirector::with_target(Target::External(url)))
+    }
 
-        let short_file_name = Redirector::generate_short_file_name(&long_path);
+    /// Reconstructs the `Redirector` for a target already recorded in a registry.
+    ///
+    /// Reads `registry.json` from the given output directory and rebuilds the
+    /// redirector for the target, including its existing short file name, so tools can
+    /// re-render or inspect an existing redirect instead of creating a new one.
+    ///
+    /// The target is looked up after normalization with the default [`PathPolicy`],
+    /// falling back to the target exactly as given. Render options that are not
+    /// recorded in the registry, such as the base URL and delay, take their defaults.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The output directory containing the registry
+    /// * `target` - The target of the existing redirect (e.g. `"api/v1/users"`)
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Redirector)` - The redirector for the existing redirect
+    /// * `Err(RedirectorError::ShortLinkNotFound)` - If the target is not registered
+    /// * `Err(RedirectorError::FailedToReadRegistry)` - If the registry cannot be read
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::Redirector;
+    /// use std::fs;
+    ///
+    /// let mut redirector = Redirector::new("api/v1/users").unwrap();
+    /// redirector.set_path("doc_test_from_existing");
+    /// redirector.write_redirect().unwrap();
+    ///
+    /// let existing = Redirector::from_existing("doc_test_from_existing", "api/v1/users").unwrap();
+    /// assert_eq!(existing.short_file_name(), redirector.short_file_name());
+    ///
+    /// assert!(Redirector::from_existing("doc_test_from_existing", "other").is_err());
+    ///
+    /// fs::remove_dir_all("doc_test_from_existing").ok();
+    /// ```
+    pub fn from_existing<P: Into<PathBuf>, S: ToString>(
+        dir: P,
+        target: S,
+    ) -> Result<Self, RedirectorError> {
+        let dir = dir.into();
+        let registry = Registry::load(&dir)?;
+        let target = target.to_string();
+
+        let normalized = UrlPath::new(&target).ok().map(|path| path.to_string());
+        let (key, entry) = normalized
+            .into_iter()
+            .chain(std::iter::once(target))
+            .find_map(|key| registry.entry(&key).map(|entry| (key, entry)))
+            .ok_or(RedirectorError::ShortLinkNotFound)?;
+
+        let short_file_name = match entry.short_name() {
+            "" => return Err(RedirectorError::ShortLinkNotFound),
+            short_name => OsString::from(format!("{short_name}.html")),
+        };
 
         Ok(Redirector {
-            long_path,
+            target: Target::from_registry_key(&key)?,
             short_file_name,
-            path: PathBuf::from("s"),
+            path: dir,
+            expires_at: entry.expires_at,
+            campaign: entry.campaign().map(str::to_string),
+            tags: entry.tags().to_vec(),
+            layout: OutputLayout::of(entry.short_file()),
+            weight: 1,
+            ..Default::default()
         })
     }
 
-    /// Generates a unique short file name based on timestamp and URL path content.
+    /// Returns a [`RedirectorBuilder`] for fluent construction of a redirector.
+    ///
+    /// The builder collects all options and validates them once when
+    /// [`RedirectorBuilder::build()`] is called.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::Redirector;
+    ///
+    /// let redirector = Redirector::builder()
+    ///     .target("a/b")
+    ///     .out_dir("s")
+    ///     .alias("promo")
+    ///     .delay(3)
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(redirector.short_file_name(), "promo.html");
+    /// ```
+    pub fn builder() -> RedirectorBuilder {
+        RedirectorBuilder::new()
+    }
+
+    /// Creates a redirector for a validated target with the default output directory.
+    fn with_target(target: Target) -> Self {
+        let short_file_name = Redirector::generate_short_file_name(&target);
+
+        Redirector {
+            target,
+            short_file_name,
+            path: PathBuf::from("s"),
+            base_url: None,
+            delay: 0,
+            hooks: Hooks::default(),
+            target_case: TargetCase::default(),
+            expires_at: None,
+            expired_page: None,
+            beacon: None,
+            gate: Gate::default(),
+            amp: false,
+            text_sidecar: false,
+            json_sidecar: false,
+            provenance: false,
+            weight: 1,
+            variants: Vec::new(),
+            campaign: None,
+            tags: Vec::new(),
+            layout: OutputLayout::default(),
+            mirrors: Vec::new(),
+            duplicate_policy: DuplicatePolicy::default(),
+            existing_file_policy: ExistingFilePolicy::default(),
+            #[cfg(feature = "fluent")]
+            locale: None,
+            #[cfg(feature = "social-preview")]
+            social_preview: None,
+            #[cfg(feature = "fetch-title")]
+            title: None,
+            verify_output: false,
+        }
+    }
+
+    /// Generates a unique short file name from the current time and the target.
     ///
-    /// Creates a unique identifier by combining the current timestamp with the URL path's
-    /// UTF-16 character values, then encoding the result using base62 for a compact,
+    /// The name combines the current timestamp with a hash of the target and a
+    /// process-wide counter, then encodes the result using base62 for a compact,
     /// URL-safe file name.
     ///
     /// # Algorithm
     ///
     /// 1. Get current timestamp in milliseconds
-    /// 2. Sum all UTF-16 code units from the URL path
-    /// 3. Add timestamp and UTF-16 sum together
-    /// 4. Encode the result using base62 (0-9, A-Z, a-z)
+    /// 2. Hash the target with a randomly keyed hasher, so separate runs creating the
+    ///    same target in the same millisecond still differ
+    /// 3. Take the next value of a monotonic counter, so names generated within one
+    ///    process in the same millisecond differ whatever their targets
+    /// 4. Pack the timestamp, 16 bits of the hash, and 16 bits of the counter into one
+    ///    number and encode it using base62 (0-9, A-Z, a-z)
     /// 5. Append ".html" extension
     ///
+    /// Shifting the timestamp past the hash and counter makes names about 13
+    /// characters long, where the timestamp alone would take about 7. The hash only
+    /// contributes 16 bits, so separate processes creating names in the same
+    /// millisecond with the same counter value collide once in 65,536 pairs. Such a
+    /// collision fails with [`RedirectorError::DuplicateShortName`] when written to the
+    /// same registry rather than replacing a page; within one process the counter
+    /// rules it out.
+    ///
     /// # Returns
     ///
     /// An `OsString` containing the generated file name with `.html` extension.
-    fn generate_short_file_name(long_path: &UrlPath) -> OsString {
-        let name = base62::encode(
-            Utc::now().timestamp_millis() as u64
-                + long_path.encode_utf16().iter().sum::<u16>() as u64,
-        );
+    fn generate_short_file_name(target: &Target) -> OsString {
+        let millis = Timestamp::now().unix_millis() as u64;
+        let hash = RandomState::new().hash_one(target.to_string()) as u16;
+        let count = NAME_COUNTER.fetch_add(1, Ordering::Relaxed) as u16;
+
+        let name =
+            base62::encode(u128::from(millis) << 32 | u128::from(hash) << 16 | u128::from(count));
+        trace::debug!(target = %target, short_name = %name, "generated short name");
+        OsString::from(format!("{name}.html"))
+    }
+
+    /// Generates a reproducible short file name from a clock's time and the target.
+    ///
+    /// The timestamp in milliseconds is packed with a 32-bit [`stable_hash()`] of the
+    /// target in place of the random hash and counter, so the same time and target
+    /// always give the same name.
+    #[cfg(feature = "chrono")]
+    pub(crate) fn clocked_short_file_name(target: &Target, now: Timestamp) -> OsString {
+        let millis = now.unix_millis() as u64;
+        let hash = stable_hash(&target.to_string());
+
+        let name = base62::encode(u128::from(millis) << 32 | u128::from(hash));
+        trace::debug!(target = %target, short_name = %name, "generated short name from clock");
         OsString::from(format!("{name}.html"))
     }
 
@@ -188,7 +854,9 @@ impl Redirector {
     ///
     /// # Returns
     ///
-    /// An `OsString` containing the generated file name with `.html` extension.
+    /// An `OsString` containing the generated file name with `.html` extension. The
+    /// name is the same under [`OutputLayout::Directory`], which writes the page as
+    /// `index.html` in a directory named after the short name instead.
     ///
     /// # Examples
     ///
@@ -203,6 +871,16 @@ impl Redirector {
         self.short_file_name.clone()
     }
 
+    /// Reports when the redirect stops working, if it expires.
+    ///
+    /// Set with [`RedirectorBuilder::expires_at()`] or [`RedirectorBuilder::ttl()`].
+    /// A redirect written after its expiry gets the expired page instead of a
+    /// redirect, and [`Registry::expire()`] sweeps redirects once they have expired.
+    #[cfg(feature = "chrono")]
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.expires_at.map(Into::into)
+    }
+
     /// Sets the output directory where redirect HTML files will be stored.
     ///
     /// By default, redirector uses "s" as the output directory. Use this method
@@ -229,6 +907,144 @@ impl Redirector {
         self.path = path.into();
     }
 
+    /// Returns the output directory where the redirect file will be stored.
+    #[cfg(feature = "build-support")]
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Sets the site base URL used to emit absolute target URLs.
+    ///
+    /// By default, generated pages redirect to the site-relative target path (e.g.
+    /// `/api/v1/`). When the redirect directory is served from a different host than
+    /// the destination, set a base URL so pages redirect to the absolute URL instead
+    /// (e.g. `https://example.com/api/v1/`). External targets are unaffected.
+    ///
+    /// The base URL may include a path prefix; any trailing slash is ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_url` - An absolute `http` or `https` URL without query or fragment
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the base URL is valid
+    /// * `Err(RedirectorError::InvalidTarget)` - If the base URL is malformed
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::Redirector;
+    ///
+    /// let mut redirector = Redirector::new("api/v1").unwrap();
+    /// redirector.set_base_url("https://example.com").unwrap();
+    ///
+    /// assert!(redirector.to_string().contains("url=https://example.com/api/v1/"));
+    /// assert!(redirector.set_base_url("not a url").is_err());
+    /// ```
+    pub fn set_base_url<S: ToString>(&mut self, base_url: S) -> Result<(), RedirectorError> {
+        self.base_url = Some(ExternalUrl::new_base(base_url.to_string())?);
+        Ok(())
+    }
+
+    /// Attaches callbacks that are invoked whenever this redirect is written.
+    ///
+    /// Replaces any hooks set previously. See [`RedirectHooks`] for when each callback
+    /// is called.
+    ///
+    /// # Arguments
+    ///
+    /// * `hooks` - The callbacks to invoke on creation, reuse, or failure
+    pub fn set_hooks<H: RedirectHooks + 'static>(&mut self, hooks: H) {
+        self.hooks = Hooks::new(hooks);
+    }
+
+    /// Sets the campaign recorded with the redirect in the registry.
+    ///
+    /// Replaces any campaign set previously, including one set with
+    /// [`RedirectorBuilder::campaign()`].
+    ///
+    /// # Arguments
+    ///
+    /// * `campaign` - The campaign name (e.g. `"summer-2025"`)
+    pub fn set_campaign<S: ToString>(&mut self, campaign: S) {
+        self.campaign = Some(campaign.to_string());
+    }
+
+    /// Sets the tags recorded with the redirect in the registry.
+    ///
+    /// Replaces any tags set previously, including those added with
+    /// [`RedirectorBuilder::tag()`]. Duplicate tags are recorded once.
+    ///
+    /// # Arguments
+    ///
+    /// * `tags` - The tags (e.g. `["marketing", "social"]`)
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::Redirector;
+    ///
+    /// let mut redirector = Redirector::new("summer/sale").unwrap();
+    /// redirector.set_campaign("summer-2025");
+    /// redirector.set_tags(["social", "marketing", "social"]);
+    ///
+    /// assert_eq!(redirector.campaign(), Some("summer-2025"));
+    /// assert_eq!(redirector.tags(), ["marketing", "social"]);
+    /// ```
+    pub fn set_tags<I, S>(&mut self, tags: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: ToString,
+    {
+        self.tags = tags.into_iter().map(|tag| tag.to_string()).collect();
+        self.tags.sort_unstable();
+        self.tags.dedup();
+    }
+
+    /// Returns the campaign recorded with the redirect, if any.
+    pub fn campaign(&self) -> Option<&str> {
+        self.campaign.as_deref()
+    }
+
+    /// Returns the tags recorded with the redirect, in sorted order.
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    /// Renders the redirect's page into a writer.
+    ///
+    /// Writes the page [`Redirector::write_redirect()`] would write, or the expired
+    /// page once the redirect has expired, without touching the filesystem or the
+    /// registry. Use it to stream a page into an HTTP response, an archive, or an
+    /// upload; to write pages and a registry to other storage, implement
+    /// [`OutputSink`] and use [`Redirector::write_redirect_to()`] instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - Where the page is written
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the page was written
+    /// * `Err(io::Error)` - If the writer failed
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::Redirector;
+    ///
+    /// let redirector = Redirector::new("api/v1/users").unwrap();
+    /// let mut body = Vec::new();
+    /// redirector.render_to(&mut body).unwrap();
+    ///
+    /// let body = String::from_utf8(body).unwrap();
+    /// assert!(body.contains("url=/api/v1/users/"));
+    /// ```
+    pub fn render_to<W: io::Write + ?Sized>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(self.page().as_bytes())
+    }
+
     /// Writes the redirect HTML file to the filesystem with registry support.
     ///
     /// Creates the output directory (if it doesn't exist) and generates a complete
@@ -239,7 +1055,8 @@ impl Redirector {
     ///
     /// This method maintains a registry (`registry.json`) in the output directory to track
     /// existing redirects. If a redirect for the same URL path already exists, it returns
-    /// the path to the existing file instead of creating a duplicate. This ensures:
+    /// the existing file instead of creating a duplicate, writing the file again if it
+    /// has been deleted since. This ensures:
     /// - No duplicate files for the same URL path
     /// - Consistent redirect behaviour across multiple calls
     /// - Efficient reuse of existing redirects
@@ -252,26 +1069,57 @@ impl Redirector {
     /// - JavaScript fallback for better browser compatibility
     /// - User-friendly fallback link for manual navigation
     ///
+    /// The page is written to a temporary file and only moved into place once complete,
+    /// so an interrupted run never leaves a half-written page behind. An existing file
+    /// at the page's path is never overwritten.
+    ///
     /// # Returns
     ///
-    /// * `Ok(String)` - The path to the created redirect file if successful
-    /// * `Err(RedirectorError::FileCreationError)` - If file operations fail
+    /// * `Ok(RedirectOutcome)` - The redirect file, its short name and target, and whether
+    ///   it was newly created or already existed
+    /// * `Err(RedirectorError::DirectoryCreationError)` - If the output directory cannot be created
+    /// * `Err(RedirectorError::DuplicateShortName)` - If another target already uses the
+    ///   short name
+    /// * `Err(RedirectorError::FileWriteError)` - If the redirect file cannot be written,
+    ///   or a file not written by link-bridge already exists at its path
+    /// * `Err(RedirectorError::InconsistentPage)` - If the redirector was built with
+    ///   [`RedirectorBuilder::verify_output()`] and the new page does not lead to its
+    ///   target consistently
+    /// * `Err(RedirectorError::FailedToReadRegistry)` - If the registry cannot be read
+    /// * `Err(RedirectorError::FailedToWriteRegistry)` - If the registry cannot be written
+    /// * `Err(RedirectorError::OutOfSync)` - If a mirror directory already has the target
+    ///   under a different short name
+    ///
+    /// # Mirror Directories
+    ///
+    /// A redirector built with [`RedirectorBuilder::mirror()`] is also written to each
+    /// mirror directory, under the short name used in the output directory. If any
+    /// directory cannot be written, the pages created by the call are removed again and
+    /// no registry is changed.
     ///
     /// # Errors
     ///
     /// This method can return the following errors:
     ///
-    /// ## `FileCreationError`
+    /// ## `DirectoryCreationError`
+    /// - Permission denied (insufficient write permissions)
+    /// - A file exists where a parent directory is expected
+    ///
+    /// ## `FileWriteError`
     /// - Permission denied (insufficient write permissions)
     /// - Disk full or insufficient space
     /// - Invalid characters in the file path
-    /// - Parent directory cannot be created
     ///
     /// ## `FailedToReadRegistry`
     /// - Corrupted or invalid JSON in `registry.json`
-    /// - Permission denied when reading/writing registry file
+    /// - Permission denied when reading the registry file
+    ///
+    /// ## `FailedToWriteRegistry`
+    /// - Permission denied when writing the registry file
     /// - Registry file locked by another process
     ///
+    /// Each error carries the path of the file or directory involved.
+    ///
     /// # Examples
     ///
     /// ## Basic Usage
@@ -284,8 +1132,9 @@ impl Redirector {
     /// redirector.set_path("doc_test_redirects");
     ///
     /// // First call creates a new redirect file and registry entry
-    /// let redirect_path = redirector.write_redirect().unwrap();
-    /// println!("Created redirect at: {}", redirect_path);
+    /// let outcome = redirector.write_redirect().unwrap();
+    /// assert!(outcome.is_created());
+    /// println!("Created redirect at: {}", outcome.file_path().display());
     ///
     /// // Clean up after the test
     /// fs::remove_dir_all("doc_test_redirects").ok();
@@ -304,53 +1153,551 @@ impl Redirector {
     /// redirector2.set_path("doc_test_registry");
     ///
     /// // First call creates the file
-    /// let path1 = redirector1.write_redirect().unwrap();
+    /// let outcome1 = redirector1.write_redirect().unwrap();
+    /// assert!(outcome1.is_created());
     ///
-    /// // Second call returns the same path (no duplicate file created)
-    /// let path2 = redirector2.write_redirect().unwrap();
-    /// assert_eq!(path1, path2);
+    /// // Second call returns the same file (no duplicate file created)
+    /// let outcome2 = redirector2.write_redirect().unwrap();
+    /// assert!(!outcome2.is_created());
+    /// assert_eq!(outcome1.file_path(), outcome2.file_path());
     ///
     /// // Clean up
     /// fs::remove_dir_all("doc_test_registry").ok();
     /// ```
-    pub fn write_redirect(&self) -> Result<String, RedirectorError> {
-        // create store directory if it doesn't exist
-        if !Path::new(&self.path).exists() {
-            fs::create_dir_all(&self.path)?;
-        }
-        const REDIRECT_REGISTRY: &str = "registry.json";
-        let mut registry: HashMap<String, String> = HashMap::new();
-        if Path::new(&self.path).join(REDIRECT_REGISTRY).exists() {
-            registry = serde_json::from_reader::<_, HashMap<String, String>>(File::open(
-                self.path.join(REDIRECT_REGISTRY),
-            )?)?;
+    pub fn write_redirect(&self) -> Result<RedirectOutcome, RedirectorError> {
+        if self.mirrors.is_empty() {
+            self.write_redirect_to(&mut FsSink)
+        } else {
+            self.write_mirrored()
         }
+    }
 
-        let file_path = self.path.join(&self.short_file_name);
-
-        if let Some(existing_path) = registry.get(&self.long_path.to_string()) {
-            // A link already exists for this path, return the existing file path
-            Ok(existing_path.clone())
-        } else {
-            let mut file = File::create(&file_path)?;
+    /// Writes the redirect HTML file and updates the registry through an [`OutputSink`].
+    ///
+    /// Behaves exactly like [`Redirector::write_redirect()`], but every file is read
+    /// and written through the sink instead of `std::fs`. Use a [`MemorySink`] to
+    /// collect the generated page and registry in memory, for example when running as
+    /// WebAssembly or in a serverless function. Only the output directory is written;
+    /// mirror directories are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `sink` - The destination for the redirect page and registry
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(RedirectOutcome)` - The redirect file, its short name and target, and whether
+    ///   it was newly created or already existed
+    /// * `Err(RedirectorError)` - The same errors as [`Redirector::write_redirect()`]
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::{MemorySink, Redirector};
+    ///
+    /// let redirector = Redirector::new("api/v1/users").unwrap();
+    /// let mut sink = MemorySink::new();
+    ///
+    /// let outcome = redirector.write_redirect_to(&mut sink).unwrap();
+    /// assert!(outcome.is_created());
+    /// assert_eq!(sink.len(), 2); // the redirect page and registry.json
+    ///
+    /// let again = redirector.write_redirect_to(&mut sink).unwrap();
+    /// assert!(!again.is_created());
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "write_redirect",
+            level = "debug",
+            skip_all,
+            fields(target = %self.target, dir = %self.path.display())
+        )
+    )]
+    pub fn write_redirect_to<S: OutputSink + ?Sized>(
+        &self,
+        sink: &mut S,
+    ) -> Result<RedirectOutcome, RedirectorError> {
+        let result = Registry::load_from(sink, &self.path).and_then(|mut registry| {
+            let outcome = self.write_into(&mut registry, sink)?;
+            if outcome.is_created() {
+                registry.save_to(sink)?;
+            }
+            Ok(outcome)
+        });
+
+        self.hooks.notify(&self.target.to_string(), &result);
+        result
+    }
 
-            file.write_all(self.to_string().as_bytes())?;
-            file.sync_all()?;
+    /// Writes the redirect HTML file and records it in an already loaded registry.
+    ///
+    /// [`Redirector::write_redirect()`] loads and saves `registry.json` on every call.
+    /// This method instead borrows a [`Registry`] the caller has loaded once: the page
+    /// is written and the new entry is recorded in `registry`, but the registry is not
+    /// saved. Call [`Registry::save()`] once after the last redirect is written, so
+    /// generating thousands of redirects parses and serializes the registry only once.
+    ///
+    /// The page is written to the registry's directory rather than the redirector's
+    /// own output directory, so the registry always describes the files beside it.
+    /// If the target is already registered, the existing file is reused.
+    ///
+    /// # Arguments
+    ///
+    /// * `registry` - The registry to record the redirect in
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(RedirectOutcome)` - The redirect file, its short name and target, and whether
+    ///   it was newly created or already existed
+    /// * `Err(RedirectorError::DirectoryCreationError)` - If the registry's directory
+    ///   cannot be created
+    /// * `Err(RedirectorError::DuplicateShortName)` - If another target already uses the
+    ///   short name
+    /// * `Err(RedirectorError::FileWriteError)` - If the redirect file cannot be written,
+    ///   or a file not written by link-bridge already exists at its path
+    /// * `Err(RedirectorError::InconsistentPage)` - If the redirector was built with
+    ///   [`RedirectorBuilder::verify_output()`] and the new page does not lead to its
+    ///   target consistently
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::{Redirector, Registry};
+    /// use std::fs;
+    ///
+    /// let mut registry = Registry::load("doc_test_write_into").unwrap();
+    /// for path in ["docs/install", "docs/usage", "docs/faq"] {
+    ///     Redirector::new(path)
+    ///         .unwrap()
+    ///         .write_redirect_into(&mut registry)
+    ///         .unwrap();
+    /// }
+    /// registry.save().unwrap(); // registry.json is written once
+    ///
+    /// assert_eq!(Registry::load("doc_test_write_into").unwrap().len(), 3);
+    ///
+    /// fs::remove_dir_all("doc_test_write_into").ok();
+    /// ```
+    pub fn write_redirect_into(
+        &self,
+        registry: &mut Registry,
+    ) -> Result<RedirectOutcome, RedirectorError> {
+        let result = self.write_into(registry, &mut FsSink);
+
+        self.hooks.notify(&self.target.to_string(), &result);
+        result
+    }
 
-            registry.insert(
-                self.long_path.to_string(),
-                file_path.to_string_lossy().to_string(),
+    /// Writes several redirects, loading and persisting each registry only once.
+    ///
+    /// Redirectors are grouped by output directory; each directory's registry is
+    /// loaded once, every redirect file is written, and each changed registry is
+    /// persisted once at the end. This avoids rewriting `registry.json` for every
+    /// redirect when generating hundreds of them.
+    ///
+    /// The operation is all-or-nothing: if any redirect fails, the files written by
+    /// this call are removed and no registry is changed. Redirectors whose target is
+    /// already registered (including earlier entries in the same batch) reuse the
+    /// existing file, exactly as [`Redirector::write_redirect()`] does. Only each
+    /// redirector's output directory is written; mirror directories are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `redirectors` - The redirectors to write
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<RedirectOutcome>)` - The outcome for each redirector, in input order
+    /// * `Err(RedirectorError)` - The first error encountered; nothing is written
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::Redirector;
+    /// use std::fs;
+    ///
+    /// let redirectors: Vec<Redirector> = ["docs/install", "docs/usage", "docs/install"]
+    ///     .iter()
+    ///     .map(|path| {
+    ///         let mut redirector = Redirector::new(path).unwrap();
+    ///         redirector.set_path("doc_test_write_all");
+    ///         redirector
+    ///     })
+    ///     .collect();
+    ///
+    /// let outcomes = Redirector::write_all(&redirectors).unwrap();
+    /// assert_eq!(outcomes.len(), 3);
+    /// assert!(outcomes[0].is_created());
+    /// assert!(!outcomes[2].is_created());
+    /// assert_eq!(outcomes[0].file_path(), outcomes[2].file_path());
+    ///
+    /// fs::remove_dir_all("doc_test_write_all").ok();
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(count = redirectors.len()))
+    )]
+    pub fn write_all(redirectors: &[Redirector]) -> Result<Vec<RedirectOutcome>, RedirectorError> {
+        let mut registries: HashMap<PathBuf, Registry> = HashMap::new();
+        let mut changed: Vec<PathBuf> = Vec::new();
+        let mut created_files: Vec<PathBuf> = Vec::new();
+
+        let result = redirectors
+            .iter()
+            .map(|redirector| {
+                let registry = match registries.entry(redirector.path.clone()) {
+                    Entry::Occupied(entry) => entry.into_mut(),
+                    Entry::Vacant(entry) => entry.insert(Registry::load(&redirector.path)?),
+                };
+
+                let outcome = redirector.write_into(registry, &mut FsSink)?;
+                if outcome.is_created() {
+                    created_files.push(outcome.file_path().to_path_buf());
+                    if !changed.contains(&redirector.path) {
+                        changed.push(redirector.path.clone());
+                    }
+                }
+                Ok(outcome)
+            })
+            .collect::<Result<Vec<RedirectOutcome>, RedirectorError>>()
+            .and_then(|outcomes| {
+                Registry::save_all(changed.iter().map(|dir| &registries[dir]))?;
+                Ok(outcomes)
+            });
+
+        if result.is_err() {
+            trace::warn!(
+                files = created_files.len(),
+                "batch write failed, removing written files"
             );
+            for file in &created_files {
+                fs::remove_file(file).ok();
+            }
+        }
 
-            serde_json::to_writer_pretty(
-                File::create(self.path.join(REDIRECT_REGISTRY))?,
-                &registry,
-            )?;
+        Redirector::notify_batch(redirectors, &result);
+        result
+    }
 
-            Ok(file_path.to_string_lossy().to_string())
+    /// Reports the result of a batch write to the hooks of every redirector.
+    fn notify_batch(
+        redirectors: &[Redirector],
+        result: &Result<Vec<RedirectOutcome>, RedirectorError>,
+    ) {
+        match result {
+            Ok(outcomes) => {
+                for (redirector, outcome) in redirectors.iter().zip(outcomes) {
+                    redirector.hooks.written(outcome);
+                }
+            }
+            Err(error) => {
+                for redirector in redirectors {
+                    let target = redirector.target.to_string();
+                    redirector.hooks.failed(&target, error);
+                }
+            }
         }
     }
-}
+
+    /// Writes the redirect file into the registry's directory through the sink and
+    /// records it in the registry.
+    ///
+    /// If the registry already has an entry for the target, the existing file path is
+    /// returned. Its file is regenerated if it has gone missing, but nothing else is
+    /// written. The registry itself is not persisted.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(RedirectOutcome)` - The redirect file and whether a new file was created
+    /// * `Err(RedirectorError::DirectoryCreationError)` - If the directory cannot be created
+    /// * `Err(RedirectorError::DuplicateShortName)` - If another target uses the short name
+    /// * `Err(RedirectorError::FileWriteError)` - If the file cannot be written
+    fn write_into<S: OutputSink + ?Sized>(
+        &self,
+        registry: &mut Registry,
+        sink: &mut S,
+    ) -> Result<RedirectOutcome, RedirectorError> {
+        let target = match self.plan_write(registry)? {
+            WritePlan::Additional(target, additional) => {
+                self.write_page(sink, &additional.file_path, &additional.page)?;
+                trace::debug!(target = %target, file = %additional.file_path.display(), "wrote additional short link");
+                return Ok(additional.record(registry, target));
+            }
+            WritePlan::Existing(target, entry) => {
+                // A link already exists for this path, return the existing file path
+                let existing_path = entry.path().to_path_buf();
+                if matches!(sink.open_file(&existing_path), Ok(None)) {
+                    // The page was deleted after it was registered, so the short link 404s
+                    trace::warn!(
+                        target = %target,
+                        file = %existing_path.display(),
+                        "regenerating missing redirect file"
+                    );
+                    self.write_page(sink, &existing_path, &self.registered_page(&entry))?;
+                } else {
+                    trace::debug!(
+                        target = %target,
+                        file = %existing_path.display(),
+                        "reusing existing redirect"
+                    );
+                }
+                return Ok(RedirectOutcome::new(
+                    existing_path,
+                    entry.short_file().to_string(),
+                    target,
+                    false,
+                ));
+            }
+            WritePlan::New(target) => target,
+        };
+        let redirector =
+            self.resolve_existing(registry, |path| matches!(sink.open_file(path), Ok(Some(_))))?;
+
+        let file_path = redirector.page_path(registry.dir());
+        redirector.write_page(sink, &file_path, &redirector.page())?;
+        redirector.verify_written(sink, &file_path)?;
+        #[cfg(feature = "social-preview")]
+        if let Some((image_path, image)) = redirector.preview_image(&file_path) {
+            sink.write_file(&image_path, image.as_bytes())
+                .map_err(|source| RedirectorError::FileWriteError {
+                    path: image_path,
+                    source,
+                })?;
+        }
+        if let Some((text_path, text)) = redirector.sidecar(&file_path) {
+            sink.write_file(&text_path, text.as_bytes())
+                .map_err(|source| RedirectorError::FileWriteError {
+                    path: text_path,
+                    source,
+                })?;
+        }
+
+        trace::debug!(target = %target, file = %file_path.display(), "wrote redirect file");
+        registry.record(
+            target.clone(),
+            file_path.to_string_lossy().to_string(),
+            &redirector,
+        );
+        if let Some((json_path, json)) = redirector.metadata(&file_path, registry, &target) {
+            sink.write_file(&json_path, json.as_bytes())
+                .map_err(|source| RedirectorError::FileWriteError {
+                    path: json_path,
+                    source,
+                })?;
+        }
+
+        Ok(RedirectOutcome::new(
+            file_path,
+            redirector.short_file(),
+            target,
+            true,
+        ))
+    }
+
+    /// Writes a page to a new file through the sink, creating its directory.
+    ///
+    /// An existing file is only replaced under [`ExistingFilePolicy::Overwrite`].
+    fn write_page<S: OutputSink + ?Sized>(
+        &self,
+        sink: &mut S,
+        file_path: &Path,
+        page: &str,
+    ) -> Result<(), RedirectorError> {
+        // create store directory if it doesn't exist
+        let dir = file_path.parent().unwrap_or(Path::new(""));
+        sink.create_dir_all(dir)
+            .map_err(|source| RedirectorError::DirectoryCreationError {
+                path: dir.to_path_buf(),
+                source,
+            })?;
+
+        let written = if self.overwrites() {
+            sink.write_file(file_path, page.as_bytes())
+        } else {
+            sink.create_file(file_path, page.as_bytes())
+        };
+        written.map_err(|source| RedirectorError::FileWriteError {
+            path: file_path.to_path_buf(),
+            source,
+        })
+    }
+
+    /// Decides what writing the redirect does from the registry alone, before any
+    /// file is looked at.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(WritePlan)` - The write to carry out
+    /// * `Err(RedirectorError::DuplicateTarget)` - If the target is registered under
+    ///   [`DuplicatePolicy::Error`]
+    /// * `Err(RedirectorError::DuplicateShortName)` - If another target uses the short
+    ///   name
+    /// * `Err(RedirectorError::ReservedName)` - If the short name is reserved by Windows
+    fn plan_write(&self, registry: &Registry) -> Result<WritePlan, RedirectorError> {
+        let target = self.target.to_string();
+        let Some((target, entry)) = self.find_registered(registry, &target) else {
+            self.check_short_name(registry)?;
+            return Ok(WritePlan::New(target));
+        };
+        Ok(match self.additional_page(registry, &target, &entry)? {
+            Some(additional) => WritePlan::Additional(target, additional),
+            None => WritePlan::Existing(target, entry),
+        })
+    }
+
+    /// Returns the registered target matching the redirector's target, with its
+    /// registry entry.
+    ///
+    /// Under [`TargetCase::Insensitive`], a target registered in another letter case
+    /// matches too.
+    fn find_registered(
+        &self,
+        registry: &Registry,
+        target: &str,
+    ) -> Option<(String, RegistryEntry)> {
+        let registered = match self.target_case {
+            TargetCase::Sensitive => target,
+            TargetCase::Insensitive => registry.get_ignore_case(target)?.0,
+        };
+        registry
+            .entry(registered)
+            .map(|entry| (registered.to_string(), entry.clone()))
+    }
+
+    /// Returns the short name: the short file name without its extension.
+    fn short_name(&self) -> String {
+        registry::short_name(&self.short_file_name.to_string_lossy()).to_string()
+    }
+
+    /// Returns the URL of the redirect page relative to the output directory.
+    fn short_file(&self) -> String {
+        self.layout.short_file(&self.short_name())
+    }
+
+    /// Returns the path the redirect page is written to within an output directory.
+    fn page_path(&self, dir: &Path) -> PathBuf {
+        self.layout.file_path(dir, &self.short_name())
+    }
+
+    /// Renders the page written for the redirect: the redirect page, or the expired
+    /// page once the redirect has expired, followed by the provenance comment if one
+    /// is written.
+    fn page(&self) -> String {
+        let page = self.current_page();
+        if !self.provenance {
+            return page;
+        }
+        let provenance = Provenance::new(self.target.to_string(), self.short_name());
+        page + &provenance.comment()
+    }
+
+    /// Renders the redirect page, or the expired page once the redirect has expired.
+    fn current_page(&self) -> String {
+        if self
+            .expires_at
+            .is_none_or(|expires_at| expires_at > Timestamp::now())
+        {
+            return self.to_string();
+        }
+        if let Some(page) = &self.expired_page {
+            return page.clone();
+        }
+        #[cfg(feature = "fluent")]
+        if let Some(locale) = &self.locale {
+            return locale.expired_page();
+        }
+        DEFAULT_EXPIRED_PAGE.to_string()
+    }
+
+    /// Renders the page written again for a registered redirect whose page has gone
+    /// missing: the retired page once the redirect has been retired.
+    ///
+    /// The page carries the registered short name, not the one generated for this
+    /// redirector, so beacons and provenance comments keep reporting the short link
+    /// that is actually served.
+    fn registered_page(&self, entry: &RegistryEntry) -> String {
+        if !entry.is_retired() {
+            let mut registered = self.clone();
+            registered.short_file_name = OsString::from(format!("{}.html", entry.short_name()));
+            return registered.page();
+        }
+        #[cfg(feature = "fluent")]
+        if let Some(locale) = &self.locale {
+            return locale.retired_page();
+        }
+        DEFAULT_RETIRED_PAGE.to_string()
+    }
+
+    /// Returns the language tag written in the `lang` attribute of the pages.
+    fn lang(&self) -> &str {
+        #[cfg(feature = "fluent")]
+        if let Some(locale) = &self.locale {
+            return locale.tag();
+        }
+        "en-US"
+    }
+
+    /// Returns the title of the page the redirect leads to, if it was fetched.
+    fn destination_title(&self) -> Option<&str> {
+        #[cfg(feature = "fetch-title")]
+        if let Some(title) = &self.title {
+            return Some(title);
+        }
+        None
+    }
+
+    /// Returns a message of the page text in the redirect's language.
+    ///
+    /// `english` is the message in English, used unless a translation was chosen.
+    #[cfg_attr(not(feature = "fluent"), allow(unused_variables))]
+    fn text(&self, id: &str, english: &str, args: &[(&str, &str)]) -> String {
+        #[cfg(feature = "fluent")]
+        if let Some(text) = self
+            .locale
+            .as_ref()
+            .and_then(|locale| locale.message(id, args))
+        {
+            return text;
+        }
+        english_text(english, args)
+    }
+
+    /// Fails if the redirector's short name is registered for another target.
+    fn check_short_name(&self, registry: &Registry) -> Result<(), RedirectorError> {
+        let short_file_name = self.short_file_name.to_string_lossy();
+        let short_name = registry::short_name(&short_file_name);
+        if is_reserved_name(short_name) {
+            return Err(RedirectorError::ReservedName(short_name.to_string()));
+        }
+        if registry.contains_short_name(short_name) {
+            trace::warn!(short_name, "short name already in use");
+            return Err(RedirectorError::DuplicateShortName(short_name.to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// What writing a redirect does, as decided by [`Redirector::plan_write()`].
+enum WritePlan {
+    /// Write a stub page giving a registered target another short name.
+    Additional(String, AdditionalPage),
+    /// Reuse the page registered for the target, writing it again if it has gone
+    /// missing.
+    Existing(String, RegistryEntry),
+    /// Write a new page for the target.
+    New(String),
+}
+
+impl From<UrlPath> for Redirector {
+    /// Creates a `Redirector` for a path that has already been validated.
+    ///
+    /// The redirector is initialized with a fresh short file name and the default
+    /// output directory of "s", exactly as [`Redirector::new()`] would.
+    fn from(path: UrlPath) -> Self {
+        Redirector::with_target(Target::Path(path))
+    }
+}
 
 impl fmt::Display for Redirector {
     /// Generates the complete HTML redirect page content.
@@ -361,41 +1708,138 @@ impl fmt::Display for Redirector {
     /// - JavaScript redirect (faster, works when JS is enabled)
     /// - Fallback link (for manual navigation if automatic redirect fails)
     ///
+    /// When a delay is configured, both the meta refresh and the JavaScript redirect
+    /// wait for that many seconds. When a beacon endpoint is configured, the script
+    /// posts the short name to it before redirecting.
+    ///
+    /// Split redirects pick one of their weighted targets at random in the script, and
+    /// only fall back to the meta refresh, which always leads to the first target,
+    /// without JavaScript.
+    ///
+    /// Gated redirects render a page that waits for the visitor to confirm instead,
+    /// ignoring the delay. Otherwise, AMP redirects render a valid AMP HTML page,
+    /// which links to the target without redirecting.
+    ///
     /// The HTML follows web standards and includes proper accessibility features.
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let target = self.long_path.to_string();
+        let target = self.target.resolve(self.base_url.as_ref());
+        let href = escape(&target);
+        let beacon = match &self.beacon {
+            Some(endpoint) => {
+                let short_file_name = self.short_file_name.to_string_lossy();
+                beacon::script(endpoint, registry::short_name(&short_file_name))
+            }
+            None => String::new(),
+        };
+        let redirect = if self.variants.is_empty() {
+            format!("window.location.href = {};", js_string(&target))
+        } else {
+            let mut targets = vec![(target.clone(), self.weight)];
+            targets.extend(
+                self.variants
+                    .iter()
+                    .map(|(variant, weight)| (variant.resolve(self.base_url.as_ref()), *weight)),
+            );
+            split::script(&targets)
+        };
+        let text = |id: &str, english: &str, args: &[(&str, &str)]| self.text(id, english, args);
+        if let Some(page) = self
+            .gate
+            .render(self.lang(), &text, &href, &redirect, &beacon)
+        {
+            return f.write_str(&page);
+        }
+
+        let title = match self.destination_title() {
+            Some(title) => escape(title),
+            None => text("redirect-title", "Page Redirection", &[]),
+        };
+        #[cfg_attr(
+            not(any(feature = "fetch-title", feature = "social-preview")),
+            allow(unused_mut)
+        )]
+        let mut meta = String::new();
+        #[cfg(feature = "fetch-title")]
+        meta.push_str(&self.title_meta());
+        #[cfg(feature = "social-preview")]
+        meta.push_str(&self.preview_meta());
+        if self.amp {
+            return f.write_str(&amp::page(self.lang(), &text, &href, &title, &meta));
+        }
+
+        let delay = self.delay;
+        let mut script = if delay == 0 {
+            redirect
+        } else {
+            format!(
+                r#"setTimeout(function () {{
+                {redirect}
+            }}, {});"#,
+                u64::from(delay) * 1000
+            )
+        };
+        if !beacon.is_empty() {
+            script = format!("{beacon}\n            {script}");
+        }
+        // The meta refresh would always pick the first target, so split pages only
+        // use it without JavaScript
+        let mut refresh = format!(r#"<meta http-equiv="refresh" content="{delay}; url={href}">"#);
+        if !self.variants.is_empty() {
+            refresh = format!("<noscript>{refresh}</noscript>");
+        }
+        let link = format!(
+            "<a href='{href}'>{}</a>",
+            text("redirect-link", "link to page", &[])
+        );
+        let fallback = text(
+            "redirect-fallback",
+            "If you are not redirected automatically, follow this { $link }.",
+            &[("link", &link)],
+        );
         write!(
             f,
             r#"
     <!DOCTYPE HTML>
-    <html lang="en-US">
+    <html lang="{}">
 
     <head>
         <meta charset="UTF-8">
-        <meta http-equiv="refresh" content="0; url={target}">
+        {refresh}
         <script type="text/javascript">
-            window.location.href = "{target}";
+            {script}
         </script>
-        <title>Page Redirection</title>
+        <title>{title}</title>{meta}
     </head>
 
     <body>
         <!-- Note: don't tell people to `click` the link, just tell them that it is a link. -->
-        If you are not redirected automatically, follow this <a href='{target}'>link to page</a>.
+        {fallback}
     </body>
 
     </html>
-    "#
+    "#,
+            self.lang()
         )
     }
 }
 
+/// Fills the `{ $name }` placeables of an English message from `args`, as Fluent
+/// fills those of a translation.
+pub(crate) fn english_text(english: &str, args: &[(&str, &str)]) -> String {
+    args.iter()
+        .fold(english.to_string(), |text, (name, value)| {
+            text.replace(&format!("{{ ${name} }}"), value)
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::Utc;
+    use std::collections::HashSet;
     use std::fs;
     use std::thread;
-    use std::time::Duration;
+    use std::time::SystemTime;
 
     #[test]
     fn test_new_redirector() {
@@ -403,17 +1847,17 @@ mod tests {
         let redirector = Redirector::new(long_link).unwrap();
 
         assert_eq!(
-            redirector.long_path,
-            UrlPath::new(long_link.to_string()).unwrap()
+            redirector.target,
+            Target::Path(UrlPath::new(long_link.to_string()).unwrap())
         );
         assert!(!redirector.short_file_name.is_empty());
         assert_eq!(redirector.path, PathBuf::from("s"));
+        assert_eq!(redirector.base_url, None);
     }
 
     #[test]
     fn test_generate_short_link_unique() {
         let redirector1 = Redirector::new("/some/path").unwrap();
-        thread::sleep(Duration::from_millis(1));
         let redirector2 = Redirector::new("/some/path").unwrap();
 
         assert_ne!(redirector1.short_file_name, redirector2.short_file_name);
@@ -441,6 +1885,95 @@ mod tests {
         assert!(output.contains("window.location.href"));
     }
 
+    #[test]
+    fn test_display_escapes_quotes_in_target() {
+        let target = "a' onmouseover='alert(1)";
+        let pages = [
+            Redirector::new(target).unwrap().to_string(),
+            Redirector::builder()
+                .target(target)
+                .amp()
+                .build()
+                .unwrap()
+                .to_string(),
+            Redirector::builder()
+                .target(target)
+                .confirm()
+                .build()
+                .unwrap()
+                .to_string(),
+        ];
+
+        for page in pages {
+            assert!(!page.contains("href='/a' "), "{page}");
+            assert!(
+                page.contains("href='/a&#39; onmouseover=&#39;alert(1)/'"),
+                "{page}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_set_base_url() {
+        let mut redirector = Redirector::new("some/path").unwrap();
+
+        redirector.set_base_url("https://example.com/").unwrap();
+        let output = format!("{redirector}");
+
+        assert!(output.contains("url=https://example.com/some/path/\""));
+        assert!(output.contains("window.location.href = \"https://example.com/some/path/\""));
+        assert!(output.contains("href='https://example.com/some/path/'"));
+    }
+
+    #[test]
+    fn test_set_base_url_with_path_prefix() {
+        let mut redirector = Redirector::new("some/path").unwrap();
+
+        redirector.set_base_url("https://example.com/blog").unwrap();
+        assert!(format!("{redirector}").contains("url=https://example.com/blog/some/path/\""));
+    }
+
+    #[test]
+    fn test_set_base_url_invalid() {
+        let mut redirector = Redirector::new("some/path").unwrap();
+
+        let result = redirector.set_base_url("ftp://example.com");
+        assert!(matches!(result, Err(RedirectorError::InvalidTarget(_))));
+        assert_eq!(redirector.base_url, None);
+    }
+
+    #[test]
+    fn test_set_base_url_ignored_for_external_target() {
+        let allowlist = HostAllowlist::new(["docs.example.com"]);
+        let mut redirector =
+            Redirector::new_external("https://docs.example.com/guide", &allowlist).unwrap();
+
+        redirector.set_base_url("https://example.com").unwrap();
+        assert!(format!("{redirector}").contains("url=https://docs.example.com/guide\""));
+    }
+
+    #[test]
+    fn test_write_redirect_registry_key_ignores_base_url() {
+        let test_dir = format!(
+            "test_write_redirect_registry_key_ignores_base_url_{}",
+            Utc::now().timestamp_nanos_opt().unwrap_or(0)
+        );
+        let mut redirector = Redirector::new("some/path").unwrap();
+        redirector.set_path(&test_dir);
+        redirector.set_base_url("https://example.com").unwrap();
+
+        let outcome = redirector.write_redirect().unwrap();
+
+        let content = fs::read_to_string(outcome.file_path()).unwrap();
+        assert!(content.contains("url=https://example.com/some/path/"));
+
+        let registry = fs::read_to_string(PathBuf::from(&test_dir).join("registry.json")).unwrap();
+        assert!(registry.contains("\"/some/path/\""));
+
+        // Clean up
+        fs::remove_dir_all(&test_dir).unwrap();
+    }
+
     #[test]
     fn test_display_with_complex_path() {
         let redirector = Redirector::new("api/v2/users").unwrap();
@@ -453,6 +1986,23 @@ mod tests {
         assert!(output.contains("window.location.href"));
     }
 
+    #[test]
+    fn test_render_to() {
+        let redirector = Redirector::new("api/v2/users").unwrap();
+        let mut page = Vec::new();
+        redirector.render_to(&mut page).unwrap();
+        assert_eq!(String::from_utf8(page).unwrap(), redirector.to_string());
+
+        let mut expired = Redirector::new("api/v2/users").unwrap();
+        expired.expires_at = Some(Timestamp::from(SystemTime::UNIX_EPOCH));
+        let mut page = Vec::new();
+        expired.render_to(&mut page).unwrap();
+        assert_eq!(page, DEFAULT_EXPIRED_PAGE.as_bytes());
+
+        // Nothing is written to disk
+        assert!(!Path::new("s").join(redirector.short_file()).exists());
+    }
+
     #[test]
     fn test_write_redirect_with_valid_path() {
         let test_dir = format!(
@@ -483,11 +2033,12 @@ mod tests {
         let result = redirector.write_redirect();
         assert!(result.is_ok());
 
-        let file_path = result.unwrap();
+        let outcome = result.unwrap();
 
-        assert!(Path::new(&file_path).exists());
+        assert!(outcome.is_created());
+        assert!(outcome.file_path().exists());
 
-        let content = fs::read_to_string(&file_path).unwrap();
+        let content = fs::read_to_string(outcome.file_path()).unwrap();
         assert!(content.contains("<!DOCTYPE HTML>"));
         assert!(content.contains("meta http-equiv=\"refresh\""));
         assert!(content.contains("window.location.href"));
@@ -515,8 +2066,8 @@ mod tests {
 
         assert!(Path::new(&subdir_path).exists());
 
-        let file_path = result.unwrap();
-        assert!(Path::new(&file_path).exists());
+        let outcome = result.unwrap();
+        assert!(outcome.file_path().exists());
 
         // Clean up
         fs::remove_dir_all(&test_dir).unwrap();
@@ -530,7 +2081,7 @@ mod tests {
         let cloned = redirector.clone();
 
         assert_eq!(redirector, cloned);
-        assert_eq!(redirector.long_path, cloned.long_path);
+        assert_eq!(redirector.target, cloned.target);
         assert_eq!(redirector.short_file_name, cloned.short_file_name);
         assert_eq!(redirector.path, cloned.path);
     }
@@ -539,7 +2090,7 @@ mod tests {
     fn test_redirector_default() {
         let redirector = Redirector::default();
 
-        assert_eq!(redirector.long_path, UrlPath::default());
+        assert_eq!(redirector.target, Target::default());
         assert_eq!(redirector.path, PathBuf::new());
         assert!(redirector.short_file_name.is_empty());
     }
@@ -556,11 +2107,13 @@ mod tests {
         let result = redirector.write_redirect();
         assert!(result.is_ok());
 
-        let returned_path = result.unwrap();
+        let outcome = result.unwrap();
         let expected_path = redirector.path.join(&redirector.short_file_name);
 
-        assert_eq!(returned_path, expected_path.to_string_lossy());
-        assert!(Path::new(&returned_path).exists());
+        assert_eq!(outcome.file_path(), expected_path);
+        assert_eq!(outcome.short_name(), expected_path.file_stem().unwrap());
+        assert_eq!(outcome.target(), "/some/path/");
+        assert!(outcome.file_path().exists());
 
         // Clean up
         fs::remove_dir_all(&test_dir).unwrap();
@@ -581,15 +2134,18 @@ mod tests {
         // First call should create a new file
         let result1 = redirector1.write_redirect();
         assert!(result1.is_ok());
-        let path1 = result1.unwrap();
+        let outcome1 = result1.unwrap();
+        assert!(outcome1.is_created());
 
         // Second call with same path should return the existing file path
         let result2 = redirector2.write_redirect();
         assert!(result2.is_ok());
-        let path2 = result2.unwrap();
+        let outcome2 = result2.unwrap();
+        assert!(!outcome2.is_created());
 
         // Should return the same path
-        assert_eq!(path1, path2);
+        assert_eq!(outcome1.file_path(), outcome2.file_path());
+        assert_eq!(outcome1.short_name(), outcome2.short_name());
 
         // Verify registry file exists
         let registry_path = PathBuf::from(&test_dir).join("registry.json");
@@ -613,21 +2169,685 @@ mod tests {
 
         let result1 = redirector1.write_redirect();
         assert!(result1.is_ok());
-        let path1 = result1.unwrap();
+        let outcome1 = result1.unwrap();
 
         let result2 = redirector2.write_redirect();
         assert!(result2.is_ok());
-        let path2 = result2.unwrap();
+        let outcome2 = result2.unwrap();
 
         // Should create different files for different paths
-        assert_ne!(path1, path2);
-        assert!(Path::new(&path1).exists());
-        assert!(Path::new(&path2).exists());
+        assert_ne!(outcome1.file_path(), outcome2.file_path());
+        assert!(outcome1.file_path().exists());
+        assert!(outcome2.file_path().exists());
+
+        // Clean up
+        fs::remove_dir_all(&test_dir).unwrap();
+    }
+
+    #[test]
+    fn test_write_redirect_duplicate_short_name() {
+        let test_dir = format!(
+            "test_write_redirect_duplicate_short_name_{}",
+            Utc::now().timestamp_nanos_opt().unwrap_or(0)
+        );
+        let redirector = |target: &str| {
+            Redirector::builder()
+                .target(target)
+                .out_dir(&test_dir)
+                .alias("promo")
+                .build()
+                .unwrap()
+        };
+        let outcome = redirector("summer/sale").write_redirect().unwrap();
+
+        match redirector("winter/sale").write_redirect() {
+            Err(RedirectorError::DuplicateShortName(name)) => assert_eq!(name, "promo"),
+            other => panic!("expected DuplicateShortName, got {other:?}"),
+        }
+        // The existing page is left alone
+        let page = fs::read_to_string(outcome.file_path()).unwrap();
+        assert!(page.contains("/summer/sale/"));
+        assert_eq!(Registry::load(&test_dir).unwrap().len(), 1);
+
+        // Clean up
+        fs::remove_dir_all(&test_dir).unwrap();
+    }
+
+    #[test]
+    fn test_write_redirect_keeps_unrelated_file() {
+        let test_dir = format!(
+            "test_write_redirect_keeps_unrelated_file_{}",
+            Utc::now().timestamp_nanos_opt().unwrap_or(0)
+        );
+        fs::create_dir_all(&test_dir).unwrap();
+        let about = PathBuf::from(&test_dir).join("about.html");
+        fs::write(&about, "hand-written page").unwrap();
+
+        let result = Redirector::builder()
+            .target("docs/about")
+            .out_dir(&test_dir)
+            .alias("about")
+            .build()
+            .unwrap()
+            .write_redirect();
+
+        match result {
+            Err(RedirectorError::FileWriteError { path, source }) => {
+                assert_eq!(path, about);
+                assert_eq!(source.kind(), io::ErrorKind::AlreadyExists);
+            }
+            other => panic!("expected FileWriteError, got {other:?}"),
+        }
+        assert_eq!(fs::read_to_string(&about).unwrap(), "hand-written page");
+        assert!(fs::read_dir(&test_dir).unwrap().all(|entry| !entry
+            .unwrap()
+            .path()
+            .to_string_lossy()
+            .ends_with(".tmp")));
+        assert!(Registry::load(&test_dir).unwrap().is_empty());
+
+        // Clean up
+        fs::remove_dir_all(&test_dir).unwrap();
+    }
+
+    #[test]
+    fn test_write_redirect_into_loaded_registry() {
+        let test_dir = format!(
+            "test_write_redirect_into_loaded_registry_{}",
+            Utc::now().timestamp_nanos_opt().unwrap_or(0)
+        );
+        let mut registry = Registry::load(&test_dir).unwrap();
+
+        // The page goes to the registry's directory, not the redirector's own
+        let outcome = Redirector::builder()
+            .target("a/one")
+            .out_dir("elsewhere")
+            .alias("one")
+            .build()
+            .unwrap()
+            .write_redirect_into(&mut registry)
+            .unwrap();
+        assert!(outcome.is_created());
+        assert_eq!(
+            outcome.file_path(),
+            PathBuf::from(&test_dir).join("one.html")
+        );
+        assert!(outcome.file_path().exists());
+        assert!(!Path::new("elsewhere").exists());
+
+        let again = Redirector::new("a/one")
+            .unwrap()
+            .write_redirect_into(&mut registry)
+            .unwrap();
+        assert!(!again.is_created());
+        assert_eq!(again.file_path(), outcome.file_path());
+
+        // Nothing is persisted until the registry is saved
+        assert!(Registry::load(&test_dir).unwrap().is_empty());
+        registry.save().unwrap();
+        assert_eq!(Registry::load(&test_dir).unwrap(), registry);
+
+        // Clean up
+        fs::remove_dir_all(&test_dir).unwrap();
+    }
+
+    #[test]
+    fn test_write_all_single_registry_write() {
+        let test_dir = format!(
+            "test_write_all_single_registry_write_{}",
+            Utc::now().timestamp_nanos_opt().unwrap_or(0)
+        );
+        let redirectors: Vec<Redirector> = ["a/one", "a/two", "a/three"]
+            .iter()
+            .map(|path| {
+                Redirector::builder()
+                    .target(path)
+                    .out_dir(&test_dir)
+                    .alias(path.replace('/', "-"))
+                    .build()
+                    .unwrap()
+            })
+            .collect();
+
+        let outcomes = Redirector::write_all(&redirectors).unwrap();
+
+        assert_eq!(outcomes.len(), 3);
+        for outcome in &outcomes {
+            assert!(outcome.is_created());
+            assert!(outcome.file_path().exists());
+        }
+        assert_eq!(outcomes[1].short_name(), "a-two");
+
+        let registry = Registry::load(&test_dir).unwrap();
+        assert_eq!(registry.len(), 3);
+        assert_eq!(registry.get("/a/two/"), outcomes[1].file_path().to_str());
+
+        // Clean up
+        fs::remove_dir_all(&test_dir).unwrap();
+    }
+
+    #[test]
+    fn test_write_all_reuses_existing_and_batch_entries() {
+        let test_dir = format!(
+            "test_write_all_reuses_existing_and_batch_entries_{}",
+            Utc::now().timestamp_nanos_opt().unwrap_or(0)
+        );
+        let mut existing = Redirector::new("some/path").unwrap();
+        existing.set_path(&test_dir);
+        let existing_outcome = existing.write_redirect().unwrap();
+
+        let redirectors: Vec<Redirector> = ["some/path", "other/path", "other/path"]
+            .iter()
+            .map(|path| {
+                let mut redirector = Redirector::new(path).unwrap();
+                redirector.set_path(&test_dir);
+                redirector
+            })
+            .collect();
+
+        let outcomes = Redirector::write_all(&redirectors).unwrap();
+
+        assert_eq!(outcomes[0].file_path(), existing_outcome.file_path());
+        assert!(!outcomes[0].is_created());
+        assert!(outcomes[1].is_created());
+        assert!(!outcomes[2].is_created());
+        assert_eq!(outcomes[1].file_path(), outcomes[2].file_path());
+        assert_eq!(Registry::load(&test_dir).unwrap().len(), 2);
+
+        // Clean up
+        fs::remove_dir_all(&test_dir).unwrap();
+    }
+
+    #[test]
+    fn test_write_all_multiple_directories() {
+        let test_dir = format!(
+            "test_write_all_multiple_directories_{}",
+            Utc::now().timestamp_nanos_opt().unwrap_or(0)
+        );
+        let dir1 = format!("{test_dir}/one");
+        let dir2 = format!("{test_dir}/two");
+        let mut redirector1 = Redirector::new("some/path").unwrap();
+        redirector1.set_path(&dir1);
+        let mut redirector2 = Redirector::new("some/path").unwrap();
+        redirector2.set_path(&dir2);
+
+        let outcomes = Redirector::write_all(&[redirector1, redirector2]).unwrap();
+
+        assert_ne!(outcomes[0].file_path(), outcomes[1].file_path());
+        assert_eq!(Registry::load(&dir1).unwrap().len(), 1);
+        assert_eq!(Registry::load(&dir2).unwrap().len(), 1);
+
+        // Clean up
+        fs::remove_dir_all(&test_dir).unwrap();
+    }
+
+    #[test]
+    fn test_write_all_rolls_back_on_error() {
+        let test_dir = format!(
+            "test_write_all_rolls_back_on_error_{}",
+            Utc::now().timestamp_nanos_opt().unwrap_or(0)
+        );
+        let bad_dir = format!("{test_dir}/bad");
+        fs::create_dir_all(&bad_dir).unwrap();
+        fs::write(PathBuf::from(&bad_dir).join("registry.json"), "not json").unwrap();
+
+        let good = Redirector::builder()
+            .target("some/path")
+            .out_dir(&test_dir)
+            .alias("good")
+            .build()
+            .unwrap();
+        let bad = Redirector::builder()
+            .target("other/path")
+            .out_dir(&bad_dir)
+            .build()
+            .unwrap();
+
+        let result = Redirector::write_all(&[good, bad]);
+
+        assert!(matches!(
+            result,
+            Err(RedirectorError::FailedToReadRegistry { .. })
+        ));
+        assert!(!PathBuf::from(&test_dir).join("good.html").exists());
+        assert!(!PathBuf::from(&test_dir).join("registry.json").exists());
+
+        // Clean up
+        fs::remove_dir_all(&test_dir).unwrap();
+    }
+
+    /// Records the hooks called by the write methods.
+    #[derive(Default)]
+    struct HookLog(std::sync::Mutex<Vec<String>>);
+
+    impl RedirectHooks for HookLog {
+        fn on_created(&self, outcome: &RedirectOutcome) {
+            let event = format!("created {}", outcome.short_name());
+            self.0.lock().unwrap().push(event);
+        }
+
+        fn on_reused(&self, outcome: &RedirectOutcome) {
+            let event = format!("reused {}", outcome.short_name());
+            self.0.lock().unwrap().push(event);
+        }
+
+        fn on_error(&self, target: &str, _error: &RedirectorError) {
+            self.0.lock().unwrap().push(format!("error {target}"));
+        }
+    }
+
+    #[test]
+    fn test_write_redirect_calls_hooks() {
+        let log = std::sync::Arc::new(HookLog::default());
+        let redirector = Redirector::builder()
+            .target("some/path")
+            .alias("hooked")
+            .hooks(std::sync::Arc::clone(&log))
+            .build()
+            .unwrap();
+        let mut sink = MemorySink::new();
+
+        redirector.write_redirect_to(&mut sink).unwrap();
+        redirector.write_redirect_to(&mut sink).unwrap();
+
+        sink.insert("s/registry.json", "not json");
+        assert!(redirector.write_redirect_to(&mut sink).is_err());
+
+        assert_eq!(
+            *log.0.lock().unwrap(),
+            ["created hooked", "reused hooked", "error /some/path/"]
+        );
+    }
+
+    #[test]
+    fn test_write_all_calls_hooks() {
+        let test_dir = format!(
+            "test_write_all_calls_hooks_{}",
+            Utc::now().timestamp_nanos_opt().unwrap_or(0)
+        );
+        let log = std::sync::Arc::new(HookLog::default());
+        let redirector = |target: &str, alias: &str, dir: &str| {
+            Redirector::builder()
+                .target(target)
+                .out_dir(dir)
+                .alias(alias)
+                .hooks(std::sync::Arc::clone(&log))
+                .build()
+                .unwrap()
+        };
+
+        let first = redirector("a", "a", &test_dir);
+        let second = redirector("a", "b", &test_dir);
+        Redirector::write_all(&[first, second]).unwrap();
+
+        let bad_dir = format!("{test_dir}/bad");
+        fs::create_dir_all(&bad_dir).unwrap();
+        fs::write(PathBuf::from(&bad_dir).join("registry.json"), "not json").unwrap();
+        let good = redirector("c", "c", &test_dir);
+        let bad = redirector("d", "d", &bad_dir);
+        assert!(Redirector::write_all(&[good, bad]).is_err());
+
+        assert_eq!(
+            *log.0.lock().unwrap(),
+            ["created a", "reused a", "error /c/", "error /d/"]
+        );
+
+        fs::remove_dir_all(&test_dir).unwrap();
+    }
+
+    #[test]
+    fn test_write_redirect_directory_error_includes_path() {
+        let test_dir = format!(
+            "test_write_redirect_directory_error_includes_path_{}",
+            Utc::now().timestamp_nanos_opt().unwrap_or(0)
+        );
+        fs::create_dir_all(&test_dir).unwrap();
+        let blocker = PathBuf::from(&test_dir).join("blocker");
+        fs::write(&blocker, "not a directory").unwrap();
+
+        let mut redirector = Redirector::new("some/path").unwrap();
+        redirector.set_path(blocker.join("out"));
+
+        match redirector.write_redirect() {
+            Err(error @ RedirectorError::DirectoryCreationError { .. }) => {
+                assert!(error.to_string().contains("blocker"));
+                assert!(std::error::Error::source(&error).is_some());
+            }
+            other => panic!("expected DirectoryCreationError, got {other:?}"),
+        }
+
+        // Clean up
+        fs::remove_dir_all(&test_dir).unwrap();
+    }
+
+    #[test]
+    fn test_write_redirect_registry_error_includes_path() {
+        let test_dir = format!(
+            "test_write_redirect_registry_error_includes_path_{}",
+            Utc::now().timestamp_nanos_opt().unwrap_or(0)
+        );
+        fs::create_dir_all(&test_dir).unwrap();
+        let registry_path = PathBuf::from(&test_dir).join("registry.json");
+        fs::write(&registry_path, "not json").unwrap();
+
+        let mut redirector = Redirector::new("some/path").unwrap();
+        redirector.set_path(&test_dir);
+
+        match redirector.write_redirect() {
+            Err(RedirectorError::FailedToReadRegistry { path, .. }) => {
+                assert_eq!(path, registry_path);
+            }
+            other => panic!("expected FailedToReadRegistry, got {other:?}"),
+        }
+
+        // Clean up
+        fs::remove_dir_all(&test_dir).unwrap();
+    }
+
+    #[test]
+    fn test_write_all_empty() {
+        let outcomes = Redirector::write_all(&[]).unwrap();
+        assert!(outcomes.is_empty());
+    }
+
+    #[test]
+    fn test_write_redirect_to_memory_sink() {
+        let redirector = Redirector::builder()
+            .target("some/path")
+            .out_dir("test_write_redirect_to_memory_sink")
+            .alias("promo")
+            .build()
+            .unwrap();
+        let mut sink = MemorySink::new();
+
+        let outcome = redirector.write_redirect_to(&mut sink).unwrap();
+
+        assert!(outcome.is_created());
+        assert_eq!(
+            sink.get_str(outcome.file_path()),
+            Some(redirector.to_string().as_str())
+        );
+        let registry =
+            Registry::load_from(&mut sink, "test_write_redirect_to_memory_sink").unwrap();
+        assert_eq!(registry.get("/some/path/"), outcome.file_path().to_str());
+        assert!(!Path::new("test_write_redirect_to_memory_sink").exists());
+    }
+
+    #[test]
+    fn test_write_redirect_to_memory_sink_reuses_seeded_registry() {
+        let mut sink = MemorySink::new();
+        sink.insert(
+            "mem/registry.json",
+            r#"{"/some/path/": "mem/existing.html"}"#,
+        );
+        sink.insert("mem/existing.html", "existing page");
+        let mut redirector = Redirector::new("some/path").unwrap();
+        redirector.set_path("mem");
+
+        let outcome = redirector.write_redirect_to(&mut sink).unwrap();
+
+        assert!(!outcome.is_created());
+        assert_eq!(outcome.short_name(), "existing");
+        assert_eq!(sink.len(), 2);
+        assert_eq!(sink.get_str("mem/existing.html"), Some("existing page"));
+    }
+
+    #[test]
+    fn test_write_redirect_regenerates_missing_file() {
+        let test_dir = format!(
+            "test_write_redirect_regenerates_missing_file_{}",
+            Utc::now().timestamp_nanos_opt().unwrap_or(0)
+        );
+        let mut redirector = Redirector::new("some/path").unwrap();
+        redirector.set_path(&test_dir);
+        let outcome = redirector.write_redirect().unwrap();
+        fs::remove_file(outcome.file_path()).unwrap();
+
+        // A fresh redirector for the same target reuses the registered short name
+        let mut again = Redirector::new("some/path").unwrap();
+        again.set_path(&test_dir);
+        let reused = again.write_redirect().unwrap();
+
+        assert!(!reused.is_created());
+        assert_eq!(reused.file_path(), outcome.file_path());
+        let page = fs::read_to_string(reused.file_path()).unwrap();
+        assert!(page.contains("url=/some/path/"));
+
+        // Clean up
+        fs::remove_dir_all(&test_dir).unwrap();
+    }
+
+    #[test]
+    fn test_pages_found_from_registry_dir_across_working_directories() {
+        let test_dir = format!(
+            "test_pages_found_from_registry_dir_{}",
+            Utc::now().timestamp_nanos_opt().unwrap_or(0)
+        );
+        let out_dir = std::env::current_dir().unwrap().join(&test_dir).join("s");
+        let build = |target: &str| {
+            Redirector::builder()
+                .target(target)
+                .out_dir(&out_dir)
+                .alias("promo")
+                .build()
+                .unwrap()
+        };
+        let page_path = build("summer/sale")
+            .write_redirect()
+            .unwrap()
+            .into_file_path();
+
+        // Record the page as a run from another working directory would have,
+        // relative to that directory
+        let elsewhere = format!("{test_dir}_elsewhere");
+        let registry_path = out_dir.join("registry.json");
+        let json = fs::read_to_string(&registry_path)
+            .unwrap()
+            .replace(&out_dir.to_string_lossy().replace('\\', "/"), &elsewhere);
+        assert!(json.contains(&format!("{elsewhere}/promo.html")));
+        fs::write(&registry_path, json).unwrap();
+
+        // A missing page is regenerated in the output directory
+        fs::remove_file(&page_path).unwrap();
+        let outcome = build("summer/sale").write_redirect().unwrap();
+        assert_eq!(outcome.file_path(), page_path);
+        assert!(page_path.exists());
+
+        let mut registry = Registry::load(&out_dir).unwrap();
+        registry.update_target("promo", "winter/sale").unwrap();
+        assert!(fs::read_to_string(&page_path)
+            .unwrap()
+            .contains("url=/winter/sale/"));
+
+        registry.remove("promo").unwrap();
+        assert!(!page_path.exists());
+        assert!(!Path::new(&elsewhere).exists());
+
+        // Clean up
+        fs::remove_dir_all(&test_dir).unwrap();
+    }
+
+    #[test]
+    fn test_regenerated_page_carries_registered_short_name() {
+        let test_dir = format!(
+            "test_regenerated_page_carries_registered_short_name_{}",
+            Utc::now().timestamp_nanos_opt().unwrap_or(0)
+        );
+        let build = || {
+            Redirector::builder()
+                .target("some/path")
+                .out_dir(&test_dir)
+                .beacon("https://example.com/hit")
+                .provenance()
+                .build()
+                .unwrap()
+        };
+        let outcome = build().write_redirect().unwrap();
+        let short_name = outcome.short_file().trim_end_matches(".html").to_string();
+        fs::remove_file(outcome.file_path()).unwrap();
+
+        // A fresh redirector generates a different short name of its own
+        let again = build();
+        assert_ne!(
+            again.short_file_name(),
+            OsString::from(outcome.short_file())
+        );
+        again.write_redirect().unwrap();
+
+        let page = fs::read_to_string(outcome.file_path()).unwrap();
+        assert!(page.contains(&format!("\"https://example.com/hit\", \"{short_name}\")")));
+        assert!(page.contains(&format!("\"short_name\":\"{short_name}\"")));
+
+        // Clean up
+        fs::remove_dir_all(&test_dir).unwrap();
+    }
+
+    #[test]
+    fn test_write_redirect_directory_layout() {
+        let test_dir = format!(
+            "test_write_redirect_directory_layout_{}",
+            Utc::now().timestamp_nanos_opt().unwrap_or(0)
+        );
+        let redirector = Redirector::builder()
+            .target("docs/install")
+            .out_dir(&test_dir)
+            .alias("install")
+            .layout(OutputLayout::Directory)
+            .build()
+            .unwrap();
+
+        let outcome = redirector.write_redirect().unwrap();
+        assert_eq!(
+            outcome.file_path(),
+            Path::new(&test_dir).join("install").join("index.html")
+        );
+        assert_eq!(outcome.short_file(), "install/");
+        assert_eq!(outcome.short_name(), "install");
+        assert!(fs::read_to_string(outcome.file_path())
+            .unwrap()
+            .contains("url=/docs/install/"));
+
+        let again = redirector.write_redirect().unwrap();
+        assert!(!again.is_created());
+        assert_eq!(again.short_file(), "install/");
+
+        let existing = Redirector::from_existing(&test_dir, "docs/install").unwrap();
+        assert_eq!(existing.layout, OutputLayout::Directory);
+        assert_eq!(existing.short_file_name(), "install.html");
+
+        let mut registry = Registry::load(&test_dir).unwrap();
+        assert_eq!(
+            registry.entry("/docs/install/").unwrap().short_file(),
+            "install/"
+        );
+        assert_eq!(registry.target_for("install"), Some("/docs/install/"));
+        registry.remove("install").unwrap();
+        assert!(!Path::new(&test_dir).join("install").exists());
+
+        fs::remove_dir_all(&test_dir).unwrap();
+    }
+
+    #[test]
+    fn test_write_redirect_target_case() {
+        let test_dir = format!(
+            "test_write_redirect_target_case_{}",
+            Utc::now().timestamp_nanos_opt().unwrap_or(0)
+        );
+        let insensitive = PathPolicy::default().target_case(TargetCase::Insensitive);
+        let mut redirector = Redirector::with_policy("Docs/Install", &insensitive).unwrap();
+        redirector.set_path(&test_dir);
+        let outcome = redirector.write_redirect().unwrap();
+
+        let mut again = Redirector::with_policy("docs/install", &insensitive).unwrap();
+        again.set_path(&test_dir);
+        let reused = again.write_redirect().unwrap();
+
+        assert!(!reused.is_created());
+        assert_eq!(reused.file_path(), outcome.file_path());
+        assert_eq!(reused.target(), "/Docs/Install/");
+
+        // Case-sensitive deduplication keeps the targets apart
+        let mut sensitive = Redirector::new("docs/install").unwrap();
+        sensitive.set_path(&test_dir);
+        assert!(sensitive.write_redirect().unwrap().is_created());
+        assert_eq!(Registry::load(&test_dir).unwrap().len(), 2);
+
+        // Clean up
+        fs::remove_dir_all(&test_dir).unwrap();
+    }
+
+    #[test]
+    fn test_from_existing() {
+        let test_dir = format!(
+            "test_from_existing_{}",
+            Utc::now().timestamp_nanos_opt().unwrap_or(0)
+        );
+        let mut redirector = Redirector::new("some/path").unwrap();
+        redirector.set_path(&test_dir);
+        redirector.write_redirect().unwrap();
+
+        let existing = Redirector::from_existing(&test_dir, "/some/path/").unwrap();
+
+        assert_eq!(existing, redirector);
+        assert_eq!(existing.to_string(), redirector.to_string());
+
+        // Clean up
+        fs::remove_dir_all(&test_dir).unwrap();
+    }
+
+    #[test]
+    fn test_from_existing_preserves_stored_form() {
+        let test_dir = format!(
+            "test_from_existing_preserves_stored_form_{}",
+            Utc::now().timestamp_nanos_opt().unwrap_or(0)
+        );
+        let redirector = Redirector::builder()
+            .target("docs/install")
+            .out_dir(&test_dir)
+            .alias("install")
+            .policy(PathPolicy::default().trailing_slash(TrailingSlash::Strip))
+            .build()
+            .unwrap();
+        redirector.write_redirect().unwrap();
+
+        let existing = Redirector::from_existing(&test_dir, "/docs/install").unwrap();
+
+        assert_eq!(existing.target.to_string(), "/docs/install");
+        assert_eq!(existing.short_file_name, OsString::from("install.html"));
 
         // Clean up
         fs::remove_dir_all(&test_dir).unwrap();
     }
 
+    #[test]
+    fn test_from_existing_external_target() {
+        let test_dir = format!(
+            "test_from_existing_external_target_{}",
+            Utc::now().timestamp_nanos_opt().unwrap_or(0)
+        );
+        let allowlist = HostAllowlist::new(["example.com"]);
+        let mut redirector = Redirector::new_external("https://example.com/x", &allowlist).unwrap();
+        redirector.set_path(&test_dir);
+        redirector.write_redirect().unwrap();
+
+        let existing = Redirector::from_existing(&test_dir, "https://example.com/x").unwrap();
+        assert_eq!(existing, redirector);
+
+        // Clean up
+        fs::remove_dir_all(&test_dir).unwrap();
+    }
+
+    #[test]
+    fn test_from_existing_not_registered() {
+        let test_dir = format!(
+            "test_from_existing_not_registered_{}",
+            Utc::now().timestamp_nanos_opt().unwrap_or(0)
+        );
+
+        let result = Redirector::from_existing(&test_dir, "some/path");
+        assert!(matches!(result, Err(RedirectorError::ShortLinkNotFound)));
+    }
+
     #[test]
     fn test_new_redirector_error_handling() {
         // Test invalid path - single segment should be okay now
@@ -652,6 +2872,36 @@ mod tests {
         assert_ne!(redirector1.short_file_name, redirector2.short_file_name);
     }
 
+    #[test]
+    fn test_generate_short_link_no_collisions_in_tight_loop() {
+        let mut names = HashSet::new();
+        for i in 0..10_000 {
+            // Anagram targets defeated the old sum of code units
+            let target = if i % 2 == 0 { "docs/ab" } else { "docs/ba" };
+            names.insert(Redirector::new(target).unwrap().short_file_name);
+        }
+        assert_eq!(names.len(), 10_000);
+    }
+
+    #[test]
+    fn test_generate_short_link_no_collisions_across_threads() {
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                thread::spawn(|| {
+                    (0..2_500)
+                        .map(|_| Redirector::new("docs/page").unwrap().short_file_name)
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        let names: HashSet<OsString> = handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect();
+        assert_eq!(names.len(), 10_000);
+    }
+
     #[test]
     fn test_short_file_name_format() {
         let redirector = Redirector::new("some/path").unwrap();
@@ -675,4 +2925,235 @@ mod tests {
         let debug_output = format!("{redirector1:?}");
         assert!(debug_output.contains("Redirector"));
     }
+
+    #[test]
+    fn test_with_policy_strict() {
+        let policy = PathPolicy::strict();
+
+        let redirector = Redirector::with_policy("api/v1", &policy).unwrap();
+        assert_eq!(redirector.target.to_string(), "/api/v1/");
+
+        let result = Redirector::with_policy("api/v1.0", &policy);
+        assert!(matches!(result, Err(RedirectorError::InvalidUrlPath(_))));
+    }
+
+    #[test]
+    fn test_with_policy_custom_is_more_permissive_than_strict() {
+        let policy = PathPolicy::custom(|segment| !segment.contains(' '));
+
+        assert!(Redirector::with_policy("files/report.v1", &policy).is_ok());
+        assert!(Redirector::with_policy("files/my report", &policy).is_err());
+    }
+
+    #[test]
+    fn test_with_policy_strip_trailing_slash() {
+        let policy = PathPolicy::default().trailing_slash(TrailingSlash::Strip);
+        let redirector = Redirector::with_policy("docs/install/", &policy).unwrap();
+        let output = format!("{redirector}");
+
+        assert!(output.contains("url=/docs/install\""));
+        assert!(!output.contains("/docs/install/"));
+    }
+
+    #[test]
+    fn test_display_file_extension_target() {
+        let redirector = Redirector::new("download/report.pdf").unwrap();
+        let output = format!("{redirector}");
+
+        assert!(output.contains("url=/download/report.pdf\""));
+        assert!(output.contains("href='/download/report.pdf'"));
+    }
+
+    #[test]
+    fn test_with_policy_strict_file_extension_target() {
+        let redirector =
+            Redirector::with_policy("files/bundle.zip", &PathPolicy::strict()).unwrap();
+        assert_eq!(redirector.target.to_string(), "/files/bundle.zip");
+    }
+
+    #[test]
+    fn test_with_policy_limits() {
+        let policy = PathPolicy::default().max_depth(1);
+
+        assert!(Redirector::with_policy("docs", &policy).is_ok());
+        let result = Redirector::with_policy("docs/install", &policy);
+        assert!(matches!(result, Err(RedirectorError::InvalidUrlPath(_))));
+    }
+
+    #[test]
+    fn test_from_url_path() {
+        let path = UrlPath::new("api/v1").unwrap();
+        let redirector = Redirector::from(path.clone());
+
+        assert_eq!(redirector.target, Target::Path(path));
+        assert!(!redirector.short_file_name.is_empty());
+        assert_eq!(redirector.path, PathBuf::from("s"));
+    }
+
+    #[test]
+    fn test_display_with_delay() {
+        let redirector = Redirector::builder()
+            .target("some/path")
+            .delay(5)
+            .build()
+            .unwrap();
+        let output = format!("{redirector}");
+
+        assert!(output.contains("content=\"5; url=/some/path/\""));
+        assert!(output.contains("setTimeout(function () {"));
+        assert!(output.contains("window.location.href = \"/some/path/\";"));
+        assert!(output.contains("}, 5000);"));
+    }
+
+    #[test]
+    fn test_display_without_delay_redirects_immediately() {
+        let redirector = Redirector::new("some/path").unwrap();
+        let output = format!("{redirector}");
+
+        assert!(output.contains("content=\"0; url=/some/path/\""));
+        assert!(!output.contains("setTimeout"));
+    }
+
+    #[test]
+    fn test_write_redirect_with_alias() {
+        let test_dir = format!(
+            "test_write_redirect_with_alias_{}",
+            Utc::now().timestamp_nanos_opt().unwrap_or(0)
+        );
+        let redirector = Redirector::builder()
+            .target("some/path")
+            .out_dir(&test_dir)
+            .alias("promo")
+            .build()
+            .unwrap();
+
+        let outcome = redirector.write_redirect().unwrap();
+
+        assert_eq!(
+            outcome.file_path(),
+            PathBuf::from(&test_dir).join("promo.html")
+        );
+        assert_eq!(outcome.short_name(), "promo");
+        assert!(outcome.file_path().exists());
+
+        // Clean up
+        fs::remove_dir_all(&test_dir).unwrap();
+    }
+
+    #[test]
+    fn test_new_external_allowed_host() {
+        let allowlist = HostAllowlist::new(["example.com"]);
+        let redirector = Redirector::new_external("https://example.com/docs", &allowlist).unwrap();
+
+        assert_eq!(redirector.target.to_string(), "https://example.com/docs");
+        assert!(!redirector.short_file_name.is_empty());
+        assert_eq!(redirector.path, PathBuf::from("s"));
+    }
+
+    #[test]
+    fn test_new_external_disallowed_host() {
+        let allowlist = HostAllowlist::new(["example.com"]);
+        let result = Redirector::new_external("https://evil.com/phish", &allowlist);
+
+        match result {
+            Err(RedirectorError::DisallowedTarget(host)) => assert_eq!(host, "evil.com"),
+            other => panic!("expected DisallowedTarget, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_new_external_empty_allowlist() {
+        let result = Redirector::new_external("https://example.com/", &HostAllowlist::default());
+        assert!(matches!(result, Err(RedirectorError::DisallowedTarget(_))));
+    }
+
+    #[test]
+    fn test_new_external_invalid_url() {
+        let allowlist = HostAllowlist::new(["example.com"]);
+        let result = Redirector::new_external("javascript:alert(1)", &allowlist);
+        assert!(matches!(result, Err(RedirectorError::InvalidTarget(_))));
+    }
+
+    #[test]
+    fn test_new_rejects_absolute_url() {
+        assert!(Redirector::new("https://example.com/docs").is_err());
+    }
+
+    #[test]
+    fn test_display_renders_external_target() {
+        let allowlist = HostAllowlist::new(["*.example.com"]);
+        let redirector =
+            Redirector::new_external("https://docs.example.com/guide", &allowlist).unwrap();
+        let output = format!("{redirector}");
+
+        assert!(output.contains("url=https://docs.example.com/guide"));
+        assert!(output.contains("window.location.href = \"https://docs.example.com/guide\""));
+    }
+
+    #[test]
+    fn test_display_with_beacon() {
+        let redirector = Redirector::builder()
+            .target("summer/sale")
+            .alias("promo")
+            .delay(2)
+            .beacon("https://stats.example.com/hits")
+            .build()
+            .unwrap();
+        let output = format!("{redirector}");
+
+        let beacon = output
+            .find(r#"navigator.sendBeacon("https://stats.example.com/hits", "promo");"#)
+            .unwrap();
+        assert!(beacon < output.find("setTimeout").unwrap());
+        assert!(!Redirector::new("summer/sale")
+            .unwrap()
+            .to_string()
+            .contains("sendBeacon"));
+
+        let result = Redirector::builder().target("a").beacon("hits").build();
+        assert!(matches!(result, Err(RedirectorError::InvalidBeacon(_))));
+    }
+
+    #[test]
+    fn test_display_split() {
+        let redirector = Redirector::builder()
+            .target("landing/a")
+            .variant("landing/b", 3)
+            .base_url("https://example.com")
+            .build()
+            .unwrap();
+        let output = redirector.to_string();
+
+        assert!(output.contains(
+            r#"[["https://example.com/landing/a/", 1], ["https://example.com/landing/b/", 3]]"#
+        ));
+        assert!(output.contains(
+            r#"<noscript><meta http-equiv="refresh" content="0; url=https://example.com/landing/a/"></noscript>"#
+        ));
+        assert!(Redirector::new("landing/a")
+            .unwrap()
+            .to_string()
+            .contains(r#"<meta http-equiv="refresh" content="0; url=/landing/a/">"#));
+    }
+
+    #[test]
+    fn test_display_confirm() {
+        let redirector = Redirector::builder()
+            .target("internal/wiki")
+            .alias("wiki")
+            .delay(5)
+            .beacon("/hits")
+            .confirm()
+            .build()
+            .unwrap();
+        let output = redirector.to_string();
+
+        assert!(output.contains("Confirm Redirection"));
+        assert!(!output.contains("http-equiv=\"refresh\""));
+        assert!(!output.contains("setTimeout"));
+        let beacon = output
+            .find(r#"navigator.sendBeacon("/hits", "wiki");"#)
+            .unwrap();
+        assert!(beacon < output.find("window.location.href").unwrap());
+    }
 }
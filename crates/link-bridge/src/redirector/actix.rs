@@ -0,0 +1,124 @@
+//! Serving redirects dynamically with `actix-web`.
+//!
+//! `Registry::router()` answers short-link requests from an `axum` service. This module
+//! provides the same for `actix-web` applications: `Registry::scope()` builds a scope
+//! that resolves short names against the registry and answers with a permanent
+//! redirect, so either framework can serve the links the static pages do.
+
+use actix_web::http::header;
+use actix_web::{web, HttpResponse, Scope};
+
+use crate::redirector::{Registry, RegistryEntry};
+
+impl Registry {
+    /// Builds an `actix-web` scope at `path` that answers `GET {path}/{short_name}`
+    /// with a redirect.
+    ///
+    /// Requests for a registered short name, with or without the `.html` extension
+    /// or a trailing slash, receive `301 Moved Permanently` and a `Location` header
+    /// holding the target. Short names retired with [`Registry::retire()`] receive
+    /// `410 Gone`, and unknown short names receive `404 Not Found`. Use the path the
+    /// static redirect pages are served from, such as `/s`. Requires the `actix-web`
+    /// feature.
+    ///
+    /// The registry is consumed, so later changes to `registry.json` are not seen;
+    /// load the registry again and rebuild the scope to pick them up. An
+    /// `HttpServer` builds its application once per worker, so clone the registry
+    /// into each.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path short names are served below
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use actix_web::App;
+    /// use link_bridge::{Redirector, Registry};
+    /// use std::fs;
+    ///
+    /// let redirector = Redirector::builder()
+    ///     .target("api/v1/users")
+    ///     .out_dir("doc_test_actix_scope")
+    ///     .alias("users")
+    ///     .build()
+    ///     .unwrap();
+    /// redirector.write_redirect().unwrap();
+    ///
+    /// // GET /s/users -> 301 Moved Permanently, Location: /api/v1/users/
+    /// let registry = Registry::load("doc_test_actix_scope").unwrap();
+    /// let app = App::new().service(registry.scope("/s"));
+    ///
+    /// fs::remove_dir_all("doc_test_actix_scope").ok();
+    /// ```
+    pub fn scope(self, path: &str) -> Scope {
+        web::scope(path)
+            .app_data(web::Data::new(self))
+            .route("/{short_name}", web::get().to(redirect))
+            .route("/{short_name}/", web::get().to(redirect))
+    }
+}
+
+/// Answers a short-link request from the registry.
+async fn redirect(registry: web::Data<Registry>, short_name: web::Path<String>) -> HttpResponse {
+    let short_name = short_name.strip_suffix(".html").unwrap_or(&short_name);
+
+    match registry.target_for(short_name) {
+        Some(target)
+            if registry
+                .entry(target)
+                .is_some_and(RegistryEntry::is_retired) =>
+        {
+            HttpResponse::Gone().finish()
+        }
+        Some(target) => HttpResponse::MovedPermanently()
+            .insert_header((header::LOCATION, target))
+            .finish(),
+        None => HttpResponse::NotFound().finish(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::http::StatusCode;
+    use actix_web::test::{call_service, init_service, TestRequest};
+    use actix_web::App;
+    use std::path::PathBuf;
+
+    fn registry() -> Registry {
+        let mut registry = Registry::empty(PathBuf::from("s"));
+        registry.insert("/docs/install/".to_string(), "s/install.html".to_string());
+        registry.insert("/docs/old/".to_string(), "s/old.html".to_string());
+        registry.entry_mut("/docs/old/").unwrap().retire();
+        registry
+    }
+
+    #[tokio::test]
+    async fn test_scope_redirects() {
+        let app = init_service(App::new().service(registry().scope("/s"))).await;
+
+        for uri in ["/s/install", "/s/install.html", "/s/install/"] {
+            let response = call_service(&app, TestRequest::get().uri(uri).to_request()).await;
+            assert_eq!(response.status(), StatusCode::MOVED_PERMANENTLY, "{uri}");
+            assert_eq!(
+                response.headers().get(header::LOCATION).unwrap(),
+                "/docs/install/"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_scope_unknown_and_retired_short_names() {
+        let app = init_service(App::new().service(registry().scope("/s"))).await;
+
+        for (uri, status) in [
+            ("/s/missing", StatusCode::NOT_FOUND),
+            ("/install", StatusCode::NOT_FOUND),
+            ("/s/old", StatusCode::GONE),
+        ] {
+            let response = call_service(&app, TestRequest::get().uri(uri).to_request()).await;
+            assert_eq!(response.status(), status, "{uri}");
+        }
+    }
+}
@@ -0,0 +1,226 @@
+//! Validation of custom short names (aliases).
+//!
+//! An alias replaces the generated short file name with a memorable one such as
+//! `promo`, producing `promo.html` in the output directory. Aliases become file names
+//! and URL segments, so only a conservative set of characters is accepted, and names
+//! Windows reserves for devices are refused. `slugify()` turns a human-entered title
+//! into a name that passes these rules.
+
+use crate::redirector::RedirectorError;
+
+/// The maximum length of an alias, in bytes.
+pub(crate) const MAX_ALIAS_LENGTH: usize = 64;
+
+/// The device names Windows reserves, which cannot be used as file names.
+const WINDOWS_RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Turns a title into a short name suitable as an alias.
+///
+/// Accented Latin letters are replaced by their unaccented forms (`ß` becomes `ss`,
+/// `æ` becomes `ae`), letters are lowercased, apostrophes are dropped, and every
+/// other run of characters that are not ASCII letters or digits becomes a single
+/// dash. Leading and trailing dashes are removed, and a long slug is cut at a dash to
+/// fit the 64 characters an alias may have.
+///
+/// The slug is accepted by
+/// [`RedirectorBuilder::alias()`](crate::RedirectorBuilder::alias) unless it is
+/// empty, because the title has no letters or digits, or a device name Windows
+/// reserves, such as `con`.
+///
+/// # Examples
+///
+/// ```rust
+/// use link_bridge::slugify;
+///
+/// assert_eq!(slugify("Über cool Pagé!"), "uber-cool-page");
+/// assert_eq!(slugify("  Summer Sale: 50% off  "), "summer-sale-50-off");
+/// assert_eq!(slugify("Don't Panic"), "dont-panic");
+/// ```
+pub fn slugify(title: &str) -> String {
+    let mut slug = String::with_capacity(title.len());
+    let mut dash = false;
+    for c in title.chars().flat_map(char::to_lowercase) {
+        if c == '\'' || c == '\u{2019}' {
+            continue;
+        }
+        let mut buf = [0; 4];
+        let ascii = if c.is_ascii_alphanumeric() {
+            &*c.encode_utf8(&mut buf)
+        } else if let Some(ascii) = transliterate(c) {
+            ascii
+        } else {
+            dash = true;
+            continue;
+        };
+        if dash && !slug.is_empty() {
+            slug.push('-');
+        }
+        dash = false;
+        slug.push_str(ascii);
+    }
+
+    if slug.len() > MAX_ALIAS_LENGTH {
+        let cut = slug[..=MAX_ALIAS_LENGTH]
+            .rfind('-')
+            .unwrap_or(MAX_ALIAS_LENGTH);
+        slug.truncate(cut);
+    }
+    slug
+}
+
+/// Returns the ASCII letters for an accented lowercase Latin letter, or `None` for
+/// any other character.
+fn transliterate(c: char) -> Option<&'static str> {
+    let ascii = match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => "a",
+        'æ' => "ae",
+        'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => "c",
+        'ď' | 'đ' | 'ð' => "d",
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => "e",
+        'ĝ' | 'ğ' | 'ġ' | 'ģ' => "g",
+        'ĥ' | 'ħ' => "h",
+        'ì' | 'í' | 'î' | 'ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' => "i",
+        'ĳ' => "ij",
+        'ĵ' => "j",
+        'ķ' => "k",
+        'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' => "l",
+        'ñ' | 'ń' | 'ņ' | 'ň' => "n",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ŏ' | 'ő' => "o",
+        'œ' => "oe",
+        'ŕ' | 'ŗ' | 'ř' => "r",
+        'ś' | 'ŝ' | 'ş' | 'š' | 'ș' => "s",
+        'ß' => "ss",
+        'ţ' | 'ť' | 'ŧ' | 'ț' => "t",
+        'þ' => "th",
+        'ù' | 'ú' | 'û' | 'ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => "u",
+        'ŵ' => "w",
+        'ý' | 'ÿ' | 'ŷ' => "y",
+        'ź' | 'ż' | 'ž' => "z",
+        _ => return None,
+    };
+    Some(ascii)
+}
+
+/// Validates a custom short name.
+///
+/// Aliases must be between 1 and [`MAX_ALIAS_LENGTH`] characters long and consist of
+/// ASCII letters, digits, dashes, and underscores. They must not start with a dash or
+/// underscore, and must not be a device name reserved by Windows. [`slugify()`]
+/// produces names that follow these rules from free text.
+///
+/// # Returns
+///
+/// * `Ok(())` - If the alias is acceptable
+/// * `Err(RedirectorError::InvalidAlias)` - If the alias is empty, too long, or
+///   contains forbidden characters
+/// * `Err(RedirectorError::ReservedName)` - If the alias is reserved by Windows
+pub(crate) fn validate_alias(alias: &str) -> Result<(), RedirectorError> {
+    let valid = !alias.is_empty()
+        && alias.len() <= MAX_ALIAS_LENGTH
+        && alias.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && alias
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+
+    if !valid {
+        return Err(RedirectorError::InvalidAlias(alias.to_string()));
+    }
+    if is_reserved_name(alias) {
+        return Err(RedirectorError::ReservedName(alias.to_string()));
+    }
+    Ok(())
+}
+
+/// Reports whether Windows reserves a short name for a device.
+///
+/// The comparison ignores letter case and anything from the first `.`, as Windows
+/// does, so `nul` and `Con.backup` are both reserved.
+pub(crate) fn is_reserved_name(name: &str) -> bool {
+    let base = name.split('.').next().unwrap_or(name);
+    WINDOWS_RESERVED_NAMES
+        .iter()
+        .any(|reserved| reserved.eq_ignore_ascii_case(base))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_alias_valid() {
+        assert!(validate_alias("promo").is_ok());
+        assert!(validate_alias("summer-sale_2025").is_ok());
+        assert!(validate_alias("A1").is_ok());
+        assert!(validate_alias(&"a".repeat(MAX_ALIAS_LENGTH)).is_ok());
+    }
+
+    #[test]
+    fn test_validate_alias_invalid() {
+        assert!(validate_alias("").is_err());
+        assert!(validate_alias("-promo").is_err());
+        assert!(validate_alias("_promo").is_err());
+        assert!(validate_alias("promo.html").is_err());
+        assert!(validate_alias("../promo").is_err());
+        assert!(validate_alias("pro mo").is_err());
+        assert!(validate_alias("café").is_err());
+        assert!(validate_alias(&"a".repeat(MAX_ALIAS_LENGTH + 1)).is_err());
+    }
+
+    #[test]
+    fn test_validate_alias_reserved() {
+        for alias in ["con", "CON", "Nul", "aux", "prn", "com1", "LPT9"] {
+            match validate_alias(alias) {
+                Err(RedirectorError::ReservedName(name)) => assert_eq!(name, alias),
+                other => panic!("expected ReservedName for {alias}, got {other:?}"),
+            }
+        }
+        for alias in ["console", "com10", "com0", "nul-page", "lpt"] {
+            assert!(validate_alias(alias).is_ok(), "{alias} should be accepted");
+        }
+    }
+
+    #[test]
+    fn test_is_reserved_name_ignores_extension() {
+        assert!(is_reserved_name("con.html"));
+        assert!(is_reserved_name("Aux.tar.gz"));
+        assert!(!is_reserved_name("contact.html"));
+    }
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Über cool Pagé!"), "uber-cool-page");
+        assert_eq!(slugify("Straße & Café"), "strasse-cafe");
+        assert_eq!(slugify("Ørsted Æble Œuvre"), "orsted-aeble-oeuvre");
+        assert_eq!(slugify("--Hello__World--"), "hello-world");
+        assert_eq!(slugify("l’été 2025"), "lete-2025");
+        assert_eq!(slugify("日本語 guide"), "guide");
+        assert_eq!(slugify("!!!"), "");
+    }
+
+    #[test]
+    fn test_slugify_passes_validation() {
+        for title in ["Über cool Pagé!", "A", "Ünïcödé everywhere", "2025 report"] {
+            let slug = slugify(title);
+            assert!(validate_alias(&slug).is_ok(), "{title} -> {slug}");
+        }
+
+        let long = slugify(&"word ".repeat(30));
+        assert!(long.len() <= MAX_ALIAS_LENGTH);
+        assert!(!long.ends_with('-'));
+        assert!(validate_alias(&long).is_ok());
+
+        let unbroken = slugify(&"a".repeat(100));
+        assert_eq!(unbroken.len(), MAX_ALIAS_LENGTH);
+    }
+
+    #[test]
+    fn test_validate_alias_error_contains_alias() {
+        match validate_alias("bad/alias") {
+            Err(RedirectorError::InvalidAlias(alias)) => assert_eq!(alias, "bad/alias"),
+            other => panic!("expected InvalidAlias, got {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,90 @@
+//! Redirect pages that are valid AMP HTML.
+//!
+//! Publishers serving short links inside AMP viewers and caches need pages that pass
+//! the AMP validator, which forbids author scripts and the meta refresh the usual
+//! redirect page relies on. This module renders the page written instead by
+//! `RedirectorBuilder::amp()`: it names the target as its canonical URL, which AMP
+//! caches and search engines follow to the original page, and links to it for the
+//! visitor.
+
+use crate::redirector::gate::PageText;
+
+/// The mandatory AMP boilerplate style, exactly as the validator expects it.
+const BOILERPLATE: &str = "<style amp-boilerplate>body{-webkit-animation:-amp-start 8s steps(1,end) 0s 1 normal both;-moz-animation:-amp-start 8s steps(1,end) 0s 1 normal both;-ms-animation:-amp-start 8s steps(1,end) 0s 1 normal both;animation:-amp-start 8s steps(1,end) 0s 1 normal both}@-webkit-keyframes -amp-start{from{visibility:hidden}to{visibility:visible}}@-moz-keyframes -amp-start{from{visibility:hidden}to{visibility:visible}}@-ms-keyframes -amp-start{from{visibility:hidden}to{visibility:visible}}@-o-keyframes -amp-start{from{visibility:hidden}to{visibility:visible}}@keyframes -amp-start{from{visibility:hidden}to{visibility:visible}}</style><noscript><style amp-boilerplate>body{-webkit-animation:none;-moz-animation:none;-ms-animation:none;animation:none}</style></noscript>";
+
+/// Renders the AMP page for a resolved target.
+///
+/// `href` is the escaped target, `title` the escaped page title, and `meta` any extra
+/// tags for the head. The page is written in the language `lang`, with `text`
+/// translating each message from its id, its English text, and its arguments.
+pub(crate) fn page(lang: &str, text: &PageText, href: &str, title: &str, meta: &str) -> String {
+    let link = format!(
+        "<a href='{href}'>{}</a>",
+        text("redirect-link", "link to page", &[])
+    );
+    let moved = text(
+        "amp-moved",
+        "This page has moved. Follow this { $link }.",
+        &[("link", &link)],
+    );
+    format!(
+        r#"
+    <!doctype html>
+    <html amp lang="{lang}">
+
+    <head>
+        <meta charset="utf-8">
+        <script async src="https://cdn.ampproject.org/v0.js"></script>
+        <title>{title}</title>
+        <link rel="canonical" href="{href}">
+        <meta name="viewport" content="width=device-width">{meta}
+        {BOILERPLATE}
+    </head>
+
+    <body>
+        <p>{moved}</p>
+    </body>
+
+    </html>
+    "#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Redirector;
+
+    #[test]
+    fn test_amp_page() {
+        let page = Redirector::builder()
+            .target("docs/install")
+            .amp()
+            .delay(5)
+            .beacon("/hits")
+            .build()
+            .unwrap()
+            .to_string();
+
+        assert!(page.contains(r#"<html amp lang="en-US">"#));
+        assert!(page.contains(r#"<link rel="canonical" href="/docs/install/">"#));
+        assert!(page.contains("Follow this <a href='/docs/install/'>link to page</a>."));
+        assert!(page.contains("<style amp-boilerplate>"));
+        // AMP only allows its own runtime script, and no meta refresh
+        assert_eq!(page.matches("<script").count(), 1);
+        assert!(!page.contains("http-equiv"));
+        assert!(!page.contains("sendBeacon"));
+    }
+
+    #[test]
+    fn test_amp_page_is_gated() {
+        let page = Redirector::builder()
+            .target("internal/wiki")
+            .amp()
+            .confirm()
+            .build()
+            .unwrap()
+            .to_string();
+        assert!(page.contains(r#"<button type="submit">Continue</button>"#));
+        assert!(!page.contains("<html amp"));
+    }
+}
@@ -0,0 +1,109 @@
+//! Exporting the registry as an Apache rewrite map.
+//!
+//! Apache's `mod_rewrite` can look the request path up in a `RewriteMap` text file of
+//! space-separated key and value lines, which it caches and scales to many thousands
+//! of entries far better than one `Redirect` directive per short link. This module
+//! provides `Registry::to_rewrite_map()`, which renders the registry as such a file.
+
+use crate::redirector::Registry;
+
+/// Where the sample directives in the map's header expect the map to be installed.
+const SAMPLE_MAP_PATH: &str = "/etc/apache2/short-links.map";
+
+impl Registry {
+    /// Renders the registry as the text file of an Apache `RewriteMap`.
+    ///
+    /// Each short name, including extra short names added with
+    /// [`Registry::add_alias()`], maps its path below `base_path`, with and without the
+    /// `.html` extension, to its target. Apache reads a value up to the first
+    /// whitespace, so whitespace and control characters in targets are percent-encoded.
+    /// Retired redirects are left out. Lines are
+    /// ordered by path, after a comment holding sample directives that answer the
+    /// mapped paths with `301` redirects from the server or virtual host
+    /// configuration:
+    ///
+    /// ```text
+    /// RewriteEngine On
+    /// RewriteMap shortlinks "txt:/etc/apache2/short-links.map"
+    /// RewriteCond ${shortlinks:%{REQUEST_URI}} !=""
+    /// RewriteRule ^ ${shortlinks:%{REQUEST_URI}} [R=301,L,NE]
+    /// ```
+    ///
+    /// # Arguments
+    ///
+    /// * `base_path` - The path the short links are served from, such as `"/s"`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::{Redirector, Registry};
+    /// use std::fs;
+    ///
+    /// Redirector::builder()
+    ///     .target("docs/install")
+    ///     .out_dir("doc_test_rewrite_map")
+    ///     .alias("install")
+    ///     .build()
+    ///     .unwrap()
+    ///     .write_redirect()
+    ///     .unwrap();
+    ///
+    /// let registry = Registry::load("doc_test_rewrite_map").unwrap();
+    /// let map = registry.to_rewrite_map("/s");
+    /// assert!(map.contains("\n/s/install /docs/install/\n"));
+    /// assert!(map.contains("\n/s/install.html /docs/install/\n"));
+    ///
+    /// fs::remove_dir_all("doc_test_rewrite_map").ok();
+    /// ```
+    pub fn to_rewrite_map<S: ToString>(&self, base_path: S) -> String {
+        let mut map = format!(
+            "# Generated by link-bridge from the registry; do not edit.\n\
+             # Install as {SAMPLE_MAP_PATH} and redirect from the server configuration with:\n\
+             #   RewriteEngine On\n\
+             #   RewriteMap shortlinks \"txt:{SAMPLE_MAP_PATH}\"\n\
+             #   RewriteCond ${{shortlinks:%{{REQUEST_URI}}}} !=\"\"\n\
+             #   RewriteRule ^ ${{shortlinks:%{{REQUEST_URI}}}} [R=301,L,NE]\n"
+        );
+        map.push_str(&self.map_lines(&base_path.to_string()));
+        map
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_to_rewrite_map() {
+        let mut registry = Registry::empty(PathBuf::from("s"));
+        registry.insert("/docs/usage/".to_string(), "s/usage.html".to_string());
+        registry.insert_alias("/docs/usage/", "how-to");
+        registry.insert("/docs/old/".to_string(), "s/old.html".to_string());
+        registry.entry_mut("/docs/old/").unwrap().retire();
+
+        assert_eq!(
+            registry.to_rewrite_map("/s"),
+            r#"# Generated by link-bridge from the registry; do not edit.
+# Install as /etc/apache2/short-links.map and redirect from the server configuration with:
+#   RewriteEngine On
+#   RewriteMap shortlinks "txt:/etc/apache2/short-links.map"
+#   RewriteCond ${shortlinks:%{REQUEST_URI}} !=""
+#   RewriteRule ^ ${shortlinks:%{REQUEST_URI}} [R=301,L,NE]
+/s/how-to /docs/usage/
+/s/how-to.html /docs/usage/
+/s/usage /docs/usage/
+/s/usage.html /docs/usage/
+"#
+        );
+    }
+
+    #[test]
+    fn test_to_rewrite_map_encodes_whitespace() {
+        let mut registry = Registry::empty(PathBuf::from("s"));
+        registry.insert("/my docs/".to_string(), "s/docs.html".to_string());
+
+        let map = registry.to_rewrite_map("/s");
+        assert!(map.ends_with("\n/s/docs /my%20docs/\n/s/docs.html /my%20docs/\n"));
+    }
+}
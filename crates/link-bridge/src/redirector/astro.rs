@@ -0,0 +1,103 @@
+//! Exporting the registry as Astro redirects.
+//!
+//! Astro declares redirects in the `redirects` object of `astro.config.mjs`, mapping
+//! each path to its destination, and generates them natively for every adapter and
+//! for static builds. This module provides `Registry::to_astro_redirects()`, which
+//! renders the registry as that object in JSON, so the configuration can import
+//! link-bridge's short links instead of repeating them.
+
+use crate::redirector::json::{object, Value};
+use crate::redirector::Registry;
+
+impl Registry {
+    /// Renders the registry as the `redirects` object of `astro.config.mjs`, in JSON.
+    ///
+    /// Each short name, including extra short names added with
+    /// [`Registry::add_alias()`], maps its path below `base_path` to a `301` redirect
+    /// to its target. Retired redirects are left out, so Astro serves their "content
+    /// gone" pages. Paths are ordered alphabetically.
+    ///
+    /// Import the file from the configuration:
+    ///
+    /// ```js
+    /// import { defineConfig } from "astro/config";
+    /// import redirects from "./redirects.json";
+    ///
+    /// export default defineConfig({ redirects });
+    /// ```
+    ///
+    /// # Arguments
+    ///
+    /// * `base_path` - The path the output directory is served from, such as `"/s"`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::{Redirector, Registry};
+    /// use std::fs;
+    ///
+    /// Redirector::builder()
+    ///     .target("docs/install")
+    ///     .out_dir("doc_test_astro_redirects")
+    ///     .alias("install")
+    ///     .build()
+    ///     .unwrap()
+    ///     .write_redirect()
+    ///     .unwrap();
+    ///
+    /// let registry = Registry::load("doc_test_astro_redirects").unwrap();
+    /// let json = registry.to_astro_redirects("/s");
+    /// assert!(json.contains(r#""/s/install": {"#));
+    /// assert!(json.contains(r#""destination": "/docs/install/""#));
+    ///
+    /// fs::remove_dir_all("doc_test_astro_redirects").ok();
+    /// ```
+    pub fn to_astro_redirects<S: ToString>(&self, base_path: S) -> String {
+        let redirects = self
+            .redirect_sources(&base_path.to_string())
+            .into_iter()
+            .map(|(source, destination)| {
+                let redirect = object([
+                    ("status", 301u64.into()),
+                    ("destination", destination.into()),
+                ]);
+                (source, redirect)
+            })
+            .collect();
+        Value::Object(redirects).to_string_pretty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_to_astro_redirects() {
+        let mut registry = Registry::empty(PathBuf::from("s"));
+        registry.insert("/docs/usage/".to_string(), "s/usage.html".to_string());
+        registry.insert(
+            "https://example.org/?a=1&b=2".to_string(),
+            "s/external.html".to_string(),
+        );
+        registry.insert_alias("/docs/usage/", "how-to");
+        registry.insert("/docs/old/".to_string(), "s/old.html".to_string());
+        registry.entry_mut("/docs/old/").unwrap().retire();
+
+        let redirects: serde_json::Value =
+            serde_json::from_str(&registry.to_astro_redirects("s")).unwrap();
+        assert_eq!(
+            redirects,
+            serde_json::json!({
+                "/s/external": { "status": 301, "destination": "https://example.org/?a=1&b=2" },
+                "/s/how-to": { "status": 301, "destination": "/docs/usage/" },
+                "/s/usage": { "status": 301, "destination": "/docs/usage/" },
+            })
+        );
+        assert_eq!(
+            Registry::empty(PathBuf::from("s")).to_astro_redirects("/s"),
+            "{}"
+        );
+    }
+}
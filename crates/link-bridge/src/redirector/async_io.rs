@@ -0,0 +1,515 @@
+//! Asynchronous redirect writing using `tokio::fs`.
+//!
+//! These methods mirror the blocking `Redirector::write_redirect()`,
+//! `Registry::load()`, and `Registry::save()`, but perform their disk I/O through
+//! `tokio::fs`, so services that generate redirects while handling requests do not
+//! block their executor threads.
+
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+
+use crate::redirector::registry::REDIRECT_REGISTRY;
+use crate::redirector::sink::temp_path;
+use crate::redirector::trace;
+use crate::redirector::{RedirectOutcome, Redirector, RedirectorError, Registry, WritePlan};
+
+impl Redirector {
+    /// Writes the redirect HTML file and updates the registry without blocking.
+    ///
+    /// Behaves exactly like [`Redirector::write_redirect()`], including reuse of an
+    /// existing redirect for the same target, but performs all disk I/O with
+    /// `tokio::fs`. Only the output directory is written; mirror directories are
+    /// ignored. Requires the `tokio` feature.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(RedirectOutcome)` - The redirect file, its short name and target, and whether
+    ///   it was newly created or already existed
+    /// * `Err(RedirectorError::DirectoryCreationError)` - If the output directory cannot be created
+    /// * `Err(RedirectorError::DuplicateShortName)` - If another target already uses the
+    ///   short name
+    /// * `Err(RedirectorError::FileWriteError)` - If the redirect file cannot be written,
+    ///   or a file not written by link-bridge already exists at its path
+    /// * `Err(RedirectorError::InconsistentPage)` - If the redirector was built with
+    ///   [`verify_output()`](crate::RedirectorBuilder::verify_output) and the new page does not lead to its
+    ///   target consistently
+    /// * `Err(RedirectorError::FailedToReadRegistry)` - If the registry cannot be read
+    /// * `Err(RedirectorError::FailedToWriteRegistry)` - If the registry cannot be written
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::Redirector;
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() {
+    /// let mut redirector = Redirector::new("api/v1/users").unwrap();
+    /// redirector.set_path("doc_test_write_redirect_async");
+    ///
+    /// let outcome = redirector.write_redirect_async().await.unwrap();
+    /// assert!(outcome.is_created());
+    ///
+    /// std::fs::remove_dir_all("doc_test_write_redirect_async").ok();
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "write_redirect_async",
+            level = "debug",
+            skip_all,
+            fields(target = %self.target, dir = %self.path.display())
+        )
+    )]
+    pub async fn write_redirect_async(&self) -> Result<RedirectOutcome, RedirectorError> {
+        let result = self.write_redirect_async_inner().await;
+        self.hooks.notify(&self.target.to_string(), &result);
+        result
+    }
+
+    /// Performs the write for [`Redirector::write_redirect_async()`] without invoking hooks.
+    async fn write_redirect_async_inner(&self) -> Result<RedirectOutcome, RedirectorError> {
+        let mut registry = Registry::load_async(&self.path).await?;
+
+        let target = match self.plan_write(&registry)? {
+            WritePlan::Additional(target, additional) => {
+                self.write_page_async(&additional.file_path, &additional.page)
+                    .await?;
+                let outcome = additional.record(&mut registry, target);
+                registry.save_async().await?;
+                return Ok(outcome);
+            }
+            WritePlan::Existing(target, entry) => {
+                let existing_path = entry.path().to_path_buf();
+                // A link already exists for this path, return the existing file path
+                if !fs::try_exists(&existing_path).await.unwrap_or(true) {
+                    // The page was deleted after it was registered, so the short link 404s
+                    trace::warn!(
+                        target = %target,
+                        file = %existing_path.display(),
+                        "regenerating missing redirect file"
+                    );
+                    self.write_page_async(&existing_path, &self.registered_page(&entry))
+                        .await?;
+                } else {
+                    trace::debug!(
+                        target = %target,
+                        file = %existing_path.display(),
+                        "reusing existing redirect"
+                    );
+                }
+                return Ok(RedirectOutcome::new(
+                    existing_path,
+                    entry.short_file().to_string(),
+                    target,
+                    false,
+                ));
+            }
+            WritePlan::New(target) => target,
+        };
+        // The same candidates as `resolve_existing()`, checked without blocking
+        let mut resolved = None;
+        for candidate in self.page_candidates(&registry) {
+            let candidate_path = candidate.page_path(registry.dir());
+            if !self.regenerates() || !fs::try_exists(&candidate_path).await.unwrap_or(false) {
+                resolved = Some(candidate);
+                break;
+            }
+        }
+        let redirector = resolved.ok_or_else(|| self.existing_file_error(&registry))?;
+
+        let file_path = redirector.page_path(&self.path);
+        redirector
+            .write_page_async(&file_path, &redirector.page())
+            .await?;
+        if redirector.verify_output {
+            let page =
+                fs::read(&file_path)
+                    .await
+                    .map_err(|source| RedirectorError::FileWriteError {
+                        path: file_path.clone(),
+                        source,
+                    })?;
+            redirector.verify_page(&file_path, &page)?;
+        }
+        #[cfg(feature = "social-preview")]
+        if let Some((image_path, image)) = redirector.preview_image(&file_path) {
+            replace_file(&image_path, image.as_bytes())
+                .await
+                .map_err(|source| RedirectorError::FileWriteError {
+                    path: image_path,
+                    source,
+                })?;
+        }
+        if let Some((text_path, text)) = redirector.sidecar(&file_path) {
+            replace_file(&text_path, text.as_bytes())
+                .await
+                .map_err(|source| RedirectorError::FileWriteError {
+                    path: text_path,
+                    source,
+                })?;
+        }
+
+        trace::debug!(target = %target, file = %file_path.display(), "wrote redirect file");
+        registry.record(
+            target.clone(),
+            file_path.to_string_lossy().to_string(),
+            &redirector,
+        );
+        if let Some((json_path, json)) = redirector.metadata(&file_path, &registry, &target) {
+            replace_file(&json_path, json.as_bytes())
+                .await
+                .map_err(|source| RedirectorError::FileWriteError {
+                    path: json_path,
+                    source,
+                })?;
+        }
+        registry.save_async().await?;
+
+        Ok(RedirectOutcome::new(
+            file_path,
+            redirector.short_file(),
+            target,
+            true,
+        ))
+    }
+
+    /// Writes a page to a new file, creating its directory.
+    async fn write_page_async(&self, file_path: &Path, page: &str) -> Result<(), RedirectorError> {
+        let dir = file_path.parent().unwrap_or(Path::new(""));
+        fs::create_dir_all(dir).await.map_err(|source| {
+            RedirectorError::DirectoryCreationError {
+                path: dir.to_path_buf(),
+                source,
+            }
+        })?;
+
+        let written = if self.overwrites() {
+            replace_file(file_path, page.as_bytes()).await
+        } else {
+            create_file(file_path, page.as_bytes()).await
+        };
+        written.map_err(|source| RedirectorError::FileWriteError {
+            path: file_path.to_path_buf(),
+            source,
+        })
+    }
+}
+
+impl Registry {
+    /// Loads the registry for an output directory without blocking.
+    ///
+    /// Behaves exactly like [`Registry::load()`] but reads `registry.json` with
+    /// `tokio::fs`. Requires the `tokio` feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The output directory containing the registry
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Registry)` - The loaded (or empty) registry
+    /// * `Err(RedirectorError::FailedToReadRegistry)` - If the registry file cannot be
+    ///   read or is not valid JSON
+    pub async fn load_async<P: Into<PathBuf>>(dir: P) -> Result<Self, RedirectorError> {
+        let dir = dir.into();
+        let registry_path = dir.join(REDIRECT_REGISTRY);
+
+        let registry = match fs::read(&registry_path).await {
+            Ok(json) => Registry::from_reader(dir, json.as_slice()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Registry::empty(dir)),
+            Err(e) => Err(e),
+        }
+        .map_err(|source| RedirectorError::FailedToReadRegistry {
+            path: registry_path.clone(),
+            source,
+        })?;
+
+        trace::debug!(
+            path = %registry_path.display(),
+            entries = registry.len(),
+            "loaded registry"
+        );
+        Ok(registry)
+    }
+
+    /// Writes the registry to `registry.json` in its output directory without blocking.
+    ///
+    /// Behaves exactly like [`Registry::save()`] but writes with `tokio::fs`. Requires
+    /// the `tokio` feature.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the registry was written
+    /// * `Err(RedirectorError::DirectoryCreationError)` - If the directory cannot be created
+    /// * `Err(RedirectorError::FailedToWriteRegistry)` - If the registry file cannot be written
+    pub async fn save_async(&self) -> Result<(), RedirectorError> {
+        fs::create_dir_all(self.dir()).await.map_err(|source| {
+            RedirectorError::DirectoryCreationError {
+                path: self.dir().to_path_buf(),
+                source,
+            }
+        })?;
+
+        let registry_path = self.registry_path();
+        trace::debug!(path = %registry_path.display(), entries = self.len(), "writing registry");
+        let mut json = Vec::new();
+        self.write_json(&mut json)
+            .map_err(|source| RedirectorError::FailedToWriteRegistry {
+                path: registry_path.clone(),
+                source,
+            })?;
+        replace_file(&registry_path, &json)
+            .await
+            .map_err(|source| RedirectorError::FailedToWriteRegistry {
+                path: registry_path,
+                source,
+            })?;
+
+        let filter_path = self.filter_path();
+        if fs::try_exists(&filter_path).await.unwrap_or(false) {
+            let filter = self.target_filter().to_bytes();
+            replace_file(&filter_path, &filter)
+                .await
+                .map_err(|source| RedirectorError::FailedToWriteRegistry {
+                    path: filter_path,
+                    source,
+                })?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes a file's contents to its temporary file and flushes them to disk.
+///
+/// The temporary file is removed if writing fails.
+async fn write_temp_file(path: &Path, contents: &[u8]) -> io::Result<PathBuf> {
+    let (mut file, temp_path) = loop {
+        let temp_path = temp_path(path);
+        match fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&temp_path)
+            .await
+        {
+            Ok(file) => break (file, temp_path),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        }
+    };
+    let write = async {
+        file.write_all(contents).await?;
+        file.sync_all().await
+    };
+    match write.await {
+        Ok(()) => Ok(temp_path),
+        Err(e) => {
+            fs::remove_file(&temp_path).await.ok();
+            Err(e)
+        }
+    }
+}
+
+/// Writes a file through a temporary file, replacing any existing contents.
+///
+/// The asynchronous counterpart of [`FsSink::write_file()`](crate::FsSink).
+async fn replace_file(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let temp_path = write_temp_file(path, contents).await?;
+    let result = fs::rename(&temp_path, path).await;
+    if result.is_err() {
+        fs::remove_file(&temp_path).await.ok();
+    }
+    result
+}
+
+/// Writes a new file through a temporary file, failing if one already exists.
+///
+/// The asynchronous counterpart of [`FsSink::create_file()`](crate::FsSink).
+async fn create_file(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let temp_path = write_temp_file(path, contents).await?;
+    // Linking fails if the destination exists, so an existing file is never replaced
+    let result = match fs::hard_link(&temp_path, path).await {
+        // Filesystems without hard links fall back to checking before renaming
+        Err(e) if e.kind() != ErrorKind::AlreadyExists => {
+            if matches!(fs::try_exists(path).await, Ok(false)) {
+                fs::rename(&temp_path, path).await
+            } else {
+                Err(e)
+            }
+        }
+        result => result,
+    };
+    fs::remove_file(&temp_path).await.ok();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn test_dir(name: &str) -> String {
+        format!("{name}_{}", Utc::now().timestamp_nanos_opt().unwrap_or(0))
+    }
+
+    #[tokio::test]
+    async fn test_write_redirect_async() {
+        let dir = test_dir("test_write_redirect_async");
+        let mut redirector = Redirector::new("some/path").unwrap();
+        redirector.set_path(&dir);
+
+        let outcome = redirector.write_redirect_async().await.unwrap();
+
+        assert!(outcome.is_created());
+        assert_eq!(outcome.target(), "/some/path/");
+        let content = std::fs::read_to_string(outcome.file_path()).unwrap();
+        assert_eq!(content, redirector.to_string());
+
+        let registry = Registry::load(&dir).unwrap();
+        assert_eq!(registry.get("/some/path/"), outcome.file_path().to_str());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_write_redirect_async_reuses_existing() {
+        let dir = test_dir("test_write_redirect_async_reuses_existing");
+        let mut redirector1 = Redirector::new("some/path").unwrap();
+        redirector1.set_path(&dir);
+        let mut redirector2 = Redirector::new("some/path").unwrap();
+        redirector2.set_path(&dir);
+
+        let outcome1 = redirector1.write_redirect().unwrap();
+        let outcome2 = redirector2.write_redirect_async().await.unwrap();
+
+        assert!(!outcome2.is_created());
+        assert_eq!(outcome1.file_path(), outcome2.file_path());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_write_redirect_async_regenerates_page_in_registry_dir() {
+        let dir = test_dir("test_write_redirect_async_regenerates_page_in_registry_dir");
+        let mut redirector = Redirector::new("some/path").unwrap();
+        redirector.set_path(&dir);
+        let outcome = redirector.write_redirect().unwrap();
+
+        // Record the page relative to another working directory
+        let registry_path = Path::new(&dir).join(REDIRECT_REGISTRY);
+        let elsewhere = format!("{dir}_elsewhere");
+        let json = std::fs::read_to_string(&registry_path)
+            .unwrap()
+            .replace(&dir, &elsewhere);
+        std::fs::write(&registry_path, json).unwrap();
+        std::fs::remove_file(outcome.file_path()).unwrap();
+
+        let regenerated = redirector.write_redirect_async().await.unwrap();
+
+        assert_eq!(regenerated.file_path(), outcome.file_path());
+        assert!(outcome.file_path().exists());
+        assert!(!Path::new(&elsewhere).exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_write_redirect_async_existing_file() {
+        let dir = test_dir("test_write_redirect_async_existing_file");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(Path::new(&dir).join("promo.html"), "hand-written").unwrap();
+        let write = |policy| {
+            let redirector = Redirector::builder()
+                .target("summer/sale")
+                .out_dir(&dir)
+                .alias("promo")
+                .on_existing_file(policy)
+                .build()
+                .unwrap();
+            async move { redirector.write_redirect_async().await }
+        };
+
+        let refused = write(crate::ExistingFilePolicy::Error).await;
+        assert!(matches!(
+            refused,
+            Err(RedirectorError::FileWriteError { source, .. })
+                if source.kind() == ErrorKind::AlreadyExists
+        ));
+
+        let outcome = write(crate::ExistingFilePolicy::Regenerate).await.unwrap();
+        assert!(outcome.is_created());
+        assert_ne!(outcome.short_name(), "promo");
+        assert!(outcome.file_path().exists());
+        assert_eq!(
+            std::fs::read_to_string(Path::new(&dir).join("promo.html")).unwrap(),
+            "hand-written"
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_write_redirect_async_additional() {
+        let dir = test_dir("test_write_redirect_async_additional");
+        let build = |alias: &str| {
+            Redirector::builder()
+                .target("summer/sale")
+                .out_dir(&dir)
+                .alias(alias)
+                .on_duplicate(crate::DuplicatePolicy::CreateAdditional)
+                .build()
+                .unwrap()
+        };
+
+        build("promo").write_redirect_async().await.unwrap();
+        let outcome = build("sale").write_redirect_async().await.unwrap();
+
+        assert!(outcome.is_created());
+        assert_eq!(outcome.short_name(), "sale");
+        let registry = Registry::load(&dir).unwrap();
+        assert_eq!(registry.target_for("sale"), Some("/summer/sale/"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_registry_load_async_missing() {
+        let dir = test_dir("test_registry_load_async_missing");
+
+        let registry = Registry::load_async(&dir).await.unwrap();
+        assert!(registry.is_empty());
+        assert!(!std::path::Path::new(&dir).exists());
+    }
+
+    #[tokio::test]
+    async fn test_registry_load_async_corrupted() {
+        let dir = test_dir("test_registry_load_async_corrupted");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(PathBuf::from(&dir).join(REDIRECT_REGISTRY), "not json").unwrap();
+
+        let result = Registry::load_async(&dir).await;
+        assert!(matches!(
+            result,
+            Err(RedirectorError::FailedToReadRegistry { .. })
+        ));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_registry_save_async_round_trip() {
+        let dir = test_dir("test_registry_save_async_round_trip");
+        let mut registry = Registry::load_async(&dir).await.unwrap();
+        registry.insert("/a/".to_string(), format!("{dir}/abc.html"));
+
+        registry.save_async().await.unwrap();
+
+        assert_eq!(Registry::load_async(&dir).await.unwrap(), registry);
+        assert_eq!(Registry::load(&dir).unwrap(), registry);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
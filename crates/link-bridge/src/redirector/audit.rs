@@ -0,0 +1,222 @@
+//! An append-only log of redirect operations.
+//!
+//! The registry only records the redirects that exist now; compliance rules often ask
+//! for a record of every short link that was published or withdrawn, and when. This
+//! module provides the `AuditLog` type, which appends one JSON line per created,
+//! reused, or deleted redirect to a file kept separate from the registry.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::redirector::json::object;
+use crate::redirector::time::Timestamp;
+use crate::redirector::{trace, RedirectHooks, RedirectOutcome, RedirectorError};
+
+/// Appends a JSON line to a file for every redirect created, reused, or deleted.
+///
+/// Each line is a compact JSON object naming the outcome, the short name, the target,
+/// and the time of the operation as an RFC 3339 timestamp, with its keys in that
+/// sorted order:
+///
+/// ```json
+/// {"outcome":"created","short_name":"install","target":"/docs/install/","timestamp":"2025-01-01T12:00:00Z"}
+/// ```
+///
+/// The outcome is `created`, `reused`, or `deleted`. Attached as a redirector's hooks,
+/// the log records every write; deletions are recorded by calling
+/// [`AuditLog::record_deleted()`] after [`Registry::remove()`](crate::Registry::remove).
+/// The file and its directory are created on first use, and existing lines are never
+/// rewritten. A line that cannot be appended by a hook never fails the write; with the
+/// `tracing` feature enabled it is logged as a warning.
+///
+/// # Examples
+///
+/// ```rust
+/// use link_bridge::{AuditLog, Redirector, Registry};
+/// use std::fs;
+///
+/// let audit = AuditLog::new("doc_test_audit_log/audit.jsonl");
+/// let redirector = Redirector::builder()
+///     .target("api/v1/users")
+///     .out_dir("doc_test_audit_log/s")
+///     .alias("users")
+///     .hooks(audit.clone())
+///     .build()
+///     .unwrap();
+/// redirector.write_redirect().unwrap();
+///
+/// let mut registry = Registry::load("doc_test_audit_log/s").unwrap();
+/// let target = registry.remove("users").unwrap();
+/// registry.save().unwrap();
+/// audit.record_deleted("users", &target).unwrap();
+///
+/// let log = fs::read_to_string("doc_test_audit_log/audit.jsonl").unwrap();
+/// let lines: Vec<&str> = log.lines().collect();
+/// assert_eq!(lines.len(), 2);
+/// assert!(lines[1].starts_with(
+///     r#"{"outcome":"deleted","short_name":"users","target":"/api/v1/users/","timestamp":""#
+/// ));
+/// assert!(lines[1].ends_with(r#"Z"}"#));
+///
+/// fs::remove_dir_all("doc_test_audit_log").ok();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditLog {
+    /// The file lines are appended to.
+    path: PathBuf,
+}
+
+impl AuditLog {
+    /// Creates an audit log appending to the given file.
+    ///
+    /// Nothing is written until the first operation is recorded.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file to append to (e.g. `"audit.jsonl"`)
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        AuditLog {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Returns the file lines are appended to.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Records that the redirect with a short name was deleted.
+    ///
+    /// # Arguments
+    ///
+    /// * `short_name` - The short name that was removed
+    /// * `target` - The target it led to, as returned by
+    ///   [`Registry::remove()`](crate::Registry::remove)
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The line was appended
+    /// * `Err(RedirectorError::DirectoryCreationError)` - If the log's directory
+    ///   cannot be created
+    /// * `Err(RedirectorError::FileWriteError)` - If the line cannot be appended
+    pub fn record_deleted(&self, short_name: &str, target: &str) -> Result<(), RedirectorError> {
+        self.append("deleted", short_name, target)
+    }
+
+    /// Appends one line describing an operation.
+    fn append(&self, outcome: &str, short_name: &str, target: &str) -> Result<(), RedirectorError> {
+        if let Some(dir) = self.path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).map_err(|source| RedirectorError::DirectoryCreationError {
+                path: dir.to_path_buf(),
+                source,
+            })?;
+        }
+
+        let mut line = object([
+            ("timestamp", Timestamp::now().to_rfc3339().into()),
+            ("outcome", outcome.into()),
+            ("short_name", short_name.into()),
+            ("target", target.into()),
+        ])
+        .to_string();
+        line.push('\n');
+
+        // One write per line, so concurrent writers do not interleave within a line
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .map_err(|source| RedirectorError::FileWriteError {
+                path: self.path.clone(),
+                source,
+            })
+    }
+
+    /// Appends a line for a write, logging rather than returning a failure.
+    fn record_written(&self, outcome: &str, written: &RedirectOutcome) {
+        if let Err(_e) = self.append(outcome, written.short_name(), written.target()) {
+            trace::warn!(file = %self.path.display(), error = %_e, "audit log append failed");
+        }
+    }
+}
+
+impl RedirectHooks for AuditLog {
+    fn on_created(&self, outcome: &RedirectOutcome) {
+        self.record_written("created", outcome);
+    }
+
+    fn on_reused(&self, outcome: &RedirectOutcome) {
+        self.record_written("reused", outcome);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MemorySink, Redirector};
+    use chrono::Utc;
+
+    fn test_dir(name: &str) -> String {
+        format!("{name}_{}", Utc::now().timestamp_nanos_opt().unwrap_or(0))
+    }
+
+    #[test]
+    fn test_audit_log() {
+        let dir = test_dir("test_audit_log");
+        let audit = AuditLog::new(format!("{dir}/logs/audit.jsonl"));
+
+        let redirector = Redirector::builder()
+            .target("docs/install")
+            .alias("install")
+            .hooks(audit.clone())
+            .build()
+            .unwrap();
+        let mut sink = MemorySink::new();
+        redirector.write_redirect_to(&mut sink).unwrap();
+        redirector.write_redirect_to(&mut sink).unwrap();
+        audit.record_deleted("install", "/docs/install/").unwrap();
+
+        let lines: Vec<serde_json::Value> = fs::read_to_string(audit.path())
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        let outcomes: Vec<&str> = lines
+            .iter()
+            .map(|line| line["outcome"].as_str().unwrap())
+            .collect();
+        assert_eq!(outcomes, ["created", "reused", "deleted"]);
+        for line in &lines {
+            assert_eq!(line["short_name"], "install");
+            assert_eq!(line["target"], "/docs/install/");
+            assert!(line["timestamp"].as_str().unwrap().ends_with('Z'));
+        }
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_audit_log_failure_does_not_fail_write() {
+        let dir = test_dir("test_audit_log_failure");
+        // The log's path is a directory, so appending fails
+        fs::create_dir_all(&dir).unwrap();
+        let audit = AuditLog::new(&dir);
+
+        let outcome = Redirector::builder()
+            .target("docs/usage")
+            .hooks(audit.clone())
+            .build()
+            .unwrap()
+            .write_redirect_to(&mut MemorySink::new())
+            .unwrap();
+        assert!(outcome.is_created());
+        assert!(matches!(
+            audit.record_deleted("usage", "/docs/usage/"),
+            Err(RedirectorError::FileWriteError { .. })
+        ));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,87 @@
+//! Client-side hit counting for redirect pages.
+//!
+//! Static redirect pages are served without any code of ours running on the server, so
+//! clicks cannot be counted there. A redirect page can instead report each visit with
+//! `navigator.sendBeacon()` just before it redirects, posting the short name to an
+//! endpoint that collects the counts. This module validates beacon endpoints and
+//! renders the script that sends the beacon.
+
+use crate::redirector::json::quote;
+use crate::redirector::target::{is_absolute_url, ExternalUrl};
+use crate::redirector::RedirectorError;
+
+/// Validates a beacon endpoint.
+///
+/// The endpoint is either a site-relative path starting with `/` (e.g. `/hits`) or an
+/// absolute `http`/`https` URL. Paths may only contain visible ASCII characters other
+/// than quotes, backslashes, and angle brackets, so the endpoint can be embedded in
+/// the page's script as it is.
+///
+/// # Returns
+///
+/// * `Ok(())` - If the endpoint is acceptable
+/// * `Err(RedirectorError::InvalidTarget)` - If an absolute endpoint URL is malformed
+/// * `Err(RedirectorError::InvalidBeacon)` - If a path endpoint is malformed
+pub(crate) fn validate_endpoint(endpoint: &str) -> Result<(), RedirectorError> {
+    if is_absolute_url(endpoint) {
+        ExternalUrl::new(endpoint.to_string())?;
+        return Ok(());
+    }
+
+    let valid = endpoint.starts_with('/')
+        && endpoint
+            .chars()
+            .all(|c| c.is_ascii_graphic() && !matches!(c, '"' | '\'' | '\\' | '<' | '>'));
+    if !valid {
+        return Err(RedirectorError::InvalidBeacon(endpoint.to_string()));
+    }
+    Ok(())
+}
+
+/// Renders the script statement that posts the short name to the beacon endpoint.
+///
+/// Browsers without `navigator.sendBeacon()` skip the beacon and redirect as usual.
+pub(crate) fn script(endpoint: &str, short_name: &str) -> String {
+    format!(
+        "navigator.sendBeacon && navigator.sendBeacon({}, {});",
+        js_string(endpoint),
+        js_string(short_name)
+    )
+}
+
+/// Quotes text as a JavaScript string literal that is safe inside a `<script>` element.
+pub(crate) fn js_string(text: &str) -> String {
+    quote(text).replace('<', "\\u003c").replace('>', "\\u003e")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_endpoint() {
+        assert!(validate_endpoint("/hits").is_ok());
+        assert!(validate_endpoint("/api/hits?source=redirect").is_ok());
+        assert!(validate_endpoint("https://stats.example.com/hits").is_ok());
+
+        for endpoint in ["", "hits", "/hits\"", "/my hits", "/hits</script>"] {
+            assert!(matches!(
+                validate_endpoint(endpoint),
+                Err(RedirectorError::InvalidBeacon(ref e)) if e == endpoint
+            ));
+        }
+        assert!(matches!(
+            validate_endpoint("https://"),
+            Err(RedirectorError::InvalidTarget(_))
+        ));
+    }
+
+    #[test]
+    fn test_script() {
+        assert_eq!(
+            script("/hits", "promo"),
+            r#"navigator.sendBeacon && navigator.sendBeacon("/hits", "promo");"#
+        );
+        assert_eq!(js_string("a\"</script>"), r#""a\"\u003c/script\u003e""#);
+    }
+}
@@ -0,0 +1,365 @@
+//! A Bloom filter of registered targets, persisted beside the registry.
+//!
+//! Bulk imports into a very large output directory spend most of their time parsing
+//! `registry.json` only to learn that nearly every target is new. This module
+//! provides `TargetFilter`, a Bloom filter of the registry's targets saved as
+//! `registry.bloom` by `Registry::save_filter()`. Loading it reads a few bytes per
+//! redirect, and a target it does not contain is certainly not registered; only the
+//! rare possible matches need the registry itself. Once the file exists, every save
+//! of the registry rebuilds it, and it can be rebuilt from the registry at any time.
+
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+use crate::redirector::sink::{FsSink, OutputSink};
+use crate::redirector::{trace, RedirectorError, Registry};
+
+/// The file name of the target filter, beside `registry.json`.
+pub(crate) const TARGET_FILTER: &str = "registry.bloom";
+
+/// What a target filter file starts with.
+const MAGIC: &[u8; 4] = b"LBBF";
+
+/// The version of the target filter file format.
+const VERSION: u8 = 1;
+
+/// The number of bits set for each target, which with [`BITS_PER_TARGET`] gives a
+/// false positive rate of about 1%.
+const HASHES: u8 = 7;
+
+/// The number of filter bits per registered target.
+const BITS_PER_TARGET: usize = 10;
+
+/// A Bloom filter of the targets in a [`Registry`].
+///
+/// [`TargetFilter::may_contain()`] never answers `false` for a registered target, and
+/// answers `true` for an unregistered one about once in a hundred lookups. Targets
+/// are matched exactly as registered, in their normalized form (e.g.
+/// `"/docs/install/"`).
+///
+/// # Examples
+///
+/// ```rust
+/// use link_bridge::{Redirector, Registry, TargetFilter};
+/// use std::fs;
+///
+/// Redirector::builder()
+///     .target("docs/install")
+///     .out_dir("doc_test_target_filter")
+///     .build()
+///     .unwrap()
+///     .write_redirect()
+///     .unwrap();
+/// Registry::load("doc_test_target_filter").unwrap().save_filter().unwrap();
+///
+/// // Later, without parsing registry.json
+/// let filter = TargetFilter::load("doc_test_target_filter").unwrap().unwrap();
+/// assert!(filter.may_contain("/docs/install/"));
+/// for target in ["/blog/launch/", "/docs/upgrade/"] {
+///     if !filter.may_contain(target) {
+///         // certainly new: no need to load the registry to check
+///     }
+/// }
+///
+/// fs::remove_dir_all("doc_test_target_filter").ok();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TargetFilter {
+    /// The filter bits, eight to a byte.
+    bits: Vec<u8>,
+    /// The number of bits set for each target.
+    hashes: u8,
+}
+
+impl TargetFilter {
+    /// Creates an empty filter sized for `capacity` targets.
+    fn with_capacity(capacity: usize) -> Self {
+        let bytes = (capacity.max(1) * BITS_PER_TARGET).div_ceil(8).max(8);
+        TargetFilter {
+            bits: vec![0; bytes],
+            hashes: HASHES,
+        }
+    }
+
+    /// Loads the target filter saved in an output directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The output directory (e.g. `"s"`)
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(TargetFilter))` - The saved filter
+    /// * `Ok(None)` - If the directory has no saved filter
+    /// * `Err(RedirectorError::FailedToReadRegistry)` - If the filter cannot be read,
+    ///   or is not a target filter
+    pub fn load<P: AsRef<Path>>(dir: P) -> Result<Option<Self>, RedirectorError> {
+        let path = dir.as_ref().join(TARGET_FILTER);
+        let read_error = |source| RedirectorError::FailedToReadRegistry {
+            path: path.clone(),
+            source,
+        };
+
+        let bytes = match fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(read_error(e)),
+        };
+        let filter = TargetFilter::from_bytes(&bytes).ok_or_else(|| {
+            read_error(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "not a link-bridge target filter",
+            ))
+        })?;
+        trace::debug!(path = %path.display(), bytes = bytes.len(), "loaded target filter");
+        Ok(Some(filter))
+    }
+
+    /// Reports whether a target may be registered.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - The normalized target (e.g. `"/docs/install/"`)
+    ///
+    /// # Returns
+    ///
+    /// `false` if the target is certainly not registered, and `true` if it probably
+    /// is; check [`Registry::contains_target()`] to be sure.
+    pub fn may_contain(&self, target: &str) -> bool {
+        self.positions(target)
+            .all(|bit| self.bits[bit / 8] & (1 << (bit % 8)) != 0)
+    }
+
+    /// Adds a target to the filter.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - The normalized target (e.g. `"/docs/install/"`)
+    pub fn insert(&mut self, target: &str) {
+        for bit in self.positions(target) {
+            self.bits[bit / 8] |= 1 << (bit % 8);
+        }
+    }
+
+    /// Returns the bits set for a target.
+    ///
+    /// The bits come from two hashes of the target combined as `h1 + i * h2`. The
+    /// hashes are FNV-1a and a SplitMix64 finalizer over it, which, unlike the
+    /// standard library's hasher, are the same on every platform and release, as a
+    /// saved filter needs.
+    fn positions(&self, target: &str) -> impl Iterator<Item = usize> {
+        let h1 = target
+            .bytes()
+            .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+            });
+        let mut h2 = h1.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        h2 = (h2 ^ (h2 >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        h2 = (h2 ^ (h2 >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        h2 = (h2 ^ (h2 >> 31)) | 1;
+
+        let len = self.bits.len() as u64 * 8;
+        (0..u64::from(self.hashes))
+            .map(move |i| (h1.wrapping_add(i.wrapping_mul(h2)) % len) as usize)
+    }
+
+    /// Renders the filter file: the magic bytes, the format version, the number of
+    /// hashes, and the filter bits.
+    pub(crate) fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(MAGIC.len() + 2 + self.bits.len());
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(VERSION);
+        bytes.push(self.hashes);
+        bytes.extend_from_slice(&self.bits);
+        bytes
+    }
+
+    /// Parses a filter file, or returns `None` if it is not one.
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let rest = bytes.strip_prefix(MAGIC)?;
+        let (&[version, hashes], bits) = rest.split_first_chunk::<2>()?;
+        if version != VERSION || hashes == 0 || bits.is_empty() {
+            return None;
+        }
+        Some(TargetFilter {
+            bits: bits.to_vec(),
+            hashes,
+        })
+    }
+}
+
+impl Registry {
+    /// Builds a target filter holding every target in the registry.
+    pub fn target_filter(&self) -> TargetFilter {
+        let mut filter = TargetFilter::with_capacity(self.len());
+        for (_, target, _) in self {
+            filter.insert(target);
+        }
+        filter
+    }
+
+    /// Writes the registry's target filter to `registry.bloom` in its output
+    /// directory, for [`TargetFilter::load()`] to read.
+    ///
+    /// Once the file exists, [`Registry::save()`] and the other ways of saving the
+    /// registry rebuild it, so it stays in step with `registry.json`. Call this again
+    /// to rebuild it after the registry was edited by other means; delete the file to
+    /// stop maintaining it.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the filter was written
+    /// * `Err(RedirectorError::DirectoryCreationError)` - If the directory cannot be
+    ///   created
+    /// * `Err(RedirectorError::FailedToWriteRegistry)` - If the filter cannot be
+    ///   written
+    pub fn save_filter(&self) -> Result<(), RedirectorError> {
+        FsSink.create_dir_all(self.dir()).map_err(|source| {
+            RedirectorError::DirectoryCreationError {
+                path: self.dir().to_path_buf(),
+                source,
+            }
+        })?;
+        self.write_filter(&mut FsSink)
+    }
+
+    /// Rebuilds the target filter through the sink if one has been saved.
+    pub(crate) fn refresh_filter<S: OutputSink + ?Sized>(
+        &self,
+        sink: &mut S,
+    ) -> Result<(), RedirectorError> {
+        let path = self.filter_path();
+        let exists = sink
+            .open_file(&path)
+            .map_err(|source| RedirectorError::FailedToReadRegistry {
+                path: path.clone(),
+                source,
+            })?
+            .is_some();
+        if exists {
+            self.write_filter(sink)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the target filter through the sink.
+    fn write_filter<S: OutputSink + ?Sized>(&self, sink: &mut S) -> Result<(), RedirectorError> {
+        let path = self.filter_path();
+        trace::debug!(path = %path.display(), entries = self.len(), "writing target filter");
+        sink.write_file(&path, &self.target_filter().to_bytes())
+            .map_err(|source| RedirectorError::FailedToWriteRegistry { path, source })
+    }
+
+    /// Returns the location of `registry.bloom` for this registry.
+    pub(crate) fn filter_path(&self) -> PathBuf {
+        self.dir().join(TARGET_FILTER)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::redirector::registry::REDIRECT_REGISTRY;
+    use crate::{MemorySink, Redirector};
+    use chrono::Utc;
+
+    fn test_dir(name: &str) -> String {
+        format!("{name}_{}", Utc::now().timestamp_nanos_opt().unwrap_or(0))
+    }
+
+    #[test]
+    fn test_filter_has_no_false_negatives() {
+        let mut registry = Registry::empty(PathBuf::from("s"));
+        for i in 0..1000 {
+            registry.insert(format!("/docs/{i}/"), format!("s/{i}.html"));
+        }
+        let filter = registry.target_filter();
+        assert!((0..1000).all(|i| filter.may_contain(&format!("/docs/{i}/"))));
+
+        let false_positives = (0..1000)
+            .filter(|i| filter.may_contain(&format!("/blog/{i}/")))
+            .count();
+        assert!(false_positives < 50, "{false_positives} false positives");
+    }
+
+    #[test]
+    fn test_filter_round_trip() {
+        let mut filter = TargetFilter::with_capacity(3);
+        filter.insert("/a/");
+        let parsed = TargetFilter::from_bytes(&filter.to_bytes()).unwrap();
+        assert_eq!(parsed, filter);
+        assert!(parsed.may_contain("/a/"));
+
+        assert_eq!(TargetFilter::from_bytes(b"LBBF"), None);
+        assert_eq!(TargetFilter::from_bytes(b"LBBF\x02\x07\xff"), None);
+        assert_eq!(TargetFilter::from_bytes(b"{}\n"), None);
+    }
+
+    #[test]
+    fn test_filter_kept_in_step_once_saved() {
+        let dir = test_dir("test_filter_kept_in_step_once_saved");
+        let write = |target| {
+            Redirector::builder()
+                .target(target)
+                .out_dir(&dir)
+                .build()
+                .unwrap()
+                .write_redirect()
+                .unwrap()
+        };
+
+        write("a");
+        assert_eq!(TargetFilter::load(&dir).unwrap(), None);
+
+        Registry::load(&dir).unwrap().save_filter().unwrap();
+        write("b");
+        let filter = TargetFilter::load(&dir).unwrap().unwrap();
+        assert!(filter.may_contain("/a/"));
+        assert!(filter.may_contain("/b/"));
+
+        fs::write(Path::new(&dir).join(TARGET_FILTER), "{}").unwrap();
+        assert!(matches!(
+            TargetFilter::load(&dir),
+            Err(RedirectorError::FailedToReadRegistry { .. })
+        ));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_filter_moves_with_relocated_registry() {
+        let old_dir = test_dir("test_filter_moves_old");
+        let new_dir = test_dir("test_filter_moves_new");
+        Redirector::builder()
+            .target("a")
+            .out_dir(&old_dir)
+            .build()
+            .unwrap()
+            .write_redirect()
+            .unwrap();
+        Registry::load(&old_dir).unwrap().save_filter().unwrap();
+
+        Registry::relocate(&old_dir, &new_dir).unwrap();
+        assert!(!Path::new(&old_dir).join(TARGET_FILTER).exists());
+        let filter = TargetFilter::load(&new_dir).unwrap().unwrap();
+        assert!(filter.may_contain("/a/"));
+
+        fs::remove_dir_all(&new_dir).unwrap();
+    }
+
+    #[test]
+    fn test_filter_refreshed_through_sink() {
+        let mut sink = MemorySink::new();
+        let registry = Registry::empty(PathBuf::from("s"));
+        registry.save_to(&mut sink).unwrap();
+        assert_eq!(sink.get(Path::new("s").join(TARGET_FILTER)), None);
+
+        sink.insert(Path::new("s").join(TARGET_FILTER), Vec::new());
+        let mut registry = registry;
+        registry.insert("/a/".to_string(), "s/a.html".to_string());
+        registry.save_to(&mut sink).unwrap();
+        let bytes = sink.get(Path::new("s").join(TARGET_FILTER)).unwrap();
+        assert!(TargetFilter::from_bytes(bytes).unwrap().may_contain("/a/"));
+        assert!(sink.get(Path::new("s").join(REDIRECT_REGISTRY)).is_some());
+    }
+}
@@ -0,0 +1,157 @@
+//! Exporting the registry as browser bookmarks.
+//!
+//! Every browser can import the Netscape bookmarks HTML format. This module provides
+//! `Registry::to_bookmarks_html()`, which renders each short link as a bookmark, so
+//! the full set of redirects can be clicked through for manual testing or shared with
+//! a team.
+
+use std::fmt::Write;
+
+use crate::redirector::target::ExternalUrl;
+use crate::redirector::{RedirectorError, Registry};
+
+impl Registry {
+    /// Renders the registry as a Netscape bookmarks HTML file.
+    ///
+    /// Each redirect becomes a bookmark titled with its short name and pointing at its
+    /// redirect page below `site_url`, with the target as the bookmark's description.
+    /// Bookmarks are ordered by short name and collected in a folder named after the
+    /// output directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `site_url` - The URL the output directory is served from, such as
+    ///   `"https://example.com/s"`
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The bookmarks file
+    /// * `Err(RedirectorError::InvalidTarget)` - If the site URL is malformed
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::{Redirector, Registry};
+    /// use std::fs;
+    ///
+    /// Redirector::builder()
+    ///     .target("docs/install")
+    ///     .out_dir("doc_test_bookmarks")
+    ///     .alias("install")
+    ///     .build()
+    ///     .unwrap()
+    ///     .write_redirect()
+    ///     .unwrap();
+    ///
+    /// let registry = Registry::load("doc_test_bookmarks").unwrap();
+    /// let html = registry.to_bookmarks_html("https://example.com/s").unwrap();
+    /// assert!(html.contains(r#"<A HREF="https://example.com/s/install.html">install</A>"#));
+    ///
+    /// fs::remove_dir_all("doc_test_bookmarks").ok();
+    /// ```
+    pub fn to_bookmarks_html<S: ToString>(&self, site_url: S) -> Result<String, RedirectorError> {
+        let site_url = ExternalUrl::new_base(site_url.to_string())?;
+
+        let mut entries: Vec<(&str, &str, &str)> = self
+            .iter()
+            .map(|(short_name, target, _)| {
+                let short_file = self.entry(target).map_or("", |entry| entry.short_file());
+                (short_name, target, short_file)
+            })
+            .collect();
+        entries.sort_unstable();
+
+        let mut html = String::from(
+            "<!DOCTYPE NETSCAPE-Bookmark-file-1>\n\
+             <META HTTP-EQUIV=\"Content-Type\" CONTENT=\"text/html; charset=UTF-8\">\n\
+             <TITLE>Bookmarks</TITLE>\n\
+             <H1>Bookmarks</H1>\n\
+             <DL><p>\n",
+        );
+        let _ = writeln!(
+            html,
+            "    <DT><H3>{}</H3>\n    <DL><p>",
+            escape(&self.dir().display().to_string())
+        );
+        for (short_name, target, short_file) in entries {
+            let _ = writeln!(
+                html,
+                "        <DT><A HREF=\"{}/{}\">{}</A>\n        <DD>{}",
+                escape(&site_url.to_string()),
+                escape(short_file),
+                escape(short_name),
+                escape(target)
+            );
+        }
+        html.push_str("    </DL><p>\n</DL><p>\n");
+
+        Ok(html)
+    }
+}
+
+/// Escapes text for inclusion in HTML, in element content or in an attribute quoted
+/// with either `"` or `'`.
+pub(crate) fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_to_bookmarks_html() {
+        let mut registry = Registry::empty(PathBuf::from("s"));
+        registry.insert("/docs/usage/".to_string(), "s/usage.html".to_string());
+        registry.insert(
+            "https://example.org/?a=1&b=2".to_string(),
+            "s/external.html".to_string(),
+        );
+
+        let html = registry
+            .to_bookmarks_html("https://example.com/s/")
+            .unwrap();
+
+        assert_eq!(
+            html,
+            "<!DOCTYPE NETSCAPE-Bookmark-file-1>
+<META HTTP-EQUIV=\"Content-Type\" CONTENT=\"text/html; charset=UTF-8\">
+<TITLE>Bookmarks</TITLE>
+<H1>Bookmarks</H1>
+<DL><p>
+    <DT><H3>s</H3>
+    <DL><p>
+        <DT><A HREF=\"https://example.com/s/external.html\">external</A>
+        <DD>https://example.org/?a=1&amp;b=2
+        <DT><A HREF=\"https://example.com/s/usage.html\">usage</A>
+        <DD>/docs/usage/
+    </DL><p>
+</DL><p>
+"
+        );
+    }
+
+    #[test]
+    fn test_to_bookmarks_html_invalid_site_url() {
+        let registry = Registry::empty(PathBuf::from("s"));
+
+        assert!(matches!(
+            registry.to_bookmarks_html("example.com/s"),
+            Err(RedirectorError::InvalidTarget(_))
+        ));
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(
+            escape("<a href=\"x\">&</a>"),
+            "&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;"
+        );
+        assert_eq!(escape("a' onmouseover='b"), "a&#39; onmouseover=&#39;b");
+    }
+}
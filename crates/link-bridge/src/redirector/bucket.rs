@@ -0,0 +1,256 @@
+//! Writing redirects and registries to cloud object storage.
+//!
+//! Serverless pipelines often have no filesystem to deploy from, and publish a static
+//! site straight to an S3, Google Cloud Storage, or Azure bucket. This module provides
+//! `ObjectStoreSink`, an `OutputSink` that stores every page and registry as an object
+//! in any store of the `object_store` crate, so redirects can be written to the bucket
+//! serving the site.
+
+use std::io;
+use std::path::{Component, Path};
+use std::sync::Arc;
+
+use object_store::path::{Path as ObjectPath, PathPart};
+use object_store::{ObjectStore, PutMode, PutOptions, PutPayload};
+use tokio::runtime::{Builder, Runtime};
+
+use crate::redirector::sink::OutputSink;
+
+/// An [`OutputSink`] that stores files as objects in an [`ObjectStore`].
+///
+/// Each file is stored under its path, relative to an optional prefix; directories are
+/// implied by the object names, so creating one does nothing. Pages are created with
+/// [`PutMode::Create`], so the store must support conditional writes for existing
+/// objects to be left in place.
+///
+/// The store's requests run on a runtime owned by the sink, as [`OutputSink`] is
+/// synchronous. Use the sink from synchronous code, or from within
+/// `tokio::task::spawn_blocking()` in an async service; calling it, or dropping it,
+/// directly on an async task panics.
+///
+/// Enable the `aws`, `gcp`, or `azure` feature of `object_store` for the store of the
+/// bucket.
+///
+/// # Examples
+///
+/// ```rust
+/// use link_bridge::{ObjectStoreSink, Redirector};
+/// use object_store::memory::InMemory;
+/// use std::sync::Arc;
+///
+/// let store = Arc::new(InMemory::new());
+/// let mut sink = ObjectStoreSink::new(store).unwrap().with_prefix("public");
+///
+/// let redirector = Redirector::builder()
+///     .target("summer/sale")
+///     .alias("promo")
+///     .build()
+///     .unwrap();
+/// redirector.write_redirect_to(&mut sink).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct ObjectStoreSink {
+    store: Arc<dyn ObjectStore>,
+    prefix: ObjectPath,
+    runtime: Runtime,
+}
+
+impl ObjectStoreSink {
+    /// Creates a sink storing files at the root of `store`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ObjectStoreSink)` - The sink
+    /// * `Err(io::Error)` - If the runtime running the store's requests cannot be
+    ///   started
+    pub fn new(store: Arc<dyn ObjectStore>) -> io::Result<Self> {
+        let runtime = Builder::new_current_thread().enable_all().build()?;
+        Ok(Self {
+            store,
+            prefix: ObjectPath::default(),
+            runtime,
+        })
+    }
+
+    /// Stores every file under `prefix`, such as the folder of the bucket the site is
+    /// served from.
+    pub fn with_prefix(mut self, prefix: &str) -> Self {
+        self.prefix = ObjectPath::from(prefix);
+        self
+    }
+
+    /// Returns the store the sink writes to.
+    pub fn store(&self) -> &Arc<dyn ObjectStore> {
+        &self.store
+    }
+
+    /// Returns the location of the object storing the file at `path`.
+    ///
+    /// Root and `.` components are ignored; `..` cannot be stored, as objects have no
+    /// parent directories to refer to.
+    fn location(&self, path: &Path) -> io::Result<ObjectPath> {
+        let mut parts: Vec<PathPart<'static>> = self
+            .prefix
+            .parts()
+            .map(|part| PathPart::from(part.as_ref().to_string()))
+            .collect();
+        for component in path.components() {
+            match component {
+                Component::Normal(part) => {
+                    parts.push(PathPart::from(part.to_string_lossy().into_owned()))
+                }
+                Component::ParentDir => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!("cannot store {} in an object store", path.display()),
+                    ))
+                }
+                Component::RootDir | Component::CurDir | Component::Prefix(_) => {}
+            }
+        }
+        Ok(ObjectPath::from_iter(parts))
+    }
+}
+
+impl OutputSink for ObjectStoreSink {
+    fn read_file(&mut self, path: &Path) -> io::Result<Option<Vec<u8>>> {
+        let location = self.location(path)?;
+        self.runtime.block_on(async {
+            match self.store.get(&location).await {
+                Ok(result) => Ok(Some(result.bytes().await.map_err(to_io)?.to_vec())),
+                Err(object_store::Error::NotFound { .. }) => Ok(None),
+                Err(e) => Err(to_io(e)),
+            }
+        })
+    }
+
+    fn create_dir_all(&mut self, _path: &Path) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn write_file(&mut self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        let location = self.location(path)?;
+        let payload = PutPayload::from(contents.to_vec());
+        self.runtime
+            .block_on(self.store.put(&location, payload))
+            .map(|_| ())
+            .map_err(to_io)
+    }
+
+    fn create_file(&mut self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        let location = self.location(path)?;
+        let payload = PutPayload::from(contents.to_vec());
+        let options = PutOptions {
+            mode: PutMode::Create,
+            ..PutOptions::default()
+        };
+        self.runtime
+            .block_on(self.store.put_opts(&location, payload, options))
+            .map(|_| ())
+            .map_err(to_io)
+    }
+}
+
+/// Converts an object store error into an I/O error of the matching kind.
+fn to_io(error: object_store::Error) -> io::Error {
+    let kind = match error {
+        object_store::Error::NotFound { .. } => io::ErrorKind::NotFound,
+        object_store::Error::AlreadyExists { .. } => io::ErrorKind::AlreadyExists,
+        object_store::Error::PermissionDenied { .. } => io::ErrorKind::PermissionDenied,
+        _ => io::ErrorKind::Other,
+    };
+    io::Error::new(kind, error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Redirector, RedirectorError, Registry};
+    use object_store::memory::InMemory;
+
+    fn object(store: &InMemory, location: &str) -> Option<String> {
+        let runtime = Builder::new_current_thread().build().unwrap();
+        runtime.block_on(async {
+            let result = store.get(&ObjectPath::from(location)).await.ok()?;
+            Some(String::from_utf8(result.bytes().await.unwrap().to_vec()).unwrap())
+        })
+    }
+
+    #[test]
+    fn test_write_redirect_to_object_store() {
+        let store = Arc::new(InMemory::new());
+        let mut sink = ObjectStoreSink::new(store.clone())
+            .unwrap()
+            .with_prefix("site/public");
+
+        let redirector = Redirector::builder()
+            .target("summer/sale")
+            .alias("promo")
+            .build()
+            .unwrap();
+        assert!(redirector
+            .write_redirect_to(&mut sink)
+            .unwrap()
+            .is_created());
+
+        let page = object(&store, "site/public/s/promo.html").unwrap();
+        assert!(page.contains("url=/summer/sale/"));
+        assert!(object(&store, "site/public/s/registry.json").is_some());
+
+        let registry = Registry::load_from(&mut sink, "s").unwrap();
+        assert_eq!(registry.get("/summer/sale/"), Some("s/promo.html"));
+
+        // Writing again finds the page in the registry
+        assert!(!redirector
+            .write_redirect_to(&mut sink)
+            .unwrap()
+            .is_created());
+    }
+
+    #[test]
+    fn test_object_store_sink_files() {
+        let mut sink = ObjectStoreSink::new(Arc::new(InMemory::new())).unwrap();
+        let path = Path::new("/s/./a.html");
+
+        assert_eq!(sink.read_file(path).unwrap(), None);
+        sink.create_file(path, b"one").unwrap();
+        assert_eq!(
+            sink.create_file(path, b"two").unwrap_err().kind(),
+            io::ErrorKind::AlreadyExists
+        );
+        assert_eq!(
+            sink.read_file(Path::new("s/a.html")).unwrap().unwrap(),
+            b"one"
+        );
+        sink.write_file(path, b"three").unwrap();
+        assert_eq!(sink.read_file(path).unwrap().unwrap(), b"three");
+
+        assert_eq!(
+            sink.write_file(Path::new("s/../a.html"), b"")
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::InvalidInput
+        );
+    }
+
+    #[test]
+    fn test_existing_object_is_not_replaced() {
+        let store = Arc::new(InMemory::new());
+        let mut sink = ObjectStoreSink::new(store.clone()).unwrap();
+        sink.write_file(Path::new("s/promo.html"), b"<p>Kept</p>")
+            .unwrap();
+
+        let result = Redirector::builder()
+            .target("summer/sale")
+            .alias("promo")
+            .build()
+            .unwrap()
+            .write_redirect_to(&mut sink);
+        assert!(matches!(
+            result,
+            Err(RedirectorError::FileWriteError { source, .. })
+                if source.kind() == io::ErrorKind::AlreadyExists
+        ));
+        assert_eq!(object(&store, "s/promo.html").unwrap(), "<p>Kept</p>");
+    }
+}
@@ -0,0 +1,1340 @@
+//! Fluent construction of `Redirector` instances.
+//!
+//! This module provides the `RedirectorBuilder` type, which collects all redirect
+//! options and validates them once when `build()` is called, instead of requiring a
+//! series of mutating setters after construction.
+
+use std::ffi::OsString;
+use std::path::PathBuf;
+#[cfg(feature = "chrono")]
+use std::sync::Arc;
+use std::time::Duration;
+
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Utc};
+
+use crate::redirector::alias::validate_alias;
+use crate::redirector::beacon::validate_endpoint;
+#[cfg(feature = "chrono")]
+use crate::redirector::clock::Clock;
+use crate::redirector::gate::Gate;
+use crate::redirector::hooks::Hooks;
+#[cfg(feature = "fluent")]
+use crate::redirector::locale::Locale;
+#[cfg(feature = "passphrase")]
+use crate::redirector::registry;
+#[cfg(feature = "signed-names")]
+use crate::redirector::signed::signed_name;
+#[cfg(feature = "social-preview")]
+use crate::redirector::social::SocialPreview;
+use crate::redirector::target::{
+    check_scheme, is_absolute_url, is_contact_url, ContactUrl, ExternalUrl, Target,
+};
+use crate::redirector::time::Timestamp;
+use crate::redirector::url_path::UrlPath;
+use crate::redirector::{
+    DuplicatePolicy, ExistingFilePolicy, HostAllowlist, OutputLayout, PathPolicy, RedirectHooks,
+    Redirector, RedirectorError,
+};
+
+/// A builder for configuring and validating a [`Redirector`].
+///
+/// Created with [`Redirector::builder()`]. Only the target is required; every other
+/// option falls back to the same default used by [`Redirector::new()`].
+///
+/// # Examples
+///
+/// ```rust
+/// use link_bridge::Redirector;
+///
+/// let redirector = Redirector::builder()
+///     .target("api/v1/users")
+///     .out_dir("s")
+///     .alias("promo")
+///     .delay(3)
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(redirector.short_file_name(), "promo.html");
+/// assert!(redirector.to_string().contains("content=\"3; url=/api/v1/users/\""));
+/// ```
+///
+/// External targets are only accepted when an allowlist is supplied:
+///
+/// ```rust
+/// use link_bridge::{HostAllowlist, Redirector, RedirectorError};
+///
+/// let result = Redirector::builder()
+///     .target("https://docs.example.com/guide")
+///     .allow_hosts(HostAllowlist::new(["docs.example.com"]))
+///     .build();
+/// assert!(result.is_ok());
+///
+/// let result = Redirector::builder().build();
+/// assert!(matches!(result, Err(RedirectorError::MissingTarget)));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RedirectorBuilder {
+    /// The raw target path or URL.
+    target: Option<String>,
+    /// The output directory for redirect files.
+    out_dir: Option<PathBuf>,
+    /// The custom short name to use instead of a generated one.
+    alias: Option<String>,
+    /// The clock short names are generated from.
+    #[cfg(feature = "chrono")]
+    clock: Option<Arc<dyn Clock>>,
+    /// The number of seconds to wait before redirecting.
+    delay: u32,
+    /// The validation policy for path targets.
+    policy: PathPolicy,
+    /// The raw site base URL.
+    base_url: Option<String>,
+    /// The hosts external targets may point at; `None` disables external targets.
+    allowlist: Option<HostAllowlist>,
+    /// Whether `mailto:` and `tel:` targets are accepted.
+    allow_contact: bool,
+    /// The callbacks invoked when the redirect is written.
+    hooks: Hooks,
+    /// When the redirect stops working, if it expires.
+    expiry: Option<Expiry>,
+    /// The page written instead of the redirect once it has expired.
+    expired_page: Option<String>,
+    /// The endpoint notified of each visit before redirecting.
+    beacon: Option<String>,
+    /// Whether the page waits for the visitor to press a button.
+    confirm: bool,
+    /// Whether the page is written as AMP HTML.
+    amp: bool,
+    /// Whether a plain-text file naming the target is written next to the page.
+    text_sidecar: bool,
+    /// Whether a JSON file of the redirect's metadata is written next to the page.
+    json_sidecar: bool,
+    /// Whether a comment recording where the page came from is written into it.
+    provenance: bool,
+    /// The passphrase the visitor must enter before redirecting.
+    #[cfg(feature = "passphrase")]
+    passphrase: Option<String>,
+    /// The key short names are signed with.
+    #[cfg(feature = "signed-names")]
+    signing_key: Option<Vec<u8>>,
+    /// The language tag of the page text.
+    #[cfg(feature = "fluent")]
+    locale: Option<String>,
+    /// The preview image written next to the page.
+    #[cfg(feature = "social-preview")]
+    social_preview: Option<SocialPreview>,
+    /// Whether the target page's title is fetched for the page.
+    #[cfg(feature = "fetch-title")]
+    fetch_title: bool,
+    /// Whether each new page is read back and checked after it is written.
+    verify_output: bool,
+    /// The relative weight of the target when the redirect is split.
+    weight: Option<u32>,
+    /// The other targets the redirect is split between, with their weights.
+    variants: Vec<(String, u32)>,
+    /// The campaign recorded with the redirect.
+    campaign: Option<String>,
+    /// The tags recorded with the redirect.
+    tags: Vec<String>,
+    /// Whether the page is written as a file or as a directory's index.
+    layout: OutputLayout,
+    /// The other output directories the redirect is written to.
+    mirrors: Vec<PathBuf>,
+    /// What a write does when the target is already registered.
+    duplicate_policy: DuplicatePolicy,
+    /// What a write does when a new page would replace a file not in the registry.
+    existing_file_policy: ExistingFilePolicy,
+}
+
+/// When a redirect built by a [`RedirectorBuilder`] expires.
+#[derive(Debug, Clone, Copy)]
+enum Expiry {
+    /// At a fixed time.
+    #[cfg(feature = "chrono")]
+    At(Timestamp),
+    /// A time after the redirector is built.
+    After(Duration),
+}
+
+impl RedirectorBuilder {
+    /// Creates a new builder with default options and no target.
+    pub fn new() -> Self {
+        RedirectorBuilder::default()
+    }
+
+    /// Sets the target to redirect to.
+    ///
+    /// The target is a site-relative URL path (e.g. `"api/v1/users"`), or an absolute
+    /// `http`/`https` URL when external targets have been enabled with
+    /// [`RedirectorBuilder::allow_hosts()`]. A `url::Url` can be passed as it is.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - The URL path or absolute URL to redirect to
+    pub fn target<S: ToString>(mut self, target: S) -> Self {
+        self.target = Some(target.to_string());
+        self
+    }
+
+    /// Sets the output directory where redirect HTML files will be stored.
+    ///
+    /// Defaults to "s".
+    ///
+    /// # Arguments
+    ///
+    /// * `out_dir` - A path-like value specifying the directory
+    pub fn out_dir<P: Into<PathBuf>>(mut self, out_dir: P) -> Self {
+        self.out_dir = Some(out_dir.into());
+        self
+    }
+
+    /// Adds an output directory the redirect is also written to.
+    ///
+    /// Call repeatedly to add several, for example to keep the staging and production
+    /// build trees in sync. [`Redirector::write_redirect()`] writes the redirect to the
+    /// output directory and to every mirror under the same short name, and changes no
+    /// registry unless every directory can be written.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - A path-like value specifying the mirror directory
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::{Redirector, Registry};
+    /// use std::fs;
+    ///
+    /// Redirector::builder()
+    ///     .target("docs/install")
+    ///     .out_dir("doc_test_mirror/production")
+    ///     .mirror("doc_test_mirror/staging")
+    ///     .alias("install")
+    ///     .build()
+    ///     .unwrap()
+    ///     .write_redirect()
+    ///     .unwrap();
+    ///
+    /// let staging = Registry::load("doc_test_mirror/staging").unwrap();
+    /// assert_eq!(staging.target_for("install"), Some("/docs/install/"));
+    ///
+    /// fs::remove_dir_all("doc_test_mirror").ok();
+    /// ```
+    pub fn mirror<P: Into<PathBuf>>(mut self, dir: P) -> Self {
+        self.mirrors.push(dir.into());
+        self
+    }
+
+    /// Generates the short name from a clock's time instead of the system time.
+    ///
+    /// With a clock, the name depends only on the clock's time and the target, so
+    /// tests and reproducible builds get the same names on every run. Pass a fixed
+    /// [`DateTime<Utc>`] to pin the time. Names generated this way drop the random
+    /// component and counter that keep ordinary names apart, so two redirects for the
+    /// same target at the same time share a name. An explicit
+    /// [`RedirectorBuilder::alias()`] takes precedence. Requires the `chrono` feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `clock` - The clock to read the time from
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use chrono::{TimeZone, Utc};
+    /// use link_bridge::Redirector;
+    ///
+    /// let redirector = Redirector::builder()
+    ///     .target("api/v1/users")
+    ///     .clock(Utc.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap())
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(redirector.short_file_name(), "2KL4OGXkyVU0Y.html");
+    /// ```
+    #[cfg(feature = "chrono")]
+    pub fn clock<C: Clock + 'static>(mut self, clock: C) -> Self {
+        self.clock = Some(Arc::new(clock));
+        self
+    }
+
+    /// Sets what a write does when the target is already registered.
+    ///
+    /// By default the existing short link is returned. See [`DuplicatePolicy`] for the
+    /// alternatives.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - The policy for targets already registered
+    pub fn on_duplicate(mut self, policy: DuplicatePolicy) -> Self {
+        self.duplicate_policy = policy;
+        self
+    }
+
+    /// Sets what a write does when a new page would replace a file not in the
+    /// registry.
+    ///
+    /// By default the write fails and the file is left untouched. See
+    /// [`ExistingFilePolicy`] for the alternatives.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - The policy for files already on disk
+    pub fn on_existing_file(mut self, policy: ExistingFilePolicy) -> Self {
+        self.existing_file_policy = policy;
+        self
+    }
+
+    /// Sets a custom short name to use instead of a generated one.
+    ///
+    /// The redirect file is written as `<alias>.html`. Aliases may contain ASCII
+    /// letters, digits, dashes, and underscores, must start with a letter or digit,
+    /// and are at most 64 characters long. Device names Windows reserves, such as
+    /// `con` or `nul`, are refused. [`slugify()`](crate::slugify) turns a title such
+    /// as `"Summer Sale!"` into an alias such as `"summer-sale"`.
+    ///
+    /// # Arguments
+    ///
+    /// * `alias` - The vanity short name (e.g. `"promo"`)
+    pub fn alias<S: ToString>(mut self, alias: S) -> Self {
+        self.alias = Some(alias.to_string());
+        self
+    }
+
+    /// Sets the number of seconds the page waits before redirecting.
+    ///
+    /// Defaults to 0, which redirects immediately.
+    ///
+    /// # Arguments
+    ///
+    /// * `seconds` - The redirect delay in seconds
+    pub fn delay(mut self, seconds: u32) -> Self {
+        self.delay = seconds;
+        self
+    }
+
+    /// Sets the validation policy applied to path targets.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - The policy controlling accepted segments and normalization
+    pub fn policy(mut self, policy: PathPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Sets the site base URL used to emit absolute target URLs.
+    ///
+    /// See [`Redirector::set_base_url()`] for details.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_url` - An absolute `http` or `https` URL without query or fragment
+    pub fn base_url<S: ToString>(mut self, base_url: S) -> Self {
+        self.base_url = Some(base_url.to_string());
+        self
+    }
+
+    /// Enables external targets on the hosts named in the allowlist.
+    ///
+    /// See [`Redirector::new_external()`] for details.
+    ///
+    /// # Arguments
+    ///
+    /// * `allowlist` - The hosts that external targets are permitted to point at
+    pub fn allow_hosts(mut self, allowlist: HostAllowlist) -> Self {
+        self.allowlist = Some(allowlist);
+        self
+    }
+
+    /// Enables `mailto:` and `tel:` targets, so a short link on a poster can start an
+    /// email or a phone call.
+    ///
+    /// Characters of a `mailto:` target that are unsafe in the page, such as spaces
+    /// and quotes, are percent-encoded, and spaces are removed from a `tel:` number.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::Redirector;
+    ///
+    /// let redirector = Redirector::builder()
+    ///     .target("mailto:support@example.com?subject=Poster enquiry")
+    ///     .allow_contact_targets()
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert!(redirector
+    ///     .to_string()
+    ///     .contains("url=mailto:support@example.com?subject=Poster%20enquiry"));
+    /// ```
+    pub fn allow_contact_targets(mut self) -> Self {
+        self.allow_contact = true;
+        self
+    }
+
+    /// Attaches callbacks that are invoked whenever the redirect is written.
+    ///
+    /// See [`RedirectHooks`] for when each callback is called.
+    ///
+    /// # Arguments
+    ///
+    /// * `hooks` - The callbacks to invoke on creation, reuse, or failure
+    pub fn hooks<H: RedirectHooks + 'static>(mut self, hooks: H) -> Self {
+        self.hooks = Hooks::new(hooks);
+        self
+    }
+
+    /// Sets the time at which the redirect stops working.
+    ///
+    /// Once it has passed, [`Registry::expire()`](crate::Registry::expire) replaces the
+    /// redirect's page with an expired page and removes it from the registry, and a
+    /// redirect written after it gets the expired page straight away. Requires the
+    /// `chrono` feature; [`RedirectorBuilder::ttl()`] sets an expiry without it.
+    ///
+    /// # Arguments
+    ///
+    /// * `expires_at` - When the redirect expires
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use chrono::{TimeZone, Utc};
+    /// use link_bridge::Redirector;
+    ///
+    /// let end_of_sale = Utc.with_ymd_and_hms(2030, 9, 1, 0, 0, 0).unwrap();
+    /// let redirector = Redirector::builder()
+    ///     .target("summer/sale")
+    ///     .expires_at(end_of_sale)
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(redirector.expires_at(), Some(end_of_sale));
+    /// ```
+    #[cfg(feature = "chrono")]
+    pub fn expires_at(mut self, expires_at: DateTime<Utc>) -> Self {
+        self.expiry = Some(Expiry::At(expires_at.into()));
+        self
+    }
+
+    /// Sets how long the redirect works for, counted from when it is built.
+    ///
+    /// Behaves like [`RedirectorBuilder::expires_at()`] with the time `ttl` after
+    /// [`RedirectorBuilder::build()`] is called.
+    ///
+    /// # Arguments
+    ///
+    /// * `ttl` - How long the redirect works for
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.expiry = Some(Expiry::After(ttl));
+        self
+    }
+
+    /// Sets the page written instead of the redirect once it has expired.
+    ///
+    /// Defaults to [`DEFAULT_EXPIRED_PAGE`](crate::DEFAULT_EXPIRED_PAGE). Pages
+    /// replaced by [`Registry::expire()`](crate::Registry::expire) get the page passed
+    /// to the sweep instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `html` - The complete HTML page
+    pub fn expired_page<S: ToString>(mut self, html: S) -> Self {
+        self.expired_page = Some(html.to_string());
+        self
+    }
+
+    /// Reports each visit to an endpoint before redirecting.
+    ///
+    /// The page's script posts the short name to the endpoint with
+    /// `navigator.sendBeacon()`, so click counts can be collected without a
+    /// server-side redirector. The endpoint is a site-relative path such as `"/hits"`
+    /// or an absolute `http`/`https` URL. Visitors without JavaScript are redirected
+    /// by the meta refresh and not counted.
+    ///
+    /// # Arguments
+    ///
+    /// * `endpoint` - The path or URL the beacon is posted to
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::Redirector;
+    ///
+    /// let redirector = Redirector::builder()
+    ///     .target("summer/sale")
+    ///     .alias("promo")
+    ///     .beacon("/hits")
+    ///     .build()
+    ///     .unwrap();
+    /// assert!(redirector
+    ///     .to_string()
+    ///     .contains(r#"navigator.sendBeacon("/hits", "promo");"#));
+    /// ```
+    pub fn beacon<S: ToString>(mut self, endpoint: S) -> Self {
+        self.beacon = Some(endpoint.to_string());
+        self
+    }
+
+    /// Makes the page wait for the visitor to confirm before redirecting.
+    ///
+    /// Instead of redirecting automatically, the page explains that the link leads to
+    /// a protected page and redirects once the visitor presses "Continue", so links to
+    /// sensitive internal resources are not followed by accident, prefetched, or
+    /// indexed. Any delay is ignored. Visitors without JavaScript get a plain link.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::Redirector;
+    ///
+    /// let page = Redirector::builder()
+    ///     .target("internal/wiki")
+    ///     .confirm()
+    ///     .build()
+    ///     .unwrap()
+    ///     .to_string();
+    /// assert!(page.contains(r#"<button type="submit">Continue</button>"#));
+    /// assert!(!page.contains("http-equiv=\"refresh\""));
+    /// ```
+    pub fn confirm(mut self) -> Self {
+        self.confirm = true;
+        self
+    }
+
+    /// Writes the page as valid AMP HTML.
+    ///
+    /// For publishers whose short links are served in AMP viewers and caches. AMP
+    /// forbids author scripts and the meta refresh, so instead of redirecting the page
+    /// names the target as its canonical URL, which AMP caches and search engines
+    /// follow, and links to it for the visitor. The delay, beacon, and split targets
+    /// are ignored. Pages made to wait with [`RedirectorBuilder::confirm()`] or a
+    /// passphrase still render their usual, scripted page.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::Redirector;
+    ///
+    /// let page = Redirector::builder()
+    ///     .target("news/launch")
+    ///     .amp()
+    ///     .build()
+    ///     .unwrap()
+    ///     .to_string();
+    /// assert!(page.contains(r#"<html amp lang="en-US">"#));
+    /// assert!(page.contains(r#"<link rel="canonical" href="/news/launch/">"#));
+    /// ```
+    pub fn amp(mut self) -> Self {
+        self.amp = true;
+        self
+    }
+
+    /// Writes a plain-text file holding only the target next to the page.
+    ///
+    /// The file is named after the page, as `<name>.txt` or `<name>/index.txt`, and
+    /// holds the target URL, made absolute by the
+    /// [`base_url()`](RedirectorBuilder::base_url) if one is set, and a newline, so
+    /// shell scripts and monitoring probes can resolve a short link without parsing
+    /// HTML. The file follows the page when its target is updated, and is removed
+    /// when the redirect is retired or removed. Split redirects name their first
+    /// target.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::{MemorySink, OutputSink, Redirector};
+    /// use std::path::Path;
+    ///
+    /// let mut sink = MemorySink::new();
+    /// Redirector::builder()
+    ///     .target("summer/sale")
+    ///     .alias("promo")
+    ///     .text_sidecar()
+    ///     .build()
+    ///     .unwrap()
+    ///     .write_redirect_to(&mut sink)
+    ///     .unwrap();
+    /// let text = sink.read_file(Path::new("s/promo.txt")).unwrap().unwrap();
+    /// assert_eq!(text, b"/summer/sale/\n");
+    /// ```
+    pub fn text_sidecar(mut self) -> Self {
+        self.text_sidecar = true;
+        self
+    }
+
+    /// Writes a JSON file of the redirect's metadata next to the page.
+    ///
+    /// The file is named after the page, as `<name>.json` or `<name>/index.json`, and
+    /// holds the short name, the target, and the tags recorded in the registry, with
+    /// the campaign and the times the redirect was created, expires, and was retired
+    /// when they are set, so edge functions and client tooling can read a redirect's
+    /// metadata at run time. The file is kept up to date when the target is updated
+    /// or the redirect retired, and removed with the redirect.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::{MemorySink, OutputSink, Redirector};
+    /// use std::path::Path;
+    ///
+    /// let mut sink = MemorySink::new();
+    /// Redirector::builder()
+    ///     .target("summer/sale")
+    ///     .alias("promo")
+    ///     .tag("flyers")
+    ///     .json_sidecar()
+    ///     .build()
+    ///     .unwrap()
+    ///     .write_redirect_to(&mut sink)
+    ///     .unwrap();
+    /// let json = sink.read_file(Path::new("s/promo.json")).unwrap().unwrap();
+    /// let metadata: serde_json::Value = serde_json::from_slice(&json).unwrap();
+    /// assert_eq!(metadata["target"], "/summer/sale/");
+    /// assert_eq!(metadata["tags"][0], "flyers");
+    /// ```
+    pub fn json_sidecar(mut self) -> Self {
+        self.json_sidecar = true;
+        self
+    }
+
+    /// Writes a comment recording where the page came from at the end of the page.
+    ///
+    /// The comment holds the version of link-bridge, the time the page was
+    /// generated, the target, and the short name the redirect is registered under, so
+    /// operators can trace any page found in production back to the run that wrote
+    /// it. Read it back with [`Provenance::parse()`](crate::Provenance::parse).
+    pub fn provenance(mut self) -> Self {
+        self.provenance = true;
+        self
+    }
+
+    /// Makes the page ask for a passphrase before redirecting.
+    ///
+    /// The page only stores a SHA-256 hash of the passphrase, salted with the short
+    /// name, and checks the visitor's entry against it in the browser. This keeps
+    /// casual visitors out but is not access control: the target remains in the page
+    /// source, so protect the target itself if it must stay private. The check uses
+    /// `crypto.subtle`, which browsers only provide to pages served over HTTPS or from
+    /// `localhost`. Implies [`RedirectorBuilder::confirm()`]. Requires the
+    /// `passphrase` feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `passphrase` - The passphrase the visitor must enter
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::Redirector;
+    ///
+    /// let page = Redirector::builder()
+    ///     .target("internal/payroll")
+    ///     .alias("payroll")
+    ///     .passphrase("open sesame")
+    ///     .build()
+    ///     .unwrap()
+    ///     .to_string();
+    /// assert!(page.contains(r#"<input type="password""#));
+    /// assert!(!page.contains("open sesame"));
+    /// ```
+    #[cfg(feature = "passphrase")]
+    pub fn passphrase<S: ToString>(mut self, passphrase: S) -> Self {
+        self.passphrase = Some(passphrase.to_string());
+        self
+    }
+
+    /// Derives the short name from an HMAC-SHA256 of the target under a secret key.
+    ///
+    /// Instead of a name built from the time, the redirect is named after a keyed hash
+    /// of its normalized target, so short names cannot be predicted or enumerated by
+    /// anyone without the key, and [`Registry::verify_signed()`] can check that a name
+    /// was issued by the holder of the key. The same target always gets the same name
+    /// under the same key. An explicit [`RedirectorBuilder::alias()`] takes
+    /// precedence. Requires the `signed-names` feature.
+    ///
+    /// [`Registry::verify_signed()`]: crate::Registry::verify_signed
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The secret key, which should be long and random
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::Redirector;
+    ///
+    /// let first = Redirector::builder()
+    ///     .target("api/v1/users")
+    ///     .signing_key("site secret")
+    ///     .build()
+    ///     .unwrap();
+    /// let second = Redirector::builder()
+    ///     .target("api/v1/users")
+    ///     .signing_key("site secret")
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(first.short_file_name(), second.short_file_name());
+    /// ```
+    #[cfg(feature = "signed-names")]
+    pub fn signing_key<K: AsRef<[u8]>>(mut self, key: K) -> Self {
+        self.signing_key = Some(key.as_ref().to_vec());
+        self
+    }
+
+    /// Writes the page text in another language.
+    ///
+    /// The redirect page, the confirmation page, and the pages written once the
+    /// redirect has expired or been retired use the translation bundled for the
+    /// language, and carry the tag in their `lang` attribute. A page set with
+    /// [`RedirectorBuilder::expired_page()`] is written as given. See
+    /// [`Locale`](crate::Locale) for the bundled languages. Requires the `fluent`
+    /// feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag` - A BCP 47 language tag (e.g. `"de"` or `"pt-BR"`)
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::Redirector;
+    ///
+    /// let page = Redirector::builder()
+    ///     .target("sommer/angebot")
+    ///     .locale("de")
+    ///     .build()
+    ///     .unwrap()
+    ///     .to_string();
+    /// assert!(page.contains(r#"<html lang="de">"#));
+    /// assert!(page.contains("<title>Weiterleitung</title>"));
+    /// ```
+    #[cfg(feature = "fluent")]
+    pub fn locale<S: ToString>(mut self, tag: S) -> Self {
+        self.locale = Some(tag.to_string());
+        self
+    }
+
+    /// Writes a preview image next to the page for link previews.
+    ///
+    /// The page references the image from its Open Graph and Twitter card tags, so
+    /// the short link is shown with a card naming it and the domain it leads to when
+    /// shared. See [`SocialPreview`](crate::SocialPreview). Requires the
+    /// `social-preview` feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `preview` - The preview, created with the URL the output directory is
+    ///   served from
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::{Redirector, SocialPreview};
+    ///
+    /// let page = Redirector::builder()
+    ///     .target("summer/sale")
+    ///     .alias("promo")
+    ///     .social_preview(SocialPreview::new("https://example.com/s"))
+    ///     .build()
+    ///     .unwrap()
+    ///     .to_string();
+    /// assert!(page.contains(r#"content="https://example.com/s/promo.svg""#));
+    /// ```
+    #[cfg(feature = "social-preview")]
+    pub fn social_preview(mut self, preview: SocialPreview) -> Self {
+        self.social_preview = Some(preview);
+        self
+    }
+
+    /// Titles the page after the page the redirect leads to.
+    ///
+    /// When the redirector is built, the target is requested and the `<title>` of
+    /// the page it answers with becomes the redirect page's `<title>` and
+    /// `og:title`, so link previews name the destination instead of showing "Page
+    /// Redirection". Path targets are requested below the
+    /// [`base_url()`](RedirectorBuilder::base_url), and are not requested without
+    /// one. A target that cannot be fetched within ten seconds, answers with an
+    /// error, or has no title leaves the default title in place; with the `tracing`
+    /// feature enabled the failure is logged as a warning. Requires the
+    /// `fetch-title` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use link_bridge::Redirector;
+    ///
+    /// let page = Redirector::builder()
+    ///     .target("blog/launch")
+    ///     .base_url("https://example.com")
+    ///     .fetch_title()
+    ///     .build()
+    ///     .unwrap()
+    ///     .to_string();
+    /// assert!(page.contains(r#"<meta property="og:title""#));
+    /// ```
+    #[cfg(feature = "fetch-title")]
+    pub fn fetch_title(mut self) -> Self {
+        self.fetch_title = true;
+        self
+    }
+
+    /// Checks each new page after it is written.
+    ///
+    /// The page is read back from the output and parsed, and the write fails with
+    /// [`RedirectorError::InconsistentPage`] unless its meta refresh, script, and
+    /// fallback link all lead to the target, so a template or escaping mistake is
+    /// caught before the page is deployed. Only the ways the page is meant to
+    /// redirect are expected: confirmation pages have no meta refresh, passphrase
+    /// pages no fallback link, AMP pages only a canonical and a fallback link, and
+    /// split pages are checked against their first target. Expired pages, reused pages, and alias stubs are not checked.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::{MemorySink, Redirector};
+    ///
+    /// let mut sink = MemorySink::new();
+    /// let outcome = Redirector::builder()
+    ///     .target("summer/sale")
+    ///     .verify_output()
+    ///     .build()
+    ///     .unwrap()
+    ///     .write_redirect_to(&mut sink)
+    ///     .unwrap();
+    /// assert!(outcome.is_created());
+    /// ```
+    pub fn verify_output(mut self) -> Self {
+        self.verify_output = true;
+        self
+    }
+
+    /// Sets the relative weight of the target when the redirect is split.
+    ///
+    /// Defaults to 1. Only used once other targets have been added with
+    /// [`RedirectorBuilder::variant()`].
+    ///
+    /// # Arguments
+    ///
+    /// * `weight` - The target's share of visits, relative to the variants' weights
+    pub fn weight(mut self, weight: u32) -> Self {
+        self.weight = Some(weight);
+        self
+    }
+
+    /// Splits the redirect with another target.
+    ///
+    /// The page picks one of the targets at random on every visit, each with a
+    /// probability proportional to its weight, so one short link can drive a simple
+    /// A/B test of landing pages. Variants are validated like the target. The registry
+    /// records the redirect under the target given to [`RedirectorBuilder::target()`],
+    /// which is also where visitors without JavaScript are sent.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - The URL path or absolute URL of another landing page
+    /// * `weight` - The variant's share of visits, relative to the other weights
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::Redirector;
+    ///
+    /// // Send three visitors in four to the new landing page
+    /// let redirector = Redirector::builder()
+    ///     .target("landing/current")
+    ///     .variant("landing/new", 3)
+    ///     .alias("launch")
+    ///     .build()
+    ///     .unwrap();
+    /// assert!(redirector
+    ///     .to_string()
+    ///     .contains(r#"[["/landing/current/", 1], ["/landing/new/", 3]]"#));
+    /// ```
+    pub fn variant<S: ToString>(mut self, target: S, weight: u32) -> Self {
+        self.variants.push((target.to_string(), weight));
+        self
+    }
+
+    /// Sets how the redirect page is laid out in the output directory.
+    ///
+    /// Defaults to [`OutputLayout::File`], which writes `s/<short>.html`. With
+    /// [`OutputLayout::Directory`], the page is written as `s/<short>/index.html` so
+    /// the short URL can be shared as `s/<short>/`, without the `.html` extension.
+    ///
+    /// # Arguments
+    ///
+    /// * `layout` - The layout of the redirect page
+    pub fn layout(mut self, layout: OutputLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Sets the campaign the redirect belongs to.
+    ///
+    /// The campaign is recorded with the redirect in the registry and returned by
+    /// [`RegistryEntry::campaign()`](crate::RegistryEntry::campaign), so links made
+    /// for one campaign can be told apart from the rest of the store.
+    ///
+    /// # Arguments
+    ///
+    /// * `campaign` - The campaign name (e.g. `"summer-2025"`)
+    pub fn campaign<S: ToString>(mut self, campaign: S) -> Self {
+        self.campaign = Some(campaign.to_string());
+        self
+    }
+
+    /// Files the redirect under a tag.
+    ///
+    /// Tags are recorded with the redirect in the registry; call repeatedly to add
+    /// several. [`Registry::tagged()`](crate::Registry::tagged) selects the redirects
+    /// filed under a tag for queries and exports.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag` - The tag (e.g. `"marketing"`)
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::{Redirector, Registry};
+    /// use std::fs;
+    ///
+    /// Redirector::builder()
+    ///     .target("summer/sale")
+    ///     .out_dir("doc_test_builder_tag")
+    ///     .campaign("summer-2025")
+    ///     .tag("marketing")
+    ///     .tag("social")
+    ///     .build()
+    ///     .unwrap()
+    ///     .write_redirect()
+    ///     .unwrap();
+    ///
+    /// let registry = Registry::load("doc_test_builder_tag").unwrap();
+    /// let entry = registry.entry("/summer/sale/").unwrap();
+    /// assert_eq!(entry.campaign(), Some("summer-2025"));
+    /// assert_eq!(entry.tags(), ["marketing", "social"]);
+    ///
+    /// fs::remove_dir_all("doc_test_builder_tag").ok();
+    /// ```
+    pub fn tag<S: ToString>(mut self, tag: S) -> Self {
+        self.tags.push(tag.to_string());
+        self
+    }
+
+    /// Validates the options and builds the [`Redirector`].
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Redirector)` - A configured redirector ready to generate redirect files
+    /// * `Err(RedirectorError::MissingTarget)` - If no target was set
+    /// * `Err(RedirectorError::InvalidUrlPath)` - If the path target is rejected by the policy
+    /// * `Err(RedirectorError::InvalidTarget)` - If the external or contact target, the
+    ///   base URL, or the social preview's site URL is malformed, or external targets
+    ///   are allowed and the target uses a scheme such as `javascript:`
+    /// * `Err(RedirectorError::DisallowedTarget)` - If the external host is not allowlisted
+    /// * `Err(RedirectorError::InvalidAlias)` - If the alias contains forbidden characters
+    /// * `Err(RedirectorError::ReservedName)` - If the alias is reserved by Windows
+    /// * `Err(RedirectorError::InvalidBeacon)` - If the beacon endpoint is malformed
+    /// * `Err(RedirectorError::ZeroWeight)` - If a target of a split redirect has no weight
+    /// * `Err(RedirectorError::UnsupportedLocale)` - If no translation is bundled for
+    ///   the locale
+    pub fn build(self) -> Result<Redirector, RedirectorError> {
+        let target = self.target.ok_or(RedirectorError::MissingTarget)?;
+        let target = parse_target(
+            target,
+            &self.policy,
+            self.allowlist.as_ref(),
+            self.allow_contact,
+        )?;
+
+        let mut redirector = Redirector::with_target(target);
+
+        if !self.variants.is_empty() {
+            let weight = self.weight.unwrap_or(1);
+            if weight == 0 {
+                return Err(RedirectorError::ZeroWeight(redirector.target.to_string()));
+            }
+            redirector.weight = weight;
+            for (variant, weight) in self.variants {
+                let variant = parse_target(
+                    variant,
+                    &self.policy,
+                    self.allowlist.as_ref(),
+                    self.allow_contact,
+                )?;
+                if weight == 0 {
+                    return Err(RedirectorError::ZeroWeight(variant.to_string()));
+                }
+                redirector.variants.push((variant, weight));
+            }
+        }
+
+        #[cfg(feature = "chrono")]
+        if let Some(clock) = &self.clock {
+            redirector.short_file_name =
+                Redirector::clocked_short_file_name(&redirector.target, clock.now().into());
+        }
+
+        // An alias replaces the signed name as it replaces the generated one
+        #[cfg(feature = "signed-names")]
+        if let Some(key) = self.signing_key {
+            let name = signed_name(&redirector.target.to_string(), &key);
+            redirector.short_file_name = OsString::from(format!("{name}.html"));
+        }
+
+        if let Some(alias) = self.alias {
+            validate_alias(&alias)?;
+            redirector.short_file_name = OsString::from(format!("{alias}.html"));
+        }
+
+        if let Some(out_dir) = self.out_dir {
+            redirector.path = out_dir;
+        }
+
+        if let Some(base_url) = self.base_url {
+            redirector.set_base_url(base_url)?;
+        }
+
+        redirector.delay = self.delay;
+        redirector.hooks = self.hooks;
+        redirector.target_case = self.policy.case();
+        redirector.expires_at = self.expiry.map(|expiry| match expiry {
+            #[cfg(feature = "chrono")]
+            Expiry::At(expires_at) => expires_at,
+            Expiry::After(ttl) => Timestamp::now().checked_add(ttl).unwrap_or(Timestamp::MAX),
+        });
+        redirector.expired_page = self.expired_page;
+        redirector.campaign = self.campaign;
+        redirector.layout = self.layout;
+        redirector.mirrors = self.mirrors;
+        redirector.duplicate_policy = self.duplicate_policy;
+        redirector.existing_file_policy = self.existing_file_policy;
+        redirector.verify_output = self.verify_output;
+        redirector.amp = self.amp;
+        redirector.text_sidecar = self.text_sidecar;
+        redirector.json_sidecar = self.json_sidecar;
+        redirector.provenance = self.provenance;
+        redirector.set_tags(self.tags);
+
+        if let Some(beacon) = self.beacon {
+            validate_endpoint(&beacon)?;
+            redirector.beacon = Some(beacon);
+        }
+
+        if self.confirm {
+            redirector.gate = Gate::Confirm;
+        }
+        #[cfg(feature = "passphrase")]
+        if let Some(passphrase) = self.passphrase {
+            let short_file_name = redirector.short_file_name.to_string_lossy().into_owned();
+            redirector.gate = Gate::passphrase(registry::short_name(&short_file_name), &passphrase);
+        }
+
+        #[cfg(feature = "fluent")]
+        if let Some(tag) = self.locale {
+            redirector.locale = Some(Locale::new(&tag)?);
+        }
+
+        #[cfg(feature = "social-preview")]
+        if let Some(preview) = self.social_preview {
+            redirector.social_preview = Some(preview.validate()?);
+        }
+
+        #[cfg(feature = "fetch-title")]
+        if self.fetch_title {
+            redirector.title = redirector.fetch_title();
+        }
+
+        Ok(redirector)
+    }
+}
+
+/// Parses a path target, an absolute URL target when external targets are allowed, or
+/// a contact target when contact targets are allowed.
+///
+/// When external or contact targets are allowed, targets with a scheme such as
+/// `javascript:` are rejected rather than read as paths.
+fn parse_target(
+    target: String,
+    policy: &PathPolicy,
+    allowlist: Option<&HostAllowlist>,
+    allow_contact: bool,
+) -> Result<Target, RedirectorError> {
+    if allowlist.is_some() || allow_contact {
+        check_scheme(&target)?;
+    }
+    if allow_contact && is_contact_url(&target) {
+        return Ok(Target::Contact(ContactUrl::new(target)?));
+    }
+
+    match allowlist {
+        Some(allowlist) if is_absolute_url(&target) => {
+            let url = ExternalUrl::new(target)?;
+            if !allowlist.permits(url.host()) {
+                return Err(RedirectorError::DisallowedTarget(url.host().to_string()));
+            }
+            Ok(Target::External(url))
+        }
+        _ => Ok(Target::Path(UrlPath::with_policy(target, policy)?)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{TargetError, TrailingSlash};
+
+    #[test]
+    fn test_build_minimal() {
+        let redirector = RedirectorBuilder::new().target("api/v1").build().unwrap();
+
+        assert_eq!(redirector.target.to_string(), "/api/v1/");
+        assert_eq!(redirector.path, PathBuf::from("s"));
+        assert_eq!(redirector.delay, 0);
+        assert_eq!(redirector.base_url, None);
+        assert!(!redirector.short_file_name.is_empty());
+    }
+
+    #[test]
+    fn test_build_missing_target() {
+        let result = RedirectorBuilder::new().out_dir("out").build();
+        assert!(matches!(result, Err(RedirectorError::MissingTarget)));
+    }
+
+    #[test]
+    fn test_build_all_options() {
+        let redirector = Redirector::builder()
+            .target("a/b")
+            .out_dir("custom")
+            .alias("promo")
+            .delay(3)
+            .base_url("https://example.com")
+            .build()
+            .unwrap();
+
+        assert_eq!(redirector.target.to_string(), "/a/b/");
+        assert_eq!(redirector.path, PathBuf::from("custom"));
+        assert_eq!(redirector.short_file_name, OsString::from("promo.html"));
+        assert_eq!(redirector.delay, 3);
+        assert!(redirector.base_url.is_some());
+    }
+
+    #[test]
+    fn test_build_invalid_alias() {
+        let result = Redirector::builder()
+            .target("a/b")
+            .alias("../escape")
+            .build();
+        assert!(matches!(result, Err(RedirectorError::InvalidAlias(_))));
+    }
+
+    #[test]
+    fn test_build_reserved_alias() {
+        let result = Redirector::builder().target("a/b").alias("Con").build();
+        assert!(matches!(result, Err(RedirectorError::ReservedName(name)) if name == "Con"));
+    }
+
+    #[test]
+    fn test_build_expiry() {
+        let hour = Duration::from_secs(3600);
+        let before = Timestamp::now();
+        let redirector = Redirector::builder()
+            .target("a/b")
+            .ttl(hour)
+            .build()
+            .unwrap();
+        let expires_at = redirector.expires_at.unwrap();
+        assert!(expires_at >= before.checked_add(hour).unwrap());
+        assert!(expires_at <= Timestamp::now().checked_add(hour).unwrap());
+
+        let redirector = Redirector::builder()
+            .target("a/b")
+            .ttl(Duration::MAX)
+            .build()
+            .unwrap();
+        assert_eq!(redirector.expires_at, Some(Timestamp::MAX));
+
+        let redirector = Redirector::builder().target("a/b").build().unwrap();
+        assert_eq!(redirector.expires_at, None);
+        assert!(redirector.page().contains("url=/a/b/"));
+    }
+
+    #[test]
+    fn test_build_invalid_path() {
+        let result = Redirector::builder().target("a?b").build();
+        assert!(matches!(result, Err(RedirectorError::InvalidUrlPath(_))));
+    }
+
+    #[test]
+    fn test_build_invalid_base_url() {
+        let result = Redirector::builder()
+            .target("a/b")
+            .base_url("example.com")
+            .build();
+        assert!(matches!(result, Err(RedirectorError::InvalidTarget(_))));
+    }
+
+    #[test]
+    fn test_build_with_policy() {
+        let redirector = Redirector::builder()
+            .target("docs/install/")
+            .policy(PathPolicy::strict().trailing_slash(TrailingSlash::Strip))
+            .build()
+            .unwrap();
+        assert_eq!(redirector.target.to_string(), "/docs/install");
+
+        let result = Redirector::builder()
+            .target("docs/v1.0")
+            .policy(PathPolicy::strict())
+            .build();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_build_external_requires_allowlist() {
+        let result = Redirector::builder()
+            .target("https://example.com/docs")
+            .build();
+        assert!(matches!(result, Err(RedirectorError::InvalidUrlPath(_))));
+
+        let redirector = Redirector::builder()
+            .target("https://example.com/docs")
+            .allow_hosts(HostAllowlist::new(["example.com"]))
+            .build()
+            .unwrap();
+        assert_eq!(redirector.target.to_string(), "https://example.com/docs");
+    }
+
+    #[test]
+    fn test_build_external_disallowed_host() {
+        let result = Redirector::builder()
+            .target("HTTPS://evil.com/")
+            .allow_hosts(HostAllowlist::new(["example.com"]))
+            .build();
+        assert!(matches!(result, Err(RedirectorError::DisallowedTarget(_))));
+    }
+
+    #[test]
+    fn test_build_external_dangerous_scheme() {
+        for target in [
+            "javascript:alert(1)",
+            "data:text/html,hi",
+            " JavaScript:alert(1)",
+        ] {
+            let result = Redirector::builder()
+                .target(target)
+                .allow_hosts(HostAllowlist::new(["example.com"]))
+                .build();
+            assert!(matches!(
+                result,
+                Err(RedirectorError::InvalidTarget(
+                    TargetError::DangerousScheme(_)
+                ))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_build_contact_targets() {
+        // Without opting in, a contact URL is read as a path
+        let redirector = Redirector::builder()
+            .target("mailto:support@example.com")
+            .build()
+            .unwrap();
+        assert!(matches!(redirector.target, Target::Path(_)));
+
+        let redirector = Redirector::builder()
+            .target("tel:+353 1 234 567")
+            .allow_contact_targets()
+            .build()
+            .unwrap();
+        assert_eq!(redirector.target.to_string(), "tel:+3531234567");
+
+        let page = Redirector::builder()
+            .target(r#"mailto:a@example.com?subject="</script>&body=x"#)
+            .allow_contact_targets()
+            .build()
+            .unwrap()
+            .to_string();
+        let url = "mailto:a@example.com?subject=%22%3C/script%3E";
+        assert!(page.contains(&format!(r#"url={url}&amp;body=x">"#)));
+        assert!(page.contains(&format!(r#"href = "{url}&body=x";"#)));
+        assert!(page.contains(&format!("href='{url}&amp;body=x'")));
+
+        // A quote in a contact target cannot break out of the fallback link
+        let page = Redirector::builder()
+            .target("mailto:o'brien@example.com?subject=' onmouseover='alert(1)")
+            .allow_contact_targets()
+            .build()
+            .unwrap()
+            .to_string();
+        assert!(!page.contains("href='mailto:o'"), "{page}");
+        assert!(page
+            .contains("href='mailto:o%27brien@example.com?subject=%27%20onmouseover=%27alert(1)'"));
+
+        for target in ["mailto:nobody", "tel:now", "javascript:alert(1)"] {
+            let result = Redirector::builder()
+                .target(target)
+                .allow_contact_targets()
+                .build();
+            assert!(
+                matches!(result, Err(RedirectorError::InvalidTarget(_))),
+                "{target} was accepted"
+            );
+        }
+        // Paths are still accepted
+        let redirector = Redirector::builder()
+            .target("contact")
+            .allow_contact_targets()
+            .build()
+            .unwrap();
+        assert_eq!(redirector.target.to_string(), "/contact/");
+    }
+
+    #[test]
+    fn test_build_path_with_allowlist() {
+        let redirector = Redirector::builder()
+            .target("api/v1")
+            .allow_hosts(HostAllowlist::new(["example.com"]))
+            .build()
+            .unwrap();
+        assert_eq!(redirector.target.to_string(), "/api/v1/");
+    }
+
+    #[test]
+    fn test_build_split() {
+        let redirector = Redirector::builder()
+            .target("landing/a")
+            .weight(2)
+            .variant("https://example.com/b", 1)
+            .allow_hosts(HostAllowlist::new(["example.com"]))
+            .build()
+            .unwrap();
+        assert_eq!(redirector.weight, 2);
+        assert_eq!(redirector.variants.len(), 1);
+        assert_eq!(
+            redirector.variants[0].0.to_string(),
+            "https://example.com/b"
+        );
+
+        let result = Redirector::builder()
+            .target("landing/a")
+            .variant("landing/b", 0)
+            .build();
+        assert!(matches!(result, Err(RedirectorError::ZeroWeight(ref t)) if t == "/landing/b/"));
+
+        let result = Redirector::builder()
+            .target("landing/a")
+            .weight(0)
+            .variant("landing/b", 1)
+            .build();
+        assert!(matches!(result, Err(RedirectorError::ZeroWeight(ref t)) if t == "/landing/a/"));
+
+        let result = Redirector::builder()
+            .target("landing/a")
+            .variant("https://example.com/b", 1)
+            .build();
+        assert!(matches!(result, Err(RedirectorError::InvalidUrlPath(_))));
+    }
+}
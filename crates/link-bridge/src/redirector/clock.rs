@@ -0,0 +1,126 @@
+//! Clocks driving short-name generation.
+//!
+//! Generated short names are built from the current time plus a random hash and a
+//! process-wide counter, so they differ on every run. Tests and reproducible builds
+//! need the same names each time instead. This module provides the `Clock` trait,
+//! which a builder can be given to supply the time, and makes names generated with
+//! it depend only on that time and the target.
+
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+
+/// A source of the time used when generating short names.
+///
+/// Attach a clock with [`RedirectorBuilder::clock()`](crate::RedirectorBuilder::clock).
+/// A redirector built with a clock takes its short name from the clock's time and a
+/// fixed hash of the target, without the random hash and counter used otherwise, so
+/// the same time and target always give the same name. A fixed [`DateTime<Utc>`] is
+/// itself a clock that always reports that time; [`SystemClock`] reports the current
+/// time. Requires the `chrono` feature.
+///
+/// # Examples
+///
+/// ```rust
+/// use chrono::{TimeZone, Utc};
+/// use link_bridge::Redirector;
+///
+/// let release = Utc.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap();
+/// let build = || {
+///     Redirector::builder()
+///         .target("api/v1/users")
+///         .clock(release)
+///         .build()
+///         .unwrap()
+/// };
+///
+/// assert_eq!(build().short_file_name(), build().short_file_name());
+/// ```
+pub trait Clock: fmt::Debug + Send + Sync {
+    /// Returns the time to generate a short name for.
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// The clock reporting the current system time. Requires the `chrono` feature.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+impl Clock for DateTime<Utc> {
+    fn now(&self) -> DateTime<Utc> {
+        *self
+    }
+}
+
+/// Hashes text with 32-bit FNV-1a.
+///
+/// Unlike the standard library's hashers, the result is the same on every run,
+/// platform, and Rust version, so names generated with a clock are reproducible.
+pub(crate) fn stable_hash(text: &str) -> u32 {
+    text.bytes().fold(0x811c_9dc5, |hash, byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Redirector;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_stable_hash() {
+        // Reference values of 32-bit FNV-1a
+        assert_eq!(stable_hash(""), 0x811c_9dc5);
+        assert_eq!(stable_hash("a"), 0xe40c_292c);
+        assert_eq!(stable_hash("foobar"), 0xbf9c_f968);
+    }
+
+    #[test]
+    fn test_clock_names_are_reproducible() {
+        let time = Utc.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap();
+        let name = |target: &str| {
+            Redirector::builder()
+                .target(target)
+                .clock(time)
+                .build()
+                .unwrap()
+                .short_file_name()
+        };
+
+        assert_eq!(name("docs/install"), name("docs/install"));
+        assert_ne!(name("docs/install"), name("docs/usage"));
+        assert_eq!(
+            name("docs/install"),
+            format!(
+                "{}.html",
+                base62::encode(
+                    u128::from(time.timestamp_millis() as u64) << 32
+                        | u128::from(stable_hash("/docs/install/"))
+                )
+            )
+            .as_str()
+        );
+
+        // An alias still takes precedence
+        let aliased = Redirector::builder()
+            .target("docs/install")
+            .alias("install")
+            .clock(time)
+            .build()
+            .unwrap();
+        assert_eq!(aliased.short_file_name(), "install.html");
+    }
+
+    #[test]
+    fn test_system_clock() {
+        let before = Utc::now();
+        let now = SystemClock.now();
+        assert!(before <= now && now <= Utc::now());
+    }
+}
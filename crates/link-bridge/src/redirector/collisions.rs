@@ -0,0 +1,189 @@
+//! Finding short names shared by several output directories.
+//!
+//! Each output directory's registry keeps its own short names unique, but nothing
+//! stops two directories from using the same short name for different targets. When
+//! the directories are merged under one site, one of the pages silently wins. This
+//! module provides `Registry::check_collisions()`, which reports such short names
+//! before the directories are merged.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+use std::path::{Path, PathBuf};
+
+use crate::redirector::{RedirectorError, Registry};
+
+/// A short name registered in several output directories with different targets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShortNameCollision {
+    /// The short name in conflict.
+    short_name: String,
+    /// Every directory registering the short name, with its target there.
+    registrations: Vec<(PathBuf, String)>,
+}
+
+impl ShortNameCollision {
+    /// Returns the short name in conflict.
+    pub fn short_name(&self) -> &str {
+        &self.short_name
+    }
+
+    /// Returns every directory registering the short name, with the target it leads
+    /// to there, in the order the directories were given.
+    pub fn registrations(&self) -> &[(PathBuf, String)] {
+        &self.registrations
+    }
+}
+
+impl Display for ShortNameCollision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:", self.short_name)?;
+        for (dir, target) in &self.registrations {
+            write!(f, " {} -> {target}", dir.display())?;
+        }
+        Ok(())
+    }
+}
+
+impl Registry {
+    /// Reports the short names that lead to different targets in different output
+    /// directories.
+    ///
+    /// The registry of each directory is loaded, and every short name, including
+    /// aliases, is compared across them. A short name registered in several
+    /// directories is only reported if the targets differ; the same redirect written
+    /// to each directory, as with
+    /// [`RedirectorBuilder::mirror()`](crate::RedirectorBuilder::mirror), is not a
+    /// collision.
+    ///
+    /// # Arguments
+    ///
+    /// * `dirs` - The output directories to compare
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<ShortNameCollision>)` - The colliding short names, in order of short
+    ///   name; empty if the directories can be merged safely
+    /// * `Err(RedirectorError::FailedToReadRegistry)` - If a registry cannot be read
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::{Redirector, Registry};
+    /// use std::fs;
+    ///
+    /// for (dir, target) in [
+    ///     ("doc_test_collisions/blog", "blog/launch"),
+    ///     ("doc_test_collisions/docs", "docs/launch"),
+    /// ] {
+    ///     Redirector::builder()
+    ///         .target(target)
+    ///         .out_dir(dir)
+    ///         .alias("launch")
+    ///         .build()
+    ///         .unwrap()
+    ///         .write_redirect()
+    ///         .unwrap();
+    /// }
+    ///
+    /// let collisions = Registry::check_collisions(&[
+    ///     "doc_test_collisions/blog",
+    ///     "doc_test_collisions/docs",
+    /// ])
+    /// .unwrap();
+    /// assert_eq!(collisions.len(), 1);
+    /// assert_eq!(collisions[0].short_name(), "launch");
+    ///
+    /// fs::remove_dir_all("doc_test_collisions").ok();
+    /// ```
+    pub fn check_collisions<P: AsRef<Path>>(
+        dirs: &[P],
+    ) -> Result<Vec<ShortNameCollision>, RedirectorError> {
+        let mut registrations: BTreeMap<String, Vec<(PathBuf, String)>> = BTreeMap::new();
+
+        for dir in dirs {
+            let dir = dir.as_ref();
+            let registry = Registry::load(dir)?;
+            for (short_name, target) in registry.short_names() {
+                registrations
+                    .entry(short_name.to_string())
+                    .or_default()
+                    .push((dir.to_path_buf(), target.to_string()));
+            }
+        }
+
+        Ok(registrations
+            .into_iter()
+            .filter(|(_, registrations)| {
+                registrations
+                    .iter()
+                    .any(|(_, target)| *target != registrations[0].1)
+            })
+            .map(|(short_name, registrations)| ShortNameCollision {
+                short_name,
+                registrations,
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Redirector;
+    use chrono::Utc;
+    use std::fs;
+
+    fn write(dir: &Path, target: &str, alias: &str) {
+        Redirector::builder()
+            .target(target)
+            .out_dir(dir)
+            .alias(alias)
+            .build()
+            .unwrap()
+            .write_redirect()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_check_collisions() {
+        let dir = PathBuf::from(format!(
+            "test_check_collisions_{}",
+            Utc::now().timestamp_nanos_opt().unwrap_or(0)
+        ));
+        let blog = dir.join("blog");
+        let docs = dir.join("docs");
+        let shop = dir.join("shop");
+        write(&blog, "blog/launch", "launch");
+        write(&docs, "docs/launch", "launch");
+        write(&blog, "about", "about");
+        write(&docs, "about", "about");
+        write(&docs, "install", "install");
+        write(&shop, "shop/sale", "sale");
+        let mut registry = Registry::load(&shop).unwrap();
+        registry.add_alias("/shop/sale/", "install").unwrap();
+        registry.save().unwrap();
+
+        let collisions = Registry::check_collisions(&[&blog, &docs, &shop]).unwrap();
+        let names: Vec<&str> = collisions.iter().map(|c| c.short_name()).collect();
+        assert_eq!(names, ["install", "launch"]);
+        assert_eq!(
+            collisions[1].registrations(),
+            [
+                (blog.clone(), "/blog/launch/".to_string()),
+                (docs.clone(), "/docs/launch/".to_string()),
+            ]
+        );
+        assert_eq!(
+            collisions[1].to_string(),
+            format!(
+                "launch: {} -> /blog/launch/ {} -> /docs/launch/",
+                blog.display(),
+                docs.display()
+            )
+        );
+
+        assert!(Registry::check_collisions(&[&blog]).unwrap().is_empty());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,845 @@
+//! Project-wide defaults read from `link-bridge.toml`.
+//!
+//! A project can keep its redirect settings, such as the output directory, base URL,
+//! and validation policy, in a `link-bridge.toml` file at its root, and CI jobs can
+//! steer them with `LINK_BRIDGE_*` environment variables. This module provides the
+//! `ProjectConfig` type that loads the file and the environment, merges in the
+//! user-level defaults kept in the platform config directory, and turns them into a
+//! pre-configured `RedirectorBuilder`, so call sites only supply the target.
+
+use std::env;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use directories::ProjectDirs;
+use serde::Deserialize;
+
+use crate::redirector::beacon::validate_endpoint;
+use crate::redirector::target::ExternalUrl;
+use crate::redirector::{
+    AuditLog, HostAllowlist, OutputLayout, PathPolicy, RedirectorBuilder, RedirectorError,
+    TargetCase, TrailingSlash, DEFAULT_MAX_DEPTH, DEFAULT_MAX_LENGTH,
+};
+#[cfg(feature = "fluent")]
+use crate::Locale;
+#[cfg(feature = "webhook")]
+use crate::{Webhook, WebhookFormat};
+
+/// The file name of the project configuration.
+pub const CONFIG_FILE_NAME: &str = "link-bridge.toml";
+
+/// Default redirect settings for a project, read from `link-bridge.toml`.
+///
+/// Every setting is optional; anything left out falls back to the default used by
+/// [`Redirector::new()`](crate::Redirector::new). Unknown keys are rejected so typos
+/// are reported instead of silently ignored.
+///
+/// ```toml
+/// out_dir = "static/s"
+/// mirrors = ["staging/static/s"]
+/// base_url = "https://example.com"
+/// site_url = "https://example.com/s" # the URL out_dir is served from
+/// delay = 0
+/// allow_hosts = ["docs.example.com"]
+/// beacon = "/hits"
+/// layout = "file"          # or "directory" for s/<short>/index.html
+/// webhook = "https://hooks.example.com/link-bridge" # requires the `webhook` feature
+/// webhook_format = "json"  # or "slack", "discord"
+/// locale = "de"            # requires the `fluent` feature
+/// audit_log = "audit.jsonl"
+///
+/// [policy]
+/// segments = "strict"       # or "standard"
+/// trailing_slash = "append" # or "strip", "preserve"
+/// max_length = 2048
+/// max_depth = 32
+/// target_case = "sensitive" # or "insensitive"
+/// ```
+///
+/// # Examples
+///
+/// ```rust
+/// use link_bridge::ProjectConfig;
+/// use std::fs;
+///
+/// fs::create_dir_all("doc_test_project_config").unwrap();
+/// fs::write(
+///     "doc_test_project_config/link-bridge.toml",
+///     "out_dir = \"doc_test_project_config/s\"\nbase_url = \"https://example.com\"\n",
+/// )
+/// .unwrap();
+///
+/// let config = ProjectConfig::from_project_root("doc_test_project_config").unwrap();
+/// let redirector = config.builder().target("api/v1/users").build().unwrap();
+/// assert!(redirector.to_string().contains("url=https://example.com/api/v1/users/"));
+///
+/// fs::remove_dir_all("doc_test_project_config").ok();
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectConfig {
+    /// The output directory for redirect files.
+    #[serde(default)]
+    out_dir: Option<PathBuf>,
+    /// The other output directories redirects are also written to.
+    #[serde(default)]
+    mirrors: Vec<PathBuf>,
+    /// The site base URL used to emit absolute target URLs.
+    #[serde(default)]
+    base_url: Option<String>,
+    /// The URL the output directory is served from, used to build short URLs.
+    #[serde(default)]
+    site_url: Option<String>,
+    /// The number of seconds to wait before redirecting.
+    #[serde(default)]
+    delay: Option<u32>,
+    /// The hosts external targets may point at.
+    #[serde(default)]
+    allow_hosts: Option<Vec<String>>,
+    /// The endpoint notified of each visit before redirecting.
+    #[serde(default)]
+    beacon: Option<String>,
+    /// Whether pages are written as files or as directories' indexes.
+    #[serde(default)]
+    layout: Option<OutputLayout>,
+    /// The URL notified when a redirect is created.
+    #[cfg(feature = "webhook")]
+    #[serde(default)]
+    webhook: Option<String>,
+    /// The payload posted to the webhook.
+    #[cfg(feature = "webhook")]
+    #[serde(default)]
+    webhook_format: WebhookFormat,
+    /// The language tag of the page text.
+    #[cfg(feature = "fluent")]
+    #[serde(default)]
+    locale: Option<String>,
+    /// The file every redirect operation is appended to.
+    #[serde(default)]
+    audit_log: Option<PathBuf>,
+    /// The validation policy for path targets.
+    #[serde(default)]
+    policy: PolicyConfig,
+}
+
+/// The `[policy]` table of `link-bridge.toml`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PolicyConfig {
+    /// Which characters path segments may contain.
+    #[serde(default)]
+    segments: SegmentsConfig,
+    /// How the trailing slash of a path target is normalized.
+    #[serde(default)]
+    trailing_slash: TrailingSlash,
+    /// The maximum length of a normalized path, in bytes.
+    #[serde(default = "default_max_length")]
+    max_length: usize,
+    /// The maximum number of path segments.
+    #[serde(default = "default_max_depth")]
+    max_depth: usize,
+    /// Whether targets differing only in letter case are the same redirect.
+    #[serde(default)]
+    target_case: TargetCase,
+}
+
+impl Default for PolicyConfig {
+    fn default() -> Self {
+        PolicyConfig {
+            segments: SegmentsConfig::default(),
+            trailing_slash: TrailingSlash::default(),
+            max_length: DEFAULT_MAX_LENGTH,
+            max_depth: DEFAULT_MAX_DEPTH,
+            target_case: TargetCase::default(),
+        }
+    }
+}
+
+fn default_max_length() -> usize {
+    DEFAULT_MAX_LENGTH
+}
+
+fn default_max_depth() -> usize {
+    DEFAULT_MAX_DEPTH
+}
+
+/// The segment rules that can be selected from configuration.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum SegmentsConfig {
+    /// The rules of [`PathPolicy::default()`].
+    #[default]
+    Standard,
+    /// The rules of [`PathPolicy::strict()`].
+    Strict,
+}
+
+impl ProjectConfig {
+    /// Loads the configuration from a TOML file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the configuration file
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ProjectConfig)` - The loaded configuration
+    /// * `Err(RedirectorError::FailedToReadConfig)` - If the file cannot be read or
+    ///   is not valid configuration
+    /// * `Err(RedirectorError::InvalidTarget)` - If the base URL, site URL, or webhook
+    ///   URL is malformed
+    /// * `Err(RedirectorError::InvalidBeacon)` - If the beacon endpoint is malformed
+    /// * `Err(RedirectorError::UnsupportedLocale)` - If no translation is bundled for
+    ///   the locale
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, RedirectorError> {
+        let path = path.as_ref();
+
+        let config = std::fs::read_to_string(path)
+            .and_then(|contents| {
+                toml::from_str::<ProjectConfig>(&contents)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+            })
+            .map_err(|source| RedirectorError::FailedToReadConfig {
+                path: path.to_path_buf(),
+                source,
+            })?;
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    /// Reads the configuration from `LINK_BRIDGE_*` environment variables.
+    ///
+    /// Each variable sets the setting of the same name in `link-bridge.toml`; unset
+    /// and empty variables leave the setting at its default. The policy and the
+    /// webhook format can only be set in the file.
+    ///
+    /// | Variable | Setting |
+    /// |----------|---------|
+    /// | `LINK_BRIDGE_OUT_DIR` | `out_dir` |
+    /// | `LINK_BRIDGE_MIRRORS` | `mirrors`, separated like `PATH` |
+    /// | `LINK_BRIDGE_BASE_URL` | `base_url` |
+    /// | `LINK_BRIDGE_SITE_URL` | `site_url` |
+    /// | `LINK_BRIDGE_DELAY` | `delay` |
+    /// | `LINK_BRIDGE_ALLOW_HOSTS` | `allow_hosts`, separated by commas |
+    /// | `LINK_BRIDGE_BEACON` | `beacon` |
+    /// | `LINK_BRIDGE_LAYOUT` | `layout` |
+    /// | `LINK_BRIDGE_WEBHOOK` | `webhook` |
+    /// | `LINK_BRIDGE_LOCALE` | `locale` |
+    /// | `LINK_BRIDGE_AUDIT_LOG` | `audit_log` |
+    ///
+    /// [`ProjectConfig::from_project_root()`] uses the variables as fallbacks for
+    /// the settings its file leaves out.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ProjectConfig)` - The configuration from the environment
+    /// * `Err(RedirectorError::FailedToReadConfig)` - If the delay or layout is not
+    ///   valid, reported with the variable's name as the path
+    /// * `Err(RedirectorError)` - The same validation errors as
+    ///   [`ProjectConfig::load()`]
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use link_bridge::ProjectConfig;
+    ///
+    /// // LINK_BRIDGE_OUT_DIR=public/s LINK_BRIDGE_BASE_URL=https://example.com
+    /// let config = ProjectConfig::from_env().unwrap();
+    /// let redirector = config.builder().target("api/v1/users").build().unwrap();
+    /// ```
+    pub fn from_env() -> Result<Self, RedirectorError> {
+        ProjectConfig::default().with_env_fallbacks(|name| env::var(name).ok())
+    }
+
+    /// Fills the settings left unset from the variables `var` looks up, and
+    /// validates the result.
+    fn with_env_fallbacks<F>(mut self, var: F) -> Result<Self, RedirectorError>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let var = |name: &str| var(name).filter(|value| !value.is_empty());
+        let invalid = |name: &str, error: String| RedirectorError::FailedToReadConfig {
+            path: PathBuf::from(name),
+            source: io::Error::new(io::ErrorKind::InvalidData, error),
+        };
+
+        if self.out_dir.is_none() {
+            self.out_dir = var("LINK_BRIDGE_OUT_DIR").map(PathBuf::from);
+        }
+        if self.mirrors.is_empty() {
+            if let Some(mirrors) = var("LINK_BRIDGE_MIRRORS") {
+                self.mirrors = env::split_paths(&mirrors).collect();
+            }
+        }
+        if self.base_url.is_none() {
+            self.base_url = var("LINK_BRIDGE_BASE_URL");
+        }
+        if self.site_url.is_none() {
+            self.site_url = var("LINK_BRIDGE_SITE_URL");
+        }
+        if self.delay.is_none() {
+            if let Some(delay) = var("LINK_BRIDGE_DELAY") {
+                let delay = delay
+                    .parse()
+                    .map_err(|e| invalid("LINK_BRIDGE_DELAY", format!("invalid delay: {e}")))?;
+                self.delay = Some(delay);
+            }
+        }
+        if self.allow_hosts.is_none() {
+            self.allow_hosts = var("LINK_BRIDGE_ALLOW_HOSTS").map(|hosts| {
+                hosts
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|host| !host.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            });
+        }
+        if self.beacon.is_none() {
+            self.beacon = var("LINK_BRIDGE_BEACON");
+        }
+        if self.layout.is_none() {
+            if let Some(layout) = var("LINK_BRIDGE_LAYOUT") {
+                self.layout = Some(match layout.as_str() {
+                    "file" => OutputLayout::File,
+                    "directory" => OutputLayout::Directory,
+                    _ => {
+                        return Err(invalid(
+                            "LINK_BRIDGE_LAYOUT",
+                            format!(
+                                "unknown layout {layout:?}, expected \"file\" or \"directory\""
+                            ),
+                        ))
+                    }
+                });
+            }
+        }
+        #[cfg(feature = "webhook")]
+        if self.webhook.is_none() {
+            self.webhook = var("LINK_BRIDGE_WEBHOOK");
+        }
+        #[cfg(feature = "fluent")]
+        if self.locale.is_none() {
+            self.locale = var("LINK_BRIDGE_LOCALE");
+        }
+        if self.audit_log.is_none() {
+            self.audit_log = var("LINK_BRIDGE_AUDIT_LOG").map(PathBuf::from);
+        }
+
+        self.validate()?;
+        Ok(self)
+    }
+
+    /// Checks the settings that are only validated when a redirect is built.
+    fn validate(&self) -> Result<(), RedirectorError> {
+        for url in [&self.base_url, &self.site_url].into_iter().flatten() {
+            ExternalUrl::new_base(url.clone())?;
+        }
+        if let Some(beacon) = &self.beacon {
+            validate_endpoint(beacon)?;
+        }
+        #[cfg(feature = "webhook")]
+        if let Some(webhook) = &self.webhook {
+            ExternalUrl::new(webhook.clone())?;
+        }
+        #[cfg(feature = "fluent")]
+        if let Some(locale) = &self.locale {
+            Locale::new(locale)?;
+        }
+        Ok(())
+    }
+
+    /// Loads `link-bridge.toml` from a project root directory.
+    ///
+    /// Settings the file leaves out, or all settings if the project has no
+    /// configuration file, fall back to the `LINK_BRIDGE_*` environment variables
+    /// read by [`ProjectConfig::from_env()`], and then to their defaults.
+    ///
+    /// # Arguments
+    ///
+    /// * `root` - The project root directory
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ProjectConfig)` - The loaded (or default) configuration
+    /// * `Err(RedirectorError)` - The same errors as [`ProjectConfig::load()`] and
+    ///   [`ProjectConfig::from_env()`]
+    pub fn from_project_root<P: AsRef<Path>>(root: P) -> Result<Self, RedirectorError> {
+        let path = root.as_ref().join(CONFIG_FILE_NAME);
+
+        let config = if path.exists() {
+            ProjectConfig::load(path)?
+        } else {
+            ProjectConfig::default()
+        };
+        config.with_env_fallbacks(|name| env::var(name).ok())
+    }
+
+    /// Returns the path of the user-level configuration file, which holds defaults
+    /// shared by all of a user's projects.
+    ///
+    /// The file is `link-bridge.toml` in the platform config directory:
+    /// `$XDG_CONFIG_HOME/link-bridge/` (usually `~/.config/link-bridge/`) on Linux,
+    /// `~/Library/Application Support/link-bridge/` on macOS, and
+    /// `%APPDATA%\link-bridge\config\` on Windows.
+    ///
+    /// # Returns
+    ///
+    /// The path, or `None` if the platform has no home directory.
+    pub fn user_config_path() -> Option<PathBuf> {
+        ProjectDirs::from("", "", "link-bridge")
+            .map(|dirs| dirs.config_dir().join(CONFIG_FILE_NAME))
+    }
+
+    /// Loads the user-level configuration file from
+    /// [`ProjectConfig::user_config_path()`].
+    ///
+    /// If the user has no configuration file, the default configuration is
+    /// returned. Merge the result under a project's configuration with
+    /// [`ProjectConfig::with_defaults()`].
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ProjectConfig)` - The loaded (or default) configuration
+    /// * `Err(RedirectorError)` - The same errors as [`ProjectConfig::load()`]
+    pub fn user() -> Result<Self, RedirectorError> {
+        match ProjectConfig::user_config_path() {
+            Some(path) if path.exists() => ProjectConfig::load(path),
+            _ => Ok(ProjectConfig::default()),
+        }
+    }
+
+    /// Fills the settings this configuration leaves out from `defaults`.
+    ///
+    /// Settings this configuration sets are kept as they are. The webhook format is
+    /// taken along with the webhook, and the validation policy is taken as a whole
+    /// when this configuration leaves every policy setting at its default.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use link_bridge::ProjectConfig;
+    ///
+    /// // A project's link-bridge.toml, over the user's own defaults
+    /// let config = ProjectConfig::from_project_root(".")
+    ///     .unwrap()
+    ///     .with_defaults(ProjectConfig::user().unwrap());
+    /// ```
+    pub fn with_defaults(mut self, defaults: ProjectConfig) -> Self {
+        self.out_dir = self.out_dir.or(defaults.out_dir);
+        if self.mirrors.is_empty() {
+            self.mirrors = defaults.mirrors;
+        }
+        self.base_url = self.base_url.or(defaults.base_url);
+        self.site_url = self.site_url.or(defaults.site_url);
+        self.delay = self.delay.or(defaults.delay);
+        self.allow_hosts = self.allow_hosts.or(defaults.allow_hosts);
+        self.beacon = self.beacon.or(defaults.beacon);
+        self.layout = self.layout.or(defaults.layout);
+        #[cfg(feature = "webhook")]
+        if self.webhook.is_none() {
+            self.webhook = defaults.webhook;
+            self.webhook_format = defaults.webhook_format;
+        }
+        #[cfg(feature = "fluent")]
+        {
+            self.locale = self.locale.or(defaults.locale);
+        }
+        self.audit_log = self.audit_log.or(defaults.audit_log);
+        if self.policy == PolicyConfig::default() {
+            self.policy = defaults.policy;
+        }
+        self
+    }
+
+    /// Returns the configured output directory, if any.
+    pub fn out_dir(&self) -> Option<&Path> {
+        self.out_dir.as_deref()
+    }
+
+    /// Returns the configured mirror directories.
+    pub fn mirrors(&self) -> &[PathBuf] {
+        &self.mirrors
+    }
+
+    /// Returns the configured site base URL, if any.
+    pub fn base_url(&self) -> Option<&str> {
+        self.base_url.as_deref()
+    }
+
+    /// Returns the configured URL the output directory is served from, if any.
+    ///
+    /// Pass it to [`RedirectOutcome::short_url()`](crate::RedirectOutcome::short_url)
+    /// to turn a written redirect into the short URL to share.
+    pub fn site_url(&self) -> Option<&str> {
+        self.site_url.as_deref()
+    }
+
+    /// Returns the configured redirect delay in seconds.
+    pub fn delay(&self) -> u32 {
+        self.delay.unwrap_or_default()
+    }
+
+    /// Returns the configured webhook URL, if any.
+    #[cfg(feature = "webhook")]
+    pub fn webhook(&self) -> Option<&str> {
+        self.webhook.as_deref()
+    }
+
+    /// Returns the configured format of webhook notifications.
+    #[cfg(feature = "webhook")]
+    pub fn webhook_format(&self) -> WebhookFormat {
+        self.webhook_format
+    }
+
+    /// Returns the configured language tag of the page text, if any.
+    #[cfg(feature = "fluent")]
+    pub fn locale(&self) -> Option<&str> {
+        self.locale.as_deref()
+    }
+
+    /// Returns the configured audit log, if any.
+    pub fn audit_log(&self) -> Option<AuditLog> {
+        self.audit_log.as_deref().map(AuditLog::new)
+    }
+
+    /// Returns the configured validation policy for path targets.
+    pub fn policy(&self) -> PathPolicy {
+        let policy = match self.policy.segments {
+            SegmentsConfig::Standard => PathPolicy::default(),
+            SegmentsConfig::Strict => PathPolicy::strict(),
+        };
+
+        policy
+            .trailing_slash(self.policy.trailing_slash)
+            .max_length(self.policy.max_length)
+            .max_depth(self.policy.max_depth)
+            .target_case(self.policy.target_case)
+    }
+
+    /// Returns a [`RedirectorBuilder`] pre-filled with the configured defaults.
+    ///
+    /// Options set on the returned builder override the configuration. A configured
+    /// webhook and audit log are attached as the builder's hooks.
+    pub fn builder(&self) -> RedirectorBuilder {
+        let mut builder = RedirectorBuilder::new()
+            .delay(self.delay())
+            .layout(self.layout.unwrap_or_default())
+            .policy(self.policy());
+
+        if let Some(out_dir) = &self.out_dir {
+            builder = builder.out_dir(out_dir);
+        }
+        for mirror in &self.mirrors {
+            builder = builder.mirror(mirror);
+        }
+        if let Some(base_url) = &self.base_url {
+            builder = builder.base_url(base_url);
+        }
+        if let Some(hosts) = &self.allow_hosts {
+            builder = builder.allow_hosts(HostAllowlist::new(hosts));
+        }
+        if let Some(beacon) = &self.beacon {
+            builder = builder.beacon(beacon);
+        }
+        #[cfg(feature = "fluent")]
+        if let Some(locale) = &self.locale {
+            builder = builder.locale(locale);
+        }
+        #[cfg(feature = "webhook")]
+        let webhook = self.webhook.as_ref().and_then(|url| {
+            let webhook = Webhook::new(url).ok()?.format(self.webhook_format);
+            Some(match &self.site_url {
+                Some(site_url) => webhook.site_url(site_url),
+                None => webhook,
+            })
+        });
+        #[cfg(not(feature = "webhook"))]
+        let webhook: Option<AuditLog> = None;
+        builder = match (webhook, self.audit_log()) {
+            (Some(webhook), Some(audit)) => builder.hooks((webhook, audit)),
+            (Some(webhook), None) => builder.hooks(webhook),
+            (None, Some(audit)) => builder.hooks(audit),
+            (None, None) => builder,
+        };
+
+        builder
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use std::fs;
+
+    fn parse(toml: &str) -> ProjectConfig {
+        toml::from_str(toml).unwrap()
+    }
+
+    #[test]
+    fn test_config_defaults() {
+        let config = parse("");
+
+        assert_eq!(config, ProjectConfig::default());
+        assert_eq!(config.out_dir(), None);
+        let redirector = config.builder().target("a/b").build().unwrap();
+        assert_eq!(redirector.path, PathBuf::from("s"));
+        assert_eq!(redirector.target.to_string(), "/a/b/");
+    }
+
+    #[test]
+    fn test_config_all_options() {
+        let config = parse(
+            r#"
+            out_dir = "public/s"
+            mirrors = ["staging/s"]
+            base_url = "https://example.com"
+            site_url = "https://example.com/s"
+            delay = 2
+            allow_hosts = ["docs.example.com"]
+            beacon = "/hits"
+            layout = "directory"
+            audit_log = "logs/audit.jsonl"
+
+            [policy]
+            segments = "strict"
+            trailing_slash = "strip"
+            max_depth = 3
+            target_case = "insensitive"
+            "#,
+        );
+
+        let redirector = config.builder().target("a/b/").build().unwrap();
+        assert_eq!(redirector.path, PathBuf::from("public/s"));
+        assert_eq!(redirector.mirrors, [PathBuf::from("staging/s")]);
+        assert_eq!(redirector.delay, 2);
+        assert_eq!(redirector.target.to_string(), "/a/b");
+        assert!(redirector.base_url.is_some());
+        assert_eq!(config.site_url(), Some("https://example.com/s"));
+        assert_eq!(redirector.beacon.as_deref(), Some("/hits"));
+        assert_eq!(redirector.target_case, TargetCase::Insensitive);
+        assert_eq!(redirector.layout, OutputLayout::Directory);
+        assert_eq!(config.audit_log(), Some(AuditLog::new("logs/audit.jsonl")));
+
+        assert!(config.builder().target("a/b.c/d").build().is_err());
+        assert!(config.builder().target("a/b/c/d").build().is_err());
+        assert!(config
+            .builder()
+            .target("https://docs.example.com/guide")
+            .build()
+            .is_ok());
+    }
+
+    #[cfg(feature = "fluent")]
+    #[test]
+    fn test_config_locale() {
+        let config = parse(r#"locale = "pt-BR""#);
+        assert_eq!(config.locale(), Some("pt-BR"));
+
+        let redirector = config.builder().target("a").build().unwrap();
+        assert_eq!(redirector.locale.unwrap().tag(), "pt-BR");
+    }
+
+    #[cfg(feature = "webhook")]
+    #[test]
+    fn test_config_webhook_format() {
+        let config = parse(
+            r#"
+            webhook = "https://hooks.slack.com/services/T000/B000/XXXX"
+            webhook_format = "slack"
+            "#,
+        );
+        assert_eq!(config.webhook_format(), WebhookFormat::Slack);
+        assert_eq!(parse("").webhook_format(), WebhookFormat::Json);
+        assert!(toml::from_str::<ProjectConfig>(r#"webhook_format = "teams""#).is_err());
+    }
+
+    #[test]
+    fn test_config_builder_overrides() {
+        let config = parse(r#"out_dir = "public/s""#);
+        let redirector = config
+            .builder()
+            .target("a")
+            .out_dir("other")
+            .build()
+            .unwrap();
+        assert_eq!(redirector.path, PathBuf::from("other"));
+    }
+
+    #[test]
+    fn test_config_rejects_unknown_keys() {
+        assert!(toml::from_str::<ProjectConfig>("outdir = \"s\"").is_err());
+        assert!(toml::from_str::<ProjectConfig>("[policy]\nsegments = \"loose\"").is_err());
+    }
+
+    #[test]
+    fn test_config_load_errors() {
+        let dir = format!(
+            "test_config_load_errors_{}",
+            Utc::now().timestamp_nanos_opt().unwrap_or(0)
+        );
+        fs::create_dir_all(&dir).unwrap();
+        let path = PathBuf::from(&dir).join(CONFIG_FILE_NAME);
+
+        fs::write(&path, "delay = \"soon\"").unwrap();
+        match ProjectConfig::load(&path) {
+            Err(RedirectorError::FailedToReadConfig {
+                path: error_path, ..
+            }) => {
+                assert_eq!(error_path, path)
+            }
+            other => panic!("expected FailedToReadConfig, got {other:?}"),
+        }
+
+        fs::write(&path, "base_url = \"example.com\"").unwrap();
+        assert!(matches!(
+            ProjectConfig::load(&path),
+            Err(RedirectorError::InvalidTarget(_))
+        ));
+
+        fs::write(&path, "site_url = \"example.com/s\"").unwrap();
+        assert!(matches!(
+            ProjectConfig::load(&path),
+            Err(RedirectorError::InvalidTarget(_))
+        ));
+
+        fs::write(&path, "beacon = \"hits\"").unwrap();
+        assert!(matches!(
+            ProjectConfig::load(&path),
+            Err(RedirectorError::InvalidBeacon(_))
+        ));
+
+        #[cfg(feature = "webhook")]
+        {
+            fs::write(&path, "webhook = \"hooks.example.com\"").unwrap();
+            assert!(matches!(
+                ProjectConfig::load(&path),
+                Err(RedirectorError::InvalidTarget(_))
+            ));
+        }
+
+        #[cfg(feature = "fluent")]
+        {
+            fs::write(&path, "locale = \"tlh\"").unwrap();
+            assert!(matches!(
+                ProjectConfig::load(&path),
+                Err(RedirectorError::UnsupportedLocale(_))
+            ));
+        }
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_config_env_fallbacks() {
+        let vars = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(var, _)| *var == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+
+        let config = ProjectConfig::default()
+            .with_env_fallbacks(vars(&[
+                ("LINK_BRIDGE_OUT_DIR", "public/s"),
+                ("LINK_BRIDGE_BASE_URL", "https://example.com"),
+                ("LINK_BRIDGE_DELAY", "3"),
+                (
+                    "LINK_BRIDGE_ALLOW_HOSTS",
+                    "docs.example.com, blog.example.com",
+                ),
+                ("LINK_BRIDGE_LAYOUT", "directory"),
+                ("LINK_BRIDGE_BEACON", ""),
+            ]))
+            .unwrap();
+        assert_eq!(config.out_dir(), Some(Path::new("public/s")));
+        assert_eq!(config.delay(), 3);
+        assert_eq!(config.beacon, None);
+        let redirector = config
+            .builder()
+            .target("https://blog.example.com/post")
+            .build()
+            .unwrap();
+        assert_eq!(redirector.layout, OutputLayout::Directory);
+        assert!(redirector.base_url.is_some());
+
+        // Settings from the file win over the environment
+        let config = parse("out_dir = \"site/s\"\ndelay = 0")
+            .with_env_fallbacks(vars(&[
+                ("LINK_BRIDGE_OUT_DIR", "public/s"),
+                ("LINK_BRIDGE_DELAY", "3"),
+            ]))
+            .unwrap();
+        assert_eq!(config.out_dir(), Some(Path::new("site/s")));
+        assert_eq!(config.delay(), 0);
+
+        for (var, value) in [
+            ("LINK_BRIDGE_DELAY", "soon"),
+            ("LINK_BRIDGE_LAYOUT", "flat"),
+        ] {
+            let lookup = move |name: &str| (name == var).then(|| value.to_string());
+            match ProjectConfig::default().with_env_fallbacks(lookup) {
+                Err(RedirectorError::FailedToReadConfig { path, .. }) => {
+                    assert_eq!(path, PathBuf::from(var))
+                }
+                other => panic!("expected FailedToReadConfig, got {other:?}"),
+            }
+        }
+        assert!(matches!(
+            ProjectConfig::default()
+                .with_env_fallbacks(vars(&[("LINK_BRIDGE_BASE_URL", "example.com")])),
+            Err(RedirectorError::InvalidTarget(_))
+        ));
+    }
+
+    #[test]
+    fn test_config_with_defaults() {
+        let user = parse(
+            r#"
+            out_dir = "public/s"
+            base_url = "https://example.com"
+            delay = 2
+            audit_log = "audit.jsonl"
+
+            [policy]
+            segments = "strict"
+            "#,
+        );
+
+        let config = parse(
+            r#"
+            out_dir = "static/s"
+            delay = 0
+            "#,
+        )
+        .with_defaults(user.clone());
+        assert_eq!(config.out_dir(), Some(Path::new("static/s")));
+        assert_eq!(config.delay(), 0);
+        assert_eq!(config.base_url(), Some("https://example.com"));
+        assert_eq!(config.audit_log(), Some(AuditLog::new("audit.jsonl")));
+        assert!(config.builder().target("a/b.c/d").build().is_err());
+
+        // A project policy replaces the user's policy entirely
+        let config = parse("[policy]\nmax_depth = 3").with_defaults(user.clone());
+        assert!(config.builder().target("a/b.c/d").build().is_ok());
+
+        assert_eq!(ProjectConfig::default().with_defaults(user.clone()), user);
+        assert_eq!(user.clone().with_defaults(ProjectConfig::default()), user);
+    }
+
+    #[test]
+    fn test_config_user_config_path() {
+        if let Some(path) = ProjectConfig::user_config_path() {
+            assert!(path.ends_with(Path::new("link-bridge").join(CONFIG_FILE_NAME)));
+        }
+    }
+
+    #[test]
+    fn test_config_from_project_root_missing_file() {
+        let config = ProjectConfig::from_project_root("test_config_no_such_root").unwrap();
+        assert_eq!(config, ProjectConfig::default());
+    }
+}
@@ -0,0 +1,313 @@
+//! Serde support for `Redirector`.
+//!
+//! Redirectors are serialized as a flat definition mirroring the `RedirectorBuilder`
+//! options, so redirects can be declared in configuration files. Deserialization runs
+//! the definition through the builder, which means every deserialized `Redirector` has
+//! been validated and is ready to write.
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::redirector::target::Target;
+use crate::redirector::{
+    DuplicatePolicy, ExistingFilePolicy, HostAllowlist, OutputLayout, PathPolicy, Redirector,
+    RedirectorError, TargetCase, TrailingSlash,
+};
+
+/// The serialized form of a [`Redirector`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct RedirectorDef {
+    /// The URL path or absolute URL to redirect to.
+    target: String,
+    /// The output directory for redirect files.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    out_dir: Option<PathBuf>,
+    /// The short name used for the redirect file, without extension.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    alias: Option<String>,
+    /// The number of seconds to wait before redirecting.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    delay: u32,
+    /// The site base URL used to emit absolute target URLs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    base_url: Option<String>,
+    /// How the trailing slash of a path target is normalized.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    trailing_slash: Option<TrailingSlash>,
+    /// Whether a target registered in another letter case is reused.
+    #[serde(default, skip_serializing_if = "is_sensitive")]
+    target_case: TargetCase,
+    /// The hosts an external target may point at.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    allow_hosts: Option<Vec<String>>,
+    /// Whether the target may be a `mailto:` or `tel:` URL.
+    #[serde(default, skip_serializing_if = "is_false")]
+    allow_contact: bool,
+    /// The endpoint notified of each visit before redirecting.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    beacon: Option<String>,
+    /// The campaign recorded with the redirect.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    campaign: Option<String>,
+    /// The tags recorded with the redirect.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    /// Whether the page is written as a file or as a directory's index.
+    #[serde(default, skip_serializing_if = "is_file")]
+    layout: OutputLayout,
+    /// What a write does when the target is already registered.
+    #[serde(default, skip_serializing_if = "is_return_existing")]
+    on_duplicate: DuplicatePolicy,
+    /// What a write does when a new page would replace a file not in the registry.
+    #[serde(default, skip_serializing_if = "is_error")]
+    on_existing_file: ExistingFilePolicy,
+}
+
+fn is_zero(value: &u32) -> bool {
+    *value == 0
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+fn is_sensitive(value: &TargetCase) -> bool {
+    *value == TargetCase::Sensitive
+}
+
+fn is_file(value: &OutputLayout) -> bool {
+    *value == OutputLayout::File
+}
+
+fn is_return_existing(value: &DuplicatePolicy) -> bool {
+    *value == DuplicatePolicy::ReturnExisting
+}
+
+fn is_error(value: &ExistingFilePolicy) -> bool {
+    *value == ExistingFilePolicy::Error
+}
+
+impl TryFrom<RedirectorDef> for Redirector {
+    type Error = RedirectorError;
+
+    fn try_from(def: RedirectorDef) -> Result<Self, Self::Error> {
+        let mut builder = Redirector::builder()
+            .target(def.target)
+            .delay(def.delay)
+            .layout(def.layout)
+            .on_duplicate(def.on_duplicate)
+            .on_existing_file(def.on_existing_file);
+
+        if let Some(out_dir) = def.out_dir {
+            builder = builder.out_dir(out_dir);
+        }
+        if let Some(alias) = def.alias {
+            builder = builder.alias(alias);
+        }
+        if let Some(base_url) = def.base_url {
+            builder = builder.base_url(base_url);
+        }
+        let mut policy = PathPolicy::default().target_case(def.target_case);
+        if let Some(trailing_slash) = def.trailing_slash {
+            policy = policy.trailing_slash(trailing_slash);
+        }
+        builder = builder.policy(policy);
+        if let Some(hosts) = def.allow_hosts {
+            builder = builder.allow_hosts(HostAllowlist::new(hosts));
+        }
+        if def.allow_contact {
+            builder = builder.allow_contact_targets();
+        }
+        if let Some(beacon) = def.beacon {
+            builder = builder.beacon(beacon);
+        }
+        if let Some(campaign) = def.campaign {
+            builder = builder.campaign(campaign);
+        }
+        for tag in def.tags {
+            builder = builder.tag(tag);
+        }
+
+        builder.build()
+    }
+}
+
+impl From<Redirector> for RedirectorDef {
+    fn from(redirector: Redirector) -> Self {
+        let short_file_name = redirector.short_file_name.to_string_lossy();
+        let alias = short_file_name
+            .strip_suffix(".html")
+            .unwrap_or(&short_file_name);
+
+        let (trailing_slash, allow_hosts) = match &redirector.target {
+            Target::Path(_) => (Some(TrailingSlash::Preserve), None),
+            Target::External(url) => (None, Some(vec![url.host().to_string()])),
+            Target::Contact(_) => (None, None),
+        };
+
+        RedirectorDef {
+            target: redirector.target.to_string(),
+            out_dir: Some(redirector.path.clone()),
+            alias: (!alias.is_empty()).then(|| alias.to_string()),
+            delay: redirector.delay,
+            base_url: redirector.base_url.as_ref().map(ToString::to_string),
+            trailing_slash,
+            target_case: redirector.target_case,
+            allow_hosts,
+            allow_contact: matches!(redirector.target, Target::Contact(_)),
+            beacon: redirector.beacon.clone(),
+            campaign: redirector.campaign.clone(),
+            tags: redirector.tags.clone(),
+            layout: redirector.layout,
+            on_duplicate: redirector.duplicate_policy,
+            on_existing_file: redirector.existing_file_policy,
+        }
+    }
+}
+
+impl Serialize for Redirector {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        RedirectorDef::from(self.clone()).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Redirector {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let def = RedirectorDef::deserialize(deserializer)?;
+        Redirector::try_from(def).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::OsString;
+
+    #[test]
+    fn test_deserialize_minimal() {
+        let redirector: Redirector = serde_json::from_str(r#"{"target": "api/v1"}"#).unwrap();
+
+        assert_eq!(redirector.target.to_string(), "/api/v1/");
+        assert_eq!(redirector.path, PathBuf::from("s"));
+        assert_eq!(redirector.delay, 0);
+        assert!(!redirector.short_file_name.is_empty());
+    }
+
+    #[test]
+    fn test_deserialize_all_options() {
+        let json = r#"{
+            "target": "download/report.pdf",
+            "out_dir": "redirects",
+            "alias": "report",
+            "delay": 2,
+            "base_url": "https://example.com",
+            "trailing_slash": "preserve"
+        }"#;
+        let redirector: Redirector = serde_json::from_str(json).unwrap();
+
+        assert_eq!(redirector.target.to_string(), "/download/report.pdf");
+        assert_eq!(redirector.path, PathBuf::from("redirects"));
+        assert_eq!(redirector.short_file_name, OsString::from("report.html"));
+        assert_eq!(redirector.delay, 2);
+        assert_eq!(
+            redirector.base_url.as_ref().map(ToString::to_string),
+            Some("https://example.com".to_string())
+        );
+    }
+
+    #[test]
+    fn test_deserialize_external_target() {
+        let json =
+            r#"{"target": "https://docs.example.com/", "allow_hosts": ["docs.example.com"]}"#;
+        let redirector: Redirector = serde_json::from_str(json).unwrap();
+        assert_eq!(redirector.target.to_string(), "https://docs.example.com/");
+
+        let json = r#"{"target": "https://evil.com/", "allow_hosts": ["docs.example.com"]}"#;
+        assert!(serde_json::from_str::<Redirector>(json).is_err());
+    }
+
+    #[test]
+    fn test_contact_target_round_trip() {
+        let json = r#"{"target": "tel:+353 1 234 567", "allow_contact": true}"#;
+        let redirector: Redirector = serde_json::from_str(json).unwrap();
+        assert_eq!(redirector.target.to_string(), "tel:+3531234567");
+
+        let json = serde_json::to_string(&redirector).unwrap();
+        assert!(json.contains(r#""allow_contact":true"#));
+        let round_trip: Redirector = serde_json::from_str(&json).unwrap();
+        assert_eq!(round_trip.target, redirector.target);
+
+        let json = r#"{"target": "tel:+3531234567"}"#;
+        let redirector: Redirector = serde_json::from_str(json).unwrap();
+        assert!(matches!(redirector.target, Target::Path(_)));
+    }
+
+    #[test]
+    fn test_deserialize_validates() {
+        assert!(serde_json::from_str::<Redirector>(r#"{"target": "a?b"}"#).is_err());
+        assert!(serde_json::from_str::<Redirector>(r#"{"target": "a", "alias": "a/b"}"#).is_err());
+        assert!(serde_json::from_str::<Redirector>(r#"{"out_dir": "s"}"#).is_err());
+    }
+
+    #[test]
+    fn test_deserialize_rejects_unknown_fields() {
+        let result = serde_json::from_str::<Redirector>(r#"{"target": "a", "dealy": 3}"#);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_serialize_round_trip() {
+        let redirector = Redirector::builder()
+            .target("docs/install")
+            .out_dir("out")
+            .delay(4)
+            .base_url("https://example.com/blog")
+            .beacon("/hits")
+            .campaign("docs")
+            .tag("onboarding")
+            .layout(OutputLayout::Directory)
+            .on_duplicate(DuplicatePolicy::CreateAdditional)
+            .on_existing_file(ExistingFilePolicy::Overwrite)
+            .policy(
+                PathPolicy::default()
+                    .trailing_slash(TrailingSlash::Strip)
+                    .target_case(TargetCase::Insensitive),
+            )
+            .build()
+            .unwrap();
+
+        let json = serde_json::to_string(&redirector).unwrap();
+        assert!(json.contains(r#""target_case":"insensitive""#));
+        assert!(json.contains(r#""tags":["onboarding"]"#));
+        assert!(json.contains(r#""on_duplicate":"create_additional""#));
+        assert!(json.contains(r#""on_existing_file":"overwrite""#));
+        let restored: Redirector = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(redirector, restored);
+    }
+
+    #[test]
+    fn test_serialize_round_trip_external() {
+        let allowlist = HostAllowlist::new(["example.com"]);
+        let redirector = Redirector::new_external("https://example.com/x", &allowlist).unwrap();
+
+        let json = serde_json::to_string(&redirector).unwrap();
+        let restored: Redirector = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(redirector, restored);
+    }
+
+    #[test]
+    fn test_serialize_omits_defaults() {
+        let redirector = Redirector::new("api/v1").unwrap();
+        let json = serde_json::to_value(&redirector).unwrap();
+
+        assert_eq!(json["target"], "/api/v1/");
+        assert_eq!(json["out_dir"], "s");
+        assert!(json.get("delay").is_none());
+        assert!(json.get("base_url").is_none());
+        assert!(json.get("target_case").is_none());
+    }
+}
@@ -0,0 +1,260 @@
+//! Rich error reports through `miette`.
+//!
+//! A bare "Invalid URL path" message leaves the user to find the stray `?` in a long
+//! target themselves. This module provides `miette::Diagnostic` for `UrlPathError`,
+//! `TargetError`, and `RedirectorError`, with an error code, a hint at the fix, and,
+//! for rejected paths and URLs, a label pointing at the offending part of the input,
+//! so applications using `miette` (and the command-line tool) can print reports such
+//! as:
+//!
+//! ```text
+//! link_bridge::invalid_path
+//!
+//!   × Invalid URL path: docs/install?os=linux
+//!    ╭────
+//!  1 │ docs/install?os=linux
+//!    ·             ┬
+//!    ·             ╰── not allowed in a path
+//!    ╰────
+//!   help: path segments cannot contain `;`, `#`, or `?`
+//! ```
+
+use std::fmt::Display;
+
+use miette::{Diagnostic, LabeledSpan, SourceCode};
+
+use crate::redirector::target::TargetError;
+use crate::redirector::url_path::UrlPathError;
+use crate::redirector::RedirectorError;
+
+/// Finds the part of a rejected path to point at, with a label describing it.
+///
+/// This is the first character the default policy forbids, or else the first empty
+/// segment. A path rejected only by a stricter or custom policy is labelled whole.
+fn path_label(path: &str) -> LabeledSpan {
+    if let Some(offset) = path.find(|c: char| matches!(c, ';' | '#' | '?') || is_never_allowed(c)) {
+        return LabeledSpan::new(Some("not allowed in a path".to_string()), offset, 1);
+    }
+    if let Some(offset) = path.find("//") {
+        return LabeledSpan::new(Some("empty segment".to_string()), offset + 1, 1);
+    }
+    let label = if path.trim_matches('/').is_empty() {
+        "no segments"
+    } else {
+        "rejected by the path policy"
+    };
+    LabeledSpan::new(Some(label.to_string()), 0, path.len())
+}
+
+/// Reports whether no path policy accepts `c`: a backslash or an ASCII control character.
+fn is_never_allowed(c: char) -> bool {
+    c == '\\' || c.is_ascii_control()
+}
+
+impl Diagnostic for UrlPathError {
+    fn code<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        let code = match self {
+            UrlPathError::InvalidPath(_) => "link_bridge::invalid_path",
+            UrlPathError::TooLong { .. } => "link_bridge::path_too_long",
+            UrlPathError::TooDeep { .. } => "link_bridge::path_too_deep",
+        };
+        Some(Box::new(code))
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        let help = match self {
+            UrlPathError::InvalidPath(path) if path.contains([';', '#', '?']) => {
+                "path segments cannot contain `;`, `#`, or `?`"
+            }
+            UrlPathError::InvalidPath(path) if path.contains(is_never_allowed) => {
+                "path segments cannot contain backslashes or control characters"
+            }
+            UrlPathError::InvalidPath(path) if path.trim_matches('/').is_empty() => {
+                "give the path at least one segment, such as `docs`"
+            }
+            UrlPathError::InvalidPath(path) if path.contains("//") => "remove the repeated slash",
+            UrlPathError::InvalidPath(_) => "check each segment against the active `PathPolicy`",
+            UrlPathError::TooLong { .. } => "raise the limit with `PathPolicy::max_length()`",
+            UrlPathError::TooDeep { .. } => "raise the limit with `PathPolicy::max_depth()`",
+        };
+        Some(Box::new(help))
+    }
+
+    fn source_code(&self) -> Option<&dyn SourceCode> {
+        match self {
+            UrlPathError::InvalidPath(path) => Some(path),
+            _ => None,
+        }
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        match self {
+            UrlPathError::InvalidPath(path) => Some(Box::new(std::iter::once(path_label(path)))),
+            _ => None,
+        }
+    }
+}
+
+impl Diagnostic for TargetError {
+    fn code<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        let code = match self {
+            TargetError::InvalidUrl(_) => "link_bridge::invalid_target",
+            TargetError::DangerousScheme(_) => "link_bridge::dangerous_scheme",
+        };
+        Some(Box::new(code))
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        let help = match self {
+            TargetError::InvalidUrl(_) => {
+                "use an `http` or `https` URL without user information, or a `mailto:` or \
+                 `tel:` link"
+            }
+            TargetError::DangerousScheme(_) => {
+                "redirects can only lead to web pages and contact links"
+            }
+        };
+        Some(Box::new(help))
+    }
+
+    fn source_code(&self) -> Option<&dyn SourceCode> {
+        match self {
+            TargetError::InvalidUrl(url) => Some(url),
+            TargetError::DangerousScheme(_) => None,
+        }
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        match self {
+            TargetError::InvalidUrl(url) => Some(Box::new(std::iter::once(LabeledSpan::new(
+                Some("not a valid target URL".to_string()),
+                0,
+                url.len(),
+            )))),
+            TargetError::DangerousScheme(_) => None,
+        }
+    }
+}
+
+impl RedirectorError {
+    /// Returns the diagnostic of the path or target error this error wraps, if any.
+    fn inner_diagnostic(&self) -> Option<&dyn Diagnostic> {
+        match self {
+            RedirectorError::InvalidUrlPath(e) => Some(e),
+            RedirectorError::InvalidTarget(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl Diagnostic for RedirectorError {
+    fn code<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.inner_diagnostic().and_then(Diagnostic::code)
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.inner_diagnostic().and_then(Diagnostic::help)
+    }
+
+    fn source_code(&self) -> Option<&dyn SourceCode> {
+        self.inner_diagnostic().and_then(Diagnostic::source_code)
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        self.inner_diagnostic().and_then(Diagnostic::labels)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PathPolicy, Redirector, UrlPath};
+
+    fn label(error: &dyn Diagnostic) -> (usize, usize, String) {
+        let label = error.labels().unwrap().next().unwrap();
+        (
+            label.offset(),
+            label.len(),
+            label.label().unwrap().to_string(),
+        )
+    }
+
+    #[test]
+    fn test_invalid_path_points_at_character() {
+        let error = UrlPath::new("docs/install?os=linux").unwrap_err();
+        assert_eq!(
+            error.code().unwrap().to_string(),
+            "link_bridge::invalid_path"
+        );
+        assert_eq!(label(&error), (12, 1, "not allowed in a path".to_string()));
+
+        let error = UrlPath::new("/\\evil.com").unwrap_err();
+        assert_eq!(label(&error), (1, 1, "not allowed in a path".to_string()));
+        assert_eq!(
+            error.help().unwrap().to_string(),
+            "path segments cannot contain backslashes or control characters"
+        );
+
+        let error = UrlPath::new("docs//install").unwrap_err();
+        assert_eq!(label(&error), (5, 1, "empty segment".to_string()));
+        assert_eq!(
+            error.help().unwrap().to_string(),
+            "remove the repeated slash"
+        );
+
+        let error = UrlPath::with_policy("café/menu", &PathPolicy::strict()).unwrap_err();
+        assert_eq!(
+            label(&error),
+            (
+                0,
+                "café/menu".len(),
+                "rejected by the path policy".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_limits_have_no_labels() {
+        let policy = PathPolicy::default().max_depth(2);
+        let error = UrlPath::with_policy("a/b/c", &policy).unwrap_err();
+        assert_eq!(
+            error.code().unwrap().to_string(),
+            "link_bridge::path_too_deep"
+        );
+        assert!(error.labels().is_none());
+        assert!(error.source_code().is_none());
+    }
+
+    #[test]
+    fn test_redirector_error_forwards_diagnostic() {
+        let error = Redirector::new("docs;v=2").unwrap_err();
+        assert_eq!(
+            error.code().unwrap().to_string(),
+            "link_bridge::invalid_path"
+        );
+        assert_eq!(label(&error), (4, 1, "not allowed in a path".to_string()));
+
+        let error = Redirector::builder()
+            .target("https://user@example.com/")
+            .allow_hosts(crate::HostAllowlist::new(["example.com"]))
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            error.code().unwrap().to_string(),
+            "link_bridge::invalid_target"
+        );
+
+        assert!(RedirectorError::MissingTarget.code().is_none());
+    }
+
+    #[test]
+    fn test_report_renders_label() {
+        let error = Redirector::new("docs/install?os=linux").unwrap_err();
+        let mut report = String::new();
+        miette::NarratableReportHandler::new()
+            .render_report(&mut report, &error)
+            .unwrap();
+        assert!(report.contains("not allowed in a path"));
+        assert!(report.contains("link_bridge::invalid_path"));
+    }
+}
@@ -0,0 +1,183 @@
+//! What happens when a redirect is written for a target already registered.
+//!
+//! By default, writing a redirect for a registered target returns the existing short
+//! link, so each target has one. Some sites deliberately want several short links
+//! per target, for example one per printed flyer, and others want a repeated target
+//! reported as a mistake. This module provides the `DuplicatePolicy` choosing between
+//! the three.
+
+use std::path::PathBuf;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::redirector::stub::stub;
+use crate::redirector::{
+    OutputLayout, RedirectOutcome, Redirector, RedirectorError, Registry, RegistryEntry,
+};
+
+/// What a write does when the redirect's target is already registered.
+///
+/// Set with [`RedirectorBuilder::on_duplicate()`](crate::RedirectorBuilder::on_duplicate).
+///
+/// # Examples
+///
+/// ```rust
+/// use link_bridge::{DuplicatePolicy, Redirector, Registry};
+/// use std::fs;
+///
+/// for flyer in ["flyer-a", "flyer-b"] {
+///     let outcome = Redirector::builder()
+///         .target("summer/sale")
+///         .out_dir("doc_test_duplicate_policy")
+///         .alias(flyer)
+///         .on_duplicate(DuplicatePolicy::CreateAdditional)
+///         .build()
+///         .unwrap()
+///         .write_redirect()
+///         .unwrap();
+///     assert!(outcome.is_created());
+///     assert_eq!(outcome.short_name(), flyer);
+/// }
+///
+/// let registry = Registry::load("doc_test_duplicate_policy").unwrap();
+/// assert_eq!(registry.len(), 1);
+/// assert_eq!(registry.target_for("flyer-b"), Some("/summer/sale/"));
+///
+/// fs::remove_dir_all("doc_test_duplicate_policy").ok();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum DuplicatePolicy {
+    /// Return the existing short link without writing anything.
+    #[default]
+    ReturnExisting,
+    /// Write another short link under the redirector's short name.
+    ///
+    /// The new short name is recorded as an extra short name of the registered
+    /// redirect, as by [`Registry::add_alias()`], and its page is a stub leading to the
+    /// redirect's page, so it follows the redirect when it is repointed or retired. The
+    /// outcome reports the new short link as created.
+    CreateAdditional,
+    /// Fail with [`RedirectorError::DuplicateTarget`].
+    Error,
+}
+
+/// An extra short link to write for a registered target.
+pub(crate) struct AdditionalPage {
+    /// The path the stub page is written to.
+    pub(crate) file_path: PathBuf,
+    /// The content of the stub page.
+    pub(crate) page: String,
+    /// The new short name.
+    short_name: String,
+    /// The URL of the stub page relative to the output directory.
+    short_file: String,
+}
+
+impl AdditionalPage {
+    /// Records the new short name on the registered target and returns the outcome
+    /// of the write.
+    pub(crate) fn record(self, registry: &mut Registry, target: String) -> RedirectOutcome {
+        registry.insert_alias(&target, &self.short_name);
+        RedirectOutcome::new(self.file_path, self.short_file, target, true)
+    }
+}
+
+impl Redirector {
+    /// Applies the duplicate policy to a write whose target is already registered.
+    ///
+    /// Returns `None` when the existing short link is reused, or the stub page to
+    /// write under the redirector's short name.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Option<AdditionalPage>)` - The page to write, if any
+    /// * `Err(RedirectorError::DuplicateTarget)` - Under [`DuplicatePolicy::Error`]
+    /// * `Err(RedirectorError::DuplicateShortName)` - If the short name is in use
+    pub(crate) fn additional_page(
+        &self,
+        registry: &Registry,
+        target: &str,
+        entry: &RegistryEntry,
+    ) -> Result<Option<AdditionalPage>, RedirectorError> {
+        match self.duplicate_policy {
+            DuplicatePolicy::ReturnExisting => Ok(None),
+            DuplicatePolicy::Error => Err(RedirectorError::DuplicateTarget(target.to_string())),
+            DuplicatePolicy::CreateAdditional => {
+                self.check_short_name(registry)?;
+                let short_name = self.short_name();
+                let (file_path, page) = stub(registry.dir(), entry, &short_name);
+                let short_file = OutputLayout::of(entry.short_file()).short_file(&short_name);
+
+                Ok(Some(AdditionalPage {
+                    file_path,
+                    page,
+                    short_name,
+                    short_file,
+                }))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use std::fs;
+
+    fn test_dir(name: &str) -> String {
+        format!("{name}_{}", Utc::now().timestamp_nanos_opt().unwrap_or(0))
+    }
+
+    fn write(
+        dir: &str,
+        alias: &str,
+        policy: DuplicatePolicy,
+    ) -> Result<RedirectOutcome, RedirectorError> {
+        Redirector::builder()
+            .target("summer/sale")
+            .out_dir(dir)
+            .alias(alias)
+            .on_duplicate(policy)
+            .build()
+            .unwrap()
+            .write_redirect()
+    }
+
+    #[test]
+    fn test_duplicate_policy() {
+        let dir = test_dir("test_duplicate_policy");
+        write(&dir, "promo", DuplicatePolicy::ReturnExisting).unwrap();
+
+        let existing = write(&dir, "sale", DuplicatePolicy::ReturnExisting).unwrap();
+        assert!(!existing.is_created());
+        assert_eq!(existing.short_name(), "promo");
+
+        let additional = write(&dir, "sale", DuplicatePolicy::CreateAdditional).unwrap();
+        assert!(additional.is_created());
+        assert_eq!(additional.short_name(), "sale");
+        assert_eq!(additional.target(), "/summer/sale/");
+        assert!(fs::read_to_string(additional.file_path())
+            .unwrap()
+            .contains("url=promo.html"));
+
+        // The extra short name is taken now
+        assert!(matches!(
+            write(&dir, "sale", DuplicatePolicy::CreateAdditional),
+            Err(RedirectorError::DuplicateShortName(_))
+        ));
+        assert!(matches!(
+            write(&dir, "deal", DuplicatePolicy::Error),
+            Err(RedirectorError::DuplicateTarget(target)) if target == "/summer/sale/"
+        ));
+
+        let registry = Registry::load(&dir).unwrap();
+        assert_eq!(registry.len(), 1);
+        assert_eq!(registry.entry("/summer/sale/").unwrap().aliases(), ["sale"]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
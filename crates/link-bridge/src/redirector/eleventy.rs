@@ -0,0 +1,98 @@
+//! Exporting the registry as Eleventy data.
+//!
+//! Eleventy sites commonly generate redirect pages themselves, paginating a
+//! template over an array of `from` and `to` pairs in the `_data/redirects.json`
+//! global data file. This module provides `Registry::to_eleventy_redirects()`, which
+//! renders the registry in that shape, so 11ty templates can build their own
+//! redirect pages from link-bridge's short links.
+
+use crate::redirector::json::{object, Value};
+use crate::redirector::Registry;
+
+impl Registry {
+    /// Renders the registry as the `_data/redirects.json` data file of an Eleventy
+    /// site.
+    ///
+    /// Each short name, including extra short names added with
+    /// [`Registry::add_alias()`], becomes a `from` path below `base_path` and the `to`
+    /// target it leads to. Retired redirects are left out. Entries are ordered by
+    /// path.
+    ///
+    /// Paginate a template over the data to write a page for each entry:
+    ///
+    /// ```text
+    /// ---
+    /// pagination:
+    ///   data: redirects
+    ///   size: 1
+    ///   alias: redirect
+    /// permalink: "{{ redirect.from }}/index.html"
+    /// ---
+    /// <meta http-equiv="refresh" content="0; url={{ redirect.to }}">
+    /// ```
+    ///
+    /// # Arguments
+    ///
+    /// * `base_path` - The path the short links are served from, such as `"/s"`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::{Redirector, Registry};
+    /// use std::fs;
+    ///
+    /// Redirector::builder()
+    ///     .target("docs/install")
+    ///     .out_dir("doc_test_eleventy_redirects")
+    ///     .alias("install")
+    ///     .build()
+    ///     .unwrap()
+    ///     .write_redirect()
+    ///     .unwrap();
+    ///
+    /// let registry = Registry::load("doc_test_eleventy_redirects").unwrap();
+    /// let json = registry.to_eleventy_redirects("/s");
+    /// assert!(json.contains(r#""from": "/s/install""#));
+    /// assert!(json.contains(r#""to": "/docs/install/""#));
+    ///
+    /// fs::remove_dir_all("doc_test_eleventy_redirects").ok();
+    /// ```
+    pub fn to_eleventy_redirects<S: ToString>(&self, base_path: S) -> String {
+        let redirects = self
+            .redirect_sources(&base_path.to_string())
+            .into_iter()
+            .map(|(from, to)| object([("from", from.into()), ("to", to.into())]))
+            .collect();
+        Value::Array(redirects).to_string_pretty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_to_eleventy_redirects() {
+        let mut registry = Registry::empty(PathBuf::from("s"));
+        registry.insert("/docs/usage/".to_string(), "s/usage.html".to_string());
+        registry.insert(
+            "https://example.org/?a=1&b=2".to_string(),
+            "s/external.html".to_string(),
+        );
+        registry.insert_alias("/docs/usage/", "how-to");
+        registry.insert("/docs/old/".to_string(), "s/old.html".to_string());
+        registry.entry_mut("/docs/old/").unwrap().retire();
+
+        let redirects: serde_json::Value =
+            serde_json::from_str(&registry.to_eleventy_redirects("/s")).unwrap();
+        assert_eq!(
+            redirects,
+            serde_json::json!([
+                { "from": "/s/external", "to": "https://example.org/?a=1&b=2" },
+                { "from": "/s/how-to", "to": "/docs/usage/" },
+                { "from": "/s/usage", "to": "/docs/usage/" },
+            ])
+        );
+    }
+}
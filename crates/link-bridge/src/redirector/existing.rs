@@ -0,0 +1,211 @@
+//! What happens when a new redirect's page would replace a file not in the registry.
+//!
+//! A new redirect's page is written under its short name, and a file may already be
+//! there that the registry knows nothing about, such as a hand-written page or one
+//! left behind by another tool. By default the write fails so nothing is lost. This
+//! module provides the `ExistingFilePolicy`, which can instead pick another short
+//! name or, when asked for explicitly, replace the file.
+
+use std::borrow::Cow;
+use std::path::Path;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::redirector::{trace, Redirector, RedirectorError, Registry};
+
+/// How many new short names are tried before giving up under
+/// [`ExistingFilePolicy::Regenerate`].
+const REGENERATE_ATTEMPTS: usize = 8;
+
+/// What a write does when the new redirect's page would replace an existing file.
+///
+/// Only files the registry does not know about are affected: a short name already in
+/// the registry is always refused with [`RedirectorError::DuplicateShortName`]. Set
+/// with [`RedirectorBuilder::on_existing_file()`](crate::RedirectorBuilder::on_existing_file).
+///
+/// # Examples
+///
+/// ```rust
+/// use link_bridge::{ExistingFilePolicy, Redirector};
+/// use std::fs;
+///
+/// fs::create_dir_all("doc_test_existing_file").unwrap();
+/// fs::write("doc_test_existing_file/promo.html", "<p>Hand-written</p>").unwrap();
+///
+/// let outcome = Redirector::builder()
+///     .target("summer/sale")
+///     .out_dir("doc_test_existing_file")
+///     .alias("promo")
+///     .on_existing_file(ExistingFilePolicy::Regenerate)
+///     .build()
+///     .unwrap()
+///     .write_redirect()
+///     .unwrap();
+///
+/// assert_ne!(outcome.short_name(), "promo");
+/// let page = fs::read_to_string("doc_test_existing_file/promo.html").unwrap();
+/// assert_eq!(page, "<p>Hand-written</p>");
+///
+/// fs::remove_dir_all("doc_test_existing_file").ok();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum ExistingFilePolicy {
+    /// Fail with [`RedirectorError::FileWriteError`], leaving the file untouched.
+    #[default]
+    Error,
+    /// Write the redirect under a newly generated short name instead.
+    ///
+    /// This applies to a short name set with
+    /// [`RedirectorBuilder::alias()`](crate::RedirectorBuilder::alias) too; the outcome
+    /// reports the short name actually used.
+    Regenerate,
+    /// Replace the existing file with the redirect's page.
+    Overwrite,
+}
+
+impl Redirector {
+    /// Returns the redirectors that may write a new redirect's page, in the order they
+    /// are tried: this one, then under [`ExistingFilePolicy::Regenerate`] copies with
+    /// new short names free in the registry.
+    ///
+    /// A copy is only tried once the page of the one before it turns out to exist.
+    /// Looking that up is left to the caller, so files can be checked through a sink
+    /// or without blocking.
+    pub(crate) fn page_candidates<'a, 'r>(
+        &'a self,
+        registry: &'r Registry,
+    ) -> impl Iterator<Item = Cow<'a, Redirector>> + use<'a, 'r> {
+        let attempts = if self.regenerates() {
+            REGENERATE_ATTEMPTS
+        } else {
+            0
+        };
+        let renamed = (0..attempts).filter_map(move |_| {
+            let mut renamed = self.clone();
+            renamed.short_file_name = Redirector::generate_short_file_name(&self.target);
+            if registry.contains_short_name(&renamed.short_name()) {
+                return None;
+            }
+            trace::warn!(
+                file = %self.page_path(registry.dir()).display(),
+                short_name = %renamed.short_name(),
+                "file exists, trying a new short name"
+            );
+            Some(Cow::Owned(renamed))
+        });
+        std::iter::once(Cow::Borrowed(self)).chain(renamed)
+    }
+
+    /// Returns the redirector whose page is written for a new redirect: the first of
+    /// [`Redirector::page_candidates()`] whose page `exists` does not report, or this
+    /// one unless the policy is [`ExistingFilePolicy::Regenerate`].
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Cow<Redirector>)` - The redirector to write
+    /// * `Err(RedirectorError::FileWriteError)` - If no free short name was found
+    pub(crate) fn resolve_existing<F>(
+        &self,
+        registry: &Registry,
+        mut exists: F,
+    ) -> Result<Cow<'_, Redirector>, RedirectorError>
+    where
+        F: FnMut(&Path) -> bool,
+    {
+        self.page_candidates(registry)
+            .find(|candidate| !self.regenerates() || !exists(&candidate.page_path(registry.dir())))
+            .ok_or_else(|| self.existing_file_error(registry))
+    }
+
+    /// Returns the error for a new redirect whose every page candidate exists.
+    pub(crate) fn existing_file_error(&self, registry: &Registry) -> RedirectorError {
+        RedirectorError::FileWriteError {
+            path: self.page_path(registry.dir()),
+            source: std::io::Error::new(std::io::ErrorKind::AlreadyExists, "file already exists"),
+        }
+    }
+
+    /// Reports whether an existing file moves a new redirect on to another short name.
+    pub(crate) fn regenerates(&self) -> bool {
+        self.existing_file_policy == ExistingFilePolicy::Regenerate
+    }
+
+    /// Reports whether a new page replaces an existing file.
+    pub(crate) fn overwrites(&self) -> bool {
+        self.existing_file_policy == ExistingFilePolicy::Overwrite
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MemorySink, OutputSink};
+    use std::path::PathBuf;
+
+    fn write(policy: ExistingFilePolicy, sink: &mut MemorySink) -> Result<String, RedirectorError> {
+        Redirector::builder()
+            .target("summer/sale")
+            .alias("promo")
+            .on_existing_file(policy)
+            .build()
+            .unwrap()
+            .write_redirect_to(sink)
+            .map(|outcome| outcome.short_name().to_string())
+    }
+
+    fn sink() -> MemorySink {
+        let mut sink = MemorySink::new();
+        sink.write_file(&PathBuf::from("s/promo.html"), b"hand-written")
+            .unwrap();
+        sink
+    }
+
+    #[test]
+    fn test_existing_file_error() {
+        let mut sink = sink();
+
+        let result = write(ExistingFilePolicy::Error, &mut sink);
+        assert!(matches!(
+            result,
+            Err(RedirectorError::FileWriteError { source, .. })
+                if source.kind() == std::io::ErrorKind::AlreadyExists
+        ));
+        assert_eq!(
+            sink.read_file(Path::new("s/promo.html")).unwrap().unwrap(),
+            b"hand-written"
+        );
+    }
+
+    #[test]
+    fn test_existing_file_regenerate() {
+        let mut sink = sink();
+
+        let short_name = write(ExistingFilePolicy::Regenerate, &mut sink).unwrap();
+        assert_ne!(short_name, "promo");
+        assert!(sink
+            .read_file(&PathBuf::from(format!("s/{short_name}.html")))
+            .unwrap()
+            .is_some());
+        assert_eq!(
+            sink.read_file(Path::new("s/promo.html")).unwrap().unwrap(),
+            b"hand-written"
+        );
+    }
+
+    #[test]
+    fn test_existing_file_overwrite() {
+        let mut sink = sink();
+
+        assert_eq!(
+            write(ExistingFilePolicy::Overwrite, &mut sink).unwrap(),
+            "promo"
+        );
+        let page = sink.read_file(Path::new("s/promo.html")).unwrap().unwrap();
+        assert!(String::from_utf8(page)
+            .unwrap()
+            .contains("url=/summer/sale/"));
+    }
+}
@@ -0,0 +1,233 @@
+//! Redirects that stop working after a set time.
+//!
+//! Campaign links should stop leading to a promotion once it has ended. A redirect
+//! built with an expiry records it in the registry, and this module provides
+//! `Registry::expire()`, which replaces the page of every expired redirect with a
+//! "this link has expired" page and removes it from the registry.
+
+use std::path::PathBuf;
+
+use crate::redirector::sink::{FsSink, OutputSink};
+use crate::redirector::time::Timestamp;
+use crate::redirector::{trace, RedirectorError, Registry};
+
+/// The page written in place of a redirect once it has expired.
+///
+/// Used by [`Registry::expire()`] and for redirects written after their expiry,
+/// unless another page is configured with
+/// [`RedirectorBuilder::expired_page()`](crate::RedirectorBuilder::expired_page) or
+/// passed to [`Registry::expire_with()`].
+pub const DEFAULT_EXPIRED_PAGE: &str = r#"<!DOCTYPE HTML>
+<html lang="en-US">
+
+<head>
+    <meta charset="UTF-8">
+    <meta name="robots" content="noindex">
+    <title>Link Expired</title>
+</head>
+
+<body>
+    This link has expired.
+</body>
+
+</html>
+"#;
+
+impl Registry {
+    /// Sweeps the redirects whose expiry has passed.
+    ///
+    /// Each expired redirect's page is replaced with [`DEFAULT_EXPIRED_PAGE`], so the
+    /// short link explains that it has expired instead of redirecting or failing with
+    /// a 404, and its entry is removed from the registry in memory; call
+    /// [`Registry::save()`] to persist it. The expired page stays in place, so its
+    /// short name cannot be reused until the page is deleted. Retired redirects keep
+    /// their retired page and are not swept.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<String>)` - The targets of the expired redirects, in order of target
+    /// * `Err(RedirectorError::FileWriteError)` - If a page cannot be replaced; the
+    ///   redirects swept before it are removed from the registry, the rest are kept
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::{Redirector, Registry};
+    /// use std::fs;
+    /// use std::time::Duration;
+    ///
+    /// // Expires as soon as it is built
+    /// let outcome = Redirector::builder()
+    ///     .target("summer/sale")
+    ///     .out_dir("doc_test_registry_expire")
+    ///     .alias("promo")
+    ///     .ttl(Duration::ZERO)
+    ///     .build()
+    ///     .unwrap()
+    ///     .write_redirect()
+    ///     .unwrap();
+    ///
+    /// let mut registry = Registry::load("doc_test_registry_expire").unwrap();
+    /// assert_eq!(registry.expire().unwrap(), ["/summer/sale/"]);
+    /// registry.save().unwrap();
+    ///
+    /// assert!(registry.is_empty());
+    /// let page = fs::read_to_string(outcome.file_path()).unwrap();
+    /// assert!(page.contains("This link has expired."));
+    ///
+    /// fs::remove_dir_all("doc_test_registry_expire").ok();
+    /// ```
+    pub fn expire(&mut self) -> Result<Vec<String>, RedirectorError> {
+        self.expire_with(DEFAULT_EXPIRED_PAGE)
+    }
+
+    /// Sweeps the redirects whose expiry has passed, replacing their pages with a
+    /// custom page.
+    ///
+    /// Behaves like [`Registry::expire()`] but writes `page` instead of
+    /// [`DEFAULT_EXPIRED_PAGE`].
+    ///
+    /// # Arguments
+    ///
+    /// * `page` - The HTML written in place of each expired redirect
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<String>)` - The targets of the expired redirects, in order of target
+    /// * `Err(RedirectorError::FileWriteError)` - If a page cannot be replaced
+    pub fn expire_with<S: AsRef<str>>(&mut self, page: S) -> Result<Vec<String>, RedirectorError> {
+        self.expire_at(Timestamp::now(), page.as_ref())
+    }
+
+    /// Sweeps the redirects that have expired by `now`.
+    fn expire_at(&mut self, now: Timestamp, page: &str) -> Result<Vec<String>, RedirectorError> {
+        let expired: Vec<(String, PathBuf)> = self
+            .iter()
+            .filter(|(_, target, _)| {
+                self.entry(target)
+                    .filter(|entry| !entry.is_retired())
+                    .and_then(|entry| entry.expires_at)
+                    .is_some_and(|expires_at| expires_at <= now)
+            })
+            .map(|(_, target, file_path)| (target.to_string(), file_path.to_path_buf()))
+            .collect();
+
+        let mut targets = Vec::with_capacity(expired.len());
+        for (target, file_path) in expired {
+            FsSink
+                .write_file(&file_path, page.as_bytes())
+                .map_err(|source| RedirectorError::FileWriteError {
+                    path: file_path.clone(),
+                    source,
+                })?;
+            trace::debug!(target = %target, file = %file_path.display(), "expired redirect");
+            self.forget(&target);
+            targets.push(target);
+        }
+
+        Ok(targets)
+    }
+}
+
+#[cfg(all(test, feature = "chrono"))]
+mod tests {
+    use super::*;
+    use crate::Redirector;
+    use chrono::{DateTime, Duration, SubsecRound, Utc};
+    use std::fs;
+
+    fn test_dir(name: &str) -> String {
+        format!("{name}_{}", Utc::now().timestamp_nanos_opt().unwrap_or(0))
+    }
+
+    fn write(target: &str, alias: &str, dir: &str, expires_at: Option<DateTime<Utc>>) {
+        let mut builder = Redirector::builder()
+            .target(target)
+            .out_dir(dir)
+            .alias(alias);
+        if let Some(expires_at) = expires_at {
+            builder = builder.expires_at(expires_at);
+        }
+        builder.build().unwrap().write_redirect().unwrap();
+    }
+
+    #[test]
+    fn test_expire() {
+        let dir = test_dir("test_expire");
+        let now = Utc::now();
+        write("ended", "ended", &dir, Some(now - Duration::hours(1)));
+        write("running", "running", &dir, Some(now + Duration::hours(1)));
+        write("forever", "forever", &dir, None);
+
+        let mut registry = Registry::load(&dir).unwrap();
+        assert_eq!(
+            registry.entry("/ended/").unwrap().expires_at(),
+            Some((now - Duration::hours(1)).trunc_subsecs(0))
+        );
+        let expired = registry.expire_with("<p>Gone</p>").unwrap();
+        registry.save().unwrap();
+
+        assert_eq!(expired, ["/ended/"]);
+        assert_eq!(
+            fs::read_to_string(format!("{dir}/ended.html")).unwrap(),
+            "<p>Gone</p>"
+        );
+        assert!(fs::read_to_string(format!("{dir}/running.html"))
+            .unwrap()
+            .contains("url=/running/"));
+        let mut registry = Registry::load(&dir).unwrap();
+        assert_eq!(registry.len(), 2);
+        assert!(!registry.contains_short_name("ended"));
+
+        // The running redirect expires once its time has passed
+        let expired = registry
+            .expire_at((now + Duration::hours(2)).into(), DEFAULT_EXPIRED_PAGE)
+            .unwrap();
+        assert_eq!(expired, ["/running/"]);
+        assert_eq!(registry.len(), 1);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_write_expired_redirect() {
+        let dir = test_dir("test_write_expired_redirect");
+        let outcome = Redirector::builder()
+            .target("ended")
+            .out_dir(&dir)
+            .expires_at(Utc::now() - Duration::days(1))
+            .expired_page("<p>Campaign over</p>")
+            .build()
+            .unwrap()
+            .write_redirect()
+            .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(outcome.file_path()).unwrap(),
+            "<p>Campaign over</p>"
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_expire_write_error() {
+        let redirector = Redirector::builder()
+            .target("a")
+            .expires_at(Utc::now() - Duration::days(1))
+            .build()
+            .unwrap();
+        let mut registry = Registry::default();
+        registry.record(
+            "/a/".to_string(),
+            "test_expire_write_error/missing/a.html".to_string(),
+            &redirector,
+        );
+
+        assert!(matches!(
+            registry.expire(),
+            Err(RedirectorError::FileWriteError { .. })
+        ));
+        assert_eq!(registry.len(), 1);
+    }
+}
@@ -0,0 +1,187 @@
+//! Serving redirects dynamically with Rocket.
+//!
+//! `Registry::router()` and `Registry::scope()` answer short-link requests from
+//! `axum` and `actix-web` services. This module provides `RedirectFairing` for Rocket
+//! applications, which mounts the registry as a catch-all route under a prefix, so
+//! the same `registry.json` backs both the static pages and server-side short links.
+
+use std::sync::Arc;
+
+use rocket::fairing::{self, Fairing, Info, Kind};
+use rocket::http::{Method, Status};
+use rocket::response::Redirect;
+use rocket::route::{Handler, Outcome, Route};
+use rocket::{Build, Data, Request, Rocket};
+
+use crate::redirector::{Registry, RegistryEntry};
+
+/// A Rocket fairing that mounts a registry's redirects under a prefix.
+///
+/// `GET` and `HEAD` requests for a registered short name, with or without the
+/// `.html` extension or a trailing slash, receive `301 Moved Permanently` and a
+/// `Location` header holding the target. Short names retired with
+/// [`Registry::retire()`] fail with `410 Gone`. Any other request below the prefix
+/// is forwarded, so the application's own routes and catchers still answer it.
+/// Requires the `rocket` feature.
+///
+/// The registry is consumed, so later changes to `registry.json` are not seen; load
+/// the registry again and relaunch to pick them up.
+///
+/// # Examples
+///
+/// ```rust
+/// use link_bridge::{RedirectFairing, Redirector, Registry};
+/// use std::fs;
+///
+/// let redirector = Redirector::builder()
+///     .target("api/v1/users")
+///     .out_dir("doc_test_redirect_fairing")
+///     .alias("users")
+///     .build()
+///     .unwrap();
+/// redirector.write_redirect().unwrap();
+///
+/// // GET /s/users -> 301 Moved Permanently, Location: /api/v1/users/
+/// let registry = Registry::load("doc_test_redirect_fairing").unwrap();
+/// let rocket = rocket::build().attach(RedirectFairing::new(registry).prefix("/s"));
+///
+/// fs::remove_dir_all("doc_test_redirect_fairing").ok();
+/// ```
+#[derive(Debug, Clone)]
+pub struct RedirectFairing {
+    /// The registry redirects are looked up in.
+    registry: Arc<Registry>,
+    /// The path the route is mounted at.
+    prefix: String,
+}
+
+impl RedirectFairing {
+    /// Creates a fairing mounting the short names of `registry` at the root of the
+    /// site.
+    pub fn new(registry: Registry) -> Self {
+        RedirectFairing {
+            registry: Arc::new(registry),
+            prefix: "/".to_string(),
+        }
+    }
+
+    /// Mounts the route at `prefix`, such as `/s`, matching the static redirect pages.
+    pub fn prefix<S: ToString>(mut self, prefix: S) -> Self {
+        self.prefix = prefix.to_string();
+        self
+    }
+
+    /// Returns the catch-all route answering short names, for mounting it without
+    /// attaching the fairing.
+    pub fn routes(&self) -> Vec<Route> {
+        let handler = RedirectHandler {
+            registry: Arc::clone(&self.registry),
+        };
+        vec![Route::new(Method::Get, "/<short_name..>", handler)]
+    }
+}
+
+#[rocket::async_trait]
+impl Fairing for RedirectFairing {
+    fn info(&self) -> Info {
+        Info {
+            name: "link-bridge redirects",
+            kind: Kind::Ignite,
+        }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> fairing::Result {
+        Ok(rocket.mount(self.prefix.as_str(), self.routes()))
+    }
+}
+
+/// The handler of the catch-all route.
+#[derive(Debug, Clone)]
+struct RedirectHandler {
+    /// The registry redirects are looked up in.
+    registry: Arc<Registry>,
+}
+
+#[rocket::async_trait]
+impl Handler for RedirectHandler {
+    async fn handle<'r>(&self, request: &'r Request<'_>, data: Data<'r>) -> Outcome<'r> {
+        let segments = request.routed_segments(0..);
+        let short_name = match segments.get(0) {
+            Some(short_name) if segments.len() == 1 => short_name,
+            _ => return Outcome::forward(data, Status::NotFound),
+        };
+        let short_name = short_name.strip_suffix(".html").unwrap_or(short_name);
+
+        match self.registry.target_for(short_name) {
+            Some(target)
+                if self
+                    .registry
+                    .entry(target)
+                    .is_some_and(RegistryEntry::is_retired) =>
+            {
+                Outcome::error(Status::Gone)
+            }
+            Some(target) => Outcome::from(request, Redirect::moved(target.to_string())),
+            None => Outcome::forward(data, Status::NotFound),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rocket::local::blocking::Client;
+    use std::path::PathBuf;
+
+    fn registry() -> Registry {
+        let mut registry = Registry::empty(PathBuf::from("s"));
+        registry.insert("/docs/install/".to_string(), "s/install.html".to_string());
+        registry.insert("/docs/old/".to_string(), "s/old.html".to_string());
+        registry.entry_mut("/docs/old/").unwrap().retire();
+        registry
+    }
+
+    #[rocket::get("/about")]
+    fn about() -> &'static str {
+        "about"
+    }
+
+    fn client() -> Client {
+        let rocket = rocket::custom(rocket::Config::debug_default())
+            .attach(RedirectFairing::new(registry()).prefix("/s"))
+            .mount("/s", rocket::routes![about]);
+        Client::tracked(rocket).unwrap()
+    }
+
+    #[test]
+    fn test_fairing_redirects() {
+        let client = client();
+
+        for uri in ["/s/install", "/s/install.html", "/s/install/"] {
+            let response = client.get(uri).dispatch();
+            assert_eq!(response.status(), Status::MovedPermanently, "{uri}");
+            assert_eq!(
+                response.headers().get_one("Location"),
+                Some("/docs/install/")
+            );
+        }
+        let response = client.head("/s/install").dispatch();
+        assert_eq!(response.status(), Status::MovedPermanently);
+    }
+
+    #[test]
+    fn test_fairing_forwards_other_requests() {
+        let client = client();
+
+        let response = client.get("/s/about").dispatch();
+        assert_eq!(response.into_string().as_deref(), Some("about"));
+        for uri in ["/s/missing", "/s/a/install", "/install"] {
+            assert_eq!(
+                client.get(uri).dispatch().status(),
+                Status::NotFound,
+                "{uri}"
+            );
+        }
+        assert_eq!(client.get("/s/old").dispatch().status(), Status::Gone);
+    }
+}
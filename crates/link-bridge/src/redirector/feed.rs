@@ -0,0 +1,260 @@
+//! An Atom feed of recently created redirects.
+//!
+//! Stakeholders often want to follow which short links are being published without
+//! reading the registry. This module provides `Registry::to_atom_feed()`, which
+//! renders the newest redirects as an Atom feed that any feed reader can subscribe to.
+
+use std::fmt::Write;
+use std::fs;
+
+use crate::redirector::bookmarks::escape;
+use crate::redirector::target::ExternalUrl;
+use crate::redirector::time::Timestamp;
+use crate::redirector::{trace, RedirectorError, Registry};
+
+/// A redirect listed in the feed.
+struct FeedEntry<'a> {
+    /// The short name of the redirect.
+    short_name: &'a str,
+    /// The URL of the redirect relative to the output directory.
+    short_file: &'a str,
+    /// The target of the redirect.
+    target: &'a str,
+    /// When the redirect was recorded, or its page was last written.
+    written: Timestamp,
+}
+
+impl Registry {
+    /// Renders the newest redirects as an Atom feed.
+    ///
+    /// Each redirect is dated by when it was recorded in the registry. Registries
+    /// written by earlier versions do not record this, so the modification time of the
+    /// redirect page is used instead, and redirects whose page is missing are left out.
+    /// Up to `limit` redirects are listed, newest first, each
+    /// linking to its redirect page below `site_url`. The feed's `updated` time is
+    /// that of the newest redirect, or the current time if there are none.
+    ///
+    /// # Arguments
+    ///
+    /// * `site_url` - The URL the output directory is served from, such as
+    ///   `"https://example.com/s"`
+    /// * `limit` - The maximum number of redirects to list
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The Atom feed as XML
+    /// * `Err(RedirectorError::InvalidTarget)` - If the site URL is malformed
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::{Redirector, Registry};
+    /// use std::fs;
+    ///
+    /// Redirector::builder()
+    ///     .target("docs/install")
+    ///     .out_dir("doc_test_feed")
+    ///     .alias("install")
+    ///     .build()
+    ///     .unwrap()
+    ///     .write_redirect()
+    ///     .unwrap();
+    ///
+    /// let registry = Registry::load("doc_test_feed").unwrap();
+    /// let feed = registry.to_atom_feed("https://example.com/s", 20).unwrap();
+    /// assert!(feed.contains(r#"<link href="https://example.com/s/install.html"/>"#));
+    ///
+    /// fs::remove_dir_all("doc_test_feed").ok();
+    /// ```
+    pub fn to_atom_feed<S: ToString>(
+        &self,
+        site_url: S,
+        limit: usize,
+    ) -> Result<String, RedirectorError> {
+        let site_url = escape(&ExternalUrl::new_base(site_url.to_string())?.to_string());
+
+        let mut entries: Vec<FeedEntry> = self
+            .iter()
+            .filter_map(|(short_name, target, file_path)| {
+                let entry = self.entry(target)?;
+                if let Some(created_at) = entry.created_at {
+                    return Some(FeedEntry {
+                        short_name,
+                        short_file: entry.short_file(),
+                        target,
+                        written: created_at,
+                    });
+                }
+                match fs::metadata(file_path).and_then(|metadata| metadata.modified()) {
+                    Ok(modified) => Some(FeedEntry {
+                        short_name,
+                        short_file: entry.short_file(),
+                        target,
+                        written: modified.into(),
+                    }),
+                    Err(_e) => {
+                        trace::warn!(
+                            file = %file_path.display(),
+                            error = %_e,
+                            "leaving redirect out of feed"
+                        );
+                        None
+                    }
+                }
+            })
+            .collect();
+        entries.sort_unstable_by(|a, b| (b.written, a.short_name).cmp(&(a.written, b.short_name)));
+        entries.truncate(limit);
+
+        let updated = entries
+            .first()
+            .map_or_else(Timestamp::now, |entry| entry.written);
+        let mut xml = format!(
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
+             <feed xmlns=\"http://www.w3.org/2005/Atom\">\n  \
+             <title>New redirects in {}</title>\n  \
+             <id>{site_url}/</id>\n  \
+             <link href=\"{site_url}/\"/>\n  \
+             <updated>{}</updated>\n  \
+             <generator>link-bridge</generator>\n",
+            escape(&self.dir().display().to_string()),
+            updated.to_rfc3339()
+        );
+        for entry in entries {
+            let short_name = escape(entry.short_name);
+            let short_file = escape(entry.short_file);
+            let _ = write!(
+                xml,
+                "  <entry>\n    \
+                 <title>{short_name}</title>\n    \
+                 <id>{site_url}/{short_file}</id>\n    \
+                 <link href=\"{site_url}/{short_file}\"/>\n    \
+                 <updated>{}</updated>\n    \
+                 <summary>{short_name} redirects to {}</summary>\n  \
+                 </entry>\n",
+                entry.written.to_rfc3339(),
+                escape(entry.target)
+            );
+        }
+        xml.push_str("</feed>\n");
+
+        Ok(xml)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Redirector;
+    use chrono::Utc;
+    use std::fs::File;
+    use std::path::PathBuf;
+    use std::time::{Duration, SystemTime};
+
+    fn test_dir(name: &str) -> String {
+        format!("{name}_{}", Utc::now().timestamp_nanos_opt().unwrap_or(0))
+    }
+
+    #[test]
+    fn test_to_atom_feed_legacy_registry() {
+        let dir = test_dir("test_to_atom_feed_legacy_registry");
+        let epoch = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+
+        for (alias, days) in [("old", 0), ("newest", 2), ("middle", 1)] {
+            let outcome = Redirector::builder()
+                .target(format!("docs/{alias}"))
+                .out_dir(&dir)
+                .alias(alias)
+                .build()
+                .unwrap()
+                .write_redirect()
+                .unwrap();
+            File::options()
+                .write(true)
+                .open(outcome.file_path())
+                .unwrap()
+                .set_modified(epoch + Duration::from_secs(86_400 * days))
+                .unwrap();
+        }
+        fs::remove_file(format!("{dir}/old.html")).unwrap();
+        // Registries written by earlier versions do not record when redirects were created
+        fs::write(
+            format!("{dir}/registry.json"),
+            format!(
+                r#"{{"/docs/middle/": "{dir}/middle.html", "/docs/newest/": "{dir}/newest.html", "/docs/old/": "{dir}/old.html"}}"#
+            ),
+        )
+        .unwrap();
+
+        let registry = Registry::load(&dir).unwrap();
+        let feed = registry.to_atom_feed("https://example.com/s", 10).unwrap();
+
+        assert_eq!(
+            feed,
+            format!(
+                r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title>New redirects in {dir}</title>
+  <id>https://example.com/s/</id>
+  <link href="https://example.com/s/"/>
+  <updated>2023-11-16T22:13:20Z</updated>
+  <generator>link-bridge</generator>
+  <entry>
+    <title>newest</title>
+    <id>https://example.com/s/newest.html</id>
+    <link href="https://example.com/s/newest.html"/>
+    <updated>2023-11-16T22:13:20Z</updated>
+    <summary>newest redirects to /docs/newest/</summary>
+  </entry>
+  <entry>
+    <title>middle</title>
+    <id>https://example.com/s/middle.html</id>
+    <link href="https://example.com/s/middle.html"/>
+    <updated>2023-11-15T22:13:20Z</updated>
+    <summary>middle redirects to /docs/middle/</summary>
+  </entry>
+</feed>
+"#
+            )
+        );
+
+        let feed = registry.to_atom_feed("https://example.com/s", 1).unwrap();
+        assert_eq!(feed.matches("<entry>").count(), 1);
+        assert!(feed.contains("<title>newest</title>"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_to_atom_feed_uses_created_at() {
+        let registry = Registry::from_reader(
+            PathBuf::from("s"),
+            br#"{
+                "/a/": {"short_file": "a.html", "path": "s/a.html", "created_at": "2024-01-02T00:00:00Z"},
+                "/b/": {"short_file": "b.html", "path": "s/b.html", "created_at": "2024-01-01T00:00:00Z"}
+            }"#
+            .as_slice(),
+        )
+        .unwrap();
+
+        let feed = registry.to_atom_feed("https://example.com/s", 10).unwrap();
+
+        assert!(feed.contains(
+            "<feed xmlns=\"http://www.w3.org/2005/Atom\">\n  <title>New redirects in s</title>"
+        ));
+        assert!(feed.contains("  <updated>2024-01-02T00:00:00Z</updated>\n  <generator>"));
+        let a = feed.find("<title>a</title>").unwrap();
+        let b = feed.find("<title>b</title>").unwrap();
+        assert!(a < b);
+    }
+
+    #[test]
+    fn test_to_atom_feed_invalid_site_url() {
+        let registry = Registry::default();
+
+        assert!(matches!(
+            registry.to_atom_feed("/s", 10),
+            Err(RedirectorError::InvalidTarget(_))
+        ));
+    }
+}
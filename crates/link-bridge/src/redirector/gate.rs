@@ -0,0 +1,216 @@
+//! Redirect pages that wait for the visitor to confirm.
+//!
+//! Links to sensitive internal resources should not be followed by accident, or by
+//! anyone who merely stumbles on the short link. This module renders the pages used
+//! instead of the automatic redirect: one asks the visitor to press a button, and with
+//! the `passphrase` feature another asks for a passphrase, checked in the browser
+//! against its hash, before redirecting.
+
+/// Translates a message of the page text from its id, its English text, and its
+/// arguments.
+pub(crate) type PageText<'a> = dyn Fn(&str, &str, &[(&str, &str)]) -> String + 'a;
+
+/// How a redirect page waits before sending the visitor on.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub(crate) enum Gate {
+    /// Redirect automatically.
+    #[default]
+    Open,
+    /// Redirect once the visitor presses a button.
+    Confirm,
+    /// Redirect once the visitor enters the passphrase with this salted hash.
+    #[cfg(feature = "passphrase")]
+    Passphrase {
+        /// The salt prepended to the passphrase before hashing.
+        salt: String,
+        /// The SHA-256 hash of the salted passphrase, as lowercase hex.
+        hash: String,
+    },
+}
+
+impl Gate {
+    /// Creates a passphrase gate, salting the passphrase with the short name.
+    ///
+    /// Salting with the short name means two links sharing a passphrase still have
+    /// different hashes.
+    #[cfg(feature = "passphrase")]
+    pub(crate) fn passphrase(short_name: &str, passphrase: &str) -> Self {
+        let salt = format!("{short_name}:");
+        let hash = sha256_hex(&format!("{salt}{passphrase}"));
+        Gate::Passphrase { salt, hash }
+    }
+
+    /// Renders the gated page for a resolved target.
+    ///
+    /// `redirect` is the script statement that redirects, and `beacon` a statement run
+    /// just before it; `target` is linked for visitors without JavaScript. The page is
+    /// written in the language `lang`, with `text` translating each message from its
+    /// id, its English text, and its arguments. Returns `None` for [`Gate::Open`],
+    /// which renders the usual redirect page.
+    pub(crate) fn render(
+        &self,
+        lang: &str,
+        text: &PageText,
+        target: &str,
+        redirect: &str,
+        beacon: &str,
+    ) -> Option<String> {
+        let (form, check) = match self {
+            Gate::Open => return None,
+            Gate::Confirm => {
+                let link = format!(
+                    "<a href='{target}'>{}</a>",
+                    text("redirect-link", "link to page", &[])
+                );
+                let noscript = text(
+                    "confirm-noscript",
+                    "Without JavaScript, follow this { $link }.",
+                    &[("link", &link)],
+                );
+                (
+                    format!(
+                        r#"
+        <noscript>{noscript}</noscript>"#
+                    ),
+                    "true".to_string(),
+                )
+            }
+            #[cfg(feature = "passphrase")]
+            Gate::Passphrase { salt, hash } => (
+                format!(
+                    r#"
+        <label for="passphrase">{}</label>
+        <input type="password" id="passphrase" autocomplete="off" required>
+        <p id="rejected" hidden>{}</p>"#,
+                    text("passphrase-label", "Passphrase", &[]),
+                    text(
+                        "passphrase-rejected",
+                        "That passphrase is not correct.",
+                        &[]
+                    )
+                ),
+                format!(
+                    r#"(await sha256("{salt}" + document.getElementById("passphrase").value)) === "{hash}""#
+                ),
+            ),
+        };
+
+        let title = text("confirm-title", "Confirm Redirection", &[]);
+        let prompt = text(
+            "confirm-prompt",
+            "This link leads to a protected page.",
+            &[],
+        );
+        let button = text("confirm-continue", "Continue", &[]);
+        Some(format!(
+            r#"
+    <!DOCTYPE HTML>
+    <html lang="{lang}">
+
+    <head>
+        <meta charset="UTF-8">
+        <meta name="robots" content="noindex">
+        <title>{title}</title>
+    </head>
+
+    <body>
+        <form id="gate">
+        <p>{prompt}</p>{form}
+        <button type="submit">{button}</button>
+        </form>
+        <script type="text/javascript">
+            async function sha256(text) {{
+                const digest = await crypto.subtle.digest("SHA-256", new TextEncoder().encode(text));
+                return Array.from(new Uint8Array(digest), (b) => b.toString(16).padStart(2, "0")).join("");
+            }}
+            document.getElementById("gate").addEventListener("submit", async function (event) {{
+                event.preventDefault();
+                if ({check}) {{
+                    {beacon}
+                    {redirect}
+                }} else {{
+                    document.getElementById("rejected").hidden = false;
+                }}
+            }});
+        </script>
+    </body>
+
+    </html>
+    "#
+        ))
+    }
+}
+
+/// Hashes text with SHA-256, returning lowercase hex.
+#[cfg(feature = "passphrase")]
+fn sha256_hex(text: &str) -> String {
+    use sha2::{Digest, Sha256};
+    use std::fmt::Write;
+
+    Sha256::digest(text.as_bytes())
+        .iter()
+        .fold(String::with_capacity(64), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::redirector::english_text;
+
+    fn english(_: &str, english: &str, args: &[(&str, &str)]) -> String {
+        english_text(english, args)
+    }
+
+    #[test]
+    fn test_render_open() {
+        assert_eq!(Gate::Open.render("en-US", &english, "/a/", "", ""), None);
+    }
+
+    #[test]
+    fn test_render_confirm() {
+        let page = Gate::Confirm
+            .render(
+                "en-US",
+                &english,
+                "/internal/wiki/",
+                r#"window.location.href = "/internal/wiki/";"#,
+                "",
+            )
+            .unwrap();
+
+        assert!(page.contains(r#"<button type="submit">Continue</button>"#));
+        assert!(page.contains(r#"window.location.href = "/internal/wiki/";"#));
+        assert!(page.contains("if (true)"));
+        assert!(!page.contains("http-equiv=\"refresh\""));
+        assert!(page.contains("<a href='/internal/wiki/'>"));
+        assert!(!page.contains("passphrase"));
+    }
+
+    #[cfg(feature = "passphrase")]
+    #[test]
+    fn test_render_passphrase() {
+        let gate = Gate::passphrase("wiki", "open sesame");
+        assert_eq!(gate, Gate::passphrase("wiki", "open sesame"));
+        assert_ne!(gate, Gate::passphrase("docs", "open sesame"));
+
+        let page = gate
+            .render("en-US", &english, "/internal/wiki/", "", "")
+            .unwrap();
+        assert!(page.contains(r#"<input type="password" id="passphrase""#));
+        assert!(page.contains(r#"sha256("wiki:" + document.getElementById("passphrase").value)"#));
+        assert!(!page.contains("open sesame"));
+        assert!(!page.contains("<noscript>"));
+    }
+
+    #[cfg(feature = "passphrase")]
+    #[test]
+    fn test_sha256_hex() {
+        assert_eq!(
+            sha256_hex("abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+}
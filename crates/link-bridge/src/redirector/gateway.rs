@@ -0,0 +1,245 @@
+//! Exporting the registry as Kubernetes Gateway API routes.
+//!
+//! Platform teams running a Kubernetes cluster often redirect at the edge, where the
+//! Gateway API's `HTTPRoute` can answer a request with a redirect before it reaches any
+//! service. This module provides `Registry::to_http_routes()`, which renders the
+//! registry as `HTTPRoute` manifests, so short links can be applied with `kubectl`
+//! alongside the rest of a site's routing.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+// JSON strings are valid double-quoted YAML scalars
+use crate::redirector::json::quote;
+use crate::redirector::target::{is_absolute_url, is_contact_url};
+use crate::redirector::{trace, Registry};
+
+/// The most rules the Gateway API accepts in one `HTTPRoute`.
+const MAX_RULES: usize = 16;
+
+impl Registry {
+    /// Renders the registry as Gateway API `HTTPRoute` manifests in YAML.
+    ///
+    /// Each target becomes a rule matching the paths of its short names below
+    /// `base_path`, with and without the `.html` extension, and answering them with a
+    /// `RequestRedirect` filter returning `301` and the target. An `HTTPRoute` holds at
+    /// most sixteen rules, so larger registries are split into several routes named
+    /// `<name>-1`, `<name>-2`, and so on, as documents of one YAML stream. Retired
+    /// redirects are left out, as are contact targets and targets with a query or
+    /// fragment, which a redirect filter cannot express. An empty registry renders as
+    /// an empty string.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the `HTTPRoute`
+    /// * `gateway` - The name of the `Gateway` the route attaches to
+    /// * `base_path` - The path the short links are served from, such as `"/s"`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::{Redirector, Registry};
+    /// use std::fs;
+    ///
+    /// Redirector::builder()
+    ///     .target("docs/install")
+    ///     .out_dir("doc_test_http_routes")
+    ///     .alias("install")
+    ///     .build()
+    ///     .unwrap()
+    ///     .write_redirect()
+    ///     .unwrap();
+    ///
+    /// let registry = Registry::load("doc_test_http_routes").unwrap();
+    /// let yaml = registry.to_http_routes("short-links", "edge", "/s");
+    /// assert!(yaml.contains("kind: HTTPRoute"));
+    /// assert!(yaml.contains(r#"value: "/s/install""#));
+    /// assert!(yaml.contains(r#"replaceFullPath: "/docs/install/""#));
+    ///
+    /// fs::remove_dir_all("doc_test_http_routes").ok();
+    /// ```
+    pub fn to_http_routes<S: ToString>(&self, name: S, gateway: S, base_path: S) -> String {
+        let mut sources: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+        for (source, target) in self.redirect_sources(&base_path.to_string()) {
+            if target.contains(['?', '#']) || is_contact_url(target) {
+                trace::warn!(target = %target, "target cannot be expressed as a route redirect");
+                continue;
+            }
+            sources.entry(target).or_default().push(source);
+        }
+        let rules: Vec<String> = sources
+            .iter()
+            .map(|(target, sources)| rule(target, sources))
+            .collect();
+
+        let name = name.to_string();
+        let gateway = gateway.to_string();
+        let chunks: Vec<&[String]> = rules.chunks(MAX_RULES).collect();
+        let mut yaml = String::new();
+        for (index, chunk) in chunks.iter().enumerate() {
+            let route_name = if chunks.len() == 1 {
+                name.clone()
+            } else {
+                format!("{name}-{}", index + 1)
+            };
+            if index > 0 {
+                yaml.push_str("---\n");
+            }
+            let _ = write!(
+                yaml,
+                "apiVersion: gateway.networking.k8s.io/v1\n\
+                 kind: HTTPRoute\n\
+                 metadata:\n\
+                 \x20 name: {}\n\
+                 spec:\n\
+                 \x20 parentRefs:\n\
+                 \x20   - name: {}\n\
+                 \x20 rules:\n",
+                quote(&route_name),
+                quote(&gateway)
+            );
+            for rule in chunk.iter() {
+                yaml.push_str(rule);
+            }
+        }
+        yaml
+    }
+}
+
+/// Renders the rule redirecting `sources` to `target`.
+fn rule(target: &str, sources: &[String]) -> String {
+    let mut rule = String::from("    - matches:\n");
+    for source in sources {
+        for path in [source.clone(), format!("{source}.html")] {
+            let _ = write!(
+                rule,
+                "        - path:\n\
+                 \x20           type: Exact\n\
+                 \x20           value: {}\n",
+                quote(&path)
+            );
+        }
+    }
+    rule.push_str(
+        "      filters:\n\
+         \x20       - type: RequestRedirect\n\
+         \x20         requestRedirect:\n\
+         \x20           statusCode: 301\n",
+    );
+
+    let path = if is_absolute_url(target) {
+        let (scheme, rest) = target.split_once("://").unwrap_or(("https", target));
+        let (authority, path) = rest.find('/').map_or((rest, "/"), |i| rest.split_at(i));
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (authority, None),
+        };
+        let _ = writeln!(rule, "            scheme: {scheme}");
+        let _ = writeln!(rule, "            hostname: {}", quote(host));
+        if let Some(port) = port {
+            let _ = writeln!(rule, "            port: {port}");
+        }
+        path
+    } else {
+        target
+    };
+    let _ = write!(
+        rule,
+        "            path:\n\
+         \x20             type: ReplaceFullPath\n\
+         \x20             replaceFullPath: {}\n",
+        quote(path)
+    );
+    rule
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_to_http_routes() {
+        let mut registry = Registry::empty(PathBuf::from("s"));
+        registry.insert("/docs/usage/".to_string(), "s/usage.html".to_string());
+        registry.insert_alias("/docs/usage/", "how-to");
+        registry.insert(
+            "https://docs.example.org:8443/guide".to_string(),
+            "s/guide.html".to_string(),
+        );
+        registry.insert(
+            "https://example.org/?a=1".to_string(),
+            "s/query.html".to_string(),
+        );
+        registry.insert(
+            "mailto:team@example.org".to_string(),
+            "s/mail.html".to_string(),
+        );
+
+        assert_eq!(
+            registry.to_http_routes("short-links", "edge", "/s"),
+            r#"apiVersion: gateway.networking.k8s.io/v1
+kind: HTTPRoute
+metadata:
+  name: "short-links"
+spec:
+  parentRefs:
+    - name: "edge"
+  rules:
+    - matches:
+        - path:
+            type: Exact
+            value: "/s/how-to"
+        - path:
+            type: Exact
+            value: "/s/how-to.html"
+        - path:
+            type: Exact
+            value: "/s/usage"
+        - path:
+            type: Exact
+            value: "/s/usage.html"
+      filters:
+        - type: RequestRedirect
+          requestRedirect:
+            statusCode: 301
+            path:
+              type: ReplaceFullPath
+              replaceFullPath: "/docs/usage/"
+    - matches:
+        - path:
+            type: Exact
+            value: "/s/guide"
+        - path:
+            type: Exact
+            value: "/s/guide.html"
+      filters:
+        - type: RequestRedirect
+          requestRedirect:
+            statusCode: 301
+            scheme: https
+            hostname: "docs.example.org"
+            port: 8443
+            path:
+              type: ReplaceFullPath
+              replaceFullPath: "/guide"
+"#
+        );
+    }
+
+    #[test]
+    fn test_to_http_routes_splits_large_registries() {
+        let mut registry = Registry::empty(PathBuf::from("s"));
+        assert_eq!(registry.to_http_routes("links", "edge", "/s"), "");
+
+        for i in 0..20 {
+            registry.insert(format!("/docs/{i}/"), format!("s/page-{i}.html"));
+        }
+        let yaml = registry.to_http_routes("links", "edge", "/s");
+        assert_eq!(yaml.matches("kind: HTTPRoute").count(), 2);
+        assert!(yaml.contains("name: \"links-1\"\n"));
+        assert!(yaml.contains("---\napiVersion"));
+        assert!(yaml.contains("name: \"links-2\"\n"));
+        assert_eq!(yaml.matches("- type: RequestRedirect").count(), 20);
+    }
+}
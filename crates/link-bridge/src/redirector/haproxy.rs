@@ -0,0 +1,114 @@
+//! Exporting the registry as an HAProxy map file.
+//!
+//! HAProxy looks up values in `map` files of whitespace-separated key and value
+//! lines, and a single `http-request redirect` rule can answer every path found in
+//! one. This module provides `Registry::to_haproxy_map()`, which renders the registry
+//! as such a map, so a load balancer can redirect short links without a page being
+//! served.
+
+use crate::redirector::Registry;
+
+/// Where the sample rule in the map's header expects the map to be installed.
+const SAMPLE_MAP_PATH: &str = "/etc/haproxy/short-links.map";
+
+impl Registry {
+    /// Renders the registry as an HAProxy `map` file.
+    ///
+    /// Each short name, including extra short names added with
+    /// [`Registry::add_alias()`], maps its path below `base_path`, with and without the
+    /// `.html` extension, to its target. Whitespace and control characters in targets
+    /// are percent-encoded, so they cannot split a value or an entry. Retired
+    /// redirects are left out. Lines are
+    /// ordered by path, after a comment holding a sample `http-request redirect` rule
+    /// that answers the mapped paths with `301` redirects:
+    ///
+    /// ```text
+    /// http-request redirect code 301 location %[path,map(/etc/haproxy/short-links.map)] if { path,map(/etc/haproxy/short-links.map) -m found }
+    /// ```
+    ///
+    /// # Arguments
+    ///
+    /// * `base_path` - The path the short links are served from, such as `"/s"`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::{Redirector, Registry};
+    /// use std::fs;
+    ///
+    /// Redirector::builder()
+    ///     .target("docs/install")
+    ///     .out_dir("doc_test_haproxy_map")
+    ///     .alias("install")
+    ///     .build()
+    ///     .unwrap()
+    ///     .write_redirect()
+    ///     .unwrap();
+    ///
+    /// let registry = Registry::load("doc_test_haproxy_map").unwrap();
+    /// let map = registry.to_haproxy_map("/s");
+    /// assert!(map.contains("\n/s/install /docs/install/\n"));
+    /// assert!(map.contains("\n/s/install.html /docs/install/\n"));
+    ///
+    /// fs::remove_dir_all("doc_test_haproxy_map").ok();
+    /// ```
+    pub fn to_haproxy_map<S: ToString>(&self, base_path: S) -> String {
+        let mut map = format!(
+            "# Generated by link-bridge from the registry; do not edit.\n\
+             # Install as {SAMPLE_MAP_PATH} and redirect from a frontend with:\n\
+             #   http-request redirect code 301 location %[path,map({SAMPLE_MAP_PATH})] \
+             if {{ path,map({SAMPLE_MAP_PATH}) -m found }}\n"
+        );
+        map.push_str(&self.map_lines(&base_path.to_string()));
+        map
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_to_haproxy_map() {
+        let mut registry = Registry::empty(PathBuf::from("s"));
+        registry.insert("/docs/usage/".to_string(), "s/usage.html".to_string());
+        registry.insert(
+            "https://example.org/?a=1&b=2".to_string(),
+            "s/external.html".to_string(),
+        );
+        registry.insert("/docs/old/".to_string(), "s/old.html".to_string());
+        registry.entry_mut("/docs/old/").unwrap().retire();
+
+        assert_eq!(
+            registry.to_haproxy_map("/s"),
+            "# Generated by link-bridge from the registry; do not edit.
+# Install as /etc/haproxy/short-links.map and redirect from a frontend with:
+#   http-request redirect code 301 location %[path,map(/etc/haproxy/short-links.map)] if { path,map(/etc/haproxy/short-links.map) -m found }
+/s/external https://example.org/?a=1&b=2
+/s/external.html https://example.org/?a=1&b=2
+/s/usage /docs/usage/
+/s/usage.html /docs/usage/
+"
+        );
+    }
+
+    #[test]
+    fn test_to_haproxy_map_encodes_whitespace() {
+        let mut registry = Registry::empty(PathBuf::from("s"));
+        registry.insert("/my docs/".to_string(), "s/docs.html".to_string());
+        registry.insert("/a\tb\nc/".to_string(), "s/tab.html".to_string());
+
+        let map = registry.to_haproxy_map("/s");
+        let lines: Vec<&str> = map.lines().filter(|line| !line.starts_with('#')).collect();
+        assert_eq!(
+            lines,
+            [
+                "/s/docs /my%20docs/",
+                "/s/docs.html /my%20docs/",
+                "/s/tab /a%09b%0Ac/",
+                "/s/tab.html /a%09b%0Ac/",
+            ]
+        );
+    }
+}
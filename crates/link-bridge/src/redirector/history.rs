@@ -0,0 +1,309 @@
+//! The targets a short link led to before it was repointed.
+//!
+//! Repointing a short link with `Registry::update_target()` replaces its target, so
+//! the registry alone cannot say where the link used to lead or undo a mistaken
+//! change. This module provides the `TargetChange` recorded in the entry for each
+//! previous target, `Registry::target_at()` to look up the target at a past time,
+//! and `Registry::revert_target()` to go back to the previous one.
+
+use std::io::{self, Write};
+
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Utc};
+
+use crate::redirector::json::{write_quoted, Value};
+use crate::redirector::time::Timestamp;
+use crate::redirector::{RedirectorError, Registry};
+
+/// A target a short link led to before being repointed.
+///
+/// Recorded in [`RegistryEntry::history()`](crate::RegistryEntry::history) by
+/// [`Registry::update_target()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TargetChange {
+    /// The previous target, as normalized.
+    target: String,
+    /// When the short link stopped leading to the target, to the second.
+    until: Timestamp,
+}
+
+impl TargetChange {
+    /// Records that a short link led to `target` until `until`.
+    pub(crate) fn new(target: String, until: Timestamp) -> Self {
+        TargetChange { target, until }
+    }
+
+    /// Returns the previous target, as normalized (e.g. `"/summer/sale/"`).
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    /// Returns when the short link was repointed away from the target.
+    #[cfg(feature = "chrono")]
+    pub fn until(&self) -> DateTime<Utc> {
+        self.until.into()
+    }
+
+    /// Parses a change from its `registry.json` value.
+    pub(crate) fn from_json(value: Value) -> Result<Self, String> {
+        let Value::Object(mut fields) = value else {
+            return Err("history must be objects".to_string());
+        };
+        let Some(Value::String(target)) = fields.remove("target") else {
+            return Err("history target is not a string".to_string());
+        };
+        let Some(Value::String(until)) = fields.remove("until") else {
+            return Err("history until is not a string".to_string());
+        };
+        let until = Timestamp::parse_rfc3339(&until)
+            .ok_or_else(|| format!("invalid history until: {until}"))?;
+
+        Ok(TargetChange { target, until })
+    }
+
+    /// Serializes the change as a `registry.json` object on one line.
+    pub(crate) fn write_json<W: Write + ?Sized>(&self, writer: &mut W) -> Result<(), io::Error> {
+        writer.write_all(b"{\"target\": ")?;
+        write_quoted(writer, &self.target)?;
+        write!(writer, ", \"until\": \"{}\"}}", self.until.to_rfc3339())
+    }
+}
+
+impl Registry {
+    /// Returns the target a short link led to at a given time.
+    ///
+    /// The short link's [`history`](crate::RegistryEntry::history) is searched for the
+    /// target it led to at `time`; if it has not been repointed since, its current
+    /// target is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `short_name` - The short name (e.g. `"promo"`), or one of its aliases
+    /// * `time` - The time to look up
+    ///
+    /// # Returns
+    ///
+    /// The target at that time, or `None` if no redirect has the short name or it was
+    /// created after `time`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use chrono::Utc;
+    /// use link_bridge::{Redirector, Registry};
+    /// use std::fs;
+    ///
+    /// Redirector::builder()
+    ///     .target("summer/sale")
+    ///     .out_dir("doc_test_registry_target_at")
+    ///     .alias("promo")
+    ///     .build()
+    ///     .unwrap()
+    ///     .write_redirect()
+    ///     .unwrap();
+    ///
+    /// let mut registry = Registry::load("doc_test_registry_target_at").unwrap();
+    /// registry.update_target("promo", "winter/sale").unwrap();
+    ///
+    /// let change = &registry.entry("/winter/sale/").unwrap().history()[0];
+    /// assert_eq!(change.target(), "/summer/sale/");
+    /// assert_eq!(registry.target_at("promo", Utc::now()), Some("/winter/sale/"));
+    ///
+    /// fs::remove_dir_all("doc_test_registry_target_at").ok();
+    /// ```
+    #[cfg(feature = "chrono")]
+    pub fn target_at(&self, short_name: &str, time: DateTime<Utc>) -> Option<&str> {
+        let time = Timestamp::from(time);
+        let target = self.target_for(short_name)?;
+        let entry = self.entry(target)?;
+        if entry.created_at.is_some_and(|created_at| time < created_at) {
+            return None;
+        }
+
+        entry
+            .history()
+            .iter()
+            .find(|change| time < change.until)
+            .map_or(Some(target), |change| Some(change.target()))
+    }
+
+    /// Repoints a short link back to the target it led to before its last change.
+    ///
+    /// Behaves like [`Registry::update_target()`] with the most recent target in the
+    /// short link's history, so the page is rewritten and the mistaken target is
+    /// itself kept in the history. Reverting again goes back and forth between the
+    /// two. The registry entry is updated in memory; call [`Registry::save()`] to
+    /// persist it.
+    ///
+    /// # Arguments
+    ///
+    /// * `short_name` - The short name (e.g. `"promo"`), or one of its aliases
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(String))` - The target the short link leads to again
+    /// * `Ok(None)` - If the short link has never been repointed
+    /// * `Err(RedirectorError::ShortLinkNotFound)` - If no redirect has that short name
+    /// * `Err(RedirectorError::DuplicateTarget)` - If another short link now
+    ///   redirects to the previous target
+    /// * `Err(RedirectorError::FileWriteError)` - If the HTML file cannot be rewritten
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::{Redirector, Registry};
+    /// use std::fs;
+    ///
+    /// Redirector::builder()
+    ///     .target("summer/sale")
+    ///     .out_dir("doc_test_registry_revert_target")
+    ///     .alias("promo")
+    ///     .build()
+    ///     .unwrap()
+    ///     .write_redirect()
+    ///     .unwrap();
+    ///
+    /// let mut registry = Registry::load("doc_test_registry_revert_target").unwrap();
+    /// registry.update_target("promo", "sumer/sale").unwrap(); // a typo
+    /// let restored = registry.revert_target("promo").unwrap();
+    /// registry.save().unwrap();
+    ///
+    /// assert_eq!(restored.as_deref(), Some("/summer/sale/"));
+    /// assert_eq!(registry.target_for("promo"), Some("/summer/sale/"));
+    ///
+    /// fs::remove_dir_all("doc_test_registry_revert_target").ok();
+    /// ```
+    pub fn revert_target(&mut self, short_name: &str) -> Result<Option<String>, RedirectorError> {
+        let target = self
+            .target_for(short_name)
+            .ok_or(RedirectorError::ShortLinkNotFound)?;
+        let Some(previous) = self
+            .entry(target)
+            .and_then(|entry| entry.history().last())
+            .map(|change| change.target().to_string())
+        else {
+            return Ok(None);
+        };
+
+        self.update_target(short_name, &previous)?;
+        Ok(Some(previous))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::redirector::json;
+    use crate::Redirector;
+    use chrono::Utc;
+    use std::fs;
+    use std::path::Path;
+
+    fn test_dir(name: &str) -> String {
+        format!("{name}_{}", Utc::now().timestamp_nanos_opt().unwrap_or(0))
+    }
+
+    #[test]
+    fn test_target_history() {
+        let dir = test_dir("test_target_history");
+        Redirector::builder()
+            .target("a")
+            .out_dir(&dir)
+            .alias("link")
+            .build()
+            .unwrap()
+            .write_redirect()
+            .unwrap();
+        let mut registry = Registry::load(&dir).unwrap();
+        let created = registry.entry("/a/").unwrap().created_at.unwrap();
+
+        assert_eq!(registry.revert_target("link").unwrap(), None);
+        registry.update_target("link", "b").unwrap();
+        registry.update_target("link", "c").unwrap();
+        // Rewriting the same target is not a change
+        registry.update_target("link", "c").unwrap();
+
+        let history = registry.entry("/c/").unwrap().history().to_vec();
+        let targets: Vec<&str> = history.iter().map(TargetChange::target).collect();
+        assert_eq!(targets, ["/a/", "/b/"]);
+        assert!(history[0].until >= created);
+
+        // The history survives a round trip
+        registry.save().unwrap();
+        let mut registry = Registry::load(&dir).unwrap();
+        assert_eq!(registry.entry("/c/").unwrap().history(), history);
+
+        assert_eq!(
+            registry.revert_target("link").unwrap().as_deref(),
+            Some("/b/")
+        );
+        assert_eq!(registry.target_for("link"), Some("/b/"));
+        let targets: Vec<String> = registry
+            .entry("/b/")
+            .unwrap()
+            .history()
+            .iter()
+            .map(|change| change.target().to_string())
+            .collect();
+        assert_eq!(targets, ["/a/", "/b/", "/c/"]);
+        assert!(fs::read_to_string(Path::new(&dir).join("link.html"))
+            .unwrap()
+            .contains("url=/b/"));
+
+        assert!(matches!(
+            registry.revert_target("missing"),
+            Err(RedirectorError::ShortLinkNotFound)
+        ));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_target_at() {
+        use chrono::Duration;
+
+        let mut registry = Registry::default();
+        registry.insert("/c/".to_string(), "s/link.html".to_string());
+        let created = registry.entry("/c/").unwrap().created_at().unwrap();
+        let first = created + Duration::days(10);
+        let second = created + Duration::days(20);
+        let entry = registry.entry_mut("/c/").unwrap();
+        entry.push_history(TargetChange::new("/a/".to_string(), first.into()));
+        entry.push_history(TargetChange::new("/b/".to_string(), second.into()));
+
+        assert_eq!(
+            registry.target_at("link", created - Duration::days(1)),
+            None
+        );
+        assert_eq!(registry.target_at("link", created), Some("/a/"));
+        assert_eq!(
+            registry.target_at("link", first - Duration::seconds(1)),
+            Some("/a/")
+        );
+        assert_eq!(registry.target_at("link", first), Some("/b/"));
+        assert_eq!(registry.target_at("link", second), Some("/c/"));
+        assert_eq!(registry.target_at("missing", second), None);
+    }
+
+    #[test]
+    fn test_target_change_json() {
+        let until = Timestamp::now().trunc_subsecs();
+        let change = TargetChange::new("/a \"b\"/".to_string(), until);
+        let mut json = Vec::new();
+        change.write_json(&mut json).unwrap();
+
+        let value = json::from_slice(&json).unwrap();
+        assert_eq!(TargetChange::from_json(value).unwrap(), change);
+
+        for invalid in [
+            r#""/a/""#,
+            r#"{"until": "2025-01-01T00:00:00Z"}"#,
+            r#"{"target": "/a/"}"#,
+            r#"{"target": "/a/", "until": "yesterday"}"#,
+        ] {
+            let value = json::from_slice(invalid.as_bytes()).unwrap();
+            assert!(TargetChange::from_json(value).is_err(), "{invalid}");
+        }
+    }
+}
@@ -0,0 +1,249 @@
+//! Callbacks invoked when redirects are written.
+//!
+//! Applications often need to react to a new redirect, for example by purging a CDN
+//! cache, sending a notification, or logging to their own system. This module provides
+//! the `RedirectHooks` trait, which is attached to a `Redirector` once and then called
+//! by every write method, so callers do not have to wrap each write themselves.
+
+use std::fmt;
+use std::sync::Arc;
+
+use crate::redirector::{RedirectOutcome, RedirectorError};
+
+/// Callbacks invoked by the write methods of a [`Redirector`].
+///
+/// Attach hooks with [`RedirectorBuilder::hooks()`] or [`Redirector::set_hooks()`].
+/// Every method has an empty default implementation, so implementors only override
+/// the events they care about. Hooks are called after the write has finished and
+/// cannot change its result.
+///
+/// Hooks must be `Send + Sync` so a redirector can be shared across threads and used
+/// from async code. To share one hook object between several redirectors, wrap it in
+/// an [`Arc`], which also implements `RedirectHooks`. To attach several hooks, pass
+/// them as a pair; each is called in turn.
+///
+/// [`Redirector`]: crate::Redirector
+/// [`RedirectorBuilder::hooks()`]: crate::RedirectorBuilder::hooks
+/// [`Redirector::set_hooks()`]: crate::Redirector::set_hooks
+///
+/// # Examples
+///
+/// ```rust
+/// use link_bridge::{MemorySink, RedirectHooks, RedirectOutcome, Redirector};
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use std::sync::Arc;
+///
+/// #[derive(Default)]
+/// struct PurgeCache {
+///     purged: AtomicUsize,
+/// }
+///
+/// impl RedirectHooks for PurgeCache {
+///     fn on_created(&self, _outcome: &RedirectOutcome) {
+///         self.purged.fetch_add(1, Ordering::SeqCst);
+///     }
+/// }
+///
+/// let hooks = Arc::new(PurgeCache::default());
+/// let redirector = Redirector::builder()
+///     .target("api/v1/users")
+///     .hooks(Arc::clone(&hooks))
+///     .build()
+///     .unwrap();
+///
+/// let mut sink = MemorySink::new();
+/// redirector.write_redirect_to(&mut sink).unwrap();
+/// redirector.write_redirect_to(&mut sink).unwrap(); // reused, not created
+///
+/// assert_eq!(hooks.purged.load(Ordering::SeqCst), 1);
+/// ```
+pub trait RedirectHooks: Send + Sync {
+    /// Called after a new redirect file has been written and registered.
+    fn on_created(&self, outcome: &RedirectOutcome) {
+        let _ = outcome;
+    }
+
+    /// Called when the target was already registered and the existing file was reused.
+    fn on_reused(&self, outcome: &RedirectOutcome) {
+        let _ = outcome;
+    }
+
+    /// Called when writing the redirect for `target` failed.
+    ///
+    /// `target` is the normalized target, in the form used as the registry key.
+    fn on_error(&self, target: &str, error: &RedirectorError) {
+        let _ = (target, error);
+    }
+}
+
+impl<H: RedirectHooks + ?Sized> RedirectHooks for Arc<H> {
+    fn on_created(&self, outcome: &RedirectOutcome) {
+        (**self).on_created(outcome);
+    }
+
+    fn on_reused(&self, outcome: &RedirectOutcome) {
+        (**self).on_reused(outcome);
+    }
+
+    fn on_error(&self, target: &str, error: &RedirectorError) {
+        (**self).on_error(target, error);
+    }
+}
+
+/// Calls both hooks in turn, so several can be attached to one redirector.
+impl<A: RedirectHooks, B: RedirectHooks> RedirectHooks for (A, B) {
+    fn on_created(&self, outcome: &RedirectOutcome) {
+        self.0.on_created(outcome);
+        self.1.on_created(outcome);
+    }
+
+    fn on_reused(&self, outcome: &RedirectOutcome) {
+        self.0.on_reused(outcome);
+        self.1.on_reused(outcome);
+    }
+
+    fn on_error(&self, target: &str, error: &RedirectorError) {
+        self.0.on_error(target, error);
+        self.1.on_error(target, error);
+    }
+}
+
+/// The hooks attached to a redirector, if any.
+///
+/// Hooks do not describe the redirect itself, so they are ignored when redirectors are
+/// compared.
+#[derive(Clone, Default)]
+pub(crate) struct Hooks(Option<Arc<dyn RedirectHooks>>);
+
+impl Hooks {
+    /// Wraps a hook implementation.
+    pub(crate) fn new<H: RedirectHooks + 'static>(hooks: H) -> Self {
+        Hooks(Some(Arc::new(hooks)))
+    }
+
+    /// Calls `on_created` or `on_reused` for a successful write, or `on_error` for a
+    /// failed one.
+    pub(crate) fn notify(&self, target: &str, result: &Result<RedirectOutcome, RedirectorError>) {
+        match result {
+            Ok(outcome) => self.written(outcome),
+            Err(error) => self.failed(target, error),
+        }
+    }
+
+    /// Calls `on_created` or `on_reused`, depending on whether the file was created.
+    pub(crate) fn written(&self, outcome: &RedirectOutcome) {
+        match &self.0 {
+            Some(hooks) if outcome.is_created() => hooks.on_created(outcome),
+            Some(hooks) => hooks.on_reused(outcome),
+            None => {}
+        }
+    }
+
+    /// Calls `on_error` for a failed write of `target`.
+    pub(crate) fn failed(&self, target: &str, error: &RedirectorError) {
+        if let Some(hooks) = &self.0 {
+            hooks.on_error(target, error);
+        }
+    }
+}
+
+impl fmt::Debug for Hooks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(_) => f.write_str("Some(RedirectHooks)"),
+            None => f.write_str("None"),
+        }
+    }
+}
+
+impl PartialEq for Hooks {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Records the name of every hook called.
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<String>>);
+
+    impl RedirectHooks for Recorder {
+        fn on_created(&self, outcome: &RedirectOutcome) {
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("created {}", outcome.target()));
+        }
+
+        fn on_reused(&self, outcome: &RedirectOutcome) {
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("reused {}", outcome.target()));
+        }
+
+        fn on_error(&self, target: &str, _error: &RedirectorError) {
+            self.0.lock().unwrap().push(format!("error {target}"));
+        }
+    }
+
+    #[test]
+    fn test_notify_dispatches_by_result() {
+        let recorder = Arc::new(Recorder::default());
+        let hooks = Hooks::new(Arc::clone(&recorder));
+
+        hooks.notify(
+            "/a/",
+            &Ok(RedirectOutcome::new(
+                "s/a.html",
+                "a.html".into(),
+                "/a/".into(),
+                true,
+            )),
+        );
+        hooks.notify(
+            "/a/",
+            &Ok(RedirectOutcome::new(
+                "s/a.html",
+                "a.html".into(),
+                "/a/".into(),
+                false,
+            )),
+        );
+        hooks.notify("/b/", &Err(RedirectorError::ShortLinkNotFound));
+
+        assert_eq!(
+            *recorder.0.lock().unwrap(),
+            ["created /a/", "reused /a/", "error /b/"]
+        );
+    }
+
+    #[test]
+    fn test_pair_calls_both() {
+        let first = Arc::new(Recorder::default());
+        let second = Arc::new(Recorder::default());
+        let hooks = Hooks::new((Arc::clone(&first), Arc::clone(&second)));
+
+        hooks.notify("/b/", &Err(RedirectorError::ShortLinkNotFound));
+
+        assert_eq!(*first.0.lock().unwrap(), ["error /b/"]);
+        assert_eq!(*second.0.lock().unwrap(), ["error /b/"]);
+    }
+
+    #[test]
+    fn test_default_hooks_do_nothing() {
+        struct Nothing;
+        impl RedirectHooks for Nothing {}
+
+        let hooks = Hooks::new(Nothing);
+        hooks.notify("/a/", &Err(RedirectorError::ShortLinkNotFound));
+        Hooks::default().notify("/a/", &Err(RedirectorError::ShortLinkNotFound));
+
+        assert_eq!(format!("{hooks:?}"), "Some(RedirectHooks)");
+        assert_eq!(hooks, Hooks::default());
+    }
+}
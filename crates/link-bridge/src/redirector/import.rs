@@ -0,0 +1,986 @@
+//! Importing redirects defined for other hosting platforms and tools.
+//!
+//! Teams adopting link-bridge usually already have short links defined elsewhere.
+//! This module provides the `ImportFormat` type, which parses those definitions into
+//! `ImportedRedirect`s that can be written as link-bridge redirect pages and registry
+//! entries, so existing links keep working after the migration.
+//!
+//! Supported formats:
+//!
+//! * [`ImportFormat::Netlify`] - a Netlify `_redirects` file
+//! * [`ImportFormat::Nginx`] - `map` blocks, map files, and `rewrite` directives from
+//!   an nginx configuration
+//! * [`ImportFormat::Bitly`] - a Bitly link export in CSV
+//! * [`ImportFormat::Yourls`] - a YOURLS link export in CSV
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::redirector::alias::validate_alias;
+use crate::redirector::{trace, HostAllowlist, RedirectOutcome, Redirector, RedirectorError};
+
+/// A source of redirect definitions that can be imported.
+///
+/// # Examples
+///
+/// ```rust
+/// use link_bridge::ImportFormat;
+/// use std::fs;
+///
+/// fs::create_dir_all("doc_test_import").unwrap();
+/// fs::write(
+///     "doc_test_import/_redirects",
+///     "# Moved pages\n/install  /docs/installation/  301\n",
+/// )
+/// .unwrap();
+///
+/// let redirects = ImportFormat::Netlify.read("doc_test_import/_redirects").unwrap();
+/// assert_eq!(redirects[0].short_name(), "install");
+/// assert_eq!(redirects[0].target(), "/docs/installation/");
+///
+/// let outcomes = ImportFormat::Netlify
+///     .write_redirects("doc_test_import/_redirects", "doc_test_import/s", &Default::default())
+///     .unwrap();
+/// assert!(outcomes[0].is_created());
+///
+/// fs::remove_dir_all("doc_test_import").ok();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    /// A Netlify `_redirects` file.
+    ///
+    /// Each line holds a source path, a target, and an optional status code, separated
+    /// by whitespace; blank lines and `#` comments are ignored. The source path becomes
+    /// the short name. Rules a static redirect page cannot reproduce are skipped:
+    /// rewrites and custom error pages (status `200`, `404`, and other non-redirect
+    /// codes), rules using splats (`*`) or placeholders (`:name`), and rules with query
+    /// parameter, country, language, or role conditions.
+    Netlify,
+    /// Redirects exported from an nginx configuration.
+    ///
+    /// Entries of `map` blocks, and of map files pulled into them with `include`, are
+    /// read as `source target;` pairs; `rewrite` directives with the `permanent` or
+    /// `redirect` flag are read as well. Everything else in the configuration is
+    /// ignored. The source path becomes the short name. Rules a static redirect page
+    /// cannot reproduce are skipped: regular expression map keys (`~`), `default`
+    /// entries, rewrites whose pattern is not an anchored literal path such as
+    /// `^/old/?$`, internal rewrites (`last` and `break`), and targets that use nginx
+    /// variables or captures (`$`).
+    Nginx,
+    /// A Bitly link export in CSV.
+    ///
+    /// The first row names the columns; the short link is read from the `bitlink` (or
+    /// `link`) column and the target from the `long_url` column, and other columns are
+    /// ignored. The path of the short link becomes the short name, so
+    /// `https://bit.ly/3abcXyz` becomes `3abcXyz`. Targets are absolute URLs, so their
+    /// hosts must be allowlisted when the redirects are written.
+    Bitly,
+    /// A YOURLS link export in CSV.
+    ///
+    /// The short link keyword is read from the `keyword` column and the target from the
+    /// `url` column, and other columns are ignored. Exports without a header row are
+    /// read as `keyword,url,...`, the column order of the YOURLS database table. The
+    /// keyword becomes the short name. Targets are absolute URLs, so their hosts must
+    /// be allowlisted when the redirects are written.
+    Yourls,
+}
+
+/// A redirect read from an imported file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedRedirect {
+    /// The line of the file that defines the redirect, starting at 1.
+    line: usize,
+    /// The short name of the redirect.
+    short_name: String,
+    /// The target of the redirect, as written in the file.
+    target: String,
+}
+
+impl ImportedRedirect {
+    /// Returns the line of the imported file that defines the redirect, starting at 1.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Returns the short name of the redirect.
+    ///
+    /// Source paths are flattened into a single short name by trimming their slashes
+    /// and any `.html` extension and joining their segments with dashes, so
+    /// `/old/post/` becomes `old-post`.
+    pub fn short_name(&self) -> &str {
+        &self.short_name
+    }
+
+    /// Returns the target of the redirect, as written in the imported file.
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    /// Creates a [`Redirector`] that writes this redirect into an output directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `out_dir` - The output directory for the redirect page and registry
+    /// * `allowlist` - The hosts that absolute URL targets may point at
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Redirector)` - The redirector
+    /// * `Err(RedirectorError)` - The errors of [`RedirectorBuilder::build()`](crate::RedirectorBuilder::build)
+    pub fn redirector<P: Into<PathBuf>>(
+        &self,
+        out_dir: P,
+        allowlist: &HostAllowlist,
+    ) -> Result<Redirector, RedirectorError> {
+        Redirector::builder()
+            .target(&self.target)
+            .alias(&self.short_name)
+            .out_dir(out_dir)
+            .allow_hosts(allowlist.clone())
+            .build()
+    }
+}
+
+impl ImportFormat {
+    /// Reads the redirects defined in a file.
+    ///
+    /// Results are in file order. Rules the format supports but link-bridge cannot
+    /// reproduce are skipped, as described for each format; with the `tracing`
+    /// feature enabled each skipped rule is logged.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file to import
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<ImportedRedirect>)` - The redirects found
+    /// * `Err(RedirectorError::FailedToReadImport)` - If the file cannot be read or a
+    ///   line is malformed
+    /// * `Err(RedirectorError::InvalidAlias)` - If a source cannot be used as a short name
+    pub fn read<P: AsRef<Path>>(&self, path: P) -> Result<Vec<ImportedRedirect>, RedirectorError> {
+        let path = path.as_ref();
+        let failed = |source| RedirectorError::FailedToReadImport {
+            path: path.to_path_buf(),
+            source,
+        };
+
+        let contents = fs::read_to_string(path).map_err(failed)?;
+        let redirects = match self {
+            ImportFormat::Netlify => parse_netlify(&contents),
+            ImportFormat::Nginx => parse_nginx(&contents),
+            ImportFormat::Bitly => parse_bitly(&contents),
+            ImportFormat::Yourls => parse_yourls(&contents),
+        };
+
+        redirects
+            .map_err(|(line, message)| {
+                failed(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("line {line}: {message}"),
+                ))
+            })?
+            .into_iter()
+            .map(|(line, source, target)| {
+                let short_name = short_name(&source);
+                validate_alias(&short_name)?;
+                Ok(ImportedRedirect {
+                    line,
+                    short_name,
+                    target,
+                })
+            })
+            .collect()
+    }
+
+    /// Reads a file and writes a redirect for every redirect it defines.
+    ///
+    /// The redirects are written with [`Redirector::write_all()`], so the registry in
+    /// `out_dir` is updated once and nothing is written if any redirect fails. The
+    /// registry holds one short link per target, so when several sources share a
+    /// target only the first is written and the rest reuse it.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file to import
+    /// * `out_dir` - The output directory for the redirect pages and registry
+    /// * `allowlist` - The hosts that absolute URL targets may point at
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<RedirectOutcome>)` - The outcome for each redirect, in file order
+    /// * `Err(RedirectorError)` - The errors of [`ImportFormat::read()`],
+    ///   [`ImportedRedirect::redirector()`], and [`Redirector::write_all()`]
+    pub fn write_redirects<P: AsRef<Path>, Q: Into<PathBuf>>(
+        &self,
+        path: P,
+        out_dir: Q,
+        allowlist: &HostAllowlist,
+    ) -> Result<Vec<RedirectOutcome>, RedirectorError> {
+        let out_dir = out_dir.into();
+        let redirectors = self
+            .read(path)?
+            .iter()
+            .map(|redirect| redirect.redirector(&out_dir, allowlist))
+            .collect::<Result<Vec<Redirector>, RedirectorError>>()?;
+
+        Redirector::write_all(&redirectors)
+    }
+}
+
+/// A parsed rule: its line number, source path, and target.
+type Rule = (usize, String, String);
+
+/// A malformed line: its line number and what is wrong with it.
+type LineError = (usize, String);
+
+/// Parses a Netlify `_redirects` file.
+fn parse_netlify(contents: &str) -> Result<Vec<Rule>, LineError> {
+    let mut rules = Vec::new();
+
+    for (index, line) in contents.lines().enumerate() {
+        let line_number = index + 1;
+        let fields: Vec<&str> = line
+            .split_whitespace()
+            .take_while(|field| !field.starts_with('#'))
+            .collect();
+
+        let (source, rest) = match fields.as_slice() {
+            [] => continue,
+            [source, rest @ ..] => (*source, rest),
+        };
+
+        // Query parameter conditions sit between the source and the target
+        let conditions = rest.iter().take_while(|field| field.contains('=')).count();
+        let Some(target) = rest.get(conditions) else {
+            return Err((line_number, format!("missing target for {source}")));
+        };
+        let options = &rest[conditions + 1..];
+
+        let status = match options.first() {
+            Some(field) if field.starts_with(|c: char| c.is_ascii_digit()) => {
+                let code = field.trim_end_matches('!');
+                Some(
+                    code.parse::<u16>()
+                        .map_err(|_| (line_number, format!("invalid status code {field}")))?,
+                )
+            }
+            _ => None,
+        };
+
+        let skip = if conditions > 0 || options.len() > usize::from(status.is_some()) {
+            Some("conditional rule")
+        } else if !matches!(status, None | Some(301 | 302 | 303 | 307 | 308)) {
+            Some("not a redirect")
+        } else if [source, *target]
+            .iter()
+            .any(|part| part.contains('*') || part.contains("/:"))
+        {
+            Some("splat or placeholder")
+        } else {
+            None
+        };
+
+        match skip {
+            Some(reason) => skipped(line_number, source, reason),
+            None => rules.push((line_number, source.to_string(), target.to_string())),
+        }
+    }
+
+    Ok(rules)
+}
+
+/// A token of an nginx configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum NginxToken {
+    /// A bare or quoted word.
+    Word(String),
+    /// The `;` ending a directive.
+    End,
+    /// The `{` opening a block.
+    Open,
+    /// The `}` closing a block.
+    Close,
+}
+
+/// Splits an nginx configuration into tokens, each with its line number.
+fn tokenize_nginx(contents: &str) -> Result<Vec<(usize, NginxToken)>, LineError> {
+    let mut tokens = Vec::new();
+    let mut chars = contents.chars().peekable();
+    let mut line = 1;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => line += 1,
+            c if c.is_whitespace() => {}
+            '#' => while chars.next_if(|&c| c != '\n').is_some() {},
+            ';' => tokens.push((line, NginxToken::End)),
+            '{' => tokens.push((line, NginxToken::Open)),
+            '}' => tokens.push((line, NginxToken::Close)),
+            '"' | '\'' => {
+                let start = line;
+                let mut word = String::new();
+                loop {
+                    match chars.next() {
+                        Some(q) if q == c => break,
+                        Some('\\') => word.extend(chars.next()),
+                        Some(other) => {
+                            line += usize::from(other == '\n');
+                            word.push(other);
+                        }
+                        None => return Err((start, "unterminated quoted string".to_string())),
+                    }
+                }
+                tokens.push((start, NginxToken::Word(word)));
+            }
+            c => {
+                let mut word = String::from(c);
+                while let Some(next) =
+                    chars.next_if(|&c| !c.is_whitespace() && !matches!(c, ';' | '{' | '}'))
+                {
+                    word.push(next);
+                }
+                tokens.push((line, NginxToken::Word(word)));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Parses the `map` entries and `rewrite` directives of an nginx configuration.
+fn parse_nginx(contents: &str) -> Result<Vec<Rule>, LineError> {
+    let mut rules = Vec::new();
+    // Whether each enclosing block is a `map` block
+    let mut blocks: Vec<bool> = Vec::new();
+    let mut words: Vec<String> = Vec::new();
+    let mut start = 0;
+
+    for (line, token) in tokenize_nginx(contents)? {
+        match token {
+            NginxToken::Word(word) => {
+                if words.is_empty() {
+                    start = line;
+                }
+                words.push(word);
+            }
+            NginxToken::Open => {
+                blocks.push(words.first().is_some_and(|word| word == "map"));
+                words.clear();
+            }
+            NginxToken::Close => {
+                if !words.is_empty() {
+                    return Err((start, format!("missing ; after {}", words.join(" "))));
+                }
+                if blocks.pop().is_none() {
+                    return Err((line, "unexpected }".to_string()));
+                }
+            }
+            NginxToken::End => {
+                // Map files have no enclosing block; their entries are path pairs
+                let in_map = blocks
+                    .last()
+                    .copied()
+                    .unwrap_or_else(|| words.len() == 2 && words[0].starts_with(['/', '~']));
+                let statement = std::mem::take(&mut words);
+
+                let rule = if statement.first().is_some_and(|word| word == "rewrite") {
+                    nginx_rewrite(start, &statement)?
+                } else if in_map {
+                    nginx_map_entry(start, &statement)?
+                } else {
+                    None
+                };
+                rules.extend(rule);
+            }
+        }
+    }
+
+    if !words.is_empty() {
+        return Err((start, format!("missing ; after {}", words.join(" "))));
+    }
+    if !blocks.is_empty() {
+        return Err((contents.lines().count(), "missing }".to_string()));
+    }
+
+    Ok(rules)
+}
+
+/// Reads a `source target;` entry of a `map` block.
+fn nginx_map_entry(line: usize, entry: &[String]) -> Result<Option<Rule>, LineError> {
+    let [source, target] = entry else {
+        return Err((
+            line,
+            format!("expected a source and a target: {}", entry.join(" ")),
+        ));
+    };
+
+    let skip = match source.as_str() {
+        "default" | "hostnames" | "volatile" | "include" => return Ok(None),
+        source if source.starts_with('~') => Some("regular expression"),
+        _ if target.is_empty() => Some("empty target"),
+        _ if target.contains('$') => Some("variable in target"),
+        _ => None,
+    };
+
+    match skip {
+        Some(reason) => {
+            skipped(line, source, reason);
+            Ok(None)
+        }
+        None => Ok(Some((line, source.clone(), target.clone()))),
+    }
+}
+
+/// Reads a `rewrite pattern target flag;` directive.
+fn nginx_rewrite(line: usize, directive: &[String]) -> Result<Option<Rule>, LineError> {
+    let [_, pattern, target, rest @ ..] = directive else {
+        return Err((line, "rewrite needs a pattern and a target".to_string()));
+    };
+
+    let skip = if !matches!(rest, [flag] if flag == "permanent" || flag == "redirect") {
+        Some("internal rewrite")
+    } else if target.contains('$') {
+        Some("variable or capture in target")
+    } else {
+        None
+    };
+    let source = literal_pattern(pattern);
+
+    match (skip, source) {
+        (None, Some(source)) => Ok(Some((line, source, target.clone()))),
+        (skip, _) => {
+            skipped(line, pattern, skip.unwrap_or("regular expression"));
+            Ok(None)
+        }
+    }
+}
+
+/// Returns the path matched by an anchored literal pattern such as `^/old/?$`.
+fn literal_pattern(pattern: &str) -> Option<String> {
+    let body = pattern.strip_prefix('^')?.strip_suffix('$')?;
+    let body = body.strip_suffix("/?").unwrap_or(body);
+
+    let mut path = String::new();
+    let mut chars = body.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => path.push(chars.next().filter(|c| !c.is_ascii_alphanumeric())?),
+            '.' | '*' | '+' | '?' | '(' | ')' | '[' | ']' | '{' | '}' | '|' | '^' | '$' => {
+                return None
+            }
+            c => path.push(c),
+        }
+    }
+
+    Some(path)
+}
+
+/// Splits CSV text into records, each with the line number it starts on.
+///
+/// Fields are separated by commas and may be quoted with double quotes, inside which
+/// commas, line breaks, and doubled quotes (`""`) are taken literally. Blank lines and
+/// a leading byte order mark are ignored.
+fn parse_csv(contents: &str) -> Result<Vec<(usize, Vec<String>)>, LineError> {
+    let mut records = Vec::new();
+    let mut chars = contents.trim_start_matches('\u{feff}').chars().peekable();
+    let mut line = 1;
+
+    while chars.peek().is_some() {
+        let start = line;
+        let mut record = Vec::new();
+        let mut field = String::new();
+        let mut quoted = false;
+
+        while let Some(c) = chars.next() {
+            match c {
+                '"' if quoted => {
+                    if chars.next_if_eq(&'"').is_some() {
+                        field.push('"');
+                    } else {
+                        quoted = false;
+                    }
+                }
+                '"' if field.is_empty() => quoted = true,
+                ',' if !quoted => record.push(std::mem::take(&mut field)),
+                '\r' if !quoted => {}
+                '\n' => {
+                    line += 1;
+                    if !quoted {
+                        break;
+                    }
+                    field.push(c);
+                }
+                c => field.push(c),
+            }
+        }
+        if quoted {
+            return Err((start, "unterminated quoted field".to_string()));
+        }
+
+        record.push(field);
+        if record.iter().any(|field| !field.trim().is_empty()) {
+            records.push((start, record));
+        }
+    }
+
+    Ok(records)
+}
+
+/// Returns the index of the first column whose header matches one of `names`.
+///
+/// Headers are compared case-insensitively, treating spaces and dashes as underscores.
+fn column(header: &[String], names: &[&str]) -> Option<usize> {
+    let header: Vec<String> = header
+        .iter()
+        .map(|name| name.trim().to_ascii_lowercase().replace([' ', '-'], "_"))
+        .collect();
+
+    names
+        .iter()
+        .find_map(|name| header.iter().position(|column| column == name))
+}
+
+/// Reads the short link and target columns of each CSV record.
+fn csv_rules(
+    records: impl IntoIterator<Item = (usize, Vec<String>)>,
+    short_column: usize,
+    target_column: usize,
+) -> Result<Vec<Rule>, LineError> {
+    let columns = short_column.max(target_column) + 1;
+
+    records
+        .into_iter()
+        .map(
+            |(line, record)| match (record.get(short_column), record.get(target_column)) {
+                (Some(short), Some(target)) => {
+                    Ok((line, short.trim().to_string(), target.trim().to_string()))
+                }
+                _ => Err((line, format!("expected at least {columns} columns"))),
+            },
+        )
+        .collect()
+}
+
+/// Parses a Bitly CSV export.
+fn parse_bitly(contents: &str) -> Result<Vec<Rule>, LineError> {
+    let mut records = parse_csv(contents)?.into_iter();
+    let Some((line, header)) = records.next() else {
+        return Ok(Vec::new());
+    };
+
+    let short = column(&header, &["bitlink", "link"])
+        .ok_or_else(|| (line, "missing bitlink column".to_string()))?;
+    let long = column(&header, &["long_url"])
+        .ok_or_else(|| (line, "missing long_url column".to_string()))?;
+
+    csv_rules(records, short, long)?
+        .into_iter()
+        .map(|(line, bitlink, target)| {
+            let without_scheme = bitlink
+                .split_once("://")
+                .map_or(&*bitlink, |(_, rest)| rest);
+            match without_scheme.split_once('/') {
+                Some((_, path)) if !path.trim_matches('/').is_empty() => {
+                    Ok((line, format!("/{path}"), target))
+                }
+                _ => Err((line, format!("invalid bitlink {bitlink}"))),
+            }
+        })
+        .collect()
+}
+
+/// Parses a YOURLS CSV export.
+fn parse_yourls(contents: &str) -> Result<Vec<Rule>, LineError> {
+    let records = parse_csv(contents)?;
+    let header = records
+        .first()
+        .and_then(|(_, header)| Some((column(header, &["keyword"])?, column(header, &["url"])?)));
+
+    let (records, (keyword, url)) = match header {
+        Some(columns) => (&records[1..], columns),
+        None => (&records[..], (0, 1)),
+    };
+
+    csv_rules(records.iter().cloned(), keyword, url)
+}
+
+/// Logs a rule that is skipped because link-bridge cannot reproduce it.
+fn skipped(_line: usize, _source: &str, _reason: &str) {
+    trace::warn!(
+        line = _line,
+        source = _source,
+        reason = _reason,
+        "skipping redirect rule"
+    );
+}
+
+/// Flattens a source path into a short name.
+fn short_name(source: &str) -> String {
+    let source = source.trim_matches('/');
+    let source = source.strip_suffix(".html").unwrap_or(source);
+
+    source
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<&str>>()
+        .join("-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Registry;
+    use chrono::Utc;
+
+    fn test_dir(name: &str) -> PathBuf {
+        PathBuf::from(format!(
+            "{name}_{}",
+            Utc::now().timestamp_nanos_opt().unwrap_or(0)
+        ))
+    }
+
+    #[test]
+    fn test_parse_netlify() {
+        let contents = "\
+# Redirects
+/install          /docs/installation/
+/old/post.html    /blog/new-post/          301!
+/guide            https://guide.example.com/  302  # external
+
+/app/*            /index.html              200
+/news/:year/*     /blog/:year/:splat
+/store id=:id     /products/:id            301
+/fr               /fr/home/                302  Language=fr
+/missing          /404.html                404
+";
+
+        assert_eq!(
+            parse_netlify(contents).unwrap(),
+            [
+                (2, "/install".to_string(), "/docs/installation/".to_string()),
+                (
+                    3,
+                    "/old/post.html".to_string(),
+                    "/blog/new-post/".to_string()
+                ),
+                (
+                    4,
+                    "/guide".to_string(),
+                    "https://guide.example.com/".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_netlify_malformed() {
+        assert_eq!(
+            parse_netlify("/ok /target\n/lonely\n"),
+            Err((2, "missing target for /lonely".to_string()))
+        );
+        assert_eq!(
+            parse_netlify("/a /b 30x\n"),
+            Err((1, "invalid status code 30x".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_nginx() {
+        let contents = r#"
+map $uri $redirect_uri {
+    default          "";
+    /install         /docs/installation/;
+    "/old post"      "/blog/new post/";   # quoted
+    ~^/legacy/(.*)$  /archive/$1;
+    include          redirects.map;
+}
+
+server {
+    listen 80;
+    rewrite ^/guide/?$       https://guide.example.com/  permanent;
+    rewrite ^/faq\.html$     /help/faq/                  redirect;
+    rewrite ^/blog/(.*)$     /posts/$1                   permanent;
+    rewrite ^/app$           /index.html                 last;
+    if ($redirect_uri) {
+        return 301 $redirect_uri;
+    }
+}
+"#;
+
+        assert_eq!(
+            parse_nginx(contents).unwrap(),
+            [
+                (4, "/install".to_string(), "/docs/installation/".to_string()),
+                (5, "/old post".to_string(), "/blog/new post/".to_string()),
+                (
+                    12,
+                    "/guide".to_string(),
+                    "https://guide.example.com/".to_string()
+                ),
+                (13, "/faq.html".to_string(), "/help/faq/".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_nginx_map_file() {
+        let contents = "/install /docs/installation/;\n/usage   /docs/usage/;\n";
+
+        assert_eq!(
+            parse_nginx(contents).unwrap(),
+            [
+                (1, "/install".to_string(), "/docs/installation/".to_string()),
+                (2, "/usage".to_string(), "/docs/usage/".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_nginx_malformed() {
+        assert_eq!(
+            parse_nginx("map $uri $new {\n  /a /b /c;\n}\n"),
+            Err((2, "expected a source and a target: /a /b /c".to_string()))
+        );
+        assert_eq!(
+            parse_nginx("map $uri $new {\n  /a /b;\n"),
+            Err((2, "missing }".to_string()))
+        );
+        assert_eq!(
+            parse_nginx("/a /b;\n}\n"),
+            Err((2, "unexpected }".to_string()))
+        );
+        assert_eq!(
+            parse_nginx("/a \"/b;\n"),
+            Err((1, "unterminated quoted string".to_string()))
+        );
+        assert_eq!(
+            parse_nginx("rewrite ^/a$;\n"),
+            Err((1, "rewrite needs a pattern and a target".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_literal_pattern() {
+        assert_eq!(literal_pattern("^/old/?$").as_deref(), Some("/old"));
+        assert_eq!(literal_pattern("^/a\\.html$").as_deref(), Some("/a.html"));
+        assert_eq!(literal_pattern("^/old/$").as_deref(), Some("/old/"));
+        assert_eq!(literal_pattern("/old"), None);
+        assert_eq!(literal_pattern("^/old"), None);
+        assert_eq!(literal_pattern("^/(.*)$"), None);
+        assert_eq!(literal_pattern("^/a.html$"), None);
+        assert_eq!(literal_pattern("^/a\\d$"), None);
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        let contents = "\u{feff}a,b,c\r\n\n\"quoted, \"\"with\"\" comma\",\"multi\nline\",\n";
+
+        assert_eq!(
+            parse_csv(contents).unwrap(),
+            [
+                (1, vec!["a".to_string(), "b".to_string(), "c".to_string()]),
+                (
+                    3,
+                    vec![
+                        "quoted, \"with\" comma".to_string(),
+                        "multi\nline".to_string(),
+                        String::new()
+                    ]
+                ),
+            ]
+        );
+        assert_eq!(
+            parse_csv("a,\"b\nc\n"),
+            Err((1, "unterminated quoted field".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_bitly() {
+        let contents = "\
+Title,Bitlink,Long URL,Created At
+Install guide,https://bit.ly/3abcXyz,https://example.com/docs/install,2024-01-01
+\"Launch, 2024\",go.example.com/launch/,https://example.com/blog/launch?utm=x,2024-02-01
+";
+
+        assert_eq!(
+            parse_bitly(contents).unwrap(),
+            [
+                (
+                    2,
+                    "/3abcXyz".to_string(),
+                    "https://example.com/docs/install".to_string()
+                ),
+                (
+                    3,
+                    "/launch/".to_string(),
+                    "https://example.com/blog/launch?utm=x".to_string()
+                ),
+            ]
+        );
+        assert_eq!(parse_bitly("").unwrap(), []);
+        assert_eq!(
+            parse_bitly("title,url\n"),
+            Err((1, "missing bitlink column".to_string()))
+        );
+        assert_eq!(
+            parse_bitly("bitlink,long_url\nhttps://bit.ly/,https://example.com/\n"),
+            Err((2, "invalid bitlink https://bit.ly/".to_string()))
+        );
+        assert_eq!(
+            parse_bitly("bitlink,long_url\nbit.ly/a\n"),
+            Err((2, "expected at least 2 columns".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_yourls() {
+        let with_header = "url,keyword,title\nhttps://example.com/docs,docs,Docs\n";
+        assert_eq!(
+            parse_yourls(with_header).unwrap(),
+            [(
+                2,
+                "docs".to_string(),
+                "https://example.com/docs".to_string()
+            )]
+        );
+
+        let without_header = "docs,https://example.com/docs,Docs,2024-01-01 10:00:00,127.0.0.1,4\n";
+        assert_eq!(
+            parse_yourls(without_header).unwrap(),
+            [(
+                1,
+                "docs".to_string(),
+                "https://example.com/docs".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_short_name() {
+        assert_eq!(short_name("/install"), "install");
+        assert_eq!(short_name("/old/post.html"), "old-post");
+        assert_eq!(short_name("/docs//guide/"), "docs-guide");
+        assert_eq!(short_name("/"), "");
+    }
+
+    #[test]
+    fn test_netlify_write_redirects() {
+        let dir = test_dir("test_netlify_write_redirects");
+        let file = dir.join("_redirects");
+        let out_dir = dir.join("s");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            &file,
+            "/install /docs/installation/ 301\n/guide https://guide.example.com/ 302\n",
+        )
+        .unwrap();
+
+        // External targets need their host on the allowlist
+        let result =
+            ImportFormat::Netlify.write_redirects(&file, &out_dir, &HostAllowlist::default());
+        assert!(matches!(result, Err(RedirectorError::DisallowedTarget(_))));
+        assert!(!out_dir.exists());
+
+        let allowlist = HostAllowlist::new(["guide.example.com"]);
+        let outcomes = ImportFormat::Netlify
+            .write_redirects(&file, &out_dir, &allowlist)
+            .unwrap();
+
+        assert_eq!(outcomes.len(), 2);
+        assert_eq!(outcomes[0].file_path(), out_dir.join("install.html"));
+        assert_eq!(outcomes[1].file_path(), out_dir.join("guide.html"));
+        assert_eq!(outcomes[1].target(), "https://guide.example.com/");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_nginx_write_redirects() {
+        let dir = test_dir("test_nginx_write_redirects");
+        let file = dir.join("redirects.conf");
+        let out_dir = dir.join("s");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            &file,
+            "map $uri $new {\n    /install /docs/installation/;\n}\nrewrite ^/usage/?$ /docs/usage/ permanent;\n",
+        )
+        .unwrap();
+
+        let outcomes = ImportFormat::Nginx
+            .write_redirects(&file, &out_dir, &HostAllowlist::default())
+            .unwrap();
+
+        assert_eq!(outcomes.len(), 2);
+        assert_eq!(outcomes[0].file_path(), out_dir.join("install.html"));
+        assert_eq!(outcomes[1].file_path(), out_dir.join("usage.html"));
+        assert_eq!(outcomes[1].target(), "/docs/usage/");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_shortener_write_redirects() {
+        let dir = test_dir("test_shortener_write_redirects");
+        let bitly = dir.join("bitly.csv");
+        let yourls = dir.join("yourls.csv");
+        let out_dir = dir.join("s");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            &bitly,
+            "bitlink,long_url\nhttps://bit.ly/install,https://example.com/docs/install\n",
+        )
+        .unwrap();
+        fs::write(
+            &yourls,
+            "keyword,url\nusage,https://example.com/docs/usage\n",
+        )
+        .unwrap();
+
+        let allowlist = HostAllowlist::new(["example.com"]);
+        let outcomes = ImportFormat::Bitly
+            .write_redirects(&bitly, &out_dir, &allowlist)
+            .unwrap();
+        assert_eq!(outcomes[0].file_path(), out_dir.join("install.html"));
+
+        let outcomes = ImportFormat::Yourls
+            .write_redirects(&yourls, &out_dir, &allowlist)
+            .unwrap();
+        assert_eq!(outcomes[0].file_path(), out_dir.join("usage.html"));
+        assert_eq!(Registry::load(&out_dir).unwrap().len(), 2);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_read_errors() {
+        let dir = test_dir("test_import_read_errors");
+        let file = dir.join("_redirects");
+
+        let missing = ImportFormat::Netlify.read(&file);
+        assert!(matches!(
+            missing,
+            Err(RedirectorError::FailedToReadImport { .. })
+        ));
+
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&file, "/a /b\n/c\n").unwrap();
+        match ImportFormat::Netlify.read(&file) {
+            Err(RedirectorError::FailedToReadImport { path, source }) => {
+                assert_eq!(path, file);
+                assert_eq!(source.to_string(), "line 2: missing target for /c");
+            }
+            other => panic!("expected FailedToReadImport, got {other:?}"),
+        }
+
+        fs::write(&file, "/ /home/\n").unwrap();
+        assert!(matches!(
+            ImportFormat::Netlify.read(&file),
+            Err(RedirectorError::InvalidAlias(_))
+        ));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
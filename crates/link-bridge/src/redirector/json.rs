@@ -0,0 +1,724 @@
+//! A small JSON reader and writer.
+//!
+//! `registry.json`, sidecar metadata, audit logs, and the redirect lists exported for
+//! other frameworks only need objects, arrays, strings, booleans, and integers. This
+//! module provides a `Value` tree for them with a parser and a writer, so the crate
+//! does not depend on `serde_json`. Values are written exactly as `serde_json` writes
+//! them, compact or pretty-printed with object keys in sorted order, so files written
+//! before and after keep the same bytes.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Write as _};
+use std::io::{self, BufRead, Write};
+
+/// A JSON value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Value {
+    /// `null`
+    Null,
+    /// `true` or `false`
+    Bool(bool),
+    /// A number, kept as the text it was written as.
+    Number(String),
+    /// A string.
+    String(String),
+    /// An array.
+    Array(Vec<Value>),
+    /// An object, with its keys in sorted order.
+    Object(BTreeMap<String, Value>),
+}
+
+impl Value {
+    /// Returns the string, if the value is one.
+    pub(crate) fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    /// Returns the item at an index, if the value is an array long enough.
+    pub(crate) fn get(&self, index: usize) -> Option<&Value> {
+        match self {
+            Value::Array(items) => items.get(index),
+            _ => None,
+        }
+    }
+
+    /// Formats the value over several lines, indented by two spaces per level.
+    pub(crate) fn to_string_pretty(&self) -> String {
+        let mut text = String::new();
+        self.write_pretty(&mut text, 0);
+        text
+    }
+
+    fn write_pretty(&self, text: &mut String, depth: usize) {
+        let indent = |text: &mut String, depth: usize| {
+            text.push('\n');
+            text.extend(std::iter::repeat_n("  ", depth));
+        };
+        match self {
+            Value::Array(items) if !items.is_empty() => {
+                text.push('[');
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        text.push(',');
+                    }
+                    indent(text, depth + 1);
+                    item.write_pretty(text, depth + 1);
+                }
+                indent(text, depth);
+                text.push(']');
+            }
+            Value::Object(fields) if !fields.is_empty() => {
+                text.push('{');
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        text.push(',');
+                    }
+                    indent(text, depth + 1);
+                    text.push_str(&quote(key));
+                    text.push_str(": ");
+                    value.write_pretty(text, depth + 1);
+                }
+                indent(text, depth);
+                text.push('}');
+            }
+            _ => {
+                let _ = write!(text, "{self}");
+            }
+        }
+    }
+}
+
+impl Display for Value {
+    /// Formats the value on one line, without spaces.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Null => f.write_str("null"),
+            Value::Bool(value) => write!(f, "{value}"),
+            Value::Number(number) => f.write_str(number),
+            Value::String(text) => f.write_str(&quote(text)),
+            Value::Array(items) => {
+                f.write_str("[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, "{item}")?;
+                }
+                f.write_str("]")
+            }
+            Value::Object(fields) => {
+                f.write_str("{")?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, "{}:{value}", quote(key))?;
+                }
+                f.write_str("}")
+            }
+        }
+    }
+}
+
+impl From<&str> for Value {
+    fn from(text: &str) -> Self {
+        Value::String(text.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(text: String) -> Self {
+        Value::String(text)
+    }
+}
+
+impl From<&String> for Value {
+    fn from(text: &String) -> Self {
+        Value::String(text.clone())
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::Bool(value)
+    }
+}
+
+impl From<u64> for Value {
+    fn from(number: u64) -> Self {
+        Value::Number(number.to_string())
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Value::Null, Into::into)
+    }
+}
+
+impl<T: Into<Value>> From<Vec<T>> for Value {
+    fn from(items: Vec<T>) -> Self {
+        Value::Array(items.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<T: Clone + Into<Value>> From<&[T]> for Value {
+    fn from(items: &[T]) -> Self {
+        Value::Array(items.iter().cloned().map(Into::into).collect())
+    }
+}
+
+/// Builds an object from its fields.
+pub(crate) fn object<'a>(fields: impl IntoIterator<Item = (&'a str, Value)>) -> Value {
+    Value::Object(
+        fields
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value))
+            .collect(),
+    )
+}
+
+/// Quotes text as a JSON string, escaping quotes, backslashes, and control
+/// characters.
+pub(crate) fn quote(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            '\u{08}' => quoted.push_str("\\b"),
+            '\u{0c}' => quoted.push_str("\\f"),
+            c if c < ' ' => {
+                let _ = write!(quoted, "\\u{:04x}", u32::from(c));
+            }
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Writes text into a writer as a JSON string.
+pub(crate) fn write_quoted<W: Write + ?Sized>(writer: &mut W, text: &str) -> io::Result<()> {
+    writer.write_all(quote(text).as_bytes())
+}
+
+/// Parses a complete JSON document.
+///
+/// # Returns
+///
+/// * `Ok(Value)` - The parsed value
+/// * `Err(io::Error)` - With `ErrorKind::InvalidData`, if the text is not valid JSON
+///   or anything but whitespace follows the value
+pub(crate) fn from_slice(json: &[u8]) -> io::Result<Value> {
+    let mut parser = Parser::new(json);
+    let value = parser.value()?;
+    parser.end()?;
+    Ok(value)
+}
+
+/// Parses a JSON object from a reader one member at a time.
+///
+/// Each member is handed to `member` as soon as its value has been read, so only
+/// one member's value is held in memory however large the document is.
+///
+/// # Returns
+///
+/// * `Ok(())` - If the document was an object and every member was accepted
+/// * `Err(io::Error)` - The error from the reader or from `member`, or with
+///   `ErrorKind::InvalidData` if the document is not a valid JSON object
+pub(crate) fn for_each_member<R, F>(reader: R, member: F) -> io::Result<()>
+where
+    R: BufRead,
+    F: FnMut(String, Value) -> io::Result<()>,
+{
+    let mut parser = Parser::new(reader);
+    parser.whitespace();
+    if parser.peek() != Some(b'{') {
+        return Err(parser.error("expected an object"));
+    }
+    parser.members(0, member)?;
+    parser.end()
+}
+
+/// Parses the JSON value at the start of some text, ignoring anything after it.
+pub(crate) fn parse_prefix(text: &str) -> Option<Value> {
+    Parser::new(text.as_bytes()).value().ok()
+}
+
+/// A recursive descent parser over the bytes of a JSON document.
+///
+/// The bytes are read as they are needed, with a lookahead of one byte, so a
+/// document never has to be in memory as a whole.
+struct Parser<R> {
+    reader: R,
+    position: usize,
+    /// The first error from the reader, reported in place of the parse error it
+    /// causes.
+    failure: Option<io::Error>,
+}
+
+impl<R: BufRead> Parser<R> {
+    /// Deepest nesting of arrays and objects accepted, as in `serde_json`.
+    const MAX_DEPTH: usize = 128;
+
+    fn new(reader: R) -> Self {
+        Parser {
+            reader,
+            position: 0,
+            failure: None,
+        }
+    }
+
+    fn error(&mut self, reason: &str) -> io::Error {
+        self.failure.take().unwrap_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{reason} at byte {} of JSON", self.position),
+            )
+        })
+    }
+
+    /// Returns the buffered bytes, or none at the end of the document or when the
+    /// reader fails.
+    fn buffer(&mut self) -> &[u8] {
+        loop {
+            match self.reader.fill_buf() {
+                Ok(_) => break,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    self.failure.get_or_insert(e);
+                    return &[];
+                }
+            }
+        }
+        self.reader.fill_buf().unwrap_or_default()
+    }
+
+    fn advance(&mut self, count: usize) {
+        self.reader.consume(count);
+        self.position += count;
+    }
+
+    /// Checks that nothing but whitespace follows the parsed value.
+    fn end(&mut self) -> io::Result<()> {
+        self.whitespace();
+        if self.peek().is_some() || self.failure.is_some() {
+            return Err(self.error("trailing characters"));
+        }
+        Ok(())
+    }
+
+    fn whitespace(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek() {
+            self.advance(1);
+        }
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        self.buffer().first().copied()
+    }
+
+    fn next(&mut self) -> Option<u8> {
+        let byte = self.peek()?;
+        self.advance(1);
+        Some(byte)
+    }
+
+    fn expect(&mut self, byte: u8) -> io::Result<()> {
+        if self.peek() != Some(byte) {
+            return Err(self.error(&format!("expected `{}`", byte as char)));
+        }
+        self.advance(1);
+        Ok(())
+    }
+
+    fn value(&mut self) -> io::Result<Value> {
+        self.nested_value(0)
+    }
+
+    fn nested_value(&mut self, depth: usize) -> io::Result<Value> {
+        if depth > Self::MAX_DEPTH {
+            return Err(self.error("nested too deeply"));
+        }
+        self.whitespace();
+        match self.peek() {
+            Some(b'n') => self.literal("null", Value::Null),
+            Some(b't') => self.literal("true", Value::Bool(true)),
+            Some(b'f') => self.literal("false", Value::Bool(false)),
+            Some(b'"') => self.string().map(Value::String),
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            Some(b'[') => {
+                self.advance(1);
+                let mut items = Vec::new();
+                self.whitespace();
+                if self.peek() == Some(b']') {
+                    self.advance(1);
+                    return Ok(Value::Array(items));
+                }
+                loop {
+                    items.push(self.nested_value(depth + 1)?);
+                    self.whitespace();
+                    match self.next() {
+                        Some(b',') => {}
+                        Some(b']') => return Ok(Value::Array(items)),
+                        _ => return Err(self.error("expected `,` or `]`")),
+                    }
+                }
+            }
+            Some(b'{') => {
+                let mut fields = BTreeMap::new();
+                self.members(depth, |key, value| {
+                    fields.insert(key, value);
+                    Ok(())
+                })?;
+                Ok(Value::Object(fields))
+            }
+            Some(_) => Err(self.error("expected a value")),
+            None => Err(self.error("unexpected end")),
+        }
+    }
+
+    /// Reads the members of the object at the current position, handing each to
+    /// `member` in order.
+    fn members<F>(&mut self, depth: usize, mut member: F) -> io::Result<()>
+    where
+        F: FnMut(String, Value) -> io::Result<()>,
+    {
+        self.expect(b'{')?;
+        self.whitespace();
+        if self.peek() == Some(b'}') {
+            self.advance(1);
+            return Ok(());
+        }
+        loop {
+            self.whitespace();
+            let key = self.string()?;
+            self.whitespace();
+            self.expect(b':')?;
+            let value = self.nested_value(depth + 1)?;
+            member(key, value)?;
+            self.whitespace();
+            match self.next() {
+                Some(b',') => {}
+                Some(b'}') => return Ok(()),
+                _ => return Err(self.error("expected `,` or `}`")),
+            }
+        }
+    }
+
+    fn literal(&mut self, literal: &str, value: Value) -> io::Result<Value> {
+        for &expected in literal.as_bytes() {
+            if self.next() != Some(expected) {
+                return Err(self.error("expected a value"));
+            }
+        }
+        Ok(value)
+    }
+
+    fn number(&mut self) -> io::Result<Value> {
+        let mut number = String::new();
+        let sign = |parser: &mut Self, number: &mut String, signs: &[u8]| {
+            if let Some(byte) = parser.peek().filter(|byte| signs.contains(byte)) {
+                parser.advance(1);
+                number.push(byte as char);
+            }
+        };
+        let digits = |parser: &mut Self, number: &mut String| {
+            let from = number.len();
+            while let Some(digit) = parser.peek().filter(u8::is_ascii_digit) {
+                parser.advance(1);
+                number.push(digit as char);
+            }
+            number.len() > from
+        };
+
+        sign(self, &mut number, b"-");
+        if self.peek() == Some(b'0') {
+            self.advance(1);
+            number.push('0');
+        } else if !digits(self, &mut number) {
+            return Err(self.error("invalid number"));
+        }
+        if self.peek() == Some(b'.') {
+            self.advance(1);
+            number.push('.');
+            if !digits(self, &mut number) {
+                return Err(self.error("invalid number"));
+            }
+        }
+        if let Some(exponent @ (b'e' | b'E')) = self.peek() {
+            self.advance(1);
+            number.push(exponent as char);
+            sign(self, &mut number, b"+-");
+            if !digits(self, &mut number) {
+                return Err(self.error("invalid number"));
+            }
+        }
+        Ok(Value::Number(number))
+    }
+
+    fn string(&mut self) -> io::Result<String> {
+        self.expect(b'"')?;
+        let mut bytes = Vec::new();
+        loop {
+            // copy plain runs straight from the buffer rather than byte by byte
+            let buffer = self.buffer();
+            let plain = buffer
+                .iter()
+                .position(|&byte| byte == b'"' || byte == b'\\' || byte < b' ')
+                .unwrap_or(buffer.len());
+            if plain > 0 {
+                bytes.extend_from_slice(&buffer[..plain]);
+                self.advance(plain);
+                continue;
+            }
+
+            match self.next() {
+                None => return Err(self.error("unterminated string")),
+                Some(b'"') => break,
+                Some(b'\\') => {
+                    let Some(escaped) = self.next() else {
+                        return Err(self.error("unterminated string"));
+                    };
+                    let c = match escaped {
+                        b'"' => '"',
+                        b'\\' => '\\',
+                        b'/' => '/',
+                        b'b' => '\u{08}',
+                        b'f' => '\u{0c}',
+                        b'n' => '\n',
+                        b'r' => '\r',
+                        b't' => '\t',
+                        b'u' => self.unicode_escape()?,
+                        _ => return Err(self.error("invalid escape")),
+                    };
+                    let mut buffer = [0; 4];
+                    bytes.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+                }
+                Some(_) => return Err(self.error("control character in string")),
+            }
+        }
+        String::from_utf8(bytes).map_err(|_| self.error("invalid UTF-8 in string"))
+    }
+
+    /// Reads the code point of a `\u` escape, combining a surrogate pair.
+    fn unicode_escape(&mut self) -> io::Result<char> {
+        let high = self.hex4()?;
+        let code = if (0xd800..0xdc00).contains(&high) {
+            if self.next() != Some(b'\\') || self.next() != Some(b'u') {
+                return Err(self.error("unpaired surrogate"));
+            }
+            let low = self.hex4()?;
+            if !(0xdc00..0xe000).contains(&low) {
+                return Err(self.error("unpaired surrogate"));
+            }
+            0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)
+        } else {
+            high
+        };
+        char::from_u32(code).ok_or_else(|| self.error("unpaired surrogate"))
+    }
+
+    fn hex4(&mut self) -> io::Result<u32> {
+        let mut code = 0;
+        for _ in 0..4 {
+            let Some(digit) = self.next().and_then(|digit| char::from(digit).to_digit(16)) else {
+                return Err(self.error("invalid unicode escape"));
+            };
+            code = code << 4 | digit;
+        }
+        Ok(code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_write_round_trip() {
+        let json = r#"{"a":[1,-2.5e3,true,false,null],"b":{"c":"d\"e\\f\n\u0001é"},"e":[]}"#;
+        let value = from_slice(json.as_bytes()).unwrap();
+        assert_eq!(value.to_string(), json);
+
+        let Value::Object(fields) = &value else {
+            panic!("expected an object");
+        };
+        assert_eq!(fields["e"], Value::Array(Vec::new()));
+    }
+
+    #[test]
+    fn test_matches_serde_json() {
+        let json = r#"{"z": "é😀\t", "a": [{"x": 1}, [], {}], "m": {"k": null}}"#;
+        let ours = from_slice(json.as_bytes()).unwrap();
+        let theirs: serde_json::Value = serde_json::from_str(json).unwrap();
+
+        assert_eq!(ours.to_string(), theirs.to_string());
+        assert_eq!(
+            ours.to_string_pretty(),
+            serde_json::to_string_pretty(&theirs).unwrap()
+        );
+        for text in [
+            "plain",
+            "quote \" and \\",
+            "\u{7f}\u{1f}\u{0}\u{8}\u{c}",
+            "</script>",
+        ] {
+            assert_eq!(quote(text), serde_json::Value::from(text).to_string());
+        }
+    }
+
+    #[test]
+    fn test_string_escapes() {
+        let json = r#""\" \\ \/ \b \f \n \r \t \u0041 \u00E9 \ud83d\ude00 \u001f""#;
+        let value = from_slice(json.as_bytes()).unwrap();
+        assert_eq!(
+            value,
+            Value::String("\" \\ / \u{8} \u{c} \n \r \t A é 😀 \u{1f}".to_string())
+        );
+        assert_eq!(
+            value.as_str(),
+            Some(serde_json::from_str::<String>(json).unwrap().as_str())
+        );
+    }
+
+    #[test]
+    fn test_string_round_trip() {
+        let mut text: String = (0..0x20).filter_map(char::from_u32).collect();
+        text.push_str("\u{7f} \" \\ / é 😀 \u{2028} \u{10ffff}");
+        let quoted = quote(&text);
+        assert_eq!(quoted, serde_json::Value::from(text.as_str()).to_string());
+        assert!(!quoted.chars().any(|c| c < ' '));
+        assert_eq!(from_slice(quoted.as_bytes()).unwrap(), Value::String(text));
+    }
+
+    #[test]
+    fn test_invalid_escapes() {
+        for json in [
+            r#""\u+041""#,
+            r#""\u-041""#,
+            r#""\u004""#,
+            r#""\u00g1""#,
+            r#""\u 041""#,
+            r#""\ud800""#,
+            r#""\ud800x""#,
+            r#""\ud800\u0041""#,
+            r#""\udc00""#,
+            r#""\ude00\ud83d""#,
+            r#""\U0041""#,
+        ] {
+            let error = from_slice(json.as_bytes()).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData, "{json}");
+            assert!(serde_json::from_str::<String>(json).is_err(), "{json}");
+        }
+    }
+
+    #[test]
+    fn test_invalid_json() {
+        for json in [
+            "",
+            "{",
+            r#"{"a" 1}"#,
+            r#"{"a": 1,}"#,
+            "[1 2]",
+            r#""unterminated"#,
+            r#""\x""#,
+            r#""\ud800""#,
+            "01",
+            "1.",
+            "nul",
+            "{} {}",
+            "\"a\u{1}\"",
+        ] {
+            let error = from_slice(json.as_bytes()).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData, "{json}");
+        }
+        assert!(from_slice(&[b'['; 200]).is_err());
+    }
+
+    #[test]
+    fn test_for_each_member() {
+        let json = r#" {"b": [1, {"x": "\ud83d\ude00 long enough to span reads"}], "a": -1.5e+3, "c": null} "#;
+        // a one-byte buffer splits every token across reads
+        let mut members = Vec::new();
+        for_each_member(
+            io::BufReader::with_capacity(1, json.as_bytes()),
+            |key, value| {
+                members.push((key, value));
+                Ok(())
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            members
+                .iter()
+                .map(|(key, _)| key.as_str())
+                .collect::<Vec<_>>(),
+            ["b", "a", "c"]
+        );
+        let Value::Object(fields) = from_slice(json.as_bytes()).unwrap() else {
+            panic!("not an object");
+        };
+        assert_eq!(members.into_iter().collect::<BTreeMap<_, _>>(), fields);
+
+        for invalid in ["[]", "{} {}", r#"{"a": 1"#, ""] {
+            let error = for_each_member(invalid.as_bytes(), |_, _| Ok(())).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData, "{invalid}");
+        }
+        let error = for_each_member(r#"{"a": 1}"#.as_bytes(), |_, _| {
+            Err(io::Error::other("rejected"))
+        })
+        .unwrap_err();
+        assert_eq!(error.to_string(), "rejected");
+    }
+
+    #[test]
+    fn test_reader_error() {
+        struct Failing;
+        impl io::Read for Failing {
+            fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+                Err(io::Error::new(io::ErrorKind::PermissionDenied, "denied"))
+            }
+        }
+
+        let reader = io::Read::chain(r#"{"a": "#.as_bytes(), Failing);
+        let error = for_each_member(io::BufReader::new(reader), |_, _| Ok(())).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::PermissionDenied);
+    }
+
+    #[test]
+    fn test_parse_prefix() {
+        assert_eq!(
+            parse_prefix(r#""a\"b"; rest"#),
+            Some(Value::String("a\"b".to_string()))
+        );
+        assert_eq!(parse_prefix("; rest"), None);
+    }
+
+    #[test]
+    fn test_object_and_conversions() {
+        let value = object([
+            ("name", "promo".into()),
+            ("tags", vec!["a", "b"].into()),
+            ("count", 2u64.into()),
+            ("missing", None::<&str>.into()),
+            ("live", true.into()),
+        ]);
+        assert_eq!(
+            value.to_string(),
+            r#"{"count":2,"live":true,"missing":null,"name":"promo","tags":["a","b"]}"#
+        );
+        assert_eq!(
+            object([("a", "b".into())]).to_string_pretty(),
+            "{\n  \"a\": \"b\"\n}"
+        );
+    }
+}
@@ -0,0 +1,98 @@
+//! Where redirect pages are written within the output directory.
+//!
+//! Redirect pages are written as `s/<short>.html` by default, so short URLs end in
+//! `.html`. Static hosts serve a directory's `index.html` for the directory's URL,
+//! so writing `s/<short>/index.html` instead lets short URLs be shared as `s/<short>/`
+//! on any host. This module provides the `OutputLayout` choosing between the two.
+
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// How redirect pages are laid out in the output directory.
+///
+/// # Examples
+///
+/// ```rust
+/// use link_bridge::{OutputLayout, Redirector};
+/// use std::fs;
+///
+/// let outcome = Redirector::builder()
+///     .target("docs/install")
+///     .out_dir("doc_test_output_layout")
+///     .alias("install")
+///     .layout(OutputLayout::Directory)
+///     .build()
+///     .unwrap()
+///     .write_redirect()
+///     .unwrap();
+///
+/// assert!(outcome.file_path().ends_with("install/index.html"));
+/// assert_eq!(outcome.short_file(), "install/");
+/// assert_eq!(outcome.short_name(), "install");
+///
+/// fs::remove_dir_all("doc_test_output_layout").ok();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum OutputLayout {
+    /// Write each page as `<short>.html`, reached at `s/<short>.html`.
+    #[default]
+    File,
+    /// Write each page as `<short>/index.html`, reached at `s/<short>/`.
+    Directory,
+}
+
+impl OutputLayout {
+    /// Returns the URL of a short name's page relative to the output directory:
+    /// `promo.html`, or `promo/` for the directory layout.
+    pub(crate) fn short_file(self, short_name: &str) -> String {
+        match self {
+            OutputLayout::File => format!("{short_name}.html"),
+            OutputLayout::Directory => format!("{short_name}/"),
+        }
+    }
+
+    /// Returns the path of a short name's page within an output directory.
+    pub(crate) fn file_path(self, dir: &Path, short_name: &str) -> PathBuf {
+        match self {
+            OutputLayout::File => dir.join(format!("{short_name}.html")),
+            OutputLayout::Directory => dir.join(short_name).join("index.html"),
+        }
+    }
+
+    /// Returns the layout a recorded short file was written with.
+    pub(crate) fn of(short_file: &str) -> Self {
+        if short_file.ends_with('/') {
+            OutputLayout::Directory
+        } else {
+            OutputLayout::File
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout_paths() {
+        let dir = Path::new("s");
+
+        assert_eq!(OutputLayout::File.short_file("promo"), "promo.html");
+        assert_eq!(
+            OutputLayout::File.file_path(dir, "promo"),
+            Path::new("s/promo.html")
+        );
+        assert_eq!(OutputLayout::Directory.short_file("promo"), "promo/");
+        assert_eq!(
+            OutputLayout::Directory.file_path(dir, "promo"),
+            Path::new("s/promo/index.html")
+        );
+
+        assert_eq!(OutputLayout::of("promo.html"), OutputLayout::File);
+        assert_eq!(OutputLayout::of("promo/"), OutputLayout::Directory);
+    }
+}
@@ -0,0 +1,299 @@
+//! Checking that redirect targets still exist.
+//!
+//! A redirect to a page that has since been deleted fails silently: the short link
+//! still resolves, but lands on an error page. This module provides
+//! `Registry::check_targets()`, which requests every target and reports the ones that
+//! answer with `404 Not Found` or a server error, so broken links can be fixed before
+//! users find them.
+
+use std::fmt::{self, Display};
+use std::time::Duration;
+
+use crate::redirector::target::{ExternalUrl, Target};
+use crate::redirector::{trace, RedirectorError, Registry};
+
+/// How long a single target check may take before the target is reported unreachable.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Why a redirect target was reported as broken.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinkProblem {
+    /// The target answered with `404 Not Found` or a `5xx` server error.
+    Status(u16),
+    /// The target could not be reached, for example because the host does not resolve
+    /// or the request timed out.
+    Unreachable(String),
+}
+
+impl Display for LinkProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LinkProblem::Status(status) => write!(f, "HTTP {status}"),
+            LinkProblem::Unreachable(reason) => write!(f, "unreachable ({reason})"),
+        }
+    }
+}
+
+/// A redirect whose target is broken.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrokenLink {
+    /// The short name of the redirect.
+    short_name: String,
+    /// The URL that was checked.
+    url: String,
+    /// What went wrong.
+    problem: LinkProblem,
+}
+
+impl BrokenLink {
+    /// Returns the short name of the redirect.
+    pub fn short_name(&self) -> &str {
+        &self.short_name
+    }
+
+    /// Returns the URL that was checked.
+    ///
+    /// Path targets are resolved against the base URL passed to
+    /// [`Registry::check_targets()`]; external targets are checked as registered.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Returns why the target was reported as broken.
+    pub fn problem(&self) -> &LinkProblem {
+        &self.problem
+    }
+}
+
+/// The result of checking every target in a registry.
+///
+/// The `Display` implementation renders a broken-link report with one line per broken
+/// redirect, suitable for printing from a CI job.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct LinkReport {
+    /// How many targets were checked.
+    checked: usize,
+    /// The broken redirects, ordered by target.
+    broken: Vec<BrokenLink>,
+}
+
+impl LinkReport {
+    /// Returns how many targets were checked.
+    pub fn checked(&self) -> usize {
+        self.checked
+    }
+
+    /// Returns the broken redirects, ordered by target.
+    pub fn broken(&self) -> &[BrokenLink] {
+        &self.broken
+    }
+
+    /// Returns `true` if no target was reported as broken.
+    pub fn is_ok(&self) -> bool {
+        self.broken.is_empty()
+    }
+}
+
+impl Display for LinkReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} of {} targets broken",
+            self.broken.len(),
+            self.checked
+        )?;
+        for link in &self.broken {
+            writeln!(f, "{}\t{}\t{}", link.short_name, link.url, link.problem)?;
+        }
+        Ok(())
+    }
+}
+
+impl Registry {
+    /// Checks that every redirect target still exists.
+    ///
+    /// A `HEAD` request is sent to each target in turn, following any redirects. A
+    /// target is reported as broken if it finally answers with `404 Not Found` or a
+    /// `5xx` server error, or if it cannot be reached within ten seconds. Any other
+    /// response, including `403 Forbidden` from sites that refuse `HEAD` requests, is
+    /// treated as working. Requires the `link-check` feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_url` - The site the path targets are resolved against, such as
+    ///   `"https://example.com"`
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(LinkReport)` - The broken-link report
+    /// * `Err(RedirectorError::InvalidTarget)` - If the base URL is malformed, or a
+    ///   registry entry is not a valid target
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use link_bridge::Registry;
+    ///
+    /// let registry = Registry::load("s").unwrap();
+    /// let report = registry.check_targets("https://example.com").unwrap();
+    ///
+    /// if !report.is_ok() {
+    ///     eprint!("{report}");
+    /// }
+    /// ```
+    pub fn check_targets<S: ToString>(&self, base_url: S) -> Result<LinkReport, RedirectorError> {
+        let base_url = ExternalUrl::new_base(base_url.to_string())?;
+        let agent: ureq::Agent = ureq::Agent::config_builder()
+            .timeout_global(Some(TIMEOUT))
+            .http_status_as_error(false)
+            .build()
+            .into();
+
+        let mut report = LinkReport::default();
+        for (short_name, target, _) in self.iter() {
+            let target = Target::from_registry_key(target)?;
+            // Contact links cannot be requested over HTTP
+            if matches!(target, Target::Contact(_)) {
+                continue;
+            }
+            let url = target.resolve(Some(&base_url));
+            report.checked += 1;
+
+            let problem = match agent.head(&url).call() {
+                Ok(response) => {
+                    let status = response.status();
+                    (status == 404 || status.is_server_error())
+                        .then(|| LinkProblem::Status(status.as_u16()))
+                }
+                Err(e) => Some(LinkProblem::Unreachable(e.to_string())),
+            };
+
+            if let Some(problem) = problem {
+                trace::warn!(short_name, url = %url, problem = %problem, "broken redirect target");
+                report.broken.push(BrokenLink {
+                    short_name: short_name.to_string(),
+                    url,
+                    problem,
+                });
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::path::PathBuf;
+    use std::thread;
+
+    /// Answers `count` HTTP requests, choosing the status from the request path.
+    fn serve(listener: TcpListener, count: usize) -> thread::JoinHandle<()> {
+        thread::spawn(move || {
+            for _ in 0..count {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim_end().is_empty() {
+                        break;
+                    }
+                }
+
+                let status = match request_line.split(' ').nth(1) {
+                    Some("/gone/") => "404 Not Found",
+                    Some("/error/") => "503 Service Unavailable",
+                    Some("/private/") => "403 Forbidden",
+                    _ => "200 OK",
+                };
+                write!(
+                    reader.get_mut(),
+                    "HTTP/1.1 {status}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                )
+                .unwrap();
+            }
+        })
+    }
+
+    #[test]
+    fn test_check_targets() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let server = serve(listener, 4);
+
+        let mut registry = Registry::empty(PathBuf::from("s"));
+        for name in ["ok", "gone", "error", "private"] {
+            registry.insert(format!("/{name}/"), format!("s/{name}.html"));
+        }
+
+        let report = registry.check_targets(&base_url).unwrap();
+        server.join().unwrap();
+
+        assert_eq!(report.checked(), 4);
+        assert!(!report.is_ok());
+        assert_eq!(
+            report.broken(),
+            [
+                BrokenLink {
+                    short_name: "error".to_string(),
+                    url: format!("{base_url}/error/"),
+                    problem: LinkProblem::Status(503),
+                },
+                BrokenLink {
+                    short_name: "gone".to_string(),
+                    url: format!("{base_url}/gone/"),
+                    problem: LinkProblem::Status(404),
+                },
+            ]
+        );
+        assert_eq!(
+            report.to_string(),
+            format!(
+                "2 of 4 targets broken\nerror\t{base_url}/error/\tHTTP 503\ngone\t{base_url}/gone/\tHTTP 404\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_check_targets_unreachable_external() {
+        // Bind and drop a listener to find a port nothing is listening on
+        let addr = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let mut registry = Registry::empty(PathBuf::from("s"));
+        registry.insert(format!("http://{addr}/page"), "s/page.html".to_string());
+
+        let report = registry.check_targets("https://example.com").unwrap();
+
+        assert_eq!(report.broken().len(), 1);
+        assert_eq!(report.broken()[0].short_name(), "page");
+        assert_eq!(report.broken()[0].url(), format!("http://{addr}/page"));
+        assert!(matches!(
+            report.broken()[0].problem(),
+            LinkProblem::Unreachable(_)
+        ));
+    }
+
+    #[test]
+    fn test_check_targets_empty_and_invalid_base_url() {
+        let registry = Registry::empty(PathBuf::from("s"));
+
+        let report = registry.check_targets("https://example.com").unwrap();
+        assert!(report.is_ok());
+        assert_eq!(report.checked(), 0);
+        assert_eq!(report.to_string(), "0 of 0 targets broken\n");
+
+        assert!(matches!(
+            registry.check_targets("example.com"),
+            Err(RedirectorError::InvalidTarget(_))
+        ));
+    }
+}
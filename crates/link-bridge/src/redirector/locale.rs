@@ -0,0 +1,324 @@
+//! Page text in languages other than English.
+//!
+//! Redirect, confirmation, expired, and retired pages are written in English by
+//! default, which reads oddly on a site in another language. This module provides
+//! `Locale`, which selects one of the translations bundled with the crate as Fluent
+//! resources, so pages can be written in the site's language.
+
+use std::collections::HashMap;
+
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource};
+use once_cell::sync::Lazy;
+use unic_langid::LanguageIdentifier;
+
+use crate::redirector::{RedirectorError, DEFAULT_EXPIRED_PAGE, DEFAULT_RETIRED_PAGE};
+
+/// The translations bundled with the crate, by language.
+///
+/// English is not listed: it is the text built into the pages, used whenever a
+/// message is missing.
+const TRANSLATIONS: [(&str, &str); 6] = [
+    ("de", include_str!("locales/de.ftl")),
+    ("es", include_str!("locales/es.ftl")),
+    ("fr", include_str!("locales/fr.ftl")),
+    ("it", include_str!("locales/it.ftl")),
+    ("nl", include_str!("locales/nl.ftl")),
+    ("pt", include_str!("locales/pt.ftl")),
+];
+
+/// The bundled translations, parsed once on first use.
+static BUNDLES: Lazy<HashMap<&'static str, FluentBundle<FluentResource>>> = Lazy::new(|| {
+    TRANSLATIONS
+        .iter()
+        .map(|(language, source)| {
+            let langid: LanguageIdentifier = language.parse().expect("bundled language is valid");
+            let resource =
+                FluentResource::try_new(source.to_string()).expect("bundled translation parses");
+            let mut bundle = FluentBundle::new_concurrent(vec![langid]);
+            // Messages are inserted into HTML, where Unicode isolation marks would
+            // show up around links
+            bundle.set_use_isolating(false);
+            bundle
+                .add_resource(resource)
+                .expect("bundled translation has no duplicate messages");
+            (*language, bundle)
+        })
+        .collect()
+});
+
+/// The language the text of a redirect's pages is written in.
+///
+/// Chosen per redirect with
+/// [`RedirectorBuilder::locale()`](crate::RedirectorBuilder::locale), or for every
+/// redirect of a project with the `locale` key of `link-bridge.toml`. The bundled
+/// languages are English (`en`), German (`de`), Spanish (`es`), French (`fr`),
+/// Italian (`it`), Dutch (`nl`), and Portuguese (`pt`); a tag with a region, such as
+/// `de-AT`, uses the translation for its language and keeps the full tag in the
+/// page's `lang` attribute.
+///
+/// # Examples
+///
+/// ```rust
+/// use link_bridge::Locale;
+///
+/// let locale = Locale::new("fr-CA").unwrap();
+/// assert_eq!(locale.tag(), "fr-CA");
+/// assert!(locale.expired_page().contains("Ce lien a expiré."));
+///
+/// assert!(Locale::new("tlh").is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Locale {
+    /// The language tag, as normalized (e.g. `"de-AT"`).
+    tag: String,
+    /// The bundled language used (e.g. `"de"`).
+    language: String,
+}
+
+impl Locale {
+    /// Selects the bundled translation for a language tag.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag` - A BCP 47 language tag (e.g. `"de"` or `"pt-BR"`)
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Locale)` - The locale
+    /// * `Err(RedirectorError::UnsupportedLocale)` - If the tag is malformed or no
+    ///   translation is bundled for its language
+    pub fn new(tag: &str) -> Result<Self, RedirectorError> {
+        let unsupported = || RedirectorError::UnsupportedLocale(tag.to_string());
+        let langid: LanguageIdentifier = tag.parse().map_err(|_| unsupported())?;
+        let language = langid.language.as_str();
+        if language != "en" && !BUNDLES.contains_key(language) {
+            return Err(unsupported());
+        }
+
+        Ok(Locale {
+            tag: langid.to_string(),
+            language: language.to_string(),
+        })
+    }
+
+    /// Returns the language tag, as written in the pages' `lang` attribute.
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    /// Returns the page written once a redirect has expired, in this language.
+    ///
+    /// In English this is [`DEFAULT_EXPIRED_PAGE`]. Pass it to
+    /// [`Registry::expire_with()`](crate::Registry::expire_with) to sweep expired
+    /// redirects in this language.
+    pub fn expired_page(&self) -> String {
+        self.notice_page(DEFAULT_EXPIRED_PAGE, "expired-title", "expired-body")
+    }
+
+    /// Returns the page written once a redirect has been retired, in this language.
+    ///
+    /// In English this is [`DEFAULT_RETIRED_PAGE`]. Pass it to
+    /// [`Registry::retire_with()`](crate::Registry::retire_with) to retire redirects
+    /// in this language.
+    pub fn retired_page(&self) -> String {
+        self.notice_page(DEFAULT_RETIRED_PAGE, "retired-title", "retired-body")
+    }
+
+    /// Translates a message, filling its placeables from `args`.
+    ///
+    /// Returns `None` in English, or if the translation lacks the message, so the
+    /// English text is used instead.
+    pub(crate) fn message(&self, id: &str, args: &[(&str, &str)]) -> Option<String> {
+        let bundle = BUNDLES.get(self.language.as_str())?;
+        let pattern = bundle.get_message(id)?.value()?;
+        let mut fluent_args = FluentArgs::new();
+        for (name, value) in args {
+            fluent_args.set(*name, *value);
+        }
+
+        let mut errors = Vec::new();
+        let text = bundle.format_pattern(pattern, Some(&fluent_args), &mut errors);
+        errors.is_empty().then(|| text.into_owned())
+    }
+
+    /// Renders a page that only states a message, starting from its English version.
+    fn notice_page(&self, english: &str, title: &str, body: &str) -> String {
+        let (Some(title), Some(body)) = (self.message(title, &[]), self.message(body, &[])) else {
+            return english.to_string();
+        };
+
+        format!(
+            r#"<!DOCTYPE HTML>
+<html lang="{}">
+
+<head>
+    <meta charset="UTF-8">
+    <meta name="robots" content="noindex">
+    <title>{title}</title>
+</head>
+
+<body>
+    {body}
+</body>
+
+</html>
+"#,
+            self.tag
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MemorySink, OutputSink, Redirector};
+    use chrono::{Duration, Utc};
+    use std::path::Path;
+
+    #[test]
+    fn test_locale_new() {
+        let locale = Locale::new("de-at").unwrap();
+        assert_eq!(locale.tag(), "de-AT");
+        assert_eq!(locale.language, "de");
+
+        assert_eq!(Locale::new("en").unwrap().tag(), "en");
+        for tag in ["tlh", "", "not a tag"] {
+            assert!(
+                matches!(Locale::new(tag), Err(RedirectorError::UnsupportedLocale(t)) if t == tag),
+                "{tag}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_bundled_translations_are_complete() {
+        let ids = [
+            "redirect-title",
+            "redirect-fallback",
+            "redirect-link",
+            "amp-moved",
+            "confirm-title",
+            "confirm-prompt",
+            "confirm-noscript",
+            "confirm-continue",
+            "passphrase-label",
+            "passphrase-rejected",
+            "expired-title",
+            "expired-body",
+            "retired-title",
+            "retired-body",
+        ];
+        for (language, _) in TRANSLATIONS {
+            let locale = Locale::new(language).unwrap();
+            for id in ids {
+                assert!(
+                    locale.message(id, &[("link", "<a>")]).is_some(),
+                    "{language} lacks {id}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_message() {
+        let locale = Locale::new("fr").unwrap();
+        assert_eq!(
+            locale
+                .message("confirm-noscript", &[("link", "<a href='/a/'>lien</a>")])
+                .unwrap(),
+            "Sans JavaScript, suivez ce <a href='/a/'>lien</a>."
+        );
+        assert_eq!(locale.message("missing", &[]), None);
+        assert_eq!(
+            Locale::new("en").unwrap().message("redirect-title", &[]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_notice_pages() {
+        assert_eq!(
+            Locale::new("en").unwrap().expired_page(),
+            DEFAULT_EXPIRED_PAGE
+        );
+        assert_eq!(
+            Locale::new("en").unwrap().retired_page(),
+            DEFAULT_RETIRED_PAGE
+        );
+
+        let page = Locale::new("nl-BE").unwrap().retired_page();
+        assert!(page.contains(r#"<html lang="nl-BE">"#));
+        assert!(page.contains("<title>Inhoud verwijderd</title>"));
+        assert!(page.contains("Deze inhoud is definitief verwijderd."));
+    }
+
+    #[test]
+    fn test_display_locale() {
+        let page = Redirector::builder()
+            .target("verano/oferta")
+            .locale("es")
+            .build()
+            .unwrap()
+            .to_string();
+        assert!(page.contains(r#"<html lang="es">"#));
+        assert!(page.contains("<title>Redirección</title>"));
+        assert!(page.contains(
+            "Si no se le redirige automáticamente, siga este \
+             <a href='/verano/oferta/'>enlace a la página</a>."
+        ));
+
+        let page = Redirector::builder()
+            .target("interne/wiki")
+            .confirm()
+            .locale("fr")
+            .build()
+            .unwrap()
+            .to_string();
+        assert!(page.contains("<title>Confirmer la redirection</title>"));
+        assert!(page.contains(r#"<button type="submit">Continuer</button>"#));
+        assert!(page.contains("suivez ce <a href='/interne/wiki/'>lien vers la page</a>."));
+
+        // English is the text built into the pages
+        let english = Redirector::builder()
+            .target("a")
+            .alias("a")
+            .build()
+            .unwrap();
+        let page = Redirector::builder()
+            .target("a")
+            .alias("a")
+            .locale("en-US")
+            .build()
+            .unwrap();
+        assert_eq!(page.to_string(), english.to_string());
+
+        assert!(matches!(
+            Redirector::builder().target("a").locale("xx").build(),
+            Err(RedirectorError::UnsupportedLocale(tag)) if tag == "xx"
+        ));
+    }
+
+    #[test]
+    fn test_write_expired_redirect_in_locale() {
+        let mut sink = MemorySink::new();
+        Redirector::builder()
+            .target("sommer/angebot")
+            .alias("angebot")
+            .expires_at(Utc::now() - Duration::days(1))
+            .locale("de")
+            .build()
+            .unwrap()
+            .write_redirect_to(&mut sink)
+            .unwrap();
+
+        let page = sink
+            .read_file(Path::new("s/angebot.html"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(page).unwrap(),
+            Locale::new("de").unwrap().expired_page()
+        );
+    }
+}
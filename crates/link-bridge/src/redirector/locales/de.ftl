@@ -0,0 +1,16 @@
+# German page text.
+
+redirect-title = Weiterleitung
+redirect-fallback = Falls Sie nicht automatisch weitergeleitet werden, folgen Sie diesem { $link }.
+redirect-link = Link zur Seite
+amp-moved = Diese Seite wurde verschoben. Folgen Sie diesem { $link }.
+confirm-title = Weiterleitung bestätigen
+confirm-prompt = Dieser Link führt zu einer geschützten Seite.
+confirm-noscript = Ohne JavaScript folgen Sie diesem { $link }.
+confirm-continue = Weiter
+passphrase-label = Passphrase
+passphrase-rejected = Diese Passphrase ist nicht korrekt.
+expired-title = Link abgelaufen
+expired-body = Dieser Link ist abgelaufen.
+retired-title = Inhalt entfernt
+retired-body = Dieser Inhalt wurde dauerhaft entfernt.
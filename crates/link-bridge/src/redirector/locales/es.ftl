@@ -0,0 +1,16 @@
+# Spanish page text.
+
+redirect-title = Redirección
+redirect-fallback = Si no se le redirige automáticamente, siga este { $link }.
+redirect-link = enlace a la página
+amp-moved = Esta página se ha trasladado. Siga este { $link }.
+confirm-title = Confirmar redirección
+confirm-prompt = Este enlace lleva a una página protegida.
+confirm-noscript = Sin JavaScript, siga este { $link }.
+confirm-continue = Continuar
+passphrase-label = Frase de contraseña
+passphrase-rejected = La frase de contraseña no es correcta.
+expired-title = Enlace caducado
+expired-body = Este enlace ha caducado.
+retired-title = Contenido retirado
+retired-body = Este contenido se ha retirado y no volverá.
@@ -0,0 +1,16 @@
+# French page text.
+
+redirect-title = Redirection
+redirect-fallback = Si vous n’êtes pas redirigé automatiquement, suivez ce { $link }.
+redirect-link = lien vers la page
+amp-moved = Cette page a été déplacée. Suivez ce { $link }.
+confirm-title = Confirmer la redirection
+confirm-prompt = Ce lien mène à une page protégée.
+confirm-noscript = Sans JavaScript, suivez ce { $link }.
+confirm-continue = Continuer
+passphrase-label = Phrase secrète
+passphrase-rejected = Cette phrase secrète est incorrecte.
+expired-title = Lien expiré
+expired-body = Ce lien a expiré.
+retired-title = Contenu supprimé
+retired-body = Ce contenu a été supprimé définitivement.
@@ -0,0 +1,16 @@
+# Italian page text.
+
+redirect-title = Reindirizzamento
+redirect-fallback = Se non vieni reindirizzato automaticamente, segui questo { $link }.
+redirect-link = link alla pagina
+amp-moved = Questa pagina è stata spostata. Segui questo { $link }.
+confirm-title = Conferma reindirizzamento
+confirm-prompt = Questo link porta a una pagina protetta.
+confirm-noscript = Senza JavaScript, segui questo { $link }.
+confirm-continue = Continua
+passphrase-label = Passphrase
+passphrase-rejected = La passphrase non è corretta.
+expired-title = Link scaduto
+expired-body = Questo link è scaduto.
+retired-title = Contenuto rimosso
+retired-body = Questo contenuto è stato rimosso definitivamente.
@@ -0,0 +1,16 @@
+# Dutch page text.
+
+redirect-title = Doorverwijzing
+redirect-fallback = Als je niet automatisch wordt doorverwezen, volg dan deze { $link }.
+redirect-link = link naar de pagina
+amp-moved = Deze pagina is verplaatst. Volg deze { $link }.
+confirm-title = Doorverwijzing bevestigen
+confirm-prompt = Deze link leidt naar een beveiligde pagina.
+confirm-noscript = Zonder JavaScript volg je deze { $link }.
+confirm-continue = Doorgaan
+passphrase-label = Wachtwoordzin
+passphrase-rejected = Die wachtwoordzin is niet juist.
+expired-title = Link verlopen
+expired-body = Deze link is verlopen.
+retired-title = Inhoud verwijderd
+retired-body = Deze inhoud is definitief verwijderd.
@@ -0,0 +1,16 @@
+# Portuguese page text.
+
+redirect-title = Redirecionamento
+redirect-fallback = Se não for redirecionado automaticamente, siga este { $link }.
+redirect-link = link para a página
+amp-moved = Esta página foi movida. Siga este { $link }.
+confirm-title = Confirmar redirecionamento
+confirm-prompt = Este link leva a uma página protegida.
+confirm-noscript = Sem JavaScript, siga este { $link }.
+confirm-continue = Continuar
+passphrase-label = Frase-passe
+passphrase-rejected = Essa frase-passe não está correta.
+expired-title = Link expirado
+expired-body = Este link expirou.
+retired-title = Conteúdo removido
+retired-body = Este conteúdo foi removido definitivamente.
@@ -0,0 +1,208 @@
+//! Exclusive access to a registry within a process.
+//!
+//! A web service that creates and deletes short links on request runs many handlers at
+//! once, and two of them loading, changing, and saving the same `registry.json` would
+//! lose each other's changes. This module provides `Registry::lock()`, which returns a
+//! `LockedRegistry` guard holding an exclusive lock on an output directory for as long
+//! as it is alive, so a scope of operations is serialized without any locking of the
+//! service's own.
+
+use std::collections::HashSet;
+use std::ops::{Deref, DerefMut};
+use std::path::{self, PathBuf};
+use std::sync::{Condvar, Mutex, MutexGuard, PoisonError};
+
+use once_cell::sync::Lazy;
+
+use crate::redirector::{trace, RedirectorError, Registry};
+
+/// The output directories currently locked by a `LockedRegistry`.
+static LOCKED_DIRS: Lazy<Mutex<HashSet<PathBuf>>> = Lazy::new(Mutex::default);
+
+/// Signalled whenever a directory is unlocked.
+static UNLOCKED: Condvar = Condvar::new();
+
+/// A registry loaded under an exclusive lock on its output directory.
+///
+/// Created by [`Registry::lock()`]. The guard dereferences to the [`Registry`], so
+/// redirects can be written into it, removed, or repointed as usual, and
+/// [`Registry::save()`] persists the changes. The lock is released when the guard is
+/// dropped; changes that have not been saved are discarded.
+///
+/// The lock is held within the current process only: every thread locking the same
+/// output directory waits for the guard to be dropped, but other processes and
+/// writes that do not go through a `LockedRegistry`, such as
+/// [`Redirector::write_redirect()`](crate::Redirector::write_redirect), are not
+/// excluded.
+#[derive(Debug)]
+pub struct LockedRegistry {
+    /// The registry loaded once the lock was acquired.
+    registry: Registry,
+    /// The locked output directory, as an absolute path.
+    key: PathBuf,
+}
+
+impl Registry {
+    /// Locks an output directory and loads its registry.
+    ///
+    /// Blocks until no other [`LockedRegistry`] in the process holds the directory,
+    /// then loads the registry, so the guard always sees the changes saved by the
+    /// previous holder. Directories are compared as absolute paths, so `s` and `./s`
+    /// share one lock.
+    ///
+    /// A thread that locks a directory it already holds a guard for waits forever.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The output directory containing the registry
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(LockedRegistry)` - The loaded (or empty) registry, locked until dropped
+    /// * `Err(RedirectorError::FailedToReadRegistry)` - If the registry file cannot be
+    ///   opened or is not valid JSON; the directory is unlocked again
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::{Redirector, Registry};
+    /// use std::fs;
+    ///
+    /// {
+    ///     let mut registry = Registry::lock("doc_test_registry_lock").unwrap();
+    ///     for path in ["docs/install", "docs/usage"] {
+    ///         Redirector::new(path)
+    ///             .unwrap()
+    ///             .write_redirect_into(&mut registry)
+    ///             .unwrap();
+    ///     }
+    ///     registry.save().unwrap();
+    /// } // the lock is released here
+    ///
+    /// assert_eq!(Registry::load("doc_test_registry_lock").unwrap().len(), 2);
+    ///
+    /// fs::remove_dir_all("doc_test_registry_lock").ok();
+    /// ```
+    pub fn lock<P: Into<PathBuf>>(dir: P) -> Result<LockedRegistry, RedirectorError> {
+        let dir = dir.into();
+        let key = path::absolute(&dir).unwrap_or_else(|_| dir.clone());
+
+        let mut locked = locked_dirs();
+        while locked.contains(&key) {
+            trace::debug!(dir = %dir.display(), "waiting for registry lock");
+            locked = UNLOCKED
+                .wait(locked)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+        locked.insert(key.clone());
+        drop(locked);
+
+        // The guard is built before loading, so a failed load releases the lock
+        let mut guard = LockedRegistry {
+            registry: Registry::empty(dir.clone()),
+            key,
+        };
+        guard.registry = Registry::load(dir)?;
+        Ok(guard)
+    }
+}
+
+impl Deref for LockedRegistry {
+    type Target = Registry;
+
+    fn deref(&self) -> &Registry {
+        &self.registry
+    }
+}
+
+impl DerefMut for LockedRegistry {
+    fn deref_mut(&mut self) -> &mut Registry {
+        &mut self.registry
+    }
+}
+
+impl Drop for LockedRegistry {
+    fn drop(&mut self) {
+        locked_dirs().remove(&self.key);
+        UNLOCKED.notify_all();
+    }
+}
+
+/// Returns the set of locked directories.
+///
+/// The set is only changed while the lock is held and never left half-updated, so a
+/// thread that panicked while holding it does not leave it unusable.
+fn locked_dirs() -> MutexGuard<'static, HashSet<PathBuf>> {
+    LOCKED_DIRS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Redirector;
+    use chrono::Utc;
+    use std::fs;
+    use std::sync::Arc;
+    use std::thread;
+
+    fn test_dir(name: &str) -> String {
+        format!("{name}_{}", Utc::now().timestamp_nanos_opt().unwrap_or(0))
+    }
+
+    #[test]
+    fn test_lock_serializes_threads() {
+        let dir = Arc::new(test_dir("test_lock_serializes_threads"));
+
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let dir = Arc::clone(&dir);
+                thread::spawn(move || {
+                    let mut registry = Registry::lock(dir.as_str()).unwrap();
+                    Redirector::new(format!("page/{i}"))
+                        .unwrap()
+                        .write_redirect_into(&mut registry)
+                        .unwrap();
+                    // Give other threads a chance to interleave if the lock were missing
+                    thread::yield_now();
+                    registry.save().unwrap();
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(Registry::load(dir.as_str()).unwrap().len(), 8);
+
+        fs::remove_dir_all(dir.as_str()).unwrap();
+    }
+
+    #[test]
+    fn test_lock_released_on_drop() {
+        let dir = test_dir("test_lock_released_on_drop");
+
+        let registry = Registry::lock(&dir).unwrap();
+        assert!(registry.is_empty());
+        assert!(locked_dirs().contains(&path::absolute(&dir).unwrap()));
+        drop(registry);
+        assert!(!locked_dirs().contains(&path::absolute(&dir).unwrap()));
+
+        // `./dir` names the same directory, so it can be locked again now
+        drop(Registry::lock(format!("./{dir}")).unwrap());
+    }
+
+    #[test]
+    fn test_lock_released_on_load_error() {
+        let dir = test_dir("test_lock_released_on_load_error");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(format!("{dir}/registry.json"), "not json").unwrap();
+
+        assert!(matches!(
+            Registry::lock(&dir),
+            Err(RedirectorError::FailedToReadRegistry { .. })
+        ));
+        assert!(!locked_dirs().contains(&path::absolute(&dir).unwrap()));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,257 @@
+//! An mdBook preprocessor that writes redirect pages for moved chapters.
+//!
+//! mdBook runs preprocessors as external commands, passing the book configuration and
+//! content as JSON on standard input and reading the book back from standard output.
+//! This module provides the `MdBookPreprocessor` type implementing that protocol: it
+//! reads a `[redirects]` table from `book.toml`, writes a link-bridge page for every
+//! entry, and hands the book back unchanged.
+//!
+//! The pages are written below the book's source directory, which the HTML renderer
+//! copies into the build output along with the other non-Markdown files.
+
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::redirector::{RedirectOutcome, Redirector, RedirectorError};
+
+/// The default directory, relative to the book's source directory, for redirect pages.
+const DEFAULT_DIR: &str = "s";
+
+/// An mdBook preprocessor that turns the `[redirects]` table of `book.toml` into
+/// redirect pages.
+///
+/// Each entry maps a short name to the chapter it redirects to, as a path relative to
+/// the book root. When `output.html.site-url` is set, it is prefixed to every target
+/// so redirects work for books served below a sub-path.
+///
+/// ```toml
+/// [preprocessor.link-bridge]
+/// dir = "s" # relative to the book's src directory; this is the default
+///
+/// [redirects]
+/// install = "guide/installation.html"
+/// faq = "reference/faq.html"
+/// ```
+///
+/// With the `mdbook` feature enabled, the `mdbook-link-bridge` binary runs this
+/// preprocessor, so adding `[preprocessor.link-bridge]` to `book.toml` is enough to
+/// enable it.
+///
+/// # Examples
+///
+/// ```rust
+/// use link_bridge::MdBookPreprocessor;
+/// use std::fs;
+///
+/// let input = r#"[
+///     {
+///         "root": "doc_test_mdbook",
+///         "config": {
+///             "book": { "src": "src" },
+///             "redirects": { "install": "guide/installation.html" }
+///         },
+///         "renderer": "html",
+///         "mdbook_version": "0.4.40"
+///     },
+///     { "sections": [], "__non_exhaustive": null }
+/// ]"#;
+///
+/// let (book, outcomes) = MdBookPreprocessor.run(input).unwrap();
+/// assert!(book.contains("sections"));
+/// assert_eq!(outcomes[0].short_name(), "install");
+///
+/// let html = fs::read_to_string("doc_test_mdbook/src/s/install.html").unwrap();
+/// assert!(html.contains("url=/guide/installation.html"));
+///
+/// fs::remove_dir_all("doc_test_mdbook").ok();
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MdBookPreprocessor;
+
+impl MdBookPreprocessor {
+    /// The name of the preprocessor, as used in `[preprocessor.link-bridge]`.
+    pub const NAME: &'static str = "link-bridge";
+
+    /// Reports whether the preprocessor supports a renderer.
+    ///
+    /// Redirect pages are HTML, so only the `html` renderer is supported.
+    pub fn supports_renderer(&self, renderer: &str) -> bool {
+        renderer == "html"
+    }
+
+    /// Runs the preprocessor on the JSON mdBook passes on standard input.
+    ///
+    /// A redirect page is written for every entry in the `[redirects]` table and the
+    /// registry is updated once, as with [`Redirector::write_all()`].
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The `[context, book]` JSON array sent by mdBook
+    ///
+    /// # Returns
+    ///
+    /// * `Ok((String, Vec<RedirectOutcome>))` - The unchanged book as JSON, to be
+    ///   written to standard output, and the outcome for each redirect
+    /// * `Err(RedirectorError::InvalidBookContext)` - If the input or the `[redirects]`
+    ///   table is malformed
+    /// * `Err(RedirectorError)` - The errors of [`RedirectorBuilder::build()`] and
+    ///   [`Redirector::write_all()`]
+    ///
+    /// [`RedirectorBuilder::build()`]: crate::RedirectorBuilder::build
+    pub fn run(&self, input: &str) -> Result<(String, Vec<RedirectOutcome>), RedirectorError> {
+        let input: Value = serde_json::from_str(input).map_err(|e| invalid(&e.to_string()))?;
+        let [context, book] = input.as_array().map(Vec::as_slice).unwrap_or_default() else {
+            return Err(invalid("expected a [context, book] array"));
+        };
+
+        let redirectors = redirectors(context)?;
+        let outcomes = Redirector::write_all(&redirectors)?;
+
+        Ok((book.to_string(), outcomes))
+    }
+}
+
+/// Builds a redirector for every entry of the `[redirects]` table.
+fn redirectors(context: &Value) -> Result<Vec<Redirector>, RedirectorError> {
+    let root = context["root"]
+        .as_str()
+        .ok_or_else(|| invalid("missing book root"))?;
+    let config = &context["config"];
+
+    let src = config["book"]["src"].as_str().unwrap_or("src");
+    let dir = match &config["preprocessor"][MdBookPreprocessor::NAME]["dir"] {
+        Value::Null => DEFAULT_DIR,
+        dir => dir
+            .as_str()
+            .ok_or_else(|| invalid("preprocessor dir must be a string"))?,
+    };
+    let out_dir: PathBuf = Path::new(root).join(src).join(dir);
+    let site_url = config["output"]["html"]["site-url"]
+        .as_str()
+        .unwrap_or("/")
+        .trim_matches('/');
+
+    let entries = match &config["redirects"] {
+        Value::Null => return Ok(Vec::new()),
+        Value::Object(entries) => entries,
+        _ => return Err(invalid("[redirects] must be a table")),
+    };
+
+    entries
+        .iter()
+        .map(|(short_name, target)| {
+            let target = target.as_str().ok_or_else(|| {
+                invalid(&format!(
+                    "redirect target for {short_name} must be a string"
+                ))
+            })?;
+            let target = target.trim_start_matches('/');
+            let target = if site_url.is_empty() {
+                target.to_string()
+            } else {
+                format!("{site_url}/{target}")
+            };
+
+            Redirector::builder()
+                .target(target)
+                .out_dir(&out_dir)
+                .alias(short_name)
+                .build()
+        })
+        .collect()
+}
+
+/// Creates the error for malformed preprocessor input.
+fn invalid(message: &str) -> RedirectorError {
+    RedirectorError::InvalidBookContext(message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use std::fs;
+
+    fn input(root: &str, config: Value) -> String {
+        serde_json::json!([
+            { "root": root, "config": config, "renderer": "html", "mdbook_version": "0.4.40" },
+            { "sections": [{ "Chapter": { "name": "Intro" } }], "__non_exhaustive": null }
+        ])
+        .to_string()
+    }
+
+    #[test]
+    fn test_run_writes_redirects() {
+        let root = format!(
+            "test_mdbook_run_{}",
+            Utc::now().timestamp_nanos_opt().unwrap_or(0)
+        );
+        let config = serde_json::json!({
+            "book": { "src": "pages" },
+            "preprocessor": { "link-bridge": { "dir": "go" } },
+            "output": { "html": { "site-url": "/docs/" } },
+            "redirects": { "faq": "/reference/faq.html", "install": "guide/install.html" }
+        });
+
+        let (book, outcomes) = MdBookPreprocessor.run(&input(&root, config)).unwrap();
+
+        let book: Value = serde_json::from_str(&book).unwrap();
+        assert_eq!(book["sections"][0]["Chapter"]["name"], "Intro");
+        assert_eq!(outcomes.len(), 2);
+
+        let out_dir = Path::new(&root).join("pages/go");
+        let html = fs::read_to_string(out_dir.join("install.html")).unwrap();
+        assert!(html.contains("url=/docs/guide/install.html\""));
+        let html = fs::read_to_string(out_dir.join("faq.html")).unwrap();
+        assert!(html.contains("url=/docs/reference/faq.html\""));
+        assert!(out_dir.join("registry.json").exists());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_run_without_redirects() {
+        let config = serde_json::json!({ "book": {} });
+
+        let (_, outcomes) = MdBookPreprocessor
+            .run(&input("test_mdbook_no_redirects", config))
+            .unwrap();
+
+        assert!(outcomes.is_empty());
+        assert!(!Path::new("test_mdbook_no_redirects").exists());
+    }
+
+    #[test]
+    fn test_run_invalid_input() {
+        let invalid = |input: &str| {
+            matches!(
+                MdBookPreprocessor.run(input),
+                Err(RedirectorError::InvalidBookContext(_))
+            )
+        };
+
+        assert!(invalid("not json"));
+        assert!(invalid("[{}]"));
+        assert!(invalid(&input(
+            "root",
+            serde_json::json!({ "redirects": [] })
+        )));
+        assert!(invalid(&input(
+            "root",
+            serde_json::json!({ "redirects": { "a": 1 } })
+        )));
+
+        let bad_alias = input("root", serde_json::json!({ "redirects": { "a/b": "c" } }));
+        assert!(matches!(
+            MdBookPreprocessor.run(&bad_alias),
+            Err(RedirectorError::InvalidAlias(_))
+        ));
+    }
+
+    #[test]
+    fn test_supports_renderer() {
+        assert!(MdBookPreprocessor.supports_renderer("html"));
+        assert!(!MdBookPreprocessor.supports_renderer("markdown"));
+    }
+}
@@ -0,0 +1,189 @@
+//! Registry health metrics in the Prometheus text format.
+//!
+//! Site pipelines that already scrape Prometheus metrics, or feed files to the node
+//! exporter's textfile collector, can watch a redirect store alongside everything
+//! else. This module provides `Registry::write_metrics()`, which writes gauges for
+//! the number of redirects, those created today, those past their expiry time, and
+//! the orphaned pages left in the output directory.
+
+use std::io::{self, Write};
+
+use crate::redirector::time::Timestamp;
+use crate::redirector::Registry;
+
+impl Registry {
+    /// Writes the registry's health metrics in the Prometheus text exposition format.
+    ///
+    /// Each gauge is labelled with the output directory, so metrics of several
+    /// registries can be written to one file:
+    ///
+    /// - `link_bridge_redirects` - the redirects in the registry
+    /// - `link_bridge_redirects_created_today` - those created since midnight UTC
+    /// - `link_bridge_redirects_expired` - those past their expiry time that have not
+    ///   been swept with [`Registry::expire()`] or retired
+    /// - `link_bridge_orphaned_pages` - the pages reported by
+    ///   [`Registry::find_orphans()`]
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - Where the metrics are written
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the metrics were written
+    /// * `Err(io::Error)` - If the output directory cannot be listed for orphaned
+    ///   pages, or the writer failed
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::{Redirector, Registry};
+    /// use std::fs;
+    ///
+    /// Redirector::builder()
+    ///     .target("summer/sale")
+    ///     .out_dir("doc_test_registry_metrics")
+    ///     .alias("promo")
+    ///     .build()
+    ///     .unwrap()
+    ///     .write_redirect()
+    ///     .unwrap();
+    ///
+    /// let mut metrics = Vec::new();
+    /// Registry::load("doc_test_registry_metrics")
+    ///     .unwrap()
+    ///     .write_metrics(&mut metrics)
+    ///     .unwrap();
+    ///
+    /// let metrics = String::from_utf8(metrics).unwrap();
+    /// assert!(metrics.contains("link_bridge_redirects{dir=\"doc_test_registry_metrics\"} 1\n"));
+    ///
+    /// fs::remove_dir_all("doc_test_registry_metrics").ok();
+    /// ```
+    pub fn write_metrics<W: Write + ?Sized>(&self, writer: &mut W) -> io::Result<()> {
+        let orphans = self.orphans().map_err(io::Error::other)?.len();
+        writer.write_all(self.metrics_at(Timestamp::now(), orphans).as_bytes())
+    }
+
+    /// Renders the metrics as they stand at `now`, with `orphans` orphaned pages.
+    fn metrics_at(&self, now: Timestamp, orphans: usize) -> String {
+        let entries = || self.iter().filter_map(|(_, target, _)| self.entry(target));
+        let created_today = entries()
+            .filter(|entry| {
+                entry
+                    .created_at
+                    .is_some_and(|created_at| created_at.day() == now.day())
+            })
+            .count();
+        let expired = entries()
+            .filter(|entry| !entry.is_retired())
+            .filter(|entry| entry.expires_at.is_some_and(|expires_at| expires_at <= now))
+            .count();
+
+        let dir = label_value(&self.dir().to_string_lossy());
+        let mut metrics = String::new();
+        for (name, help, value) in [
+            (
+                "link_bridge_redirects",
+                "Redirects in the registry.",
+                self.len(),
+            ),
+            (
+                "link_bridge_redirects_created_today",
+                "Redirects created since midnight UTC.",
+                created_today,
+            ),
+            (
+                "link_bridge_redirects_expired",
+                "Redirects past their expiry time that have not been swept.",
+                expired,
+            ),
+            (
+                "link_bridge_orphaned_pages",
+                "Pages in the output directory that no redirect refers to.",
+                orphans,
+            ),
+        ] {
+            metrics.push_str(&format!(
+                "# HELP {name} {help}\n# TYPE {name} gauge\n{name}{{dir=\"{dir}\"}} {value}\n"
+            ));
+        }
+        metrics
+    }
+}
+
+/// Escapes a Prometheus label value: backslashes, double quotes, and newlines.
+fn label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    #[test]
+    fn test_metrics_counts() {
+        let mut registry = Registry::empty(PathBuf::from("s"));
+        registry.insert("/a/".to_string(), "s/a.html".to_string());
+        registry.insert("/b/".to_string(), "s/b.html".to_string());
+        registry.insert("/c/".to_string(), "s/c.html".to_string());
+        registry.entry_mut("/c/").unwrap().retire();
+
+        let now = Timestamp::now();
+        let tomorrow = now.checked_add(Duration::from_secs(86_400)).unwrap();
+        let metrics = registry.metrics_at(now, 2);
+        assert!(metrics.contains("# TYPE link_bridge_redirects gauge\n"));
+        assert!(metrics.contains("link_bridge_redirects{dir=\"s\"} 3\n"));
+        assert!(metrics.contains("link_bridge_redirects_created_today{dir=\"s\"} 3\n"));
+        assert!(metrics.contains("link_bridge_redirects_expired{dir=\"s\"} 0\n"));
+        assert!(metrics.contains("link_bridge_orphaned_pages{dir=\"s\"} 2\n"));
+
+        let metrics = registry.metrics_at(tomorrow, 0);
+        assert!(metrics.contains("link_bridge_redirects_created_today{dir=\"s\"} 0\n"));
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_metrics_expired_and_orphans() {
+        use chrono::Utc;
+        use std::fs;
+
+        let dir = format!(
+            "test_metrics_expired_and_orphans_{}",
+            Utc::now().timestamp_nanos_opt().unwrap_or(0)
+        );
+        crate::Redirector::builder()
+            .target("a")
+            .out_dir(&dir)
+            .alias("old")
+            .expires_at(Utc::now() - chrono::Duration::days(1))
+            .build()
+            .unwrap()
+            .write_redirect()
+            .unwrap();
+        fs::write(PathBuf::from(&dir).join("stale.html"), "<p>Old</p>").unwrap();
+
+        let mut metrics = Vec::new();
+        Registry::load(&dir)
+            .unwrap()
+            .write_metrics(&mut metrics)
+            .unwrap();
+        let metrics = String::from_utf8(metrics).unwrap();
+        assert!(metrics.contains(&format!(
+            "link_bridge_redirects_expired{{dir=\"{dir}\"}} 1\n"
+        )));
+        assert!(metrics.contains(&format!("link_bridge_orphaned_pages{{dir=\"{dir}\"}} 1\n")));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_label_value() {
+        assert_eq!(label_value("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
+    }
+}
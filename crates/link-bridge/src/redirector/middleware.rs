@@ -0,0 +1,219 @@
+//! Serving redirects as `tower` middleware.
+//!
+//! `Registry::router()` ties dynamic redirects to `axum`, while many services are
+//! assembled from `tower` layers on `hyper` or another framework. This module provides
+//! `RedirectLayer` and the `RedirectService` it wraps around a service, which answer
+//! requests for registered short names with `301 Moved Permanently` and pass every
+//! other request on, so the redirect map can be dropped into any `tower` stack.
+
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use futures_util::future::{Either, Ready};
+use http::{header, HeaderValue, Method, Request, Response, StatusCode};
+use tower::{Layer, Service};
+
+use crate::redirector::{Registry, RegistryEntry};
+
+/// A [`Layer`] that answers short-link requests from a registry.
+///
+/// The services it builds redirect `GET` and `HEAD` requests for a registered short
+/// name, with or without the `.html` extension or a trailing slash, with
+/// `301 Moved Permanently` and a `Location` header holding the target. Short names
+/// retired with [`Registry::retire()`] receive `410 Gone`. Every other request is
+/// passed to the wrapped service. Requires the `tower` feature.
+///
+/// The registry is consumed, so later changes to `registry.json` are not seen; load
+/// the registry again and rebuild the layer to pick them up.
+///
+/// # Examples
+///
+/// ```rust
+/// use http::{Request, Response};
+/// use link_bridge::{RedirectLayer, Redirector, Registry};
+/// use std::convert::Infallible;
+/// use std::fs;
+/// use tower::{service_fn, Layer};
+///
+/// let redirector = Redirector::builder()
+///     .target("api/v1/users")
+///     .out_dir("doc_test_redirect_layer")
+///     .alias("users")
+///     .build()
+///     .unwrap();
+/// redirector.write_redirect().unwrap();
+///
+/// // GET /s/users -> 301 Moved Permanently, Location: /api/v1/users/
+/// let registry = Registry::load("doc_test_redirect_layer").unwrap();
+/// let site = service_fn(|_request: Request<()>| async {
+///     Ok::<_, Infallible>(Response::new(String::from("site page")))
+/// });
+/// let service = RedirectLayer::new(registry).prefix("/s").layer(site);
+///
+/// fs::remove_dir_all("doc_test_redirect_layer").ok();
+/// ```
+#[derive(Debug, Clone)]
+pub struct RedirectLayer {
+    /// The registry redirects are looked up in.
+    registry: Arc<Registry>,
+    /// The path short names are served below.
+    prefix: String,
+}
+
+impl RedirectLayer {
+    /// Creates a layer serving the short names of `registry` at the root of the site.
+    pub fn new(registry: Registry) -> Self {
+        RedirectLayer {
+            registry: Arc::new(registry),
+            prefix: String::new(),
+        }
+    }
+
+    /// Serves short names below `prefix`, such as `/s`, matching the static redirect
+    /// pages.
+    pub fn prefix<S: ToString>(mut self, prefix: S) -> Self {
+        self.prefix = prefix.to_string().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Answers a request for a registered short name, or returns `None` to pass it on.
+    fn respond<B, ResBody: Default>(&self, request: &Request<B>) -> Option<Response<ResBody>> {
+        if request.method() != Method::GET && request.method() != Method::HEAD {
+            return None;
+        }
+        let short_name = request
+            .uri()
+            .path()
+            .strip_prefix(&self.prefix)?
+            .strip_prefix('/')?;
+        let short_name = short_name.strip_suffix('/').unwrap_or(short_name);
+        let short_name = short_name.strip_suffix(".html").unwrap_or(short_name);
+        if short_name.is_empty() || short_name.contains('/') {
+            return None;
+        }
+
+        let target = self.registry.target_for(short_name)?;
+        let mut response = Response::new(ResBody::default());
+        if self
+            .registry
+            .entry(target)
+            .is_some_and(RegistryEntry::is_retired)
+        {
+            *response.status_mut() = StatusCode::GONE;
+            return Some(response);
+        }
+        match HeaderValue::from_str(target) {
+            Ok(location) => {
+                *response.status_mut() = StatusCode::MOVED_PERMANENTLY;
+                response.headers_mut().insert(header::LOCATION, location);
+            }
+            Err(_) => *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR,
+        }
+        Some(response)
+    }
+}
+
+impl<S> Layer<S> for RedirectLayer {
+    type Service = RedirectService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RedirectService {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+/// A [`Service`] that answers short-link requests from a registry and passes every
+/// other request to the service it wraps.
+///
+/// Built by [`RedirectLayer`]. Redirects are answered with an empty body of the
+/// wrapped service's response body type.
+#[derive(Debug, Clone)]
+pub struct RedirectService<S> {
+    /// The service answering requests that are not for a short name.
+    inner: S,
+    /// The registry and prefix redirects are served from.
+    layer: RedirectLayer,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for RedirectService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>>,
+    ResBody: Default,
+{
+    type Response = Response<ResBody>;
+    type Error = S::Error;
+    type Future = Either<Ready<Result<Self::Response, Self::Error>>, S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<ReqBody>) -> Self::Future {
+        match self.layer.respond(&request) {
+            Some(response) => Either::Left(futures_util::future::ready(Ok(response))),
+            None => Either::Right(self.inner.call(request)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::Infallible;
+    use std::path::PathBuf;
+    use tower::{service_fn, ServiceExt};
+
+    fn registry() -> Registry {
+        let mut registry = Registry::empty(PathBuf::from("s"));
+        registry.insert("/docs/install/".to_string(), "s/install.html".to_string());
+        registry.insert("/docs/old/".to_string(), "s/old.html".to_string());
+        registry.entry_mut("/docs/old/").unwrap().retire();
+        registry
+    }
+
+    async fn call(layer: &RedirectLayer, request: Request<()>) -> Response<String> {
+        let site = service_fn(|request: Request<()>| async move {
+            Ok::<_, Infallible>(Response::new(format!("site {}", request.uri().path())))
+        });
+        layer.layer(site).oneshot(request).await.unwrap()
+    }
+
+    async fn get(layer: &RedirectLayer, uri: &str) -> Response<String> {
+        call(layer, Request::get(uri).body(()).unwrap()).await
+    }
+
+    #[tokio::test]
+    async fn test_layer_redirects() {
+        let layer = RedirectLayer::new(registry());
+
+        for uri in ["/install", "/install.html", "/install/"] {
+            let response = get(&layer, uri).await;
+            assert_eq!(response.status(), StatusCode::MOVED_PERMANENTLY);
+            assert_eq!(response.headers()[header::LOCATION], "/docs/install/");
+            assert_eq!(response.body(), "");
+        }
+
+        let response = call(&layer, Request::head("/install").body(()).unwrap()).await;
+        assert_eq!(response.status(), StatusCode::MOVED_PERMANENTLY);
+        assert_eq!(get(&layer, "/old").await.status(), StatusCode::GONE);
+    }
+
+    #[tokio::test]
+    async fn test_layer_passes_other_requests_on() {
+        let layer = RedirectLayer::new(registry()).prefix("/s/");
+
+        let response = get(&layer, "/s/install?ref=mail").await;
+        assert_eq!(response.headers()[header::LOCATION], "/docs/install/");
+
+        for uri in ["/install", "/s/missing", "/s/", "/s/a/install", "/sinstall"] {
+            let response = get(&layer, uri).await;
+            assert_eq!(response.status(), StatusCode::OK, "{uri}");
+            assert_eq!(response.body(), &format!("site {uri}"));
+        }
+
+        let response = call(&layer, Request::post("/s/install").body(()).unwrap()).await;
+        assert_eq!(response.body(), "site /s/install");
+    }
+}
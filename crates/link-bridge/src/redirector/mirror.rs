@@ -0,0 +1,154 @@
+//! Writing each redirect to several output directories.
+//!
+//! A site built into more than one tree, such as a staging and a production build,
+//! needs the same short links in each. This module writes a redirect built with
+//! mirror directories into every one of them in one call, under the same short name,
+//! so their registries stay in sync.
+
+use std::ffi::OsString;
+use std::fs;
+
+use crate::redirector::sink::FsSink;
+use crate::redirector::{trace, RedirectOutcome, Redirector, RedirectorError, Registry};
+
+impl Redirector {
+    /// Writes the redirect to its output directory and to every mirror directory.
+    ///
+    /// Notifies the hooks once, with the outcome for the output directory.
+    pub(crate) fn write_mirrored(&self) -> Result<RedirectOutcome, RedirectorError> {
+        let result = write_mirrored(self);
+        self.hooks.notify(&self.target.to_string(), &result);
+        result
+    }
+}
+
+/// Writes the redirect to every directory, removing the new pages again if any write
+/// fails so no registry is left ahead of the others.
+fn write_mirrored(redirector: &Redirector) -> Result<RedirectOutcome, RedirectorError> {
+    let mut created_files = Vec::new();
+
+    let result = (|| {
+        let mut registry = Registry::load(&redirector.path)?;
+        let outcome = redirector.write_into(&mut registry, &mut FsSink)?;
+        let mut changed = Vec::new();
+        if outcome.is_created() {
+            created_files.push(outcome.file_path().to_path_buf());
+            changed.push(registry);
+        }
+
+        // Mirrors reuse the short name chosen for the output directory
+        let mut mirror = redirector.clone();
+        mirror.short_file_name = OsString::from(format!("{}.html", outcome.short_name()));
+        for dir in &redirector.mirrors {
+            let mut registry = Registry::load(dir)?;
+            let mirrored = mirror.write_into(&mut registry, &mut FsSink)?;
+            if mirrored.short_name() != outcome.short_name() {
+                return Err(RedirectorError::OutOfSync {
+                    path: dir.clone(),
+                    short_name: mirrored.short_name().to_string(),
+                });
+            }
+            if mirrored.is_created() {
+                created_files.push(mirrored.file_path().to_path_buf());
+                changed.push(registry);
+            }
+        }
+
+        Registry::save_all(&changed)?;
+        Ok(outcome)
+    })();
+
+    if result.is_err() {
+        trace::warn!(
+            files = created_files.len(),
+            "mirrored write failed, removing written files"
+        );
+        for file in &created_files {
+            fs::remove_file(file).ok();
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use std::path::Path;
+
+    fn test_dir(name: &str) -> String {
+        format!("{name}_{}", Utc::now().timestamp_nanos_opt().unwrap_or(0))
+    }
+
+    #[test]
+    fn test_write_mirrored() {
+        let dir = test_dir("test_write_mirrored");
+        let staging = format!("{dir}/staging");
+        let production = format!("{dir}/production");
+
+        // The production tree already has the redirect under its own short name
+        Redirector::builder()
+            .target("docs/install")
+            .out_dir(&production)
+            .alias("install")
+            .build()
+            .unwrap()
+            .write_redirect()
+            .unwrap();
+
+        let outcome = Redirector::builder()
+            .target("docs/install")
+            .out_dir(&production)
+            .mirror(&staging)
+            .build()
+            .unwrap()
+            .write_redirect()
+            .unwrap();
+
+        assert!(!outcome.is_created());
+        assert_eq!(outcome.short_name(), "install");
+        assert!(Path::new(&staging).join("install.html").exists());
+        let registry = Registry::load(&staging).unwrap();
+        assert_eq!(registry.target_for("install"), Some("/docs/install/"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_write_mirrored_out_of_sync() {
+        let dir = test_dir("test_write_mirrored_out_of_sync");
+        let staging = format!("{dir}/staging");
+        let production = format!("{dir}/production");
+
+        Redirector::builder()
+            .target("docs/install")
+            .out_dir(&staging)
+            .alias("setup")
+            .build()
+            .unwrap()
+            .write_redirect()
+            .unwrap();
+
+        let result = Redirector::builder()
+            .target("docs/install")
+            .out_dir(&production)
+            .alias("install")
+            .mirror(&staging)
+            .build()
+            .unwrap()
+            .write_redirect();
+
+        match result {
+            Err(RedirectorError::OutOfSync { path, short_name }) => {
+                assert_eq!(path, Path::new(&staging));
+                assert_eq!(short_name, "setup");
+            }
+            other => panic!("expected OutOfSync, got {other:?}"),
+        }
+        assert!(!Path::new(&production).join("install.html").exists());
+        assert!(Registry::load(&production).unwrap().is_empty());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,121 @@
+//! Exporting the registry as Next.js redirects.
+//!
+//! Hybrid sites built with Next.js declare server-side redirects in the `redirects()`
+//! function of `next.config.js`, as an array of `source`, `destination`, and
+//! `permanent` objects. This module provides `Registry::to_next_redirects()`, which
+//! renders the registry in that shape, so the configuration can load link-bridge's
+//! short links instead of repeating them.
+
+use crate::redirector::json::{object, Value};
+use crate::redirector::Registry;
+
+impl Registry {
+    /// Renders the registry as the JSON array returned by `redirects()` in
+    /// `next.config.js`.
+    ///
+    /// Each short name, including extra short names added with
+    /// [`Registry::add_alias()`], becomes a permanent redirect from its path below
+    /// `base_path` to its target. Retired redirects are left out, so Next.js serves
+    /// their "content gone" pages. Redirects are ordered by source.
+    ///
+    /// Load the file from the configuration:
+    ///
+    /// ```js
+    /// module.exports = {
+    ///   async redirects() {
+    ///     return require("./redirects.json");
+    ///   },
+    /// };
+    /// ```
+    ///
+    /// # Arguments
+    ///
+    /// * `base_path` - The path the output directory is served from, such as `"/s"`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::{Redirector, Registry};
+    /// use std::fs;
+    ///
+    /// Redirector::builder()
+    ///     .target("docs/install")
+    ///     .out_dir("doc_test_next_redirects")
+    ///     .alias("install")
+    ///     .build()
+    ///     .unwrap()
+    ///     .write_redirect()
+    ///     .unwrap();
+    ///
+    /// let registry = Registry::load("doc_test_next_redirects").unwrap();
+    /// let json = registry.to_next_redirects("/s");
+    /// assert!(json.contains(r#""source": "/s/install""#));
+    /// assert!(json.contains(r#""destination": "/docs/install/""#));
+    ///
+    /// fs::remove_dir_all("doc_test_next_redirects").ok();
+    /// ```
+    pub fn to_next_redirects<S: ToString>(&self, base_path: S) -> String {
+        let redirects = self
+            .redirect_sources(&base_path.to_string())
+            .into_iter()
+            .map(|(source, destination)| {
+                object([
+                    ("source", source.into()),
+                    ("destination", destination.into()),
+                    ("permanent", true.into()),
+                ])
+            })
+            .collect();
+        Value::Array(redirects).to_string_pretty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_to_next_redirects() {
+        let mut registry = Registry::empty(PathBuf::from("s"));
+        registry.insert("/docs/usage/".to_string(), "s/usage.html".to_string());
+        registry.insert(
+            "https://example.org/?a=1&b=2".to_string(),
+            "s/external.html".to_string(),
+        );
+        registry.insert_alias("/docs/usage/", "how-to");
+        registry.insert("/docs/old/".to_string(), "s/old.html".to_string());
+        registry.entry_mut("/docs/old/").unwrap().retire();
+
+        let redirects: serde_json::Value =
+            serde_json::from_str(&registry.to_next_redirects("/s/")).unwrap();
+        assert_eq!(
+            redirects,
+            serde_json::json!([
+                {
+                    "source": "/s/external",
+                    "destination": "https://example.org/?a=1&b=2",
+                    "permanent": true,
+                },
+                {
+                    "source": "/s/how-to",
+                    "destination": "/docs/usage/",
+                    "permanent": true,
+                },
+                {
+                    "source": "/s/usage",
+                    "destination": "/docs/usage/",
+                    "permanent": true,
+                },
+            ])
+        );
+
+        let redirects: serde_json::Value =
+            serde_json::from_str(&registry.to_next_redirects("")).unwrap();
+        assert_eq!(redirects[2]["source"], "/usage");
+        assert_eq!(
+            Registry::empty(PathBuf::from("s")).to_next_redirects("/s"),
+            "[]"
+        );
+    }
+}
@@ -0,0 +1,238 @@
+//! Finding redirect pages that the registry no longer knows about.
+//!
+//! Pages are left behind when a registry is edited by hand, restored from a backup,
+//! or swept with `Registry::expire()`, and a deployment keeps serving them as stale
+//! short links. This module provides `Registry::find_orphans()`, which reports such
+//! pages, and `Registry::clean_orphans()`, which deletes them.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::redirector::registry::remove_page;
+use crate::redirector::{trace, OutputLayout, RedirectorError, Registry};
+
+impl Registry {
+    /// Reports the HTML pages in an output directory that are not referenced by its
+    /// registry.
+    ///
+    /// Pages are looked for where either [`OutputLayout`] writes them: `<name>.html`
+    /// directly in the directory, and `<name>/index.html` one level below it. A page
+    /// is referenced if it belongs to a registered short name or alias. Nothing is
+    /// deleted.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The output directory (e.g. `"s"`)
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<PathBuf>)` - The unreferenced pages, in order of path
+    /// * `Err(RedirectorError::FailedToReadRegistry)` - If the registry cannot be read
+    /// * `Err(RedirectorError::DirectoryReadError)` - If the directory cannot be listed
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::{Redirector, Registry};
+    /// use std::fs;
+    /// use std::path::Path;
+    ///
+    /// Redirector::builder()
+    ///     .target("summer/sale")
+    ///     .out_dir("doc_test_registry_find_orphans")
+    ///     .alias("promo")
+    ///     .build()
+    ///     .unwrap()
+    ///     .write_redirect()
+    ///     .unwrap();
+    /// fs::write("doc_test_registry_find_orphans/stale.html", "<p>Old</p>").unwrap();
+    ///
+    /// let orphans = Registry::find_orphans("doc_test_registry_find_orphans").unwrap();
+    /// assert_eq!(orphans, [Path::new("doc_test_registry_find_orphans/stale.html")]);
+    ///
+    /// fs::remove_dir_all("doc_test_registry_find_orphans").ok();
+    /// ```
+    pub fn find_orphans<P: Into<PathBuf>>(dir: P) -> Result<Vec<PathBuf>, RedirectorError> {
+        let registry = Registry::load(dir)?;
+        registry.orphans()
+    }
+
+    /// Deletes the HTML pages in an output directory that are not referenced by its
+    /// registry.
+    ///
+    /// The pages are those reported by [`Registry::find_orphans()`]. A page written
+    /// as a directory's index also has its directory removed once it is empty.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The output directory (e.g. `"s"`)
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<PathBuf>)` - The deleted pages, in order of path
+    /// * `Err(RedirectorError::FailedToReadRegistry)` - If the registry cannot be read
+    /// * `Err(RedirectorError::DirectoryReadError)` - If the directory cannot be listed
+    /// * `Err(RedirectorError::FileRemovalError)` - If a page cannot be deleted; the
+    ///   pages before it have been deleted
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::{Redirector, Registry};
+    /// use std::fs;
+    /// use std::path::Path;
+    ///
+    /// let outcome = Redirector::builder()
+    ///     .target("summer/sale")
+    ///     .out_dir("doc_test_registry_clean_orphans")
+    ///     .build()
+    ///     .unwrap()
+    ///     .write_redirect()
+    ///     .unwrap();
+    /// fs::write("doc_test_registry_clean_orphans/stale.html", "<p>Old</p>").unwrap();
+    ///
+    /// Registry::clean_orphans("doc_test_registry_clean_orphans").unwrap();
+    /// assert!(!Path::new("doc_test_registry_clean_orphans/stale.html").exists());
+    /// assert!(outcome.file_path().exists());
+    ///
+    /// fs::remove_dir_all("doc_test_registry_clean_orphans").ok();
+    /// ```
+    pub fn clean_orphans<P: Into<PathBuf>>(dir: P) -> Result<Vec<PathBuf>, RedirectorError> {
+        let orphans = Registry::find_orphans(dir)?;
+        for file_path in &orphans {
+            let layout = if file_path.ends_with("index.html") {
+                OutputLayout::Directory
+            } else {
+                OutputLayout::File
+            };
+            remove_page(file_path, layout)?;
+            trace::debug!(file = %file_path.display(), "removed orphaned page");
+        }
+        Ok(orphans)
+    }
+
+    /// Lists the pages in the output directory that no short name or alias refers to.
+    pub(crate) fn orphans(&self) -> Result<Vec<PathBuf>, RedirectorError> {
+        let dir = self.dir();
+        let referenced: BTreeSet<PathBuf> = self
+            .short_names()
+            .filter_map(|(short_name, target)| {
+                let layout = OutputLayout::of(self.entry(target)?.short_file());
+                Some(layout.file_path(dir, short_name))
+            })
+            .collect();
+
+        let mut orphans = Vec::new();
+        for path in list_dir(dir)? {
+            let page = if path.is_dir() {
+                let index = path.join("index.html");
+                if !index.is_file() {
+                    continue;
+                }
+                index
+            } else if path.extension().is_some_and(|ext| ext == "html") {
+                path
+            } else {
+                continue;
+            };
+            if !referenced.contains(&page) {
+                orphans.push(page);
+            }
+        }
+        orphans.sort();
+
+        Ok(orphans)
+    }
+}
+
+/// Lists the entries of a directory; a directory that does not exist is empty.
+fn list_dir(dir: &Path) -> Result<Vec<PathBuf>, RedirectorError> {
+    let read_error = |source| RedirectorError::DirectoryReadError {
+        path: dir.to_path_buf(),
+        source,
+    };
+
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(read_error(e)),
+    };
+    entries
+        .map(|entry| entry.map(|entry| entry.path()).map_err(read_error))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Redirector;
+    use chrono::Utc;
+
+    fn test_dir(name: &str) -> String {
+        format!("{name}_{}", Utc::now().timestamp_nanos_opt().unwrap_or(0))
+    }
+
+    #[test]
+    fn test_find_and_clean_orphans() {
+        let dir = test_dir("test_orphans");
+        Redirector::builder()
+            .target("summer/sale")
+            .out_dir(&dir)
+            .alias("promo")
+            .build()
+            .unwrap()
+            .write_redirect()
+            .unwrap();
+        Redirector::builder()
+            .target("docs/install")
+            .out_dir(&dir)
+            .alias("install")
+            .layout(OutputLayout::Directory)
+            .build()
+            .unwrap()
+            .write_redirect()
+            .unwrap();
+        let mut registry = Registry::load(&dir).unwrap();
+        registry.add_alias("/summer/sale/", "sale").unwrap();
+        registry.save().unwrap();
+
+        let dir = PathBuf::from(dir);
+        fs::write(dir.join("stale.html"), "stale").unwrap();
+        fs::create_dir_all(dir.join("old")).unwrap();
+        fs::write(dir.join("old/index.html"), "stale").unwrap();
+        fs::write(dir.join("notes.txt"), "kept").unwrap();
+        fs::create_dir_all(dir.join("assets")).unwrap();
+
+        let orphans = Registry::find_orphans(&dir).unwrap();
+        assert_eq!(
+            orphans,
+            [dir.join("old/index.html"), dir.join("stale.html")]
+        );
+        // Reporting deletes nothing
+        assert!(dir.join("stale.html").exists());
+
+        assert_eq!(Registry::clean_orphans(&dir).unwrap(), orphans);
+        assert!(!dir.join("stale.html").exists());
+        assert!(!dir.join("old").exists());
+        for kept in [
+            "promo.html",
+            "sale.html",
+            "install/index.html",
+            "notes.txt",
+            "assets",
+        ] {
+            assert!(dir.join(kept).exists(), "{kept} was removed");
+        }
+        assert!(Registry::find_orphans(&dir).unwrap().is_empty());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_find_orphans_missing_dir() {
+        let dir = test_dir("test_orphans_missing");
+        assert!(Registry::find_orphans(&dir).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,236 @@
+//! The result of writing a redirect.
+//!
+//! This module provides the `RedirectOutcome` type returned by
+//! `Redirector::write_redirect()` and `Redirector::write_all()`, which describes the
+//! redirect file and whether it was created by the call or reused from the registry.
+
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "serde")]
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use crate::redirector::registry;
+
+/// Describes a redirect written by [`Redirector::write_redirect()`].
+///
+/// A redirect is either newly created, in which case its HTML file was written by the
+/// call, or it already existed in the registry, in which case the existing file was
+/// reused and nothing was written.
+///
+/// [`Redirector::write_redirect()`]: crate::Redirector::write_redirect
+///
+/// # Examples
+///
+/// ```rust
+/// use link_bridge::Redirector;
+/// use std::fs;
+///
+/// let redirector = Redirector::builder()
+///     .target("api/v1/users")
+///     .out_dir("doc_test_outcome")
+///     .alias("users")
+///     .build()
+///     .unwrap();
+///
+/// let outcome = redirector.write_redirect().unwrap();
+/// assert!(outcome.is_created());
+/// assert_eq!(outcome.short_name(), "users");
+/// assert_eq!(outcome.target(), "/api/v1/users/");
+/// assert!(outcome.file_path().exists());
+///
+/// let outcome = redirector.write_redirect().unwrap();
+/// assert!(!outcome.is_created());
+///
+/// fs::remove_dir_all("doc_test_outcome").ok();
+/// ```
+///
+/// # Serialization
+///
+/// With the `serde` feature enabled, `RedirectOutcome` implements `Serialize` as an
+/// object with `short_name`, `short_file`, `target`, `file`, and `created` fields, so
+/// scripts can read the result of a write without parsing text. The file path is
+/// written with `/` separators, as in the registry.
+///
+/// ```rust
+/// # #[cfg(feature = "serde")]
+/// # {
+/// use link_bridge::Redirector;
+/// use std::fs;
+///
+/// let outcome = Redirector::builder()
+///     .target("api/v1/users")
+///     .out_dir("doc_test_outcome_json")
+///     .alias("users")
+///     .build()
+///     .unwrap()
+///     .write_redirect()
+///     .unwrap();
+///
+/// assert_eq!(
+///     serde_json::to_string(&outcome).unwrap(),
+///     r#"{"short_name":"users","short_file":"users.html","target":"/api/v1/users/","file":"doc_test_outcome_json/users.html","created":true}"#
+/// );
+///
+/// fs::remove_dir_all("doc_test_outcome_json").ok();
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RedirectOutcome {
+    /// The path of the redirect HTML file.
+    file_path: PathBuf,
+    /// The URL of the redirect relative to the output directory.
+    short_file: String,
+    /// The target the redirect points at, as recorded in the registry.
+    target: String,
+    /// Whether the redirect file was written by this call.
+    created: bool,
+}
+
+impl RedirectOutcome {
+    /// Creates an outcome for a redirect file, its URL relative to the output
+    /// directory, and its target.
+    pub(crate) fn new<P: Into<PathBuf>>(
+        file_path: P,
+        short_file: String,
+        target: String,
+        created: bool,
+    ) -> Self {
+        RedirectOutcome {
+            file_path: file_path.into(),
+            short_file,
+            target,
+            created,
+        }
+    }
+
+    /// Returns the path of the redirect HTML file.
+    pub fn file_path(&self) -> &Path {
+        &self.file_path
+    }
+
+    /// Returns the URL of the redirect relative to the output directory.
+    ///
+    /// This is the file name (e.g. `"promo.html"`), or the directory (e.g.
+    /// `"promo/"`) for redirects written with
+    /// [`OutputLayout::Directory`](crate::OutputLayout::Directory).
+    pub fn short_file(&self) -> &str {
+        &self.short_file
+    }
+
+    /// Returns the short name of the redirect, which is its URL without the `.html`
+    /// extension or trailing slash.
+    pub fn short_name(&self) -> &str {
+        registry::short_name(&self.short_file)
+    }
+
+    /// Returns the full short URL of the redirect, ready to share.
+    ///
+    /// The redirect's URL relative to the output directory is appended to `base`, so
+    /// callers do not have to turn [`RedirectOutcome::file_path()`] into a URL
+    /// themselves. A trailing slash on `base` is ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `base` - The URL the output directory is served from, such as
+    ///   `"https://example.com/s"`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::Redirector;
+    /// use std::fs;
+    ///
+    /// let outcome = Redirector::builder()
+    ///     .target("api/v1/users")
+    ///     .out_dir("doc_test_outcome_short_url")
+    ///     .alias("users")
+    ///     .build()
+    ///     .unwrap()
+    ///     .write_redirect()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(
+    ///     outcome.short_url("https://example.com/s"),
+    ///     "https://example.com/s/users.html"
+    /// );
+    ///
+    /// fs::remove_dir_all("doc_test_outcome_short_url").ok();
+    /// ```
+    pub fn short_url(&self, base: &str) -> String {
+        format!("{}/{}", base.trim_end_matches('/'), self.short_file)
+    }
+
+    /// Returns the target the redirect points at, as recorded in the registry.
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    /// Reports whether the redirect file was newly created.
+    ///
+    /// Returns `false` when a redirect for the target was already registered and the
+    /// existing file was reused.
+    pub fn is_created(&self) -> bool {
+        self.created
+    }
+
+    /// Consumes the outcome and returns the path of the redirect HTML file.
+    pub fn into_file_path(self) -> PathBuf {
+        self.file_path
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for RedirectOutcome {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut outcome = serializer.serialize_struct("RedirectOutcome", 5)?;
+        outcome.serialize_field("short_name", self.short_name())?;
+        outcome.serialize_field("short_file", &self.short_file)?;
+        outcome.serialize_field("target", &self.target)?;
+        let file = self.file_path.to_string_lossy();
+        if cfg!(windows) {
+            outcome.serialize_field("file", &file.replace('\\', "/"))?;
+        } else {
+            outcome.serialize_field("file", &file)?;
+        }
+        outcome.serialize_field("created", &self.created)?;
+        outcome.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_outcome_accessors() {
+        let outcome = RedirectOutcome::new(
+            "s/promo.html",
+            "promo.html".to_string(),
+            "/a/b/".to_string(),
+            true,
+        );
+
+        assert_eq!(outcome.file_path(), Path::new("s/promo.html"));
+        assert_eq!(outcome.short_file(), "promo.html");
+        assert_eq!(outcome.short_name(), "promo");
+        assert_eq!(outcome.target(), "/a/b/");
+        assert!(outcome.is_created());
+        assert_eq!(outcome.into_file_path(), PathBuf::from("s/promo.html"));
+    }
+
+    #[test]
+    fn test_outcome_existing() {
+        let outcome = RedirectOutcome::new(
+            "s/abc/index.html",
+            "abc/".to_string(),
+            "/a/b/".to_string(),
+            false,
+        );
+        assert!(!outcome.is_created());
+        assert_eq!(outcome.short_name(), "abc");
+        assert_eq!(
+            outcome.short_url("https://example.com/s/"),
+            "https://example.com/s/abc/"
+        );
+    }
+}
@@ -0,0 +1,442 @@
+//! Parallel batch generation with `rayon`.
+//!
+//! Large migrations write tens of thousands of redirect pages, and writing them one
+//! after another leaves most cores idle. This module provides
+//! `Redirector::write_all_parallel()`, which records every redirect in its registry on
+//! the calling thread and then renders and writes the pages on the `rayon` thread pool.
+
+use std::borrow::Cow;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rayon::prelude::*;
+
+use crate::redirector::sidecar::{metadata_path, sidecar_path};
+use crate::redirector::sink::{FsSink, OutputSink};
+#[cfg(feature = "social-preview")]
+use crate::redirector::social::image_path;
+use crate::redirector::{trace, RedirectOutcome, Redirector, RedirectorError, Registry, WritePlan};
+
+impl Redirector {
+    /// Writes several redirects in parallel, loading and persisting each registry only once.
+    ///
+    /// Behaves exactly like [`Redirector::write_all()`], including its all-or-nothing
+    /// guarantee and its outcomes, but writes the redirect pages on the `rayon` global
+    /// thread pool. Registry updates are not shared between threads: every redirect is
+    /// first recorded in its registry on the calling thread, in input order, so short
+    /// names and reused entries are the same as with `write_all()`. Only then are the
+    /// new pages rendered and written in parallel, and each changed registry is
+    /// persisted once all of them have been written. Requires the `rayon` feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `redirectors` - The redirectors to write
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<RedirectOutcome>)` - The outcome for each redirector, in input order
+    /// * `Err(RedirectorError)` - The first error encountered, in input order; nothing
+    ///   is written
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::Redirector;
+    /// use std::fs;
+    ///
+    /// let redirectors: Vec<Redirector> = (0..100)
+    ///     .map(|i| {
+    ///         Redirector::builder()
+    ///             .target(format!("docs/page-{i}"))
+    ///             .out_dir("doc_test_write_all_parallel")
+    ///             .alias(format!("page-{i}"))
+    ///             .build()
+    ///             .unwrap()
+    ///     })
+    ///     .collect();
+    ///
+    /// let outcomes = Redirector::write_all_parallel(&redirectors).unwrap();
+    /// assert_eq!(outcomes.len(), 100);
+    /// assert!(outcomes.iter().all(|outcome| outcome.is_created()));
+    ///
+    /// fs::remove_dir_all("doc_test_write_all_parallel").ok();
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(count = redirectors.len()))
+    )]
+    pub fn write_all_parallel(
+        redirectors: &[Redirector],
+    ) -> Result<Vec<RedirectOutcome>, RedirectorError> {
+        let result = write_all_parallel(redirectors);
+        Redirector::notify_batch(redirectors, &result);
+        result
+    }
+}
+
+/// Records every redirect, writes the new pages in parallel, and saves the registries.
+fn write_all_parallel(redirectors: &[Redirector]) -> Result<Vec<RedirectOutcome>, RedirectorError> {
+    let mut registries: HashMap<PathBuf, Registry> = HashMap::new();
+    let mut changed: Vec<PathBuf> = Vec::new();
+    let mut outcomes = Vec::with_capacity(redirectors.len());
+    // The redirectors whose page must be written, with the page's path and, for a page
+    // other than the redirect itself, its content
+    let mut pages: Vec<(Cow<'_, Redirector>, PathBuf, Option<String>)> = Vec::new();
+    let mut planned: HashSet<PathBuf> = HashSet::new();
+    // The JSON metadata of new pages, rendered from their entries once recorded
+    let mut metadata: HashMap<PathBuf, (PathBuf, String)> = HashMap::new();
+
+    for redirector in redirectors {
+        let registry = match registries.entry(redirector.path.clone()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(Registry::load(&redirector.path)?),
+        };
+
+        let target = match redirector.plan_write(registry)? {
+            WritePlan::Additional(target, additional) => {
+                planned.insert(additional.file_path.clone());
+                pages.push((
+                    Cow::Borrowed(redirector),
+                    additional.file_path.clone(),
+                    Some(additional.page.clone()),
+                ));
+                outcomes.push(additional.record(registry, target));
+                if !changed.contains(&redirector.path) {
+                    changed.push(redirector.path.clone());
+                }
+                continue;
+            }
+            WritePlan::Existing(target, entry) => {
+                let existing_path = entry.path().to_path_buf();
+                // A registered page that has gone missing is written again
+                if !existing_path.exists() && planned.insert(existing_path.clone()) {
+                    trace::warn!(
+                        target = %target,
+                        file = %existing_path.display(),
+                        "regenerating missing redirect file"
+                    );
+                    pages.push((
+                        Cow::Borrowed(redirector),
+                        existing_path.clone(),
+                        Some(redirector.registered_page(&entry)),
+                    ));
+                    if !changed.contains(&redirector.path) {
+                        changed.push(redirector.path.clone());
+                    }
+                }
+                outcomes.push(RedirectOutcome::new(
+                    existing_path,
+                    entry.short_file().to_string(),
+                    target,
+                    false,
+                ));
+                continue;
+            }
+            WritePlan::New(target) => target,
+        };
+        let renamed = redirector
+            .resolve_existing(registry, |path| path.exists() || planned.contains(path))?;
+
+        let file_path = renamed.page_path(&redirector.path);
+        registry.record(
+            target.clone(),
+            file_path.to_string_lossy().to_string(),
+            &renamed,
+        );
+        if let Some(json) = renamed.metadata(&file_path, registry, &target) {
+            metadata.insert(file_path.clone(), json);
+        }
+        outcomes.push(RedirectOutcome::new(
+            &file_path,
+            renamed.short_file(),
+            target,
+            true,
+        ));
+        planned.insert(file_path.clone());
+        pages.push((renamed, file_path, None));
+        if !changed.contains(&redirector.path) {
+            changed.push(redirector.path.clone());
+        }
+    }
+
+    // Pages written as a directory's index need their own directory
+    let dirs: HashSet<&Path> = changed
+        .iter()
+        .map(PathBuf::as_path)
+        .chain(
+            pages
+                .iter()
+                .filter_map(|(_, file_path, _)| file_path.parent()),
+        )
+        .collect();
+    for dir in dirs {
+        FsSink
+            .create_dir_all(dir)
+            .map_err(|source| RedirectorError::DirectoryCreationError {
+                path: dir.to_path_buf(),
+                source,
+            })?;
+    }
+
+    trace::debug!(pages = pages.len(), "writing redirect files in parallel");
+    let written: Vec<Result<&PathBuf, RedirectorError>> = pages
+        .par_iter()
+        .map(|(redirector, file_path, page)| {
+            // Only new redirect pages get a preview image and sidecars and are
+            // verified, not regenerated pages
+            #[cfg(feature = "social-preview")]
+            let image = match page {
+                Some(_) => None,
+                None => redirector.preview_image(file_path),
+            };
+            let text = match page {
+                Some(_) => None,
+                None => redirector.sidecar(file_path),
+            };
+            let new_page = page.is_none();
+            let page = match page {
+                Some(page) => page.clone(),
+                None => redirector.page(),
+            };
+            let written = if redirector.overwrites() {
+                FsSink.write_file(file_path, page.as_bytes())
+            } else {
+                FsSink.create_file(file_path, page.as_bytes())
+            };
+            written.map_err(|source| RedirectorError::FileWriteError {
+                path: file_path.clone(),
+                source,
+            })?;
+            if new_page {
+                if let Err(e) = redirector.verify_written(&mut FsSink, file_path) {
+                    fs::remove_file(file_path).ok();
+                    return Err(e);
+                }
+            }
+            #[cfg(feature = "social-preview")]
+            if let Some((image_path, image)) = image {
+                FsSink
+                    .write_file(&image_path, image.as_bytes())
+                    .map_err(|source| RedirectorError::FileWriteError {
+                        path: image_path,
+                        source,
+                    })?;
+            }
+            if let Some((text_path, text)) = text {
+                FsSink
+                    .write_file(&text_path, text.as_bytes())
+                    .map_err(|source| RedirectorError::FileWriteError {
+                        path: text_path,
+                        source,
+                    })?;
+            }
+            if let Some((json_path, json)) = metadata.get(file_path.as_path()) {
+                FsSink
+                    .write_file(json_path, json.as_bytes())
+                    .map_err(|source| RedirectorError::FileWriteError {
+                        path: json_path.clone(),
+                        source,
+                    })?;
+            }
+            Ok(file_path)
+        })
+        .collect();
+
+    let mut written_files = Vec::new();
+    let mut first_error = None;
+    for result in written {
+        match result {
+            Ok(file_path) => written_files.push(file_path),
+            Err(e) if first_error.is_none() => first_error = Some(e),
+            Err(_) => {}
+        }
+    }
+
+    let saved = match first_error {
+        Some(e) => Err(e),
+        None => Registry::save_all(changed.iter().map(|dir| &registries[dir])),
+    };
+    if let Err(e) = saved {
+        trace::warn!(
+            files = written_files.len(),
+            "parallel write failed, removing written files"
+        );
+        for file in written_files {
+            fs::remove_file(file).ok();
+            #[cfg(feature = "social-preview")]
+            fs::remove_file(image_path(file)).ok();
+            fs::remove_file(sidecar_path(file)).ok();
+            fs::remove_file(metadata_path(file)).ok();
+        }
+        return Err(e);
+    }
+
+    Ok(outcomes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DuplicatePolicy, OutputLayout};
+    use chrono::Utc;
+
+    fn test_dir(name: &str) -> String {
+        format!("{name}_{}", Utc::now().timestamp_nanos_opt().unwrap_or(0))
+    }
+
+    fn redirector(target: &str, alias: &str, dir: &str) -> Redirector {
+        Redirector::builder()
+            .target(target)
+            .out_dir(dir)
+            .alias(alias)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_write_all_parallel() {
+        let dir = test_dir("test_write_all_parallel");
+        let other_dir = format!("{dir}/other");
+        redirector("existing", "existing", &dir)
+            .write_redirect()
+            .unwrap();
+
+        let mut redirectors: Vec<Redirector> = (0..50)
+            .map(|i| redirector(&format!("page/{i}"), &format!("page-{i}"), &dir))
+            .collect();
+        redirectors.push(redirector("existing", "again", &dir));
+        redirectors.push(redirector("page/7", "duplicate", &dir));
+        redirectors.push(redirector("page/7", "elsewhere", &other_dir));
+
+        let outcomes = Redirector::write_all_parallel(&redirectors).unwrap();
+
+        assert_eq!(outcomes.len(), 53);
+        for (i, outcome) in outcomes[..50].iter().enumerate() {
+            assert!(outcome.is_created());
+            assert_eq!(outcome.short_name(), format!("page-{i}"));
+            let page = fs::read_to_string(outcome.file_path()).unwrap();
+            assert!(page.contains(&format!("url=/page/{i}/")));
+        }
+        assert!(!outcomes[50].is_created());
+        assert_eq!(outcomes[50].short_name(), "existing");
+        assert!(!outcomes[51].is_created());
+        assert_eq!(outcomes[51].short_name(), "page-7");
+        assert!(outcomes[52].is_created());
+
+        assert_eq!(Registry::load(&dir).unwrap().len(), 51);
+        assert_eq!(Registry::load(&other_dir).unwrap().len(), 1);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_write_all_parallel_rolls_back_on_error() {
+        let dir = test_dir("test_write_all_parallel_rolls_back_on_error");
+        // A directory where a page should go makes that page's write fail
+        fs::create_dir_all(format!("{dir}/blocked.html")).unwrap();
+
+        let redirectors = [
+            redirector("a", "a", &dir),
+            redirector("b", "blocked", &dir),
+            redirector("c", "c", &dir),
+        ];
+
+        match Redirector::write_all_parallel(&redirectors) {
+            Err(RedirectorError::FileWriteError { path, .. }) => {
+                assert_eq!(path, PathBuf::from(&dir).join("blocked.html"));
+            }
+            other => panic!("expected FileWriteError, got {other:?}"),
+        }
+        assert!(!PathBuf::from(&dir).join("a.html").exists());
+        assert!(!PathBuf::from(&dir).join("c.html").exists());
+        assert!(!PathBuf::from(&dir).join("registry.json").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_write_all_parallel_regenerates_missing_file() {
+        let dir = test_dir("test_write_all_parallel_regenerates_missing_file");
+        let outcome = redirector("gone", "gone", &dir).write_redirect().unwrap();
+        fs::remove_file(outcome.file_path()).unwrap();
+
+        let redirectors = [
+            redirector("gone", "other", &dir),
+            redirector("gone", "x", &dir),
+        ];
+        let outcomes = Redirector::write_all_parallel(&redirectors).unwrap();
+
+        assert!(outcomes.iter().all(|outcome| !outcome.is_created()));
+        assert_eq!(outcomes[0].file_path(), outcome.file_path());
+        assert!(outcome.file_path().exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_write_all_parallel_directory_layout() {
+        let dir = test_dir("test_write_all_parallel_directory_layout");
+        let redirectors: Vec<Redirector> = (0..3)
+            .map(|i| {
+                Redirector::builder()
+                    .target(format!("page/{i}"))
+                    .out_dir(&dir)
+                    .alias(format!("page-{i}"))
+                    .layout(OutputLayout::Directory)
+                    .build()
+                    .unwrap()
+            })
+            .collect();
+
+        let outcomes = Redirector::write_all_parallel(&redirectors).unwrap();
+
+        for (i, outcome) in outcomes.iter().enumerate() {
+            assert_eq!(outcome.short_file(), format!("page-{i}/"));
+            assert!(PathBuf::from(&dir)
+                .join(format!("page-{i}"))
+                .join("index.html")
+                .exists());
+        }
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_write_all_parallel_additional() {
+        let dir = test_dir("test_write_all_parallel_additional");
+        let redirectors: Vec<Redirector> = ["flyer-a", "flyer-b", "flyer-c"]
+            .into_iter()
+            .map(|alias| {
+                Redirector::builder()
+                    .target("summer/sale")
+                    .out_dir(&dir)
+                    .alias(alias)
+                    .on_duplicate(DuplicatePolicy::CreateAdditional)
+                    .build()
+                    .unwrap()
+            })
+            .collect();
+
+        let outcomes = Redirector::write_all_parallel(&redirectors).unwrap();
+
+        assert!(outcomes.iter().all(RedirectOutcome::is_created));
+        assert!(fs::read_to_string(outcomes[2].file_path())
+            .unwrap()
+            .contains("url=flyer-a.html"));
+        let registry = Registry::load(&dir).unwrap();
+        assert_eq!(registry.len(), 1);
+        assert_eq!(
+            registry.entry("/summer/sale/").unwrap().aliases(),
+            ["flyer-b", "flyer-c"]
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_write_all_parallel_empty() {
+        assert!(Redirector::write_all_parallel(&[]).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,641 @@
+//! Configurable validation rules for URL paths.
+//!
+//! This module provides the `PathPolicy` type, which decides which path segments are
+//! acceptable when a `UrlPath` is created. Sites can choose the standard rules, a
+//! strict set of characters, or supply their own validator closure without changing
+//! the path normalization logic. The policy also controls how trailing slashes are
+//! normalized, leaving targets that name a file (such as `report.pdf`) without one,
+//! limits the length and depth of accepted paths, and decides whether targets differing
+//! only in letter case are deduplicated.
+
+use std::fmt;
+use std::sync::Arc;
+
+use crate::redirector::url_path::UrlPathError;
+
+/// The rule applied to each segment of a URL path.
+#[derive(Clone, Default)]
+enum SegmentRule {
+    /// Any characters except `;`, `#`, and `?`.
+    #[default]
+    Standard,
+    /// ASCII letters, digits, dashes, and underscores only.
+    Strict,
+    /// A caller-supplied validator.
+    Custom(Arc<dyn Fn(&str) -> bool + Send + Sync>),
+}
+
+/// How a trailing slash on a URL path is normalized.
+///
+/// A leading slash is always added; this setting only affects the end of the path.
+/// Paths whose final segment names a file, such as `download/report.pdf`, never have
+/// a slash appended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum TrailingSlash {
+    /// Always end the path with a slash (`api/v1` becomes `/api/v1/`).
+    #[default]
+    Append,
+    /// Never end the path with a slash (`api/v1/` becomes `/api/v1`).
+    Strip,
+    /// Keep the trailing slash exactly as provided.
+    Preserve,
+}
+
+/// Whether targets differing only in letter case are the same redirect.
+///
+/// Many web servers, such as IIS or any server on a case-insensitive filesystem, serve
+/// `/Docs/Install/` and `/docs/install/` as the same page. With
+/// [`TargetCase::Insensitive`], writing a redirect for a target already registered in
+/// another letter case reuses the existing short link instead of creating a second one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum TargetCase {
+    /// Targets differing in letter case are separate redirects.
+    #[default]
+    Sensitive,
+    /// Targets differing only in letter case share one redirect.
+    Insensitive,
+}
+
+/// The default maximum length of a URL path, in bytes.
+pub const DEFAULT_MAX_LENGTH: usize = 2048;
+
+/// The default maximum number of segments in a URL path.
+pub const DEFAULT_MAX_DEPTH: usize = 32;
+
+/// Controls which URL paths are accepted as redirect targets and how they are normalized.
+///
+/// A path is split into segments on forward slashes; empty segments (such as those
+/// produced by `//` or a path consisting only of `/`) are always rejected. Each
+/// remaining segment is then checked against the policy's rule:
+///
+/// - [`PathPolicy::default()`] - any characters except `;`, `#`, and `?` (the rules
+///   used by [`Redirector::new()`](crate::Redirector::new))
+/// - [`PathPolicy::strict()`] - ASCII letters, digits, dashes, and underscores only,
+///   plus a file extension on the final segment (e.g. `report.pdf`)
+/// - [`PathPolicy::custom()`] - a caller-supplied validator closure
+///
+/// # Examples
+///
+/// ```rust
+/// use link_bridge::{PathPolicy, Redirector};
+///
+/// let strict = PathPolicy::strict();
+/// assert!(Redirector::with_policy("api/v1", &strict).is_ok());
+/// assert!(Redirector::with_policy("café/menu", &strict).is_err());
+///
+/// // Only allow lowercase segments
+/// let lowercase = PathPolicy::custom(|segment| !segment.chars().any(char::is_uppercase));
+/// assert!(Redirector::with_policy("docs/install", &lowercase).is_ok());
+/// assert!(Redirector::with_policy("Docs/Install", &lowercase).is_err());
+/// ```
+///
+/// The trailing slash handling is set with [`PathPolicy::trailing_slash()`]:
+///
+/// ```rust
+/// use link_bridge::{PathPolicy, Redirector, TrailingSlash};
+///
+/// let policy = PathPolicy::default().trailing_slash(TrailingSlash::Preserve);
+/// let redirector = Redirector::with_policy("download/report.pdf", &policy).unwrap();
+/// assert!(redirector.to_string().contains("url=/download/report.pdf\""));
+/// ```
+///
+/// Paths longer than [`DEFAULT_MAX_LENGTH`] bytes or deeper than [`DEFAULT_MAX_DEPTH`]
+/// segments are rejected so pathological inputs cannot bloat generated pages and
+/// registry keys. The limits can be adjusted:
+///
+/// ```rust
+/// use link_bridge::{PathPolicy, Redirector};
+///
+/// let policy = PathPolicy::default().max_length(16).max_depth(2);
+///
+/// assert!(Redirector::with_policy("docs/install", &policy).is_ok());
+/// assert!(Redirector::with_policy("docs/install/linux", &policy).is_err());
+/// assert!(Redirector::with_policy("documentation/install", &policy).is_err());
+/// ```
+///
+/// Targets are deduplicated case-sensitively unless set otherwise with
+/// [`PathPolicy::target_case()`]:
+///
+/// ```rust
+/// use link_bridge::{PathPolicy, Redirector, TargetCase};
+/// use std::fs;
+///
+/// let policy = PathPolicy::default().target_case(TargetCase::Insensitive);
+/// let write = |target| {
+///     Redirector::builder()
+///         .target(target)
+///         .out_dir("doc_test_target_case")
+///         .policy(policy.clone())
+///         .build()
+///         .unwrap()
+///         .write_redirect()
+///         .unwrap()
+/// };
+///
+/// let first = write("Docs/Install");
+/// let second = write("docs/install");
+/// assert!(!second.is_created());
+/// assert_eq!(first.file_path(), second.file_path());
+///
+/// fs::remove_dir_all("doc_test_target_case").ok();
+/// ```
+#[derive(Clone)]
+pub struct PathPolicy {
+    /// The rule each path segment must satisfy.
+    segment_rule: SegmentRule,
+    /// How the trailing slash is normalized.
+    trailing_slash: TrailingSlash,
+    /// The maximum length of a path, in bytes.
+    max_length: usize,
+    /// The maximum number of segments in a path.
+    max_depth: usize,
+    /// Whether targets differing only in letter case are the same redirect.
+    target_case: TargetCase,
+}
+
+impl Default for PathPolicy {
+    fn default() -> Self {
+        PathPolicy {
+            segment_rule: SegmentRule::default(),
+            trailing_slash: TrailingSlash::default(),
+            max_length: DEFAULT_MAX_LENGTH,
+            max_depth: DEFAULT_MAX_DEPTH,
+            target_case: TargetCase::default(),
+        }
+    }
+}
+
+impl PathPolicy {
+    /// Creates a policy accepting only ASCII letters, digits, dashes, and underscores.
+    ///
+    /// The final segment may additionally carry a file extension, so targets such as
+    /// `downloads/report.pdf` are accepted.
+    pub fn strict() -> Self {
+        PathPolicy {
+            segment_rule: SegmentRule::Strict,
+            ..Default::default()
+        }
+    }
+
+    /// Creates a policy that validates each segment with the supplied closure.
+    ///
+    /// The closure receives a single, non-empty path segment (without slashes) and
+    /// returns `true` if the segment is acceptable.
+    ///
+    /// # Arguments
+    ///
+    /// * `validator` - The closure used to validate each path segment
+    pub fn custom<F>(validator: F) -> Self
+    where
+        F: Fn(&str) -> bool + Send + Sync + 'static,
+    {
+        PathPolicy {
+            segment_rule: SegmentRule::Custom(Arc::new(validator)),
+            ..Default::default()
+        }
+    }
+
+    /// Sets how the trailing slash of accepted paths is normalized.
+    ///
+    /// The default, [`TrailingSlash::Append`], always ends paths with a slash. Use
+    /// [`TrailingSlash::Strip`] for sites configured without trailing slashes, or
+    /// [`TrailingSlash::Preserve`] to keep the path as it was provided.
+    ///
+    /// # Arguments
+    ///
+    /// * `trailing_slash` - The trailing slash normalization to apply
+    pub fn trailing_slash(mut self, trailing_slash: TrailingSlash) -> Self {
+        self.trailing_slash = trailing_slash;
+        self
+    }
+
+    /// Sets the maximum length of accepted paths, in bytes.
+    ///
+    /// The length is measured on the path as provided, before normalization.
+    /// Defaults to [`DEFAULT_MAX_LENGTH`].
+    ///
+    /// # Arguments
+    ///
+    /// * `max_length` - The maximum number of bytes permitted in a path
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.max_length = max_length;
+        self
+    }
+
+    /// Sets the maximum number of segments in accepted paths.
+    ///
+    /// For example, `api/v1/users` has a depth of three. Defaults to
+    /// [`DEFAULT_MAX_DEPTH`].
+    ///
+    /// # Arguments
+    ///
+    /// * `max_depth` - The maximum number of segments permitted in a path
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Sets whether targets differing only in letter case are the same redirect.
+    ///
+    /// The default, [`TargetCase::Sensitive`], treats `/Docs/Install/` and
+    /// `/docs/install/` as separate redirects. Use [`TargetCase::Insensitive`] for sites
+    /// served by a case-insensitive web server, so both share one short link. Targets
+    /// keep the letter case they were first registered with.
+    ///
+    /// # Arguments
+    ///
+    /// * `target_case` - How letter case is treated when deduplicating targets
+    pub fn target_case(mut self, target_case: TargetCase) -> Self {
+        self.target_case = target_case;
+        self
+    }
+
+    /// Returns how letter case is treated when deduplicating targets.
+    pub(crate) fn case(&self) -> TargetCase {
+        self.target_case
+    }
+
+    /// Checks a path against the policy's length and depth limits.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the path is within both limits
+    /// * `Err(UrlPathError::TooLong)` - If the path exceeds the maximum length
+    /// * `Err(UrlPathError::TooDeep)` - If the path exceeds the maximum depth
+    pub(crate) fn check_limits(&self, path: &str) -> Result<(), UrlPathError> {
+        if path.len() > self.max_length {
+            return Err(UrlPathError::TooLong {
+                length: path.len(),
+                max: self.max_length,
+            });
+        }
+
+        let trimmed = path.trim_matches('/');
+        let depth = if trimmed.is_empty() {
+            0
+        } else {
+            trimmed.split('/').count()
+        };
+        if depth > self.max_depth {
+            return Err(UrlPathError::TooDeep {
+                depth,
+                max: self.max_depth,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Reports whether a single path segment is acceptable under this policy.
+    ///
+    /// Whatever the rule, empty segments and segments containing a backslash or an
+    /// ASCII control character are refused: browsers read `/\evil.com` as the
+    /// protocol-relative `//evil.com`, which would lead off-site.
+    ///
+    /// # Arguments
+    ///
+    /// * `segment` - A path segment without slashes (e.g. `"v1"`)
+    pub fn is_valid_segment(&self, segment: &str) -> bool {
+        if segment.is_empty() || segment.contains(|c: char| c == '\\' || c.is_ascii_control()) {
+            return false;
+        }
+
+        match &self.segment_rule {
+            SegmentRule::Standard => !segment.contains([';', '#', '?']),
+            SegmentRule::Strict => segment
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'),
+            SegmentRule::Custom(validator) => validator(segment),
+        }
+    }
+
+    /// Reports whether a complete path is acceptable under this policy.
+    ///
+    /// A single leading and a single trailing slash are permitted; every segment in
+    /// between must be non-empty and satisfy the policy's segment rule. Under the strict
+    /// rule the final segment may also carry a file extension.
+    pub(crate) fn is_valid_path(&self, path: &str) -> bool {
+        let path = path.strip_prefix('/').unwrap_or(path);
+        let path = path.strip_suffix('/').unwrap_or(path);
+
+        let (parents, last) = match path.rsplit_once('/') {
+            Some((parents, last)) => (Some(parents), last),
+            None => (None, path),
+        };
+
+        let last_valid = match (&self.segment_rule, file_extension(last)) {
+            (SegmentRule::Strict, Some(extension)) => {
+                self.is_valid_segment(&last[..last.len() - extension.len() - 1])
+            }
+            _ => self.is_valid_segment(last),
+        };
+
+        last_valid
+            && parents.is_none_or(|parents| {
+                parents
+                    .split('/')
+                    .all(|segment| self.is_valid_segment(segment))
+            })
+    }
+
+    /// Normalizes a path that has already been validated by this policy.
+    ///
+    /// Ensures the path starts with a slash and applies the trailing slash setting.
+    pub(crate) fn normalize(&self, mut path: String) -> String {
+        if !path.starts_with('/') {
+            path.insert(0, '/');
+        }
+
+        let names_file = path.rsplit('/').next().and_then(file_extension).is_some();
+
+        match self.trailing_slash {
+            TrailingSlash::Append if !path.ends_with('/') && !names_file => path.push('/'),
+            TrailingSlash::Strip if path.ends_with('/') => {
+                path.pop();
+            }
+            _ => {}
+        }
+
+        path
+    }
+}
+
+/// File extensions of downloadable files, matched case-insensitively.
+///
+/// Only these mark a segment as a file. Treating every dotted segment as one would
+/// strip the trailing slash from directory-like paths such as `users/john.doe` or
+/// `sites/example.com`, so their registry keys would change between releases.
+const FILE_EXTENSIONS: [&str; 47] = [
+    "7z", "apk", "avi", "bz2", "csv", "deb", "dmg", "doc", "docx", "epub", "exe", "gif", "gz",
+    "htm", "html", "ico", "iso", "jpeg", "jpg", "json", "m4a", "mov", "mp3", "mp4", "msi", "odp",
+    "ods", "odt", "ogg", "pdf", "png", "ppt", "pptx", "rar", "rpm", "svg", "tar", "tgz", "txt",
+    "wav", "webm", "webp", "xls", "xlsx", "xml", "xz", "zip",
+];
+
+/// Returns the file extension of a path segment, if it names a file.
+///
+/// A segment names a file when it consists of a non-empty stem, a dot, and one of the
+/// known download extensions, such as `pdf`, `zip`, or `png` (e.g. `report.pdf` or
+/// `archive.tar.gz`). Segments such as `v1.0`, `john.doe`, or `node.js` are treated
+/// as directories.
+pub(crate) fn file_extension(segment: &str) -> Option<&str> {
+    let (stem, extension) = segment.rsplit_once('.')?;
+
+    let is_extension = FILE_EXTENSIONS
+        .iter()
+        .any(|known| known.eq_ignore_ascii_case(extension));
+
+    (!stem.is_empty() && is_extension).then_some(extension)
+}
+
+impl fmt::Debug for PathPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rule = match self.segment_rule {
+            SegmentRule::Standard => "Standard",
+            SegmentRule::Strict => "Strict",
+            SegmentRule::Custom(_) => "Custom",
+        };
+        f.debug_struct("PathPolicy")
+            .field("segment_rule", &rule)
+            .field("trailing_slash", &self.trailing_slash)
+            .field("max_length", &self.max_length)
+            .field("max_depth", &self.max_depth)
+            .field("target_case", &self.target_case)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_policy_matches_standard_rules() {
+        let policy = PathPolicy::default();
+
+        assert!(policy.is_valid_path("api/v1"));
+        assert!(policy.is_valid_path("/api/v1/"));
+        assert!(policy.is_valid_path("café/müsli"));
+        assert!(!policy.is_valid_path(""));
+        assert!(!policy.is_valid_path("/"));
+        assert!(!policy.is_valid_path("api//v1"));
+        assert!(!policy.is_valid_path("api?param=value"));
+        assert!(!policy.is_valid_path("api;session=123"));
+        assert!(!policy.is_valid_path("api#section"));
+    }
+
+    #[test]
+    fn test_backslashes_and_control_characters_refused_by_every_policy() {
+        for policy in [
+            PathPolicy::default(),
+            PathPolicy::strict(),
+            PathPolicy::custom(|_| true),
+        ] {
+            assert!(!policy.is_valid_path("\\\\host"));
+            assert!(!policy.is_valid_path("/\\host"));
+            assert!(!policy.is_valid_path("docs\\install"));
+            assert!(!policy.is_valid_path("docs/in\nstall"));
+            assert!(!policy.is_valid_path("docs/\tinstall"));
+            assert!(!policy.is_valid_path("docs/\u{7f}"));
+        }
+    }
+
+    #[test]
+    fn test_strict_policy() {
+        let policy = PathPolicy::strict();
+
+        assert!(policy.is_valid_path("api-v1/user_data/123"));
+        assert!(!policy.is_valid_path("café/müsli"));
+        assert!(!policy.is_valid_path("api/v1.0"));
+        assert!(!policy.is_valid_path("api v1"));
+    }
+
+    #[test]
+    fn test_custom_policy() {
+        let policy = PathPolicy::custom(|segment| segment.len() <= 3);
+
+        assert!(policy.is_valid_path("api/v1"));
+        assert!(!policy.is_valid_path("api/version"));
+    }
+
+    #[test]
+    fn test_custom_policy_never_sees_empty_segments() {
+        let policy = PathPolicy::custom(|_| true);
+
+        assert!(!policy.is_valid_path(""));
+        assert!(!policy.is_valid_path("/"));
+        assert!(!policy.is_valid_path("a//b"));
+    }
+
+    #[test]
+    fn test_is_valid_segment() {
+        let policy = PathPolicy::default();
+
+        assert!(policy.is_valid_segment("v1"));
+        assert!(!policy.is_valid_segment(""));
+        assert!(!policy.is_valid_segment("v1?x"));
+    }
+
+    #[test]
+    fn test_normalize_append() {
+        let policy = PathPolicy::default();
+
+        assert_eq!(policy.normalize("api/v1".to_string()), "/api/v1/");
+        assert_eq!(policy.normalize("/api/v1/".to_string()), "/api/v1/");
+    }
+
+    #[test]
+    fn test_normalize_strip() {
+        let policy = PathPolicy::default().trailing_slash(TrailingSlash::Strip);
+
+        assert_eq!(policy.normalize("api/v1/".to_string()), "/api/v1");
+        assert_eq!(policy.normalize("/api/v1".to_string()), "/api/v1");
+    }
+
+    #[test]
+    fn test_normalize_preserve() {
+        let policy = PathPolicy::default().trailing_slash(TrailingSlash::Preserve);
+
+        assert_eq!(policy.normalize("api/v1/".to_string()), "/api/v1/");
+        assert_eq!(
+            policy.normalize("download/report.pdf".to_string()),
+            "/download/report.pdf"
+        );
+    }
+
+    #[test]
+    fn test_trailing_slash_kept_by_strict_and_custom() {
+        let strict = PathPolicy::strict().trailing_slash(TrailingSlash::Strip);
+        assert_eq!(strict.normalize("api/".to_string()), "/api");
+        assert!(!strict.is_valid_path("api.v1/docs"));
+    }
+
+    #[test]
+    fn test_file_extension() {
+        assert_eq!(file_extension("report.pdf"), Some("pdf"));
+        assert_eq!(file_extension("archive.tar.gz"), Some("gz"));
+        assert_eq!(file_extension("image.PNG"), Some("PNG"));
+        assert_eq!(file_extension("mp3.mp3"), Some("mp3"));
+        assert_eq!(file_extension("v1.0"), None);
+        assert_eq!(file_extension(".htaccess"), None);
+        assert_eq!(file_extension("trailing."), None);
+        assert_eq!(file_extension("docs"), None);
+        assert_eq!(file_extension("file.notanextension"), None);
+        assert_eq!(file_extension("john.doe"), None);
+        assert_eq!(file_extension("node.js"), None);
+        assert_eq!(file_extension("example.com"), None);
+    }
+
+    #[test]
+    fn test_normalize_append_skips_file_targets() {
+        let policy = PathPolicy::default();
+
+        assert_eq!(
+            policy.normalize("download/report.pdf".to_string()),
+            "/download/report.pdf"
+        );
+        assert_eq!(
+            policy.normalize("assets/logo.png".to_string()),
+            "/assets/logo.png"
+        );
+        assert_eq!(
+            policy.normalize("release/v1.0".to_string()),
+            "/release/v1.0/"
+        );
+        assert_eq!(
+            policy.normalize("users/john.doe".to_string()),
+            "/users/john.doe/"
+        );
+        assert_eq!(
+            policy.normalize("sites/example.com".to_string()),
+            "/sites/example.com/"
+        );
+        assert_eq!(
+            policy.normalize("files.d/readme".to_string()),
+            "/files.d/readme/"
+        );
+    }
+
+    #[test]
+    fn test_strict_policy_allows_final_file_extension() {
+        let policy = PathPolicy::strict();
+
+        assert!(policy.is_valid_path("download/report.pdf"));
+        assert!(policy.is_valid_path("bundle.zip"));
+        assert!(policy.is_valid_path("assets/logo-small.png"));
+        assert!(!policy.is_valid_path("assets.d/logo.png"));
+        assert!(!policy.is_valid_path("download/re port.pdf"));
+        assert!(!policy.is_valid_path("download/archive.tar.gz"));
+    }
+
+    #[test]
+    fn test_check_limits_defaults() {
+        let policy = PathPolicy::default();
+
+        assert!(policy.check_limits("api/v1").is_ok());
+        assert!(policy.check_limits(&"a".repeat(DEFAULT_MAX_LENGTH)).is_ok());
+        assert!(matches!(
+            policy.check_limits(&"a".repeat(DEFAULT_MAX_LENGTH + 1)),
+            Err(UrlPathError::TooLong { .. })
+        ));
+        assert!(policy.check_limits(&"a/".repeat(DEFAULT_MAX_DEPTH)).is_ok());
+        assert!(matches!(
+            policy.check_limits(&"a/".repeat(DEFAULT_MAX_DEPTH + 1)),
+            Err(UrlPathError::TooDeep { .. })
+        ));
+    }
+
+    #[test]
+    fn test_check_limits_custom_length() {
+        let policy = PathPolicy::default().max_length(8);
+
+        assert!(policy.check_limits("/api/v1/").is_ok());
+        match policy.check_limits("/api/v10/") {
+            Err(UrlPathError::TooLong { length, max }) => {
+                assert_eq!(length, 9);
+                assert_eq!(max, 8);
+            }
+            other => panic!("expected TooLong, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_check_limits_custom_depth() {
+        let policy = PathPolicy::default().max_depth(2);
+
+        assert!(policy.check_limits("/api/v1/").is_ok());
+        match policy.check_limits("api/v1/users") {
+            Err(UrlPathError::TooDeep { depth, max }) => {
+                assert_eq!(depth, 3);
+                assert_eq!(max, 2);
+            }
+            other => panic!("expected TooDeep, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_policy_debug() {
+        assert!(format!("{:?}", PathPolicy::default()).contains("Standard"));
+        assert!(format!("{:?}", PathPolicy::strict()).contains("Strict"));
+        assert!(format!("{:?}", PathPolicy::custom(|_| true)).contains("Custom"));
+    }
+
+    #[test]
+    fn test_policy_clone_shares_validator() {
+        let policy = PathPolicy::custom(|segment| segment == "ok");
+        let cloned = policy.clone();
+
+        assert!(cloned.is_valid_segment("ok"));
+        assert!(!cloned.is_valid_segment("no"));
+    }
+}
@@ -0,0 +1,263 @@
+//! A local preview server for generated redirects.
+//!
+//! Before deploying, it helps to click through the generated pages and check where
+//! each short name leads. This module provides `Registry::serve()`, which starts a
+//! small HTTP server over the registry's output directory: it serves the generated
+//! files, answers bare short names with a redirect, and lists every redirect on its
+//! index page.
+
+use std::fmt::Write;
+use std::io;
+use std::path::{Component, Path as FsPath};
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::http::{header, StatusCode};
+use axum::response::{Html, IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use tokio::net::{TcpListener, ToSocketAddrs};
+
+use crate::redirector::bookmarks::escape;
+use crate::redirector::{Registry, RegistryEntry};
+
+impl Registry {
+    /// Serves the output directory over HTTP for local previews.
+    ///
+    /// The server answers:
+    ///
+    /// * `GET /` with an index page listing every short name and its target
+    /// * `GET /{file}` with a file from the output directory, such as a generated
+    ///   `install.html` page or `registry.json`
+    /// * `GET /{dir}/` with the `index.html` page of a redirect written with
+    ///   [`OutputLayout::Directory`](crate::OutputLayout::Directory)
+    /// * `GET /{short_name}` with a `302 Found` redirect to the registered target, or
+    ///   `410 Gone` once the redirect has been retired with [`Registry::retire()`]
+    ///
+    /// The server runs until the returned future is dropped or fails. It is meant for
+    /// checking redirects before deploying, not for production traffic; use
+    /// [`Registry::router()`] to serve redirects from an application. Requires the
+    /// `preview` feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `addr` - The address to listen on, such as `"127.0.0.1:8080"`
+    ///
+    /// # Returns
+    ///
+    /// * `Err(io::Error)` - If the address cannot be bound or the server fails
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use link_bridge::Registry;
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> std::io::Result<()> {
+    /// let registry = Registry::load("s").unwrap();
+    /// registry.serve("127.0.0.1:8080").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn serve<A: ToSocketAddrs>(self, addr: A) -> io::Result<()> {
+        let listener = TcpListener::bind(addr).await?;
+        axum::serve(listener, self.preview_router()).await
+    }
+
+    /// Builds the router behind [`Registry::serve()`].
+    fn preview_router(self) -> Router {
+        Router::new()
+            .route("/", get(index))
+            .route("/{name}", get(file_or_redirect))
+            .route("/{name}/", get(index_or_redirect))
+            .with_state(Arc::new(self))
+    }
+}
+
+/// Lists every redirect in the registry, ordered by short name.
+async fn index(State(registry): State<Arc<Registry>>) -> Html<String> {
+    let mut entries: Vec<(&str, &str)> = registry
+        .iter()
+        .map(|(short_name, target, _)| (short_name, target))
+        .collect();
+    entries.sort_unstable();
+
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head><meta charset=\"UTF-8\"><title>link-bridge preview</title></head>\n<body>\n<h1>{}</h1>\n<ul>\n",
+        escape(&registry.dir().display().to_string())
+    );
+    for (short_name, target) in entries {
+        let short_name = escape(short_name);
+        let target = escape(target);
+        let _ = writeln!(
+            html,
+            "<li><a href=\"/{short_name}\">{short_name}</a> &rarr; {target}</li>"
+        );
+    }
+    html.push_str("</ul>\n</body>\n</html>\n");
+
+    Html(html)
+}
+
+/// Serves a file from the output directory, or redirects a short name to its target.
+async fn file_or_redirect(
+    State(registry): State<Arc<Registry>>,
+    Path(name): Path<String>,
+) -> Response {
+    if is_file_name(&name) {
+        if let Ok(contents) = tokio::fs::read(registry.dir().join(&name)).await {
+            return ([(header::CONTENT_TYPE, content_type(&name))], contents).into_response();
+        }
+    }
+
+    redirect(&registry, &name)
+}
+
+/// Serves a directory's `index.html` page, or redirects a short name to its target.
+async fn index_or_redirect(
+    State(registry): State<Arc<Registry>>,
+    Path(name): Path<String>,
+) -> Response {
+    if is_file_name(&name) {
+        let index = registry.dir().join(&name).join("index.html");
+        if let Ok(contents) = tokio::fs::read(index).await {
+            return (
+                [(header::CONTENT_TYPE, content_type("index.html"))],
+                contents,
+            )
+                .into_response();
+        }
+    }
+
+    redirect(&registry, &name)
+}
+
+/// Redirects a short name to its registered target.
+fn redirect(registry: &Registry, name: &str) -> Response {
+    match registry.target_for(name) {
+        Some(target)
+            if registry
+                .entry(target)
+                .is_some_and(RegistryEntry::is_retired) =>
+        {
+            StatusCode::GONE.into_response()
+        }
+        Some(target) => (StatusCode::FOUND, [(header::LOCATION, target)]).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// Reports whether a request segment names a file directly inside the output directory.
+fn is_file_name(name: &str) -> bool {
+    let mut components = FsPath::new(name).components();
+    matches!(
+        (components.next(), components.next()),
+        (Some(Component::Normal(_)), None)
+    )
+}
+
+/// Returns the content type for a served file.
+fn content_type(name: &str) -> &'static str {
+    match FsPath::new(name).extension().and_then(|ext| ext.to_str()) {
+        Some("html") => "text/html; charset=utf-8",
+        Some("json") => "application/json",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{OutputLayout, Redirector};
+    use axum::body::{to_bytes, Body};
+    use axum::http::Request;
+    use chrono::Utc;
+    use std::fs;
+    use tower::ServiceExt;
+
+    async fn get_response(router: Router, uri: &str) -> Response {
+        let request = Request::get(uri).body(Body::empty()).unwrap();
+        router.oneshot(request).await.unwrap()
+    }
+
+    async fn body(response: Response) -> String {
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_preview_router() {
+        let dir = format!(
+            "test_preview_router_{}",
+            Utc::now().timestamp_nanos_opt().unwrap_or(0)
+        );
+        Redirector::builder()
+            .target("docs/install")
+            .out_dir(&dir)
+            .alias("install")
+            .build()
+            .unwrap()
+            .write_redirect()
+            .unwrap();
+        let router = Registry::load(&dir).unwrap().preview_router();
+
+        let response = get_response(router.clone(), "/").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(body(response)
+            .await
+            .contains("<a href=\"/install\">install</a> &rarr; /docs/install/"));
+
+        let response = get_response(router.clone(), "/install.html").await;
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "text/html; charset=utf-8"
+        );
+        assert!(body(response).await.contains("url=/docs/install/"));
+
+        let response = get_response(router.clone(), "/registry.json").await;
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+
+        let response = get_response(router.clone(), "/install").await;
+        assert_eq!(response.status(), StatusCode::FOUND);
+        assert_eq!(response.headers()[header::LOCATION], "/docs/install/");
+
+        let response = get_response(router.clone(), "/missing").await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        Redirector::builder()
+            .target("docs/usage")
+            .out_dir(&dir)
+            .alias("usage")
+            .layout(OutputLayout::Directory)
+            .build()
+            .unwrap()
+            .write_redirect()
+            .unwrap();
+        let router = Registry::load(&dir).unwrap().preview_router();
+        let response = get_response(router.clone(), "/usage/").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(body(response).await.contains("url=/docs/usage/"));
+        let response = get_response(router.clone(), "/install/").await;
+        assert_eq!(response.status(), StatusCode::FOUND);
+
+        let response = get_response(router, "/..%2FCargo.toml").await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let mut registry = Registry::load(&dir).unwrap();
+        registry.retire("/docs/install/").unwrap();
+        let response = get_response(registry.preview_router(), "/install").await;
+        assert_eq!(response.status(), StatusCode::GONE);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_is_file_name() {
+        assert!(is_file_name("install.html"));
+        assert!(!is_file_name(".."));
+        assert!(!is_file_name("."));
+        assert!(!is_file_name("../secret"));
+        assert!(!is_file_name("/etc/passwd"));
+        assert!(!is_file_name(""));
+    }
+}
@@ -0,0 +1,198 @@
+//! Tracing written pages back to the run that generated them.
+//!
+//! A redirect page found in production says where it leads but not which build wrote
+//! it, or from which registry entry. This module provides the `Provenance` comment
+//! written into each page by `RedirectorBuilder::provenance()`, recording the crate
+//! version, the time of generation, the target, and the short name the page is
+//! registered under, and `Provenance::parse()` to read it back out of a page.
+
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Utc};
+
+use crate::redirector::json::{self, object, Value};
+use crate::redirector::time::Timestamp;
+
+/// What the provenance comment starts with.
+const MARKER: &str = "<!-- link-bridge-provenance ";
+
+/// Where a redirect page came from, as recorded in a comment in the page.
+///
+/// Written by pages built with
+/// [`RedirectorBuilder::provenance()`](crate::RedirectorBuilder::provenance), and
+/// read back with [`Provenance::parse()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Provenance {
+    /// The version of link-bridge that wrote the page.
+    version: String,
+    /// When the page was generated, to the second.
+    generated_at: Timestamp,
+    /// The target the page leads to, as normalized.
+    target: String,
+    /// The short name the redirect is registered under.
+    short_name: String,
+}
+
+impl Provenance {
+    /// Records that the running version of the crate is generating the page for
+    /// `target` under `short_name` now.
+    pub(crate) fn new(target: String, short_name: String) -> Self {
+        Provenance {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            generated_at: Timestamp::now().trunc_subsecs(),
+            target,
+            short_name,
+        }
+    }
+
+    /// Reads the provenance comment back out of a page.
+    ///
+    /// # Arguments
+    ///
+    /// * `page` - The HTML of a redirect page
+    ///
+    /// # Returns
+    ///
+    /// The provenance, or `None` if the page has no well-formed provenance comment.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::{Provenance, Redirector};
+    /// use std::fs;
+    ///
+    /// let outcome = Redirector::builder()
+    ///     .target("summer/sale")
+    ///     .out_dir("doc_test_provenance")
+    ///     .alias("promo")
+    ///     .provenance()
+    ///     .build()
+    ///     .unwrap()
+    ///     .write_redirect()
+    ///     .unwrap();
+    ///
+    /// let page = fs::read_to_string(outcome.file_path()).unwrap();
+    /// let provenance = Provenance::parse(&page).unwrap();
+    /// assert_eq!(provenance.target(), "/summer/sale/");
+    /// assert_eq!(provenance.short_name(), "promo");
+    /// assert_eq!(provenance.version(), env!("CARGO_PKG_VERSION"));
+    ///
+    /// fs::remove_dir_all("doc_test_provenance").ok();
+    /// ```
+    pub fn parse(page: &str) -> Option<Self> {
+        let (_, rest) = page.split_once(MARKER)?;
+        let (json, _) = rest.split_once(" -->")?;
+        let Value::Object(mut fields) = json::from_slice(json.as_bytes()).ok()? else {
+            return None;
+        };
+        let mut field = |name: &str| match fields.remove(name) {
+            Some(Value::String(value)) => Some(value),
+            _ => None,
+        };
+        let version = field("version")?;
+        let generated_at = Timestamp::parse_rfc3339(&field("generated_at")?)?;
+        let target = field("target")?;
+        let short_name = field("short_name")?;
+
+        Some(Provenance {
+            version,
+            generated_at,
+            target,
+            short_name,
+        })
+    }
+
+    /// Returns the version of link-bridge that wrote the page.
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// Returns when the page was generated.
+    #[cfg(feature = "chrono")]
+    pub fn generated_at(&self) -> DateTime<Utc> {
+        self.generated_at.into()
+    }
+
+    /// Returns the target the page leads to, as normalized (e.g. `"/summer/sale/"`).
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    /// Returns the short name the redirect is registered under, which identifies its
+    /// entry in the registry.
+    pub fn short_name(&self) -> &str {
+        &self.short_name
+    }
+
+    /// Renders the comment written at the end of the page.
+    ///
+    /// A comment cannot hold `--`, so hyphen pairs in the JSON are escaped, which
+    /// leaves the JSON meaning the same.
+    pub(crate) fn comment(&self) -> String {
+        let json = object([
+            ("version", (&self.version).into()),
+            ("generated_at", self.generated_at.to_rfc3339().into()),
+            ("target", (&self.target).into()),
+            ("short_name", (&self.short_name).into()),
+        ])
+        .to_string()
+        .replace("--", "-\\u002d");
+        format!("{MARKER}{json} -->\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MemorySink, OutputSink, Redirector};
+    use std::path::Path;
+
+    #[test]
+    fn test_comment_round_trip() {
+        let provenance = Provenance::new("/a--b/-->/".to_string(), "promo".to_string());
+        let comment = provenance.comment();
+        assert!(!comment[4..comment.len() - 4].contains("--"));
+
+        let page = format!("<html></html>\n{comment}");
+        assert_eq!(Provenance::parse(&page), Some(provenance));
+        assert_eq!(Provenance::parse("<html></html>"), None);
+        assert_eq!(
+            Provenance::parse(r#"<!-- link-bridge-provenance {"version": "1"} -->"#),
+            None
+        );
+    }
+
+    #[test]
+    fn test_write_provenance() {
+        let mut sink = MemorySink::new();
+        let before = Timestamp::now().trunc_subsecs();
+        Redirector::builder()
+            .target("summer/sale")
+            .alias("promo")
+            .confirm()
+            .provenance()
+            .build()
+            .unwrap()
+            .write_redirect_to(&mut sink)
+            .unwrap();
+
+        let page = sink.read_file(Path::new("s/promo.html")).unwrap().unwrap();
+        let page = String::from_utf8(page).unwrap();
+        assert!(page.ends_with(" -->\n"));
+        let provenance = Provenance::parse(&page).unwrap();
+        assert_eq!(provenance.target(), "/summer/sale/");
+        assert_eq!(provenance.short_name(), "promo");
+        assert!(provenance.generated_at >= before);
+
+        // Without the option, pages carry no comment
+        let mut sink = MemorySink::new();
+        Redirector::builder()
+            .target("summer/sale")
+            .alias("promo")
+            .build()
+            .unwrap()
+            .write_redirect_to(&mut sink)
+            .unwrap();
+        let page = sink.read_file(Path::new("s/promo.html")).unwrap().unwrap();
+        assert_eq!(Provenance::parse(&String::from_utf8(page).unwrap()), None);
+    }
+}
@@ -0,0 +1,186 @@
+//! Exporting a QR code for every short link.
+//!
+//! Print campaigns put short links on flyers and posters as QR codes, often hundreds
+//! at a time, and generating them one by one in another tool is slow and error-prone.
+//! This module provides `Registry::export_qr_codes()`, which writes an SVG QR code
+//! for each registered redirect into a `qr/` folder of the output directory.
+
+use std::path::PathBuf;
+
+use qrcode::render::svg;
+use qrcode::QrCode;
+
+use crate::redirector::sink::{FsSink, OutputSink};
+use crate::redirector::target::{ExternalUrl, TargetError};
+use crate::redirector::{trace, RedirectorError, Registry};
+
+/// The folder of the output directory the QR codes are written to.
+const QR_DIR: &str = "qr";
+
+/// The minimum width and height of a QR code, in pixels.
+const MIN_SIZE: u32 = 256;
+
+impl Registry {
+    /// Writes an SVG QR code for each registered redirect.
+    ///
+    /// Each code encodes the redirect's short URL below `site_url` and is written to
+    /// `<dir>/qr/<short_name>.svg`, replacing any code written before. Extra short
+    /// names get no code of their own. Export a [`Registry::tagged()`] registry to
+    /// only write the codes of one campaign's links.
+    ///
+    /// # Arguments
+    ///
+    /// * `site_url` - The URL the output directory is served from, such as
+    ///   `"https://example.com/s"`
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<PathBuf>)` - The files written, in order of short name
+    /// * `Err(RedirectorError::InvalidTarget)` - If the site URL is malformed, or a
+    ///   short URL is too long for a QR code
+    /// * `Err(RedirectorError::DirectoryCreationError)` - If the `qr/` folder cannot
+    ///   be created
+    /// * `Err(RedirectorError::FileWriteError)` - If a code cannot be written; the
+    ///   codes before it have been written
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::{Redirector, Registry};
+    /// use std::fs;
+    /// use std::path::Path;
+    ///
+    /// Redirector::builder()
+    ///     .target("summer/sale")
+    ///     .out_dir("doc_test_export_qr_codes")
+    ///     .alias("promo")
+    ///     .build()
+    ///     .unwrap()
+    ///     .write_redirect()
+    ///     .unwrap();
+    ///
+    /// let registry = Registry::load("doc_test_export_qr_codes").unwrap();
+    /// let written = registry.export_qr_codes("https://example.com/s").unwrap();
+    /// assert_eq!(written, [Path::new("doc_test_export_qr_codes/qr/promo.svg")]);
+    ///
+    /// fs::remove_dir_all("doc_test_export_qr_codes").ok();
+    /// ```
+    pub fn export_qr_codes<S: ToString>(
+        &self,
+        site_url: S,
+    ) -> Result<Vec<PathBuf>, RedirectorError> {
+        let site_url = ExternalUrl::new_base(site_url.to_string())?;
+
+        let mut entries: Vec<(&str, &str)> = self
+            .iter()
+            .map(|(short_name, target, _)| {
+                let short_file = self.entry(target).map_or("", |entry| entry.short_file());
+                (short_name, short_file)
+            })
+            .collect();
+        entries.sort_unstable();
+
+        let dir = self.dir().join(QR_DIR);
+        FsSink
+            .create_dir_all(&dir)
+            .map_err(|source| RedirectorError::DirectoryCreationError {
+                path: dir.clone(),
+                source,
+            })?;
+
+        let mut written = Vec::with_capacity(entries.len());
+        for (short_name, short_file) in entries {
+            let code = qr_svg(&format!("{site_url}/{short_file}"))?;
+            let file_path = dir.join(format!("{short_name}.svg"));
+            FsSink
+                .write_file(&file_path, code.as_bytes())
+                .map_err(|source| RedirectorError::FileWriteError {
+                    path: file_path.clone(),
+                    source,
+                })?;
+            written.push(file_path);
+        }
+        trace::debug!(dir = %dir.display(), codes = written.len(), "exported QR codes");
+
+        Ok(written)
+    }
+}
+
+/// Renders a URL as an SVG QR code.
+fn qr_svg(url: &str) -> Result<String, TargetError> {
+    let code = QrCode::new(url.as_bytes()).map_err(|_| TargetError::InvalidUrl(url.to_string()))?;
+
+    Ok(code
+        .render::<svg::Color>()
+        .min_dimensions(MIN_SIZE, MIN_SIZE)
+        .build())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{OutputLayout, Redirector};
+    use chrono::Utc;
+    use std::fs;
+
+    fn test_dir(name: &str) -> String {
+        format!("{name}_{}", Utc::now().timestamp_nanos_opt().unwrap_or(0))
+    }
+
+    #[test]
+    fn test_export_qr_codes() {
+        let dir = test_dir("test_export_qr_codes");
+        for (target, alias, layout) in [
+            ("summer/sale", "promo", OutputLayout::File),
+            ("docs/install", "install", OutputLayout::Directory),
+        ] {
+            Redirector::builder()
+                .target(target)
+                .out_dir(&dir)
+                .alias(alias)
+                .layout(layout)
+                .tag(alias)
+                .build()
+                .unwrap()
+                .write_redirect()
+                .unwrap();
+        }
+        let registry = Registry::load(&dir).unwrap();
+
+        let written = registry.export_qr_codes("https://example.com/s/").unwrap();
+        let qr_dir = PathBuf::from(&dir).join(QR_DIR);
+        assert_eq!(
+            written,
+            [qr_dir.join("install.svg"), qr_dir.join("promo.svg")]
+        );
+        let code = fs::read_to_string(qr_dir.join("promo.svg")).unwrap();
+        assert!(code.contains("<svg"));
+        assert_eq!(code, qr_svg("https://example.com/s/promo.html").unwrap());
+        assert_eq!(
+            fs::read_to_string(qr_dir.join("install.svg")).unwrap(),
+            qr_svg("https://example.com/s/install/").unwrap()
+        );
+
+        // The QR codes are not mistaken for orphaned pages
+        assert!(Registry::find_orphans(&dir).unwrap().is_empty());
+
+        fs::remove_dir_all(&qr_dir).unwrap();
+        let written = registry
+            .tagged("promo")
+            .export_qr_codes("https://example.com/s");
+        assert_eq!(written.unwrap(), [qr_dir.join("promo.svg")]);
+
+        assert!(matches!(
+            registry.export_qr_codes("example.com"),
+            Err(RedirectorError::InvalidTarget(_))
+        ));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_qr_svg_too_long() {
+        let url = format!("https://example.com/{}", "a".repeat(8000));
+        assert!(matches!(qr_svg(&url), Err(TargetError::InvalidUrl(_))));
+    }
+}
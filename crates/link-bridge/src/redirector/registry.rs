@@ -0,0 +1,2104 @@
+//! The redirect registry stored alongside generated redirect files.
+//!
+//! Each output directory contains a `registry.json` file mapping redirect targets to
+//! the files that redirect to them. This module provides the `Registry` type used to
+//! load, query, update, and persist that mapping, so several redirects can be written
+//! against a single loaded registry, and the `RegistryEntry` type recording each
+//! redirect file.
+
+use std::collections::{btree_map, BTreeMap, HashMap};
+use std::fs;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Utc};
+
+use crate::redirector::alias::validate_alias;
+use crate::redirector::json::{self, write_quoted, Value};
+use crate::redirector::sidecar::{
+    metadata_path, move_sidecars, refresh_metadata, sidecar_path, sidecar_text,
+};
+use crate::redirector::sink::{temp_path, FsSink, OutputSink};
+#[cfg(feature = "social-preview")]
+use crate::redirector::social::{image_path, rename_preview};
+use crate::redirector::stub::stub;
+use crate::redirector::time::Timestamp;
+use crate::redirector::trace;
+use crate::redirector::{OutputLayout, Redirector, RedirectorError, TargetChange, UrlPath};
+
+/// The file name of the registry within an output directory.
+pub(crate) const REDIRECT_REGISTRY: &str = "registry.json";
+
+/// The mapping from redirect targets to redirect files for one output directory.
+///
+/// Each target maps to a [`RegistryEntry`] recording the redirect file's name, its path
+/// with `/` separators, and when it was created. Registries written by earlier versions,
+/// which map each target to a bare file path, are still read; their entries have no
+/// creation time.
+///
+/// # Examples
+///
+/// ```rust
+/// use link_bridge::{Redirector, Registry};
+/// use std::fs;
+///
+/// let mut redirector = Redirector::new("api/v1/users").unwrap();
+/// redirector.set_path("doc_test_registry_type");
+/// let file_path = redirector.write_redirect().unwrap().into_file_path();
+///
+/// let registry = Registry::load("doc_test_registry_type").unwrap();
+/// assert_eq!(registry.len(), 1);
+/// assert_eq!(registry.get("/api/v1/users/"), file_path.to_str());
+///
+/// fs::remove_dir_all("doc_test_registry_type").ok();
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Registry {
+    /// The output directory the registry belongs to.
+    dir: PathBuf,
+    /// The mapping from target to redirect file, ordered by target so the serialized
+    /// file is stable.
+    entries: BTreeMap<String, RegistryEntry>,
+    /// The reverse index from short name to target, kept in step with `entries` so
+    /// lookups by short name do not scan every redirect.
+    short_names: HashMap<String, String>,
+}
+
+impl Registry {
+    /// Loads the registry for an output directory.
+    ///
+    /// If the directory has no `registry.json` yet, an empty registry is returned; it
+    /// will be created when the registry is saved. The file is parsed as it is read,
+    /// so it is never held in memory in full.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The output directory containing the registry
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Registry)` - The loaded (or empty) registry
+    /// * `Err(RedirectorError::FailedToReadRegistry)` - If the registry file cannot be
+    ///   opened or is not valid JSON
+    pub fn load<P: Into<PathBuf>>(dir: P) -> Result<Self, RedirectorError> {
+        Registry::load_from(&mut FsSink, dir)
+    }
+
+    /// Loads the registry for an output directory from an [`OutputSink`].
+    ///
+    /// Behaves like [`Registry::load()`] but reads `registry.json` through the sink,
+    /// for example from a [`MemorySink`](crate::MemorySink).
+    ///
+    /// # Arguments
+    ///
+    /// * `sink` - The sink holding the registry
+    /// * `dir` - The output directory containing the registry
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Registry)` - The loaded (or empty) registry
+    /// * `Err(RedirectorError::FailedToReadRegistry)` - If the registry cannot be read
+    ///   or is not valid JSON
+    pub fn load_from<S, P>(sink: &mut S, dir: P) -> Result<Self, RedirectorError>
+    where
+        S: OutputSink + ?Sized,
+        P: Into<PathBuf>,
+    {
+        let dir = dir.into();
+        let registry_path = dir.join(REDIRECT_REGISTRY);
+
+        let registry = match sink.open_file(&registry_path) {
+            Ok(Some(reader)) => Registry::from_reader(dir, BufReader::new(reader)),
+            Ok(None) => Ok(Registry::empty(dir)),
+            Err(e) => Err(e),
+        }
+        .map_err(|source| {
+            trace::warn!(path = %registry_path.display(), error = %source, "failed to read registry");
+            RedirectorError::FailedToReadRegistry {
+                path: registry_path.clone(),
+                source,
+            }
+        })?;
+
+        trace::debug!(
+            path = %registry_path.display(),
+            entries = registry.len(),
+            "loaded registry"
+        );
+        Ok(registry)
+    }
+
+    /// Writes the registry to `registry.json` in its output directory.
+    ///
+    /// The output directory is created if it does not exist. A target filter saved
+    /// with [`Registry::save_filter()`] is rebuilt alongside.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the registry was written
+    /// * `Err(RedirectorError::DirectoryCreationError)` - If the directory cannot be created
+    /// * `Err(RedirectorError::FailedToWriteRegistry)` - If the registry file cannot be written
+    pub fn save(&self) -> Result<(), RedirectorError> {
+        self.save_to(&mut FsSink)
+    }
+
+    /// Writes the registry to `registry.json` through an [`OutputSink`].
+    ///
+    /// Behaves like [`Registry::save()`] but writes through the sink, for example into
+    /// a [`MemorySink`](crate::MemorySink).
+    ///
+    /// # Arguments
+    ///
+    /// * `sink` - The sink to write the registry to
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the registry was written
+    /// * `Err(RedirectorError::DirectoryCreationError)` - If the directory cannot be created
+    /// * `Err(RedirectorError::FailedToWriteRegistry)` - If the registry cannot be written
+    pub fn save_to<S: OutputSink + ?Sized>(&self, sink: &mut S) -> Result<(), RedirectorError> {
+        self.write_to(sink, &self.registry_path())?;
+        self.refresh_filter(sink)
+    }
+
+    /// Returns the output directory the registry belongs to.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the redirect file path registered for a target, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - The normalized target (e.g. `"/api/v1/"`)
+    pub fn get(&self, target: &str) -> Option<&str> {
+        self.entries.get(target).map(|entry| entry.path.as_str())
+    }
+
+    /// Returns the entry registered for a target, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - The normalized target (e.g. `"/api/v1/"`)
+    pub fn entry(&self, target: &str) -> Option<&RegistryEntry> {
+        self.entries.get(target)
+    }
+
+    /// Returns the registered target matching a target in any letter case, with its
+    /// redirect file path.
+    ///
+    /// An exact match is preferred; otherwise the first target in order that differs
+    /// only in letter case is returned. Used when targets are deduplicated with
+    /// [`TargetCase::Insensitive`](crate::TargetCase::Insensitive).
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - The normalized target (e.g. `"/Api/V1/"`)
+    pub fn get_ignore_case(&self, target: &str) -> Option<(&str, &str)> {
+        self.entries
+            .get_key_value(target)
+            .or_else(|| {
+                self.entries
+                    .iter()
+                    .find(|(registered, _)| eq_ignore_case(registered, target))
+            })
+            .map(|(registered, entry)| (registered.as_str(), entry.path.as_str()))
+    }
+
+    /// Reports whether a redirect is registered for a target.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - The normalized target (e.g. `"/api/v1/"`)
+    pub fn contains_target(&self, target: &str) -> bool {
+        self.entries.contains_key(target)
+    }
+
+    /// Returns the number of registered redirects.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Reports whether the registry has no redirects.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns an iterator over the registered redirects.
+    ///
+    /// Each item is a `(short_name, target, file_path)` tuple, where `short_name` is
+    /// the redirect file name without its extension. Items are yielded in order of
+    /// target.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::{Redirector, Registry};
+    /// use std::fs;
+    ///
+    /// let redirector = Redirector::builder()
+    ///     .target("docs/install")
+    ///     .out_dir("doc_test_registry_iter")
+    ///     .alias("install")
+    ///     .build()
+    ///     .unwrap();
+    /// redirector.write_redirect().unwrap();
+    ///
+    /// let registry = Registry::load("doc_test_registry_iter").unwrap();
+    /// for (short_name, target, file_path) in &registry {
+    ///     assert_eq!(short_name, "install");
+    ///     assert_eq!(target, "/docs/install/");
+    ///     assert!(file_path.ends_with("install.html"));
+    /// }
+    ///
+    /// fs::remove_dir_all("doc_test_registry_iter").ok();
+    /// ```
+    pub fn iter(&self) -> RegistryIter<'_> {
+        RegistryIter {
+            inner: self.entries.iter(),
+        }
+    }
+
+    /// Returns a copy of the registry holding only the redirects filed under a tag.
+    ///
+    /// The copy supports the same queries and exports as the full registry, so one
+    /// team's links can be listed, exported as bookmarks, or published as a feed
+    /// without the rest of the store. It belongs to the same output directory:
+    /// saving it would drop every other redirect from `registry.json`.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag` - The tag to keep (e.g. `"marketing"`)
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::{Redirector, Registry};
+    /// use std::fs;
+    ///
+    /// for (target, tag) in [("summer/sale", "marketing"), ("docs/install", "docs")] {
+    ///     Redirector::builder()
+    ///         .target(target)
+    ///         .out_dir("doc_test_registry_tagged")
+    ///         .tag(tag)
+    ///         .build()
+    ///         .unwrap()
+    ///         .write_redirect()
+    ///         .unwrap();
+    /// }
+    ///
+    /// let registry = Registry::load("doc_test_registry_tagged").unwrap();
+    /// let marketing = registry.tagged("marketing");
+    /// assert_eq!(marketing.len(), 1);
+    /// assert!(marketing.contains_target("/summer/sale/"));
+    ///
+    /// fs::remove_dir_all("doc_test_registry_tagged").ok();
+    /// ```
+    pub fn tagged(&self, tag: &str) -> Registry {
+        let mut tagged = Registry::empty(self.dir.clone());
+        for (target, entry) in &self.entries {
+            if entry.has_tag(tag) {
+                tagged.insert_entry(target.clone(), entry.clone());
+            }
+        }
+        tagged
+    }
+
+    /// Returns the target registered for a short name, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `short_name` - The redirect file name without its extension (e.g. `"promo"`)
+    pub fn target_for(&self, short_name: &str) -> Option<&str> {
+        self.short_names.get(short_name).map(String::as_str)
+    }
+
+    /// Reports whether a redirect with a short name is registered.
+    ///
+    /// # Arguments
+    ///
+    /// * `short_name` - The redirect file name without its extension (e.g. `"promo"`)
+    pub fn contains_short_name(&self, short_name: &str) -> bool {
+        self.short_names.contains_key(short_name)
+    }
+
+    /// Repoints an existing short link at a new target.
+    ///
+    /// The redirect HTML file is rewritten in place, so the short URL people have
+    /// already shared keeps working but now leads to the new target. The registry
+    /// entry is updated in memory; call [`Registry::save()`] to persist it.
+    ///
+    /// The new target is validated with the default [`PathPolicy`](crate::PathPolicy)
+    /// and the page is rendered with the default redirect options; use
+    /// [`Registry::update_target_with()`] to keep a delay, base URL, beacon, gate,
+    /// locale, or other option the redirect was written with. A retired short link is
+    /// brought back into use. Aliases lead to the redirect's page, so they
+    /// follow it to the new target.
+    ///
+    /// The previous target is kept in the entry's
+    /// [`history()`](RegistryEntry::history), with the time it was replaced, so it can
+    /// be looked up with [`Registry::target_at()`] or restored with
+    /// [`Registry::revert_target()`].
+    ///
+    /// # Arguments
+    ///
+    /// * `short_name` - The redirect file name without its extension (e.g. `"promo"`),
+    ///   or one of its aliases
+    /// * `new_target` - The URL path the short link should now redirect to
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The target the short link previously pointed at
+    /// * `Err(RedirectorError::ShortLinkNotFound)` - If no redirect has that short name
+    /// * `Err(RedirectorError::InvalidUrlPath)` - If the new target is invalid
+    /// * `Err(RedirectorError::DuplicateTarget)` - If another short link already
+    ///   redirects to the new target
+    /// * `Err(RedirectorError::FileWriteError)` - If the HTML file cannot be rewritten
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::{Redirector, Registry};
+    /// use std::fs;
+    ///
+    /// let redirector = Redirector::builder()
+    ///     .target("summer/sale")
+    ///     .out_dir("doc_test_update_target")
+    ///     .alias("promo")
+    ///     .build()
+    ///     .unwrap();
+    /// let file_path = redirector.write_redirect().unwrap().into_file_path();
+    ///
+    /// let mut registry = Registry::load("doc_test_update_target").unwrap();
+    /// let previous = registry.update_target("promo", "winter/sale").unwrap();
+    /// registry.save().unwrap();
+    ///
+    /// assert_eq!(previous, "/summer/sale/");
+    /// assert_eq!(registry.target_for("promo"), Some("/winter/sale/"));
+    /// assert!(fs::read_to_string(&file_path).unwrap().contains("/winter/sale/"));
+    ///
+    /// fs::remove_dir_all("doc_test_update_target").ok();
+    /// ```
+    pub fn update_target<S: ToString>(
+        &mut self,
+        short_name: &str,
+        new_target: S,
+    ) -> Result<String, RedirectorError> {
+        let redirector = Redirector::from(UrlPath::new(new_target)?);
+        self.update_target_with(short_name, &redirector)
+    }
+
+    /// Repoints an existing short link at the target of a redirector, rendering the
+    /// page with the redirector's options.
+    ///
+    /// Behaves like [`Registry::update_target()`], but the new target has already
+    /// been validated by building `redirector`, and its page is rendered the way
+    /// [`Redirector::write_redirect()`] would render it, with the delay, base URL,
+    /// beacon, gate, locale, and any other option it was built with. The page keeps
+    /// the short link's registered short name; the redirector's own short name and
+    /// output directory are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `short_name` - The redirect file name without its extension (e.g. `"promo"`),
+    ///   or one of its aliases
+    /// * `redirector` - A redirector for the new target, built with the options the
+    ///   page should be rendered with
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The target the short link previously pointed at
+    /// * `Err(RedirectorError::ShortLinkNotFound)` - If no redirect has that short name
+    /// * `Err(RedirectorError::DuplicateTarget)` - If another short link already
+    ///   redirects to the new target
+    /// * `Err(RedirectorError::FileWriteError)` - If the HTML file cannot be rewritten
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::{Redirector, Registry};
+    /// use std::fs;
+    ///
+    /// let file_path = Redirector::builder()
+    ///     .target("summer/sale")
+    ///     .out_dir("doc_test_update_target_with")
+    ///     .alias("promo")
+    ///     .delay(5)
+    ///     .build()
+    ///     .unwrap()
+    ///     .write_redirect()
+    ///     .unwrap()
+    ///     .into_file_path();
+    ///
+    /// let redirector = Redirector::builder()
+    ///     .target("winter/sale")
+    ///     .delay(5)
+    ///     .build()
+    ///     .unwrap();
+    /// let mut registry = Registry::load("doc_test_update_target_with").unwrap();
+    /// registry.update_target_with("promo", &redirector).unwrap();
+    /// registry.save().unwrap();
+    ///
+    /// let page = fs::read_to_string(&file_path).unwrap();
+    /// assert!(page.contains("content=\"5; url=/winter/sale/\""));
+    ///
+    /// fs::remove_dir_all("doc_test_update_target_with").ok();
+    /// ```
+    pub fn update_target_with(
+        &mut self,
+        short_name: &str,
+        redirector: &Redirector,
+    ) -> Result<String, RedirectorError> {
+        let new_key = redirector.target.to_string();
+
+        let old_key = self
+            .target_for(short_name)
+            .ok_or(RedirectorError::ShortLinkNotFound)?
+            .to_string();
+
+        if new_key != old_key && self.contains_target(&new_key) {
+            return Err(RedirectorError::DuplicateTarget(new_key));
+        }
+
+        let mut entry = self.entries[&old_key].clone();
+        entry.retired_at = None;
+        if new_key != old_key {
+            entry.push_history(TargetChange::new(
+                old_key.clone(),
+                Timestamp::now().trunc_subsecs(),
+            ));
+        }
+        let mut registered = redirector.clone();
+        registered.short_file_name = format!("{}.html", entry.short_name()).into();
+        FsSink
+            .write_file(entry.path(), registered.page().as_bytes())
+            .map_err(|source| RedirectorError::FileWriteError {
+                path: entry.path().to_path_buf(),
+                source,
+            })?;
+        // Only a page written with a sidecar has one to keep up to date
+        let text_path = sidecar_path(entry.path());
+        if text_path.exists() {
+            FsSink
+                .write_file(&text_path, sidecar_text(&new_key).as_bytes())
+                .map_err(|source| RedirectorError::FileWriteError {
+                    path: text_path,
+                    source,
+                })?;
+        }
+
+        refresh_metadata(entry.path(), &new_key, &entry)?;
+
+        self.forget(&old_key);
+        self.insert_entry(new_key, entry);
+
+        Ok(old_key)
+    }
+
+    /// Removes a short link and deletes its redirect HTML file.
+    ///
+    /// The registry entry is removed in memory; call [`Registry::save()`] to persist
+    /// it. A redirect file that has already been deleted is not an error. The
+    /// directory of a page written with
+    /// [`OutputLayout::Directory`](crate::OutputLayout::Directory) is removed too
+    /// once it is empty.
+    ///
+    /// Removing a redirect deletes the stub pages of its aliases as well. Removing an
+    /// alias added with [`Registry::add_alias()`] deletes only its stub page and
+    /// keeps the redirect.
+    ///
+    /// # Arguments
+    ///
+    /// * `short_name` - The redirect file name without its extension (e.g. `"promo"`)
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The target the removed short link pointed at
+    /// * `Err(RedirectorError::ShortLinkNotFound)` - If no redirect has that short name
+    /// * `Err(RedirectorError::FileRemovalError)` - If the HTML file cannot be deleted
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::{Redirector, Registry};
+    /// use std::fs;
+    ///
+    /// let redirector = Redirector::builder()
+    ///     .target("summer/sale")
+    ///     .out_dir("doc_test_registry_remove")
+    ///     .alias("promo")
+    ///     .build()
+    ///     .unwrap();
+    /// let file_path = redirector.write_redirect().unwrap().into_file_path();
+    ///
+    /// let mut registry = Registry::load("doc_test_registry_remove").unwrap();
+    /// assert_eq!(registry.remove("promo").unwrap(), "/summer/sale/");
+    /// registry.save().unwrap();
+    ///
+    /// assert!(registry.is_empty());
+    /// assert!(!file_path.exists());
+    ///
+    /// fs::remove_dir_all("doc_test_registry_remove").ok();
+    /// ```
+    pub fn remove(&mut self, short_name: &str) -> Result<String, RedirectorError> {
+        let target = self
+            .target_for(short_name)
+            .ok_or(RedirectorError::ShortLinkNotFound)?
+            .to_string();
+
+        let entry = &self.entries[&target];
+        let layout = OutputLayout::of(entry.short_file());
+
+        // Removing an alias only deletes its stub page
+        if entry.short_name() != short_name {
+            remove_page(&layout.file_path(&self.dir, short_name), layout)?;
+            if let Some(entry) = self.entries.get_mut(&target) {
+                entry.aliases.retain(|alias| alias != short_name);
+            }
+            self.short_names.remove(short_name);
+            return Ok(target);
+        }
+
+        remove_page(entry.path(), layout)?;
+        for alias in entry.aliases() {
+            remove_page(&layout.file_path(&self.dir, alias), layout)?;
+        }
+        self.forget(&target);
+
+        Ok(target)
+    }
+
+    /// Renames a short link.
+    ///
+    /// The redirect's page is moved to the new short name, keeping the redirect's
+    /// layout, together with its sidecars and preview image, and the stub pages of its
+    /// aliases are rewritten to lead to it. Renaming an alias moves only its stub
+    /// page. The old short name stops working and is
+    /// free for reuse; use [`Registry::rename_keeping_old()`] to keep it leading to
+    /// the redirect. The registry entry is updated in memory; call
+    /// [`Registry::save()`] to persist it.
+    ///
+    /// # Arguments
+    ///
+    /// * `old_short` - The current short name (e.g. `"promo"`), or one of the aliases
+    /// * `new_short` - The new short name (e.g. `"sale"`)
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The target of the renamed short link
+    /// * `Err(RedirectorError::InvalidAlias)` - If the new short name contains
+    ///   forbidden characters
+    /// * `Err(RedirectorError::ReservedName)` - If the new short name is reserved by
+    ///   Windows
+    /// * `Err(RedirectorError::ShortLinkNotFound)` - If no redirect has the old short
+    ///   name
+    /// * `Err(RedirectorError::DuplicateShortName)` - If the new short name is already
+    ///   in use
+    /// * `Err(RedirectorError::DirectoryCreationError)` - If the page's new directory
+    ///   cannot be created
+    /// * `Err(RedirectorError::FileWriteError)` - If the page cannot be moved, a file
+    ///   already exists at its new path, or a stub page cannot be rewritten
+    /// * `Err(RedirectorError::FileRemovalError)` - If an alias's old stub page or the
+    ///   old JSON sidecar cannot be deleted
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::{Redirector, Registry};
+    /// use std::fs;
+    /// use std::path::Path;
+    ///
+    /// let old_path = Redirector::builder()
+    ///     .target("summer/sale")
+    ///     .out_dir("doc_test_registry_rename")
+    ///     .alias("promo")
+    ///     .build()
+    ///     .unwrap()
+    ///     .write_redirect()
+    ///     .unwrap()
+    ///     .into_file_path();
+    ///
+    /// let mut registry = Registry::load("doc_test_registry_rename").unwrap();
+    /// registry.rename("promo", "sale").unwrap();
+    /// registry.save().unwrap();
+    ///
+    /// assert_eq!(registry.target_for("sale"), Some("/summer/sale/"));
+    /// assert!(!registry.contains_short_name("promo"));
+    /// assert!(!old_path.exists());
+    /// assert!(Path::new("doc_test_registry_rename/sale.html").exists());
+    ///
+    /// fs::remove_dir_all("doc_test_registry_rename").ok();
+    /// ```
+    pub fn rename(&mut self, old_short: &str, new_short: &str) -> Result<String, RedirectorError> {
+        self.rename_short_name(old_short, new_short, false)
+    }
+
+    /// Renames a short link, keeping the old short name leading to it.
+    ///
+    /// Behaves like [`Registry::rename()`], but the old short name becomes an alias
+    /// of the redirect, as if added with [`Registry::add_alias()`], so links already
+    /// shared under it keep working.
+    ///
+    /// # Arguments
+    ///
+    /// * `old_short` - The current short name (e.g. `"promo"`), or one of the aliases
+    /// * `new_short` - The new short name (e.g. `"sale"`)
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The target of the renamed short link
+    /// * `Err(RedirectorError)` - As for [`Registry::rename()`]
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::{Redirector, Registry};
+    /// use std::fs;
+    ///
+    /// let old_path = Redirector::builder()
+    ///     .target("summer/sale")
+    ///     .out_dir("doc_test_registry_rename_keeping_old")
+    ///     .alias("promo")
+    ///     .build()
+    ///     .unwrap()
+    ///     .write_redirect()
+    ///     .unwrap()
+    ///     .into_file_path();
+    ///
+    /// let mut registry = Registry::load("doc_test_registry_rename_keeping_old").unwrap();
+    /// registry.rename_keeping_old("promo", "sale").unwrap();
+    /// registry.save().unwrap();
+    ///
+    /// assert_eq!(registry.entry("/summer/sale/").unwrap().short_name(), "sale");
+    /// assert_eq!(registry.entry("/summer/sale/").unwrap().aliases(), ["promo"]);
+    /// assert!(fs::read_to_string(&old_path).unwrap().contains("url=sale.html"));
+    ///
+    /// fs::remove_dir_all("doc_test_registry_rename_keeping_old").ok();
+    /// ```
+    pub fn rename_keeping_old(
+        &mut self,
+        old_short: &str,
+        new_short: &str,
+    ) -> Result<String, RedirectorError> {
+        self.rename_short_name(old_short, new_short, true)
+    }
+
+    /// Renames a short name or alias, leaving the old one as an alias if `keep_old`.
+    fn rename_short_name(
+        &mut self,
+        old_short: &str,
+        new_short: &str,
+        keep_old: bool,
+    ) -> Result<String, RedirectorError> {
+        validate_alias(new_short)?;
+        let target = self
+            .target_for(old_short)
+            .ok_or(RedirectorError::ShortLinkNotFound)?
+            .to_string();
+        if self.contains_short_name(new_short) {
+            return Err(RedirectorError::DuplicateShortName(new_short.to_string()));
+        }
+
+        let mut entry = self.entries[&target].clone();
+        let layout = OutputLayout::of(entry.short_file());
+        let old_path = layout.file_path(&self.dir, old_short);
+        let new_path = layout.file_path(&self.dir, new_short);
+        let write_error = |path: &Path| {
+            let path = path.to_path_buf();
+            move |source| RedirectorError::FileWriteError { path, source }
+        };
+
+        if let Some(dir) = new_path.parent() {
+            FsSink.create_dir_all(dir).map_err(|source| {
+                RedirectorError::DirectoryCreationError {
+                    path: dir.to_path_buf(),
+                    source,
+                }
+            })?;
+        }
+
+        if entry.short_name() == old_short {
+            if new_path.exists() {
+                return Err(write_error(&new_path)(io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    "file already exists",
+                )));
+            }
+            let old_page = entry.path().to_path_buf();
+            fs::rename(&old_page, &new_path).map_err(write_error(&new_path))?;
+            entry.short_file = layout.short_file(new_short);
+            entry.set_path(&new_path);
+            move_sidecars(&old_page, &new_path, &target, &entry)?;
+            #[cfg(feature = "social-preview")]
+            rename_preview(&old_page, &new_path, layout, old_short, new_short)
+                .map_err(write_error(&new_path))?;
+
+            // Stubs lead to the page by its short file, so they follow it
+            for alias in &entry.aliases {
+                let (file_path, page) = stub(&self.dir, &entry, alias);
+                FsSink
+                    .write_file(&file_path, page.as_bytes())
+                    .map_err(write_error(&file_path))?;
+            }
+            if keep_old {
+                let (file_path, page) = stub(&self.dir, &entry, old_short);
+                FsSink
+                    .write_file(&file_path, page.as_bytes())
+                    .map_err(write_error(&file_path))?;
+                entry.aliases.push(old_short.to_string());
+            } else if layout == OutputLayout::Directory {
+                if let Some(dir) = old_path.parent() {
+                    fs::remove_dir(dir).ok();
+                }
+            }
+        } else {
+            let (file_path, page) = stub(&self.dir, &entry, new_short);
+            FsSink
+                .create_file(&file_path, page.as_bytes())
+                .map_err(write_error(&file_path))?;
+            if !keep_old {
+                remove_page(&old_path, layout)?;
+                entry.aliases.retain(|alias| alias != old_short);
+            }
+            entry.aliases.push(new_short.to_string());
+        }
+
+        entry.aliases.sort_unstable();
+        trace::debug!(target = %target, old_short, new_short, "renamed short link");
+
+        self.insert_entry(target.clone(), entry);
+        Ok(target)
+    }
+
+    /// Records the redirect file for a target without expiry or metadata.
+    #[cfg(test)]
+    pub(crate) fn insert(&mut self, target: String, file_path: String) {
+        let short_file = file_name(&file_path).to_string();
+        let entry = RegistryEntry::new(
+            file_path,
+            short_file,
+            Some(Timestamp::now().trunc_subsecs()),
+        );
+        self.insert_entry(target, entry);
+    }
+
+    /// Records the redirect file written for a target by a redirector, with the
+    /// redirector's expiry, campaign, and tags.
+    ///
+    /// Registries are shared between platforms, so file paths are always recorded with
+    /// `/` separators. Times are recorded to the second.
+    pub(crate) fn record(&mut self, target: String, file_path: String, redirector: &Redirector) {
+        let file_path = if cfg!(windows) {
+            file_path.replace('\\', "/")
+        } else {
+            file_path
+        };
+        let mut entry = RegistryEntry::new(
+            file_path,
+            redirector.short_file(),
+            Some(Timestamp::now().trunc_subsecs()),
+        );
+        entry.expires_at = redirector.expires_at.map(Timestamp::trunc_subsecs);
+        entry.campaign.clone_from(&redirector.campaign);
+        entry.tags.clone_from(&redirector.tags);
+        self.insert_entry(target, entry);
+    }
+
+    /// Records an entry for a target, replacing any previous entry.
+    fn insert_entry(&mut self, target: String, entry: RegistryEntry) {
+        self.forget(&target);
+        for name in entry.short_names() {
+            self.short_names.insert(name.to_string(), target.clone());
+        }
+        self.entries.insert(target, entry);
+    }
+
+    /// Records an alias for a registered target.
+    pub(crate) fn insert_alias(&mut self, target: &str, alias: &str) {
+        if let Some(entry) = self.entries.get_mut(target) {
+            if let Err(index) = entry.aliases.binary_search_by(|a| a.as_str().cmp(alias)) {
+                entry.aliases.insert(index, alias.to_string());
+                self.short_names
+                    .insert(alias.to_string(), target.to_string());
+            }
+        }
+    }
+
+    /// Returns every short name, including aliases, with the target it leads to.
+    pub(crate) fn short_names(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.short_names
+            .iter()
+            .map(|(short_name, target)| (short_name.as_str(), target.as_str()))
+    }
+
+    /// Returns the path below `base_path` of every short name that redirects, with its
+    /// target, ordered by path.
+    ///
+    /// Extra short names are included and retired redirects left out.
+    pub(crate) fn redirect_sources(&self, base_path: &str) -> Vec<(String, &str)> {
+        let base_path = base_path.trim_matches('/');
+        let prefix = if base_path.is_empty() {
+            String::new()
+        } else {
+            format!("/{base_path}")
+        };
+
+        let mut redirects = Vec::new();
+        for (_, target, _) in self.iter() {
+            let Some(entry) = self.entry(target) else {
+                continue;
+            };
+            if entry.is_retired() {
+                continue;
+            }
+            for short_name in std::iter::once(entry.short_name())
+                .chain(entry.aliases().iter().map(String::as_str))
+            {
+                redirects.push((format!("{prefix}/{short_name}"), target));
+            }
+        }
+        redirects.sort_unstable();
+        redirects
+    }
+
+    /// Renders the `key value` lines of a whitespace-separated map file, as read by
+    /// Apache's `RewriteMap` and HAProxy, mapping the path below `base_path` of every
+    /// short name that redirects, with and without the `.html` extension, to its
+    /// target.
+    ///
+    /// Both servers end a value at the first whitespace and an entry at a newline, so
+    /// whitespace and control characters in targets are percent-encoded.
+    pub(crate) fn map_lines(&self, base_path: &str) -> String {
+        let mut lines = String::new();
+        for (source, target) in self.redirect_sources(base_path) {
+            let mut value = String::with_capacity(target.len());
+            for c in target.chars() {
+                if c.is_whitespace() || c.is_control() {
+                    let mut bytes = [0; 4];
+                    for byte in c.encode_utf8(&mut bytes).bytes() {
+                        value.push_str(&format!("%{byte:02X}"));
+                    }
+                } else {
+                    value.push(c);
+                }
+            }
+            lines.push_str(&format!("{source} {value}\n{source}.html {value}\n"));
+        }
+        lines
+    }
+
+    /// Returns the entry for a target for changing in place.
+    pub(crate) fn entry_mut(&mut self, target: &str) -> Option<&mut RegistryEntry> {
+        self.entries.get_mut(target)
+    }
+
+    /// Removes the entry for a target without touching its redirect file.
+    pub(crate) fn forget(&mut self, target: &str) -> Option<RegistryEntry> {
+        let entry = self.entries.remove(target)?;
+        for name in entry.short_names() {
+            self.short_names.remove(name);
+        }
+        Some(entry)
+    }
+
+    /// Moves the registry to another output directory without touching any files.
+    pub(crate) fn set_dir(&mut self, dir: PathBuf) {
+        self.dir = dir;
+    }
+
+    /// Creates an empty registry for an output directory.
+    pub(crate) fn empty(dir: PathBuf) -> Self {
+        Registry {
+            dir,
+            entries: BTreeMap::new(),
+            short_names: HashMap::new(),
+        }
+    }
+
+    /// Parses a `registry.json` file as it is read.
+    ///
+    /// Both structured entries and the bare file paths written by earlier versions are
+    /// accepted, and each page is located in `dir` by its short file whatever path was
+    /// recorded for it. Entries are parsed one at a time, so besides the registry itself only
+    /// the entry being read is held in memory, never the whole file.
+    pub(crate) fn from_reader<R: BufRead>(dir: PathBuf, reader: R) -> Result<Self, io::Error> {
+        let mut registry = Registry::empty(dir);
+        json::for_each_member(reader, |target, value| {
+            let mut entry = RegistryEntry::from_json(value).map_err(|reason| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("invalid entry for {target}: {reason}"),
+                )
+            })?;
+            // The recorded path is relative to wherever the page was written from,
+            // so find the page in the registry's directory instead
+            let layout = OutputLayout::of(entry.short_file());
+            entry.set_path(&layout.file_path(&registry.dir, entry.short_name()));
+            // a repeated target replaces the earlier entry, as in any JSON object
+            if let Some(replaced) = registry.entries.insert(target.clone(), entry) {
+                for name in replaced.short_names() {
+                    if registry.short_names.get(name) == Some(&target) {
+                        registry.short_names.remove(name);
+                    }
+                }
+            }
+            for name in registry.entries[&target].short_names() {
+                registry
+                    .short_names
+                    .insert(name.to_string(), target.clone());
+            }
+            Ok(())
+        })?;
+        Ok(registry)
+    }
+
+    /// Serializes the registry in the `registry.json` format into a writer.
+    ///
+    /// Entries are written sorted by target, each field on its own line, and the file
+    /// ends with a newline, so saving the same registry always produces the same bytes
+    /// and adding a redirect only adds lines. The JSON is written as it is produced
+    /// rather than built in memory first.
+    pub(crate) fn write_json<W: Write + ?Sized>(&self, writer: &mut W) -> Result<(), io::Error> {
+        if self.entries.is_empty() {
+            return writer.write_all(b"{}\n");
+        }
+
+        writer.write_all(b"{")?;
+        for (i, (target, entry)) in self.entries.iter().enumerate() {
+            if i > 0 {
+                writer.write_all(b",")?;
+            }
+            writer.write_all(b"\n  ")?;
+            write_quoted(writer, target)?;
+            writer.write_all(b": ")?;
+            entry.write_json(writer)?;
+        }
+        writer.write_all(b"\n}\n")
+    }
+
+    /// Returns the location of `registry.json` for this registry.
+    pub(crate) fn registry_path(&self) -> PathBuf {
+        self.dir.join(REDIRECT_REGISTRY)
+    }
+
+    /// Persists several registries together.
+    ///
+    /// Every registry is first written to a temporary file beside its `registry.json`;
+    /// only once all of them have been written successfully are they renamed into
+    /// place. If any write fails, the temporary files are removed and no registry is
+    /// changed.
+    pub(crate) fn save_all<'a, I>(registries: I) -> Result<(), RedirectorError>
+    where
+        I: IntoIterator<Item = &'a Registry>,
+    {
+        let registries: Vec<&Registry> = registries.into_iter().collect();
+        let mut staged: Vec<(PathBuf, PathBuf)> = Vec::new();
+
+        for registry in &registries {
+            let final_path = registry.registry_path();
+            let temp_path = temp_path(&final_path);
+            if let Err(e) = registry.write_to(&mut FsSink, &temp_path) {
+                fs::remove_file(&temp_path).ok();
+                for (temp_path, _) in &staged {
+                    fs::remove_file(temp_path).ok();
+                }
+                return Err(e);
+            }
+            staged.push((temp_path, final_path));
+        }
+
+        for (temp_path, final_path) in staged {
+            fs::rename(&temp_path, &final_path).map_err(|source| {
+                RedirectorError::FailedToWriteRegistry {
+                    path: final_path,
+                    source,
+                }
+            })?;
+        }
+        for registry in registries {
+            registry.refresh_filter(&mut FsSink)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the registry as JSON to the given file, creating the directory if needed.
+    pub(crate) fn write_to<S: OutputSink + ?Sized>(
+        &self,
+        sink: &mut S,
+        path: &Path,
+    ) -> Result<(), RedirectorError> {
+        sink.create_dir_all(&self.dir).map_err(|source| {
+            RedirectorError::DirectoryCreationError {
+                path: self.dir.clone(),
+                source,
+            }
+        })?;
+        trace::debug!(path = %path.display(), entries = self.len(), "writing registry");
+        sink.write_file_with(path, &mut |writer| self.write_json(writer))
+            .map_err(|source| RedirectorError::FailedToWriteRegistry {
+                path: path.to_path_buf(),
+                source,
+            })
+    }
+}
+
+/// A redirect file recorded in a [`Registry`].
+///
+/// The file name is recorded separately from the path, and the path always uses `/`
+/// separators, so a registry generated on Windows reads the same when it is deployed
+/// from Linux.
+///
+/// # Examples
+///
+/// ```rust
+/// use link_bridge::{Redirector, Registry};
+/// use std::fs;
+///
+/// Redirector::builder()
+///     .target("docs/install")
+///     .out_dir("doc_test_registry_entry")
+///     .alias("install")
+///     .build()
+///     .unwrap()
+///     .write_redirect()
+///     .unwrap();
+///
+/// let registry = Registry::load("doc_test_registry_entry").unwrap();
+/// let entry = registry.entry("/docs/install/").unwrap();
+/// assert_eq!(entry.short_file(), "install.html");
+/// assert_eq!(entry.short_name(), "install");
+/// assert!(entry.path().ends_with("install.html"));
+/// # #[cfg(feature = "chrono")]
+/// assert!(entry.created_at().is_some());
+///
+/// fs::remove_dir_all("doc_test_registry_entry").ok();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegistryEntry {
+    /// The URL of the redirect relative to the output directory: the file name
+    /// (e.g. `promo.html`), or the directory (e.g. `promo/`) for the directory layout.
+    short_file: String,
+    /// The path of the redirect file as it was written, with `/` separators.
+    path: String,
+    /// When the redirect was recorded, to the second; unknown for entries read from
+    /// registries written by earlier versions.
+    pub(crate) created_at: Option<Timestamp>,
+    /// When the redirect stops working, to the second, if it expires.
+    pub(crate) expires_at: Option<Timestamp>,
+    /// When the redirect was retired, to the second, if it has been.
+    pub(crate) retired_at: Option<Timestamp>,
+    /// The campaign the redirect belongs to, if any.
+    campaign: Option<String>,
+    /// The tags the redirect is filed under, sorted and without duplicates.
+    tags: Vec<String>,
+    /// The extra short names leading to the redirect through stub pages, sorted.
+    aliases: Vec<String>,
+    /// The targets the redirect led to before being repointed, oldest first.
+    history: Vec<TargetChange>,
+}
+
+impl RegistryEntry {
+    /// Creates an entry for a redirect file path, reached at `short_file` within the
+    /// output directory.
+    fn new(path: String, short_file: String, created_at: Option<Timestamp>) -> Self {
+        RegistryEntry {
+            short_file,
+            path,
+            created_at,
+            expires_at: None,
+            retired_at: None,
+            campaign: None,
+            tags: Vec::new(),
+            aliases: Vec::new(),
+            history: Vec::new(),
+        }
+    }
+
+    /// Returns the URL of the redirect relative to the output directory.
+    ///
+    /// This is the redirect file name, including its extension (e.g. `"promo.html"`),
+    /// or the directory (e.g. `"promo/"`) for redirects written with
+    /// [`OutputLayout::Directory`](crate::OutputLayout::Directory).
+    pub fn short_file(&self) -> &str {
+        &self.short_file
+    }
+
+    /// Returns the short name: the redirect's URL without the extension or trailing
+    /// slash.
+    pub fn short_name(&self) -> &str {
+        short_name(&self.short_file)
+    }
+
+    /// Returns the path of the redirect file in the registry's output directory, with
+    /// `/` separators.
+    pub fn path(&self) -> &Path {
+        Path::new(&self.path)
+    }
+
+    /// Returns when the redirect was recorded, if known.
+    ///
+    /// Entries read from registries written by earlier versions have no creation time.
+    #[cfg(feature = "chrono")]
+    pub fn created_at(&self) -> Option<DateTime<Utc>> {
+        self.created_at.map(Into::into)
+    }
+
+    /// Returns when the redirect stops working, if it expires.
+    ///
+    /// Expired redirects are swept by [`Registry::expire()`].
+    #[cfg(feature = "chrono")]
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.expires_at.map(Into::into)
+    }
+
+    /// Returns when the redirect was retired, if it has been.
+    ///
+    /// Redirects are retired by [`Registry::retire()`].
+    #[cfg(feature = "chrono")]
+    pub fn retired_at(&self) -> Option<DateTime<Utc>> {
+        self.retired_at.map(Into::into)
+    }
+
+    /// Returns `true` if the redirect has been retired, so its page says the content
+    /// is gone instead of redirecting.
+    pub fn is_retired(&self) -> bool {
+        self.retired_at.is_some()
+    }
+
+    /// Marks the redirect retired now, unless it already is.
+    pub(crate) fn retire(&mut self) {
+        self.retired_at
+            .get_or_insert_with(|| Timestamp::now().trunc_subsecs());
+    }
+
+    /// Returns the campaign the redirect belongs to, if any.
+    pub fn campaign(&self) -> Option<&str> {
+        self.campaign.as_deref()
+    }
+
+    /// Returns the tags the redirect is filed under, in sorted order.
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    /// Returns `true` if the redirect is filed under the tag.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.binary_search_by(|t| t.as_str().cmp(tag)).is_ok()
+    }
+
+    /// Returns the extra short names added with [`Registry::add_alias()`], in sorted
+    /// order.
+    pub fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+
+    /// Returns the targets the redirect led to before being repointed with
+    /// [`Registry::update_target()`], oldest first.
+    pub fn history(&self) -> &[TargetChange] {
+        &self.history
+    }
+
+    /// Records the path the redirect file has been moved to, with `/` separators.
+    pub(crate) fn set_path(&mut self, path: &Path) {
+        let path = path.to_string_lossy();
+        self.path = if cfg!(windows) {
+            path.replace('\\', "/")
+        } else {
+            path.into_owned()
+        };
+    }
+
+    /// Records a target the redirect led to before being repointed.
+    pub(crate) fn push_history(&mut self, change: TargetChange) {
+        self.history.push(change);
+    }
+
+    /// Returns the short name followed by every alias.
+    fn short_names(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.short_name()).chain(self.aliases.iter().map(String::as_str))
+    }
+
+    /// Parses an entry from its `registry.json` value.
+    ///
+    /// Earlier versions recorded each entry as the bare file path, joined with the
+    /// separator of the platform that wrote it; such paths are read with `/`
+    /// separators and without a creation time.
+    fn from_json(value: Value) -> Result<Self, String> {
+        let mut fields = match value {
+            Value::String(path) => {
+                let path = path.replace('\\', "/");
+                let short_file = file_name(&path).to_string();
+                return Ok(RegistryEntry::new(path, short_file, None));
+            }
+            Value::Object(fields) => fields,
+            _ => return Err("expected a file path or an object".to_string()),
+        };
+
+        let mut text = |field: &str| match fields.remove(field) {
+            Some(Value::String(text)) => Ok(Some(text)),
+            None | Some(Value::Null) => Ok(None),
+            Some(_) => Err(format!("{field} is not a string")),
+        };
+        let path = text("path")?.ok_or("missing path")?.replace('\\', "/");
+        let short_file = text("short_file")?.unwrap_or_else(|| file_name(&path).to_string());
+        let mut time = |field: &str| {
+            text(field)?
+                .map(|time| {
+                    Timestamp::parse_rfc3339(&time)
+                        .ok_or_else(|| format!("invalid {field}: {time}"))
+                })
+                .transpose()
+        };
+        let created_at = time("created_at")?;
+        let expires_at = time("expires_at")?;
+        let retired_at = time("retired_at")?;
+        let campaign = text("campaign")?;
+        let mut names = |field: &str| {
+            let mut names = match fields.remove(field) {
+                Some(Value::Array(names)) => names
+                    .into_iter()
+                    .map(|name| match name {
+                        Value::String(name) => Ok(name),
+                        _ => Err(format!("{field} must be strings")),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?,
+                None | Some(Value::Null) => Vec::new(),
+                Some(_) => return Err(format!("{field} is not an array")),
+            };
+            names.sort_unstable();
+            names.dedup();
+            Ok(names)
+        };
+        let tags = names("tags")?;
+        let aliases = names("aliases")?;
+        let history = match fields.remove("history") {
+            Some(Value::Array(changes)) => changes
+                .into_iter()
+                .map(TargetChange::from_json)
+                .collect::<Result<Vec<_>, _>>()?,
+            None | Some(Value::Null) => Vec::new(),
+            Some(_) => return Err("history is not an array".to_string()),
+        };
+
+        Ok(RegistryEntry {
+            short_file,
+            path,
+            created_at,
+            expires_at,
+            retired_at,
+            campaign,
+            tags,
+            aliases,
+            history,
+        })
+    }
+
+    /// Serializes the entry as a `registry.json` object, indented to sit below its target.
+    fn write_json<W: Write + ?Sized>(&self, writer: &mut W) -> Result<(), io::Error> {
+        writer.write_all(b"{\n    \"short_file\": ")?;
+        write_quoted(writer, &self.short_file)?;
+        writer.write_all(b",\n    \"path\": ")?;
+        write_quoted(writer, &self.path)?;
+        if let Some(campaign) = &self.campaign {
+            writer.write_all(b",\n    \"campaign\": ")?;
+            write_quoted(writer, campaign)?;
+        }
+        if !self.tags.is_empty() {
+            write!(writer, ",\n    \"tags\": {}", Value::from(&self.tags[..]))?;
+        }
+        if !self.aliases.is_empty() {
+            write!(
+                writer,
+                ",\n    \"aliases\": {}",
+                Value::from(&self.aliases[..])
+            )?;
+        }
+        if !self.history.is_empty() {
+            writer.write_all(b",\n    \"history\": [")?;
+            for (index, change) in self.history.iter().enumerate() {
+                if index > 0 {
+                    writer.write_all(b", ")?;
+                }
+                change.write_json(writer)?;
+            }
+            writer.write_all(b"]")?;
+        }
+        for (field, time) in [
+            ("created_at", self.created_at),
+            ("expires_at", self.expires_at),
+            ("retired_at", self.retired_at),
+        ] {
+            if let Some(time) = time {
+                write!(writer, ",\n    \"{field}\": \"{}\"", time.to_rfc3339())?;
+            }
+        }
+        writer.write_all(b"\n  }")
+    }
+}
+
+impl<'a> IntoIterator for &'a Registry {
+    type Item = (&'a str, &'a str, &'a Path);
+    type IntoIter = RegistryIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the redirects in a [`Registry`].
+///
+/// Created by [`Registry::iter()`]; yields `(short_name, target, file_path)` tuples.
+#[derive(Debug, Clone)]
+pub struct RegistryIter<'a> {
+    inner: btree_map::Iter<'a, String, RegistryEntry>,
+}
+
+impl<'a> Iterator for RegistryIter<'a> {
+    type Item = (&'a str, &'a str, &'a Path);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next()
+            .map(|(target, entry)| (entry.short_name(), target.as_str(), entry.path()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl ExactSizeIterator for RegistryIter<'_> {}
+
+/// Returns the file name of a recorded redirect file path.
+///
+/// Both `/` and `\\` are treated as separators, so registries written on Windows read
+/// the same everywhere.
+pub(crate) fn file_name(file_path: &str) -> &str {
+    file_path.rsplit(['/', '\\']).next().unwrap_or(file_path)
+}
+
+/// Returns the short name of a redirect file: its file name without the extension.
+///
+/// A short file ending in `/` names a directory, whose name is the short name.
+pub(crate) fn short_name(file_path: &str) -> &str {
+    if let Some(dir) = file_path.strip_suffix('/') {
+        return file_name(dir);
+    }
+    let file_name = file_name(file_path);
+    match file_name.rsplit_once('.') {
+        Some((stem, _)) if !stem.is_empty() => stem,
+        _ => file_name,
+    }
+}
+
+/// Deletes a redirect or stub page, and the directory of a page written as a
+/// directory's index once it is empty. A page that has already been deleted is not an
+/// error.
+pub(crate) fn remove_page(file_path: &Path, layout: OutputLayout) -> Result<(), RedirectorError> {
+    match fs::remove_file(file_path) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(source) => {
+            return Err(RedirectorError::FileRemovalError {
+                path: file_path.to_path_buf(),
+                source,
+            })
+        }
+    }
+    #[cfg(feature = "social-preview")]
+    fs::remove_file(image_path(file_path)).ok();
+    fs::remove_file(sidecar_path(file_path)).ok();
+    fs::remove_file(metadata_path(file_path)).ok();
+    // A page written as a directory's index leaves its directory behind
+    if layout == OutputLayout::Directory {
+        if let Some(dir) = file_path.parent() {
+            fs::remove_dir(dir).ok();
+        }
+    }
+    Ok(())
+}
+
+/// Compares two targets, ignoring letter case.
+fn eq_ignore_case(a: &str, b: &str) -> bool {
+    a.chars()
+        .flat_map(char::to_lowercase)
+        .eq(b.chars().flat_map(char::to_lowercase))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn test_dir(name: &str) -> String {
+        format!("{name}_{}", Utc::now().timestamp_nanos_opt().unwrap_or(0))
+    }
+
+    #[test]
+    fn test_load_missing_registry_is_empty() {
+        let dir = test_dir("test_load_missing_registry_is_empty");
+        let registry = Registry::load(&dir).unwrap();
+
+        assert!(registry.is_empty());
+        assert_eq!(registry.len(), 0);
+        assert_eq!(registry.dir(), Path::new(&dir));
+        assert!(!Path::new(&dir).exists());
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let dir = test_dir("test_save_and_load_round_trip");
+        let mut registry = Registry::load(&dir).unwrap();
+        registry.insert("/a/".to_string(), format!("{dir}/x.html"));
+        registry.insert("/b/".to_string(), format!("{dir}/y.html"));
+        registry.save().unwrap();
+
+        let loaded = Registry::load(&dir).unwrap();
+        assert_eq!(loaded, registry);
+        assert_eq!(loaded.get("/a/"), Some(format!("{dir}/x.html").as_str()));
+        assert!(loaded.contains_target("/b/"));
+        assert!(!loaded.contains_target("/c/"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_save_all() {
+        let dir1 = test_dir("test_save_all_one");
+        let dir2 = test_dir("test_save_all_two");
+        let mut registry1 = Registry::load(&dir1).unwrap();
+        registry1.insert("/a/".to_string(), format!("{dir1}/x.html"));
+        let mut registry2 = Registry::load(&dir2).unwrap();
+        registry2.insert("/b/".to_string(), format!("{dir2}/y.html"));
+
+        Registry::save_all([&registry1, &registry2]).unwrap();
+
+        assert_eq!(Registry::load(&dir1).unwrap(), registry1);
+        assert_eq!(Registry::load(&dir2).unwrap(), registry2);
+        // No temporary files are left behind
+        assert_eq!(fs::read_dir(&dir1).unwrap().count(), 1);
+
+        fs::remove_dir_all(&dir1).unwrap();
+        fs::remove_dir_all(&dir2).unwrap();
+    }
+
+    #[test]
+    fn test_iter() {
+        let mut registry = Registry::default();
+        registry.insert("/a/".to_string(), "s/abc.html".to_string());
+        registry.insert("/b/".to_string(), "s/def.html".to_string());
+
+        let mut items: Vec<(&str, &str, &Path)> = registry.iter().collect();
+        items.sort();
+
+        assert_eq!(
+            items,
+            vec![
+                ("abc", "/a/", Path::new("s/abc.html")),
+                ("def", "/b/", Path::new("s/def.html")),
+            ]
+        );
+        assert_eq!(registry.iter().len(), 2);
+    }
+
+    #[test]
+    fn test_into_iterator_for_reference() {
+        let mut registry = Registry::default();
+        registry.insert("/a/".to_string(), "s/abc.html".to_string());
+
+        let mut count = 0;
+        for (short_name, target, file_path) in &registry {
+            assert_eq!(short_name, "abc");
+            assert_eq!(target, "/a/");
+            assert_eq!(file_path, Path::new("s/abc.html"));
+            count += 1;
+        }
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn test_iter_empty() {
+        let registry = Registry::default();
+        assert_eq!(registry.iter().next(), None);
+    }
+
+    #[test]
+    fn test_target_for() {
+        let mut registry = Registry::default();
+        registry.insert("/a/".to_string(), "s/abc.html".to_string());
+
+        assert_eq!(registry.target_for("abc"), Some("/a/"));
+        assert_eq!(registry.target_for("xyz"), None);
+    }
+
+    #[test]
+    fn test_short_name_index_follows_changes() {
+        let dir = test_dir("test_short_name_index_follows_changes");
+        Redirector::builder()
+            .target("a/path")
+            .out_dir(&dir)
+            .alias("link")
+            .build()
+            .unwrap()
+            .write_redirect()
+            .unwrap();
+
+        // The index is rebuilt when the registry is loaded
+        let mut registry = Registry::load(&dir).unwrap();
+        assert!(registry.contains_short_name("link"));
+        assert_eq!(registry.target_for("link"), Some("/a/path/"));
+
+        registry.update_target("link", "b/path").unwrap();
+        assert_eq!(registry.target_for("link"), Some("/b/path/"));
+
+        // Moving a target to a new file drops its old short name
+        registry.insert("/b/path/".to_string(), format!("{dir}/moved.html"));
+        assert!(!registry.contains_short_name("link"));
+        assert_eq!(registry.target_for("moved"), Some("/b/path/"));
+
+        registry.remove("moved").unwrap();
+        assert!(!registry.contains_short_name("moved"));
+        assert_eq!(registry, Registry::empty(PathBuf::from(&dir)));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_update_target() {
+        let dir = test_dir("test_update_target");
+        let redirector = Redirector::builder()
+            .target("old/path")
+            .out_dir(&dir)
+            .alias("link")
+            .build()
+            .unwrap();
+        let file_path = redirector.write_redirect().unwrap().into_file_path();
+
+        let mut registry = Registry::load(&dir).unwrap();
+        let previous = registry.update_target("link", "new/path").unwrap();
+
+        assert_eq!(previous, "/old/path/");
+        assert!(!registry.contains_target("/old/path/"));
+        assert_eq!(registry.get("/new/path/"), file_path.to_str());
+
+        let content = fs::read_to_string(&file_path).unwrap();
+        assert!(content.contains("url=/new/path/"));
+        assert!(!content.contains("/old/path/"));
+
+        // The registry on disk is unchanged until saved
+        assert!(Registry::load(&dir).unwrap().contains_target("/old/path/"));
+        registry.save().unwrap();
+        assert!(Registry::load(&dir).unwrap().contains_target("/new/path/"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_update_target_with_keeps_options() {
+        let dir = test_dir("test_update_target_with_keeps_options");
+        let build = |target: &str| {
+            Redirector::builder()
+                .target(target)
+                .out_dir(&dir)
+                .beacon("https://example.com/hit")
+                .confirm()
+                .build()
+                .unwrap()
+        };
+        let outcome = build("old/path").write_redirect().unwrap();
+        let short_name = outcome.short_file().trim_end_matches(".html").to_string();
+
+        let mut registry = Registry::load(&dir).unwrap();
+        let previous = registry
+            .update_target_with(&short_name, &build("new/path"))
+            .unwrap();
+        assert_eq!(previous, "/old/path/");
+        assert_eq!(registry.target_for(&short_name), Some("/new/path/"));
+
+        let content = fs::read_to_string(outcome.file_path()).unwrap();
+        assert!(!content.contains("/old/path/"));
+        assert!(content.contains("/new/path/"));
+        assert!(content.contains("<form id=\"gate\">"));
+        assert!(content.contains(&format!("\"https://example.com/hit\", \"{short_name}\"")));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_update_target_same_target_rewrites_file() {
+        let dir = test_dir("test_update_target_same_target_rewrites_file");
+        let redirector = Redirector::builder()
+            .target("a/path")
+            .out_dir(&dir)
+            .alias("link")
+            .build()
+            .unwrap();
+        let file_path = redirector.write_redirect().unwrap().into_file_path();
+        fs::write(&file_path, "corrupted").unwrap();
+
+        let mut registry = Registry::load(&dir).unwrap();
+        registry.update_target("link", "a/path").unwrap();
+
+        assert!(fs::read_to_string(&file_path)
+            .unwrap()
+            .contains("url=/a/path/"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_update_target_unknown_short_name() {
+        let mut registry = Registry::default();
+        let result = registry.update_target("missing", "a/b");
+        assert!(matches!(result, Err(RedirectorError::ShortLinkNotFound)));
+    }
+
+    #[test]
+    fn test_update_target_invalid_target() {
+        let mut registry = Registry::default();
+        registry.insert("/a/".to_string(), "s/abc.html".to_string());
+
+        let result = registry.update_target("abc", "bad?target");
+        assert!(matches!(result, Err(RedirectorError::InvalidUrlPath(_))));
+        assert!(registry.contains_target("/a/"));
+    }
+
+    #[test]
+    fn test_update_target_duplicate_target() {
+        let mut registry = Registry::default();
+        registry.insert("/a/".to_string(), "s/abc.html".to_string());
+        registry.insert("/b/".to_string(), "s/def.html".to_string());
+
+        let result = registry.update_target("abc", "b");
+        match result {
+            Err(RedirectorError::DuplicateTarget(target)) => assert_eq!(target, "/b/"),
+            other => panic!("expected DuplicateTarget, got {other:?}"),
+        }
+        assert_eq!(registry.get("/a/"), Some("s/abc.html"));
+    }
+
+    #[test]
+    fn test_remove() {
+        let dir = test_dir("test_remove");
+        let redirector = Redirector::builder()
+            .target("a/path")
+            .out_dir(&dir)
+            .alias("link")
+            .build()
+            .unwrap();
+        let file_path = redirector.write_redirect().unwrap().into_file_path();
+
+        let mut registry = Registry::load(&dir).unwrap();
+        assert_eq!(registry.remove("link").unwrap(), "/a/path/");
+
+        assert!(registry.is_empty());
+        assert!(!file_path.exists());
+
+        // The registry on disk is unchanged until saved
+        assert_eq!(Registry::load(&dir).unwrap().len(), 1);
+        registry.save().unwrap();
+        assert!(Registry::load(&dir).unwrap().is_empty());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_rename() {
+        let dir = test_dir("test_rename");
+        Redirector::builder()
+            .target("a/path")
+            .out_dir(&dir)
+            .alias("link")
+            .text_sidecar()
+            .json_sidecar()
+            .build()
+            .unwrap()
+            .write_redirect()
+            .unwrap();
+        let mut registry = Registry::load(&dir).unwrap();
+        registry.add_alias("/a/path/", "extra").unwrap();
+        let dir = PathBuf::from(dir);
+
+        assert_eq!(registry.rename("link", "moved").unwrap(), "/a/path/");
+        let entry = registry.entry("/a/path/").unwrap();
+        assert_eq!(entry.short_file(), "moved.html");
+        assert_eq!(entry.path(), dir.join("moved.html"));
+        assert!(!registry.contains_short_name("link"));
+        assert!(!dir.join("link.html").exists());
+        assert!(fs::read_to_string(dir.join("moved.html"))
+            .unwrap()
+            .contains("/a/path/"));
+        // The sidecars follow the page, the metadata naming the new short name
+        assert!(!dir.join("link.txt").exists());
+        assert!(!dir.join("link.json").exists());
+        assert_eq!(
+            fs::read_to_string(dir.join("moved.txt")).unwrap(),
+            "/a/path/\n"
+        );
+        let metadata: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(dir.join("moved.json")).unwrap()).unwrap();
+        assert_eq!(metadata["short_name"], "moved");
+        assert_eq!(metadata["target"], "/a/path/");
+        // The alias stub follows the page
+        assert!(fs::read_to_string(dir.join("extra.html"))
+            .unwrap()
+            .contains("url=moved.html"));
+
+        // Renaming an alias moves only its stub
+        registry.rename("extra", "bonus").unwrap();
+        assert_eq!(registry.entry("/a/path/").unwrap().aliases(), ["bonus"]);
+        assert!(!dir.join("extra.html").exists());
+        assert!(dir.join("bonus.html").exists());
+
+        registry.rename_keeping_old("moved", "final").unwrap();
+        assert_eq!(
+            registry.entry("/a/path/").unwrap().aliases(),
+            ["bonus", "moved"]
+        );
+        assert_eq!(registry.target_for("moved"), Some("/a/path/"));
+        assert!(fs::read_to_string(dir.join("moved.html"))
+            .unwrap()
+            .contains("url=final.html"));
+
+        registry.save().unwrap();
+        assert_eq!(
+            Registry::load(&dir).unwrap().target_for("final"),
+            Some("/a/path/")
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "social-preview")]
+    #[test]
+    fn test_rename_moves_preview() {
+        use crate::SocialPreview;
+
+        for layout in [OutputLayout::File, OutputLayout::Directory] {
+            let dir = test_dir("test_rename_moves_preview");
+            let old_page = Redirector::builder()
+                .target("a/path")
+                .out_dir(&dir)
+                .alias("link")
+                .layout(layout)
+                .social_preview(SocialPreview::new("https://example.com/s"))
+                .build()
+                .unwrap()
+                .write_redirect()
+                .unwrap()
+                .into_file_path();
+            let mut registry = Registry::load(&dir).unwrap();
+
+            registry.rename("link", "moved").unwrap();
+            let new_page = registry.entry("/a/path/").unwrap().path().to_path_buf();
+            assert!(!image_path(&old_page).exists(), "{layout:?}");
+            let image = fs::read_to_string(image_path(&new_page)).unwrap();
+            assert!(image.contains(">example.com/s/moved</text>"), "{layout:?}");
+            assert!(!image.contains("link"), "{layout:?}");
+            let image_url = match layout {
+                OutputLayout::File => "https://example.com/s/moved.svg",
+                OutputLayout::Directory => "https://example.com/s/moved/index.svg",
+            };
+            assert!(
+                fs::read_to_string(&new_page).unwrap().contains(&format!(
+                    r#"<meta property="og:image" content="{image_url}">"#
+                )),
+                "{layout:?}"
+            );
+
+            fs::remove_dir_all(&dir).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_rename_errors() {
+        let dir = test_dir("test_rename_errors");
+        for (target, alias) in [("a", "first"), ("b", "second")] {
+            Redirector::builder()
+                .target(target)
+                .out_dir(&dir)
+                .alias(alias)
+                .build()
+                .unwrap()
+                .write_redirect()
+                .unwrap();
+        }
+        fs::write(Path::new(&dir).join("taken.html"), "hand-written").unwrap();
+        let mut registry = Registry::load(&dir).unwrap();
+
+        assert!(matches!(
+            registry.rename("missing", "other"),
+            Err(RedirectorError::ShortLinkNotFound)
+        ));
+        assert!(matches!(
+            registry.rename("first", "second"),
+            Err(RedirectorError::DuplicateShortName(_))
+        ));
+        assert!(matches!(
+            registry.rename("first", "../up"),
+            Err(RedirectorError::InvalidAlias(_))
+        ));
+        assert!(matches!(
+            registry.rename("first", "taken"),
+            Err(RedirectorError::FileWriteError { .. })
+        ));
+        assert!(registry.contains_short_name("first"));
+        assert!(Path::new(&dir).join("first.html").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_rename_directory_layout() {
+        let dir = test_dir("test_rename_directory_layout");
+        Redirector::builder()
+            .target("a/path")
+            .out_dir(&dir)
+            .alias("link")
+            .layout(OutputLayout::Directory)
+            .build()
+            .unwrap()
+            .write_redirect()
+            .unwrap();
+        let mut registry = Registry::load(&dir).unwrap();
+
+        registry.rename("link", "moved").unwrap();
+        let dir = PathBuf::from(dir);
+        assert_eq!(registry.entry("/a/path/").unwrap().short_file(), "moved/");
+        assert!(dir.join("moved/index.html").exists());
+        assert!(!dir.join("link").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_remove_missing_file_and_unknown_name() {
+        let mut registry = Registry::default();
+        registry.insert(
+            "/a/".to_string(),
+            "test_remove_missing/abc.html".to_string(),
+        );
+
+        assert!(matches!(
+            registry.remove("unknown"),
+            Err(RedirectorError::ShortLinkNotFound)
+        ));
+        assert_eq!(registry.remove("abc").unwrap(), "/a/");
+        assert!(registry.is_empty());
+    }
+
+    #[test]
+    fn test_load_corrupted_registry() {
+        let dir = test_dir("test_load_corrupted_registry");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(Path::new(&dir).join(REDIRECT_REGISTRY), "not json").unwrap();
+
+        let result = Registry::load(&dir);
+        assert!(matches!(
+            result,
+            Err(RedirectorError::FailedToReadRegistry { .. })
+        ));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_legacy_registry_format() {
+        let dir = test_dir("test_load_legacy_registry_format");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            Path::new(&dir).join(REDIRECT_REGISTRY),
+            r#"{"/some/path/": "s/abc.html"}"#,
+        )
+        .unwrap();
+
+        let registry = Registry::load(&dir).unwrap();
+        assert_eq!(
+            registry.get("/some/path/"),
+            Path::new(&dir).join("abc.html").to_str()
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_registry_with_windows_separators() {
+        let registry = Registry::from_reader(
+            PathBuf::from("s"),
+            br#"{"/a/": "s\\abc.html", "/b/": "out\\s/def.html"}"#.as_slice(),
+        )
+        .unwrap();
+
+        assert_eq!(registry.target_for("abc"), Some("/a/"));
+        assert_eq!(registry.target_for("def"), Some("/b/"));
+        let short_names: Vec<&str> = registry.iter().map(|(name, _, _)| name).collect();
+        assert_eq!(short_names, ["abc", "def"]);
+    }
+
+    #[test]
+    fn test_load_registry_with_repeated_target() {
+        let registry = Registry::from_reader(
+            PathBuf::from("s"),
+            br#"{"/a/": "s/old.html", "/b/": "s/b.html", "/a/": "s/new.html"}"#.as_slice(),
+        )
+        .unwrap();
+
+        assert_eq!(registry.len(), 2);
+        assert_eq!(registry.target_for("new"), Some("/a/"));
+        assert_eq!(registry.target_for("old"), None);
+        assert_eq!(registry.target_for("b"), Some("/b/"));
+    }
+
+    #[test]
+    fn test_load_legacy_registry() {
+        let registry = Registry::from_reader(
+            PathBuf::from("s"),
+            br#"{"/a/": "s\\abc.html", "/b/": {"path": "s/def.html"}}"#.as_slice(),
+        )
+        .unwrap();
+
+        let entry = registry.entry("/a/").unwrap();
+        assert_eq!(entry.short_file(), "abc.html");
+        assert_eq!(entry.short_name(), "abc");
+        assert_eq!(entry.path(), Path::new("s/abc.html"));
+        assert_eq!(entry.created_at, None);
+        assert_eq!(registry.get("/b/"), Some("s/def.html"));
+        assert_eq!(registry.entry("/b/").unwrap().short_file(), "def.html");
+
+        let mut json = Vec::new();
+        registry.write_json(&mut json).unwrap();
+        let reloaded = Registry::from_reader(PathBuf::from("s"), json.as_slice()).unwrap();
+        assert_eq!(reloaded, registry);
+    }
+
+    #[test]
+    fn test_entry_campaign_and_tags() {
+        let registry = Registry::from_reader(
+            PathBuf::from("s"),
+            br#"{
+                "/a/": {"path": "s/a.html", "campaign": "launch", "tags": ["web", "ads", "web"]},
+                "/b/": {"path": "s/b.html", "tags": ["docs"]},
+                "/c/": "s/c.html"
+            }"#
+            .as_slice(),
+        )
+        .unwrap();
+
+        let entry = registry.entry("/a/").unwrap();
+        assert_eq!(entry.campaign(), Some("launch"));
+        assert_eq!(entry.tags(), ["ads", "web"]);
+        assert!(entry.has_tag("web"));
+        assert!(!entry.has_tag("docs"));
+        assert!(registry.entry("/c/").unwrap().tags().is_empty());
+
+        let web = registry.tagged("web");
+        assert_eq!(web.len(), 1);
+        assert_eq!(web.target_for("a"), Some("/a/"));
+        assert_eq!(web.dir(), Path::new("s"));
+        assert!(registry.tagged("none").is_empty());
+
+        let mut json = Vec::new();
+        registry.write_json(&mut json).unwrap();
+        let json = String::from_utf8(json).unwrap();
+        assert!(json.contains(
+            "\"path\": \"s/a.html\",\n    \"campaign\": \"launch\",\n    \"tags\": [\"ads\",\"web\"]"
+        ));
+        let reloaded = Registry::from_reader(PathBuf::from("s"), json.as_bytes()).unwrap();
+        assert_eq!(reloaded, registry);
+
+        for invalid in [
+            r#"{"/a/": {"path": "a.html", "tags": "web"}}"#,
+            r#"{"/a/": {"path": "a.html", "tags": [1]}}"#,
+        ] {
+            assert!(Registry::from_reader(PathBuf::from("s"), invalid.as_bytes()).is_err());
+        }
+    }
+
+    #[test]
+    fn test_load_registry_invalid_entry() {
+        for json in [
+            r#"{"/a/": 1}"#,
+            r#"{"/a/": {"short_file": "a.html"}}"#,
+            r#"{"/a/": {"path": "s/a.html", "created_at": "yesterday"}}"#,
+        ] {
+            let error = Registry::from_reader(PathBuf::from("s"), json.as_bytes()).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+            assert!(error.to_string().contains("/a/"));
+        }
+    }
+
+    #[test]
+    fn test_short_name() {
+        assert_eq!(short_name("s/promo.html"), "promo");
+        assert_eq!(short_name("s\\promo.html"), "promo");
+        assert_eq!(short_name("promo"), "promo");
+        assert_eq!(file_name("out/s\\promo.html"), "promo.html");
+    }
+
+    #[test]
+    fn test_get_ignore_case() {
+        let mut registry = Registry::empty(PathBuf::from("s"));
+        registry.insert("/Docs/Install/".to_string(), "s/a.html".to_string());
+        registry.insert("/docs/install/".to_string(), "s/b.html".to_string());
+        registry.insert("/Straße/".to_string(), "s/c.html".to_string());
+
+        assert_eq!(
+            registry.get_ignore_case("/docs/install/"),
+            Some(("/docs/install/", "s/b.html"))
+        );
+        assert_eq!(
+            registry.get_ignore_case("/DOCS/INSTALL/"),
+            Some(("/Docs/Install/", "s/a.html"))
+        );
+        assert_eq!(
+            registry.get_ignore_case("/straße/"),
+            Some(("/Straße/", "s/c.html"))
+        );
+        assert_eq!(registry.get_ignore_case("/docs/"), None);
+    }
+
+    #[test]
+    fn test_write_json_is_sorted_and_stable() {
+        let mut registry = Registry::empty(PathBuf::from("s"));
+        registry.insert("/c/".to_string(), "s/c.html".to_string());
+        registry.insert("/a/".to_string(), "s/a.html".to_string());
+        registry.insert("/b/".to_string(), "s/b.html".to_string());
+        let created_at = Timestamp::parse_rfc3339("2024-05-06T07:08:09Z").unwrap();
+        for entry in registry.entries.values_mut() {
+            entry.created_at = Some(created_at);
+        }
+        registry.entries.get_mut("/b/").unwrap().created_at = None;
+
+        let mut json = Vec::new();
+        registry.write_json(&mut json).unwrap();
+        let json = String::from_utf8(json).unwrap();
+        assert_eq!(
+            json,
+            r#"{
+  "/a/": {
+    "short_file": "a.html",
+    "path": "s/a.html",
+    "created_at": "2024-05-06T07:08:09Z"
+  },
+  "/b/": {
+    "short_file": "b.html",
+    "path": "s/b.html"
+  },
+  "/c/": {
+    "short_file": "c.html",
+    "path": "s/c.html",
+    "created_at": "2024-05-06T07:08:09Z"
+  }
+}
+"#
+        );
+
+        let reloaded = Registry::from_reader(PathBuf::from("s"), json.as_bytes()).unwrap();
+        assert_eq!(reloaded, registry);
+
+        let targets: Vec<&str> = registry.iter().map(|(_, target, _)| target).collect();
+        assert_eq!(targets, ["/a/", "/b/", "/c/"]);
+    }
+}
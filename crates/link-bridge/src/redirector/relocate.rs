@@ -0,0 +1,281 @@
+//! Moving an output directory along with its registry.
+//!
+//! Each registry entry records the path its page was written to, including the
+//! output directory, so moving the directory by hand leaves the registry pointing at
+//! the old location and later removals and renames miss the pages. This module
+//! provides `Registry::relocate()`, which moves the pages and rewrites the recorded
+//! paths together.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::redirector::bloom::TARGET_FILTER;
+use crate::redirector::registry::REDIRECT_REGISTRY;
+use crate::redirector::sidecar::{metadata_path, sidecar_path};
+use crate::redirector::sink::{FsSink, OutputSink};
+#[cfg(feature = "social-preview")]
+use crate::redirector::social::image_path;
+use crate::redirector::{trace, OutputLayout, RedirectorError, Registry};
+
+impl Registry {
+    /// Moves an output directory's pages and registry to another directory.
+    ///
+    /// Every redirect page and alias stub recorded in the registry is moved to the
+    /// same place below `new_dir`, the recorded paths are rewritten, and the registry
+    /// is saved in `new_dir` and deleted from `old_dir`. Files the registry does not
+    /// know about are left where they are, and `old_dir` is removed once it is empty.
+    /// A page that has already been deleted is not an error.
+    ///
+    /// If `old_dir` has no registry but `new_dir` does, the directory is taken to
+    /// have been moved by hand already, and only the recorded paths are rewritten.
+    ///
+    /// # Arguments
+    ///
+    /// * `old_dir` - The output directory the registry was written in (e.g. `"s"`)
+    /// * `new_dir` - The output directory to move it to (e.g. `"static/s"`)
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Registry)` - The registry in its new directory
+    /// * `Err(RedirectorError::FailedToReadRegistry)` - If a registry cannot be read
+    /// * `Err(RedirectorError::FileWriteError)` - If `new_dir` already has a registry
+    ///   as well as `old_dir`, or a page cannot be moved; the pages moved before it
+    ///   stay in `new_dir`
+    /// * `Err(RedirectorError::DirectoryCreationError)` - If a directory in `new_dir`
+    ///   cannot be created
+    /// * `Err(RedirectorError::FailedToWriteRegistry)` - If the registry cannot be
+    ///   saved in `new_dir`
+    /// * `Err(RedirectorError::FileRemovalError)` - If the registry cannot be deleted
+    ///   from `old_dir`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::{Redirector, Registry};
+    /// use std::fs;
+    /// use std::path::Path;
+    ///
+    /// Redirector::builder()
+    ///     .target("summer/sale")
+    ///     .out_dir("doc_test_registry_relocate/s")
+    ///     .alias("promo")
+    ///     .build()
+    ///     .unwrap()
+    ///     .write_redirect()
+    ///     .unwrap();
+    ///
+    /// let registry = Registry::relocate(
+    ///     "doc_test_registry_relocate/s",
+    ///     "doc_test_registry_relocate/static/s",
+    /// )
+    /// .unwrap();
+    ///
+    /// let entry = registry.entry("/summer/sale/").unwrap();
+    /// assert_eq!(
+    ///     entry.path(),
+    ///     Path::new("doc_test_registry_relocate/static/s/promo.html")
+    /// );
+    /// assert!(entry.path().exists());
+    /// assert!(!Path::new("doc_test_registry_relocate/s").exists());
+    ///
+    /// fs::remove_dir_all("doc_test_registry_relocate").ok();
+    /// ```
+    pub fn relocate<P, Q>(old_dir: P, new_dir: Q) -> Result<Registry, RedirectorError>
+    where
+        P: Into<PathBuf>,
+        Q: Into<PathBuf>,
+    {
+        let old_dir = old_dir.into();
+        let new_dir = new_dir.into();
+        let old_registry = old_dir.join(REDIRECT_REGISTRY);
+        let new_registry = new_dir.join(REDIRECT_REGISTRY);
+
+        let moving = old_registry.exists();
+        let mut registry = if moving {
+            if new_registry.exists() {
+                return Err(RedirectorError::FileWriteError {
+                    path: new_registry,
+                    source: io::Error::new(io::ErrorKind::AlreadyExists, "file already exists"),
+                });
+            }
+            Registry::load(&old_dir)?
+        } else {
+            Registry::load(&new_dir)?
+        };
+
+        let targets: Vec<String> = registry
+            .iter()
+            .map(|(_, target, _)| target.to_string())
+            .collect();
+        for target in targets {
+            let Some(entry) = registry.entry_mut(&target) else {
+                continue;
+            };
+            let layout = OutputLayout::of(entry.short_file());
+            let new_path = layout.file_path(&new_dir, entry.short_name());
+            if moving {
+                move_page(entry.path(), &new_path, layout)?;
+                for alias in entry.aliases() {
+                    move_page(
+                        &layout.file_path(&old_dir, alias),
+                        &layout.file_path(&new_dir, alias),
+                        layout,
+                    )?;
+                }
+            }
+            entry.set_path(&new_path);
+        }
+
+        registry.set_dir(new_dir);
+        registry.save()?;
+        let old_filter = old_dir.join(TARGET_FILTER);
+        if moving && old_filter.exists() {
+            registry.save_filter()?;
+            fs::remove_file(&old_filter).map_err(|source| RedirectorError::FileRemovalError {
+                path: old_filter,
+                source,
+            })?;
+        }
+        if moving {
+            fs::remove_file(&old_registry).map_err(|source| RedirectorError::FileRemovalError {
+                path: old_registry,
+                source,
+            })?;
+            fs::remove_dir(&old_dir).ok();
+        }
+        trace::debug!(
+            from = %old_dir.display(),
+            to = %registry.dir().display(),
+            entries = registry.len(),
+            "relocated registry"
+        );
+
+        Ok(registry)
+    }
+}
+
+/// Moves a page to its new path, and removes the directory of a page written as a
+/// directory's index once it is empty. A page that does not exist is skipped.
+fn move_page(from: &Path, to: &Path, layout: OutputLayout) -> Result<(), RedirectorError> {
+    if !from.exists() {
+        return Ok(());
+    }
+    if let Some(dir) = to.parent() {
+        FsSink
+            .create_dir_all(dir)
+            .map_err(|source| RedirectorError::DirectoryCreationError {
+                path: dir.to_path_buf(),
+                source,
+            })?;
+    }
+    fs::rename(from, to).map_err(|source| RedirectorError::FileWriteError {
+        path: to.to_path_buf(),
+        source,
+    })?;
+    #[cfg(feature = "social-preview")]
+    fs::rename(image_path(from), image_path(to)).ok();
+    fs::rename(sidecar_path(from), sidecar_path(to)).ok();
+    fs::rename(metadata_path(from), metadata_path(to)).ok();
+    if layout == OutputLayout::Directory {
+        if let Some(dir) = from.parent() {
+            fs::remove_dir(dir).ok();
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Redirector;
+    use chrono::Utc;
+
+    fn test_dir(name: &str) -> PathBuf {
+        PathBuf::from(format!(
+            "{name}_{}",
+            Utc::now().timestamp_nanos_opt().unwrap_or(0)
+        ))
+    }
+
+    fn write(dir: &Path, target: &str, alias: &str, layout: OutputLayout) {
+        Redirector::builder()
+            .target(target)
+            .out_dir(dir)
+            .alias(alias)
+            .layout(layout)
+            .build()
+            .unwrap()
+            .write_redirect()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_relocate() {
+        let dir = test_dir("test_relocate");
+        let old_dir = dir.join("s");
+        let new_dir = dir.join("static").join("s");
+        write(&old_dir, "a", "first", OutputLayout::File);
+        write(&old_dir, "b", "second", OutputLayout::Directory);
+        let mut registry = Registry::load(&old_dir).unwrap();
+        registry.add_alias("/a/", "extra").unwrap();
+        registry.save().unwrap();
+        fs::write(old_dir.join("notes.txt"), "kept").unwrap();
+
+        let registry = Registry::relocate(&old_dir, &new_dir).unwrap();
+        assert_eq!(registry.dir(), new_dir);
+        assert_eq!(
+            registry.entry("/a/").unwrap().path(),
+            new_dir.join("first.html")
+        );
+        assert_eq!(
+            registry.entry("/b/").unwrap().path(),
+            new_dir.join("second").join("index.html")
+        );
+        for moved in ["first.html", "extra.html", "second/index.html"] {
+            assert!(new_dir.join(moved).exists(), "{moved} was not moved");
+        }
+        assert_eq!(Registry::load(&new_dir).unwrap(), registry);
+
+        // Unknown files stay behind, so the old directory is kept
+        assert!(!old_dir.join(REDIRECT_REGISTRY).exists());
+        assert!(!old_dir.join("second").exists());
+        assert!(old_dir.join("notes.txt").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_relocate_after_manual_move() {
+        let dir = test_dir("test_relocate_manual");
+        let old_dir = dir.join("s");
+        let new_dir = dir.join("t");
+        write(&old_dir, "a", "first", OutputLayout::File);
+        fs::rename(&old_dir, &new_dir).unwrap();
+
+        let registry = Registry::relocate(&old_dir, &new_dir).unwrap();
+        let entry = registry.entry("/a/").unwrap();
+        assert_eq!(entry.path(), new_dir.join("first.html"));
+        assert!(entry.path().exists());
+        assert_eq!(Registry::load(&new_dir).unwrap(), registry);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_relocate_refuses_existing_registry() {
+        let dir = test_dir("test_relocate_existing");
+        let old_dir = dir.join("s");
+        let new_dir = dir.join("t");
+        write(&old_dir, "a", "first", OutputLayout::File);
+        write(&new_dir, "b", "second", OutputLayout::File);
+
+        assert!(matches!(
+            Registry::relocate(&old_dir, &new_dir),
+            Err(RedirectorError::FileWriteError { .. })
+        ));
+        assert!(old_dir.join("first.html").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
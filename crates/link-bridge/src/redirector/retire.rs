@@ -0,0 +1,209 @@
+//! Redirects retired in place of deletion.
+//!
+//! Deleting a redirect makes its short link fail with a 404, which looks like a
+//! mistake, and frees the short name to lead somewhere else later. This module
+//! provides `Registry::retire()`, which replaces a redirect's page with a "this
+//! content is gone" tombstone and marks its entry retired, so the short link keeps
+//! answering and tells visitors the content will not be coming back.
+
+use std::fs;
+
+use crate::redirector::sidecar::{refresh_metadata, sidecar_path};
+use crate::redirector::sink::{FsSink, OutputSink};
+use crate::redirector::{trace, RedirectorError, Registry};
+
+/// The page written in place of a redirect once it has been retired.
+///
+/// Used by [`Registry::retire()`], and when the page of a retired redirect is written
+/// again after being deleted, unless another page is passed to
+/// [`Registry::retire_with()`].
+pub const DEFAULT_RETIRED_PAGE: &str = r#"<!DOCTYPE HTML>
+<html lang="en-US">
+
+<head>
+    <meta charset="UTF-8">
+    <meta name="robots" content="noindex">
+    <title>Content Gone</title>
+</head>
+
+<body>
+    This content is gone and will not be coming back.
+</body>
+
+</html>
+"#;
+
+impl Registry {
+    /// Retires the redirect for a target.
+    ///
+    /// The redirect's page is replaced with [`DEFAULT_RETIRED_PAGE`] and its entry is
+    /// marked retired in memory; call [`Registry::save()`] to persist it. Unlike
+    /// [`Registry::remove()`], the entry stays in the registry, so the short link keeps
+    /// answering and its short name is never reused. The `axum` router and the preview
+    /// server answer retired short links with `410 Gone`.
+    ///
+    /// Retiring a redirect again rewrites its page but keeps the original retirement
+    /// time. Repointing it with [`Registry::update_target()`] brings it back into use.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - The registered target, as normalized (e.g. `"/summer/sale/"`)
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The short name of the retired redirect
+    /// * `Err(RedirectorError::ShortLinkNotFound)` - If no redirect has that target
+    /// * `Err(RedirectorError::FileWriteError)` - If the page cannot be replaced; the
+    ///   entry is left unchanged
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::{Redirector, Registry};
+    /// use std::fs;
+    ///
+    /// let outcome = Redirector::builder()
+    ///     .target("summer/sale")
+    ///     .out_dir("doc_test_registry_retire")
+    ///     .alias("promo")
+    ///     .build()
+    ///     .unwrap()
+    ///     .write_redirect()
+    ///     .unwrap();
+    ///
+    /// let mut registry = Registry::load("doc_test_registry_retire").unwrap();
+    /// assert_eq!(registry.retire("/summer/sale/").unwrap(), "promo");
+    /// registry.save().unwrap();
+    ///
+    /// assert!(registry.entry("/summer/sale/").unwrap().is_retired());
+    /// let page = fs::read_to_string(outcome.file_path()).unwrap();
+    /// assert!(page.contains("This content is gone"));
+    ///
+    /// fs::remove_dir_all("doc_test_registry_retire").ok();
+    /// ```
+    pub fn retire(&mut self, target: &str) -> Result<String, RedirectorError> {
+        self.retire_with(target, DEFAULT_RETIRED_PAGE)
+    }
+
+    /// Retires the redirect for a target, replacing its page with a custom page.
+    ///
+    /// Behaves like [`Registry::retire()`] but writes `page` instead of
+    /// [`DEFAULT_RETIRED_PAGE`].
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - The registered target, as normalized (e.g. `"/summer/sale/"`)
+    /// * `page` - The HTML written in place of the redirect
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The short name of the retired redirect
+    /// * `Err(RedirectorError::ShortLinkNotFound)` - If no redirect has that target
+    /// * `Err(RedirectorError::FileWriteError)` - If the page cannot be replaced
+    pub fn retire_with<S: AsRef<str>>(
+        &mut self,
+        target: &str,
+        page: S,
+    ) -> Result<String, RedirectorError> {
+        let entry = self
+            .entry_mut(target)
+            .ok_or(RedirectorError::ShortLinkNotFound)?;
+
+        let file_path = entry.path().to_path_buf();
+        FsSink
+            .write_file(&file_path, page.as_ref().as_bytes())
+            .map_err(|source| RedirectorError::FileWriteError {
+                path: file_path.clone(),
+                source,
+            })?;
+        fs::remove_file(sidecar_path(&file_path)).ok();
+        trace::debug!(target = %target, file = %file_path.display(), "retired redirect");
+
+        entry.retire();
+        refresh_metadata(&file_path, target, entry)?;
+        Ok(entry.short_name().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Redirector;
+    use chrono::Utc;
+    use std::fs;
+
+    fn test_dir(name: &str) -> String {
+        format!("{name}_{}", Utc::now().timestamp_nanos_opt().unwrap_or(0))
+    }
+
+    #[test]
+    fn test_retire() {
+        let dir = test_dir("test_retire");
+        let redirector = Redirector::builder()
+            .target("summer/sale")
+            .out_dir(&dir)
+            .alias("promo")
+            .build()
+            .unwrap();
+        let file_path = redirector.write_redirect().unwrap().into_file_path();
+
+        let mut registry = Registry::load(&dir).unwrap();
+        assert!(matches!(
+            registry.retire("/winter/sale/"),
+            Err(RedirectorError::ShortLinkNotFound)
+        ));
+        assert_eq!(
+            registry
+                .retire_with("/summer/sale/", "<p>Gone</p>")
+                .unwrap(),
+            "promo"
+        );
+        let retired_at = registry.entry("/summer/sale/").unwrap().retired_at;
+        assert!(retired_at.is_some());
+        registry.save().unwrap();
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), "<p>Gone</p>");
+
+        // The entry survives a round trip and keeps its short name
+        let registry = Registry::load(&dir).unwrap();
+        assert_eq!(registry.target_for("promo"), Some("/summer/sale/"));
+        assert_eq!(
+            registry.entry("/summer/sale/").unwrap().retired_at,
+            retired_at
+        );
+
+        // A deleted tombstone is written again, not the redirect
+        fs::remove_file(&file_path).unwrap();
+        let outcome = redirector.write_redirect().unwrap();
+        assert!(!outcome.is_created());
+        assert_eq!(
+            fs::read_to_string(&file_path).unwrap(),
+            DEFAULT_RETIRED_PAGE
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_update_target_unretires() {
+        let dir = test_dir("test_update_target_unretires");
+        Redirector::builder()
+            .target("summer/sale")
+            .out_dir(&dir)
+            .alias("promo")
+            .build()
+            .unwrap()
+            .write_redirect()
+            .unwrap();
+
+        let mut registry = Registry::load(&dir).unwrap();
+        registry.retire("/summer/sale/").unwrap();
+        registry.update_target("promo", "winter/sale").unwrap();
+
+        assert!(!registry.entry("/winter/sale/").unwrap().is_retired());
+        assert!(fs::read_to_string(format!("{dir}/promo.html"))
+            .unwrap()
+            .contains("url=/winter/sale/"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
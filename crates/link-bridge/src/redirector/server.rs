@@ -0,0 +1,178 @@
+//! Serving redirects dynamically with `axum`.
+//!
+//! The registry already records every short name and its target, so a web service can
+//! answer short-link requests with an HTTP redirect instead of serving the generated
+//! HTML pages. This module provides `Registry::router()`, which builds an `axum`
+//! router that does exactly that, letting the same registry power static files and a
+//! dynamic service.
+
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+
+use crate::redirector::{Registry, RegistryEntry};
+
+/// The HTTP status used for dynamic redirects.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RedirectStatus {
+    /// `301 Moved Permanently`. Browsers and proxies may cache the redirect
+    /// indefinitely, so later changes to the target may not be seen.
+    Permanent,
+    /// `302 Found`. The redirect is not cached, so a short link repointed with
+    /// [`Registry::update_target()`] takes effect immediately.
+    #[default]
+    Temporary,
+}
+
+impl RedirectStatus {
+    /// Returns the HTTP status code for the redirect.
+    fn status_code(self) -> StatusCode {
+        match self {
+            RedirectStatus::Permanent => StatusCode::MOVED_PERMANENTLY,
+            RedirectStatus::Temporary => StatusCode::FOUND,
+        }
+    }
+}
+
+/// The state shared by the redirect handler.
+#[derive(Debug)]
+struct RedirectState {
+    /// The registry redirects are looked up in.
+    registry: Registry,
+    /// The status of every redirect response.
+    status: RedirectStatus,
+}
+
+impl Registry {
+    /// Builds an `axum` router that answers `GET /{short_name}` with a redirect.
+    ///
+    /// Requests for a registered short name, with or without the `.html` extension
+    /// or a trailing slash, receive the chosen redirect status and a `Location` header holding the target.
+    /// Short names retired with [`Registry::retire()`] receive `410 Gone`, and unknown
+    /// short names receive `404 Not Found`. Nest the router to serve it below
+    /// a prefix, such as `/s`, matching the static redirect pages. Requires the `axum`
+    /// feature.
+    ///
+    /// The registry is consumed, so later changes to `registry.json` are not seen;
+    /// load the registry again and rebuild the router to pick them up.
+    ///
+    /// # Arguments
+    ///
+    /// * `status` - Whether redirects are permanent (301) or temporary (302)
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use axum::Router;
+    /// use link_bridge::{RedirectStatus, Redirector, Registry};
+    /// use std::fs;
+    ///
+    /// let redirector = Redirector::builder()
+    ///     .target("api/v1/users")
+    ///     .out_dir("doc_test_router")
+    ///     .alias("users")
+    ///     .build()
+    ///     .unwrap();
+    /// redirector.write_redirect().unwrap();
+    ///
+    /// // GET /s/users -> 302 Found, Location: /api/v1/users/
+    /// let registry = Registry::load("doc_test_router").unwrap();
+    /// let app: Router = Router::new().nest("/s", registry.router(RedirectStatus::Temporary));
+    ///
+    /// fs::remove_dir_all("doc_test_router").ok();
+    /// ```
+    pub fn router(self, status: RedirectStatus) -> Router {
+        let state = Arc::new(RedirectState {
+            registry: self,
+            status,
+        });
+
+        Router::new()
+            .route("/{short_name}", get(redirect))
+            .route("/{short_name}/", get(redirect))
+            .with_state(state)
+    }
+}
+
+/// Answers a short-link request from the registry.
+async fn redirect(
+    State(state): State<Arc<RedirectState>>,
+    Path(short_name): Path<String>,
+) -> Response {
+    let short_name = short_name.strip_suffix(".html").unwrap_or(&short_name);
+
+    match state.registry.target_for(short_name) {
+        Some(target)
+            if state
+                .registry
+                .entry(target)
+                .is_some_and(RegistryEntry::is_retired) =>
+        {
+            StatusCode::GONE.into_response()
+        }
+        Some(target) => (state.status.status_code(), [(header::LOCATION, target)]).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::Request;
+    use std::path::PathBuf;
+    use tower::ServiceExt;
+
+    fn registry() -> Registry {
+        let mut registry = Registry::empty(PathBuf::from("s"));
+        registry.insert("/docs/install/".to_string(), "s/install.html".to_string());
+        registry
+    }
+
+    async fn get_response(router: Router, uri: &str) -> Response {
+        let request = Request::get(uri).body(Body::empty()).unwrap();
+        router.oneshot(request).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_router_redirects() {
+        let router = registry().router(RedirectStatus::Temporary);
+
+        for uri in ["/install", "/install.html", "/install/"] {
+            let response = get_response(router.clone(), uri).await;
+            assert_eq!(response.status(), StatusCode::FOUND);
+            assert_eq!(response.headers()[header::LOCATION], "/docs/install/");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_router_permanent_nested() {
+        let router = Router::new().nest("/s", registry().router(RedirectStatus::Permanent));
+
+        let response = get_response(router, "/s/install").await;
+        assert_eq!(response.status(), StatusCode::MOVED_PERMANENTLY);
+        assert_eq!(response.headers()[header::LOCATION], "/docs/install/");
+    }
+
+    #[tokio::test]
+    async fn test_router_unknown_short_name() {
+        let router = registry().router(RedirectStatus::default());
+
+        let response = get_response(router, "/missing").await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_router_retired_short_name() {
+        let mut registry = registry();
+        registry.entry_mut("/docs/install/").unwrap().retire();
+        let router = registry.router(RedirectStatus::default());
+
+        let response = get_response(router, "/install").await;
+        assert_eq!(response.status(), StatusCode::GONE);
+    }
+}
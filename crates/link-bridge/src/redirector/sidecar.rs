@@ -0,0 +1,303 @@
+//! Files describing a redirect, written next to its page.
+//!
+//! Shell scripts, monitoring probes, and edge functions that want to know where a
+//! short link leads would otherwise have to parse the HTML of its page. This module
+//! provides the sidecars written next to each page: the plain-text file of
+//! `RedirectorBuilder::text_sidecar()`, `<name>.txt` or `<name>/index.txt`, holding
+//! only the target URL, which `curl https://example.com/s/promo.txt` resolves, and the
+//! JSON file of `RedirectorBuilder::json_sidecar()`, `<name>.json`, holding the
+//! redirect's metadata from the registry.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::redirector::json::object;
+use crate::redirector::sink::{FsSink, OutputSink};
+use crate::redirector::time::Timestamp;
+use crate::redirector::{Redirector, RedirectorError, Registry, RegistryEntry};
+
+/// Returns the path of the plain-text sidecar written next to a page.
+pub(crate) fn sidecar_path(file_path: &Path) -> PathBuf {
+    file_path.with_extension("txt")
+}
+
+/// Renders the contents of a sidecar: the target and a newline.
+pub(crate) fn sidecar_text(target: &str) -> String {
+    format!("{target}\n")
+}
+
+/// Returns the path of the JSON metadata sidecar written next to a page.
+pub(crate) fn metadata_path(file_path: &Path) -> PathBuf {
+    file_path.with_extension("json")
+}
+
+/// Renders the JSON metadata of a registered redirect.
+///
+/// Holds the short name, the target, and the tags, with the campaign and the times
+/// the redirect was created, expires, and was retired when they are set.
+pub(crate) fn metadata_json(target: &str, entry: &RegistryEntry) -> String {
+    let mut fields = vec![
+        ("short_name", entry.short_name().into()),
+        ("target", target.into()),
+        ("tags", entry.tags().into()),
+    ];
+    if let Some(campaign) = entry.campaign() {
+        fields.push(("campaign", campaign.into()));
+    }
+    for (field, time) in [
+        ("created_at", entry.created_at),
+        ("expires_at", entry.expires_at),
+        ("retired_at", entry.retired_at),
+    ] {
+        if let Some(time) = time {
+            fields.push((field, time.to_rfc3339().into()));
+        }
+    }
+    object(fields).to_string_pretty() + "\n"
+}
+
+/// Rewrites the JSON metadata next to the page at `file_path`, if the page was
+/// written with one.
+pub(crate) fn refresh_metadata(
+    file_path: &Path,
+    target: &str,
+    entry: &RegistryEntry,
+) -> Result<(), RedirectorError> {
+    let json_path = metadata_path(file_path);
+    if !json_path.exists() {
+        return Ok(());
+    }
+    FsSink
+        .write_file(&json_path, metadata_json(target, entry).as_bytes())
+        .map_err(|source| RedirectorError::FileWriteError {
+            path: json_path,
+            source,
+        })
+}
+
+/// Moves the sidecars of a page renamed from `old_page` to `new_page`.
+///
+/// The JSON metadata names the short name, so it is rewritten from the renamed
+/// `entry` rather than moved. Sidecars the page was not written with are not
+/// created.
+pub(crate) fn move_sidecars(
+    old_page: &Path,
+    new_page: &Path,
+    target: &str,
+    entry: &RegistryEntry,
+) -> Result<(), RedirectorError> {
+    let write_error =
+        |path: PathBuf| move |source| RedirectorError::FileWriteError { path, source };
+
+    let text_path = sidecar_path(old_page);
+    if text_path.exists() {
+        let new_text_path = sidecar_path(new_page);
+        fs::rename(&text_path, &new_text_path).map_err(write_error(new_text_path))?;
+    }
+    let json_path = metadata_path(old_page);
+    if json_path.exists() {
+        let new_json_path = metadata_path(new_page);
+        FsSink
+            .write_file(&new_json_path, metadata_json(target, entry).as_bytes())
+            .map_err(write_error(new_json_path))?;
+        fs::remove_file(&json_path).map_err(|source| RedirectorError::FileRemovalError {
+            path: json_path,
+            source,
+        })?;
+    }
+    Ok(())
+}
+
+impl Redirector {
+    /// Renders the plain-text sidecar written next to the page at `file_path`.
+    ///
+    /// Returns `None` if the redirect has no sidecar, or has already expired. The
+    /// sidecar of a split redirect names its first target.
+    pub(crate) fn sidecar(&self, file_path: &Path) -> Option<(PathBuf, String)> {
+        if !self.text_sidecar
+            || self
+                .expires_at
+                .is_some_and(|expires_at| expires_at <= Timestamp::now())
+        {
+            return None;
+        }
+        let target = self.target.resolve(self.base_url.as_ref());
+        Some((sidecar_path(file_path), sidecar_text(&target)))
+    }
+
+    /// Renders the JSON metadata written next to the page at `file_path`, from the
+    /// entry just recorded for `target` in the registry.
+    ///
+    /// Returns `None` if the redirect has no metadata sidecar.
+    pub(crate) fn metadata(
+        &self,
+        file_path: &Path,
+        registry: &Registry,
+        target: &str,
+    ) -> Option<(PathBuf, String)> {
+        if !self.json_sidecar {
+            return None;
+        }
+        let entry = registry.entry(target)?;
+        Some((metadata_path(file_path), metadata_json(target, entry)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MemorySink, OutputLayout, OutputSink, Registry};
+    use chrono::Utc;
+    use std::fs;
+
+    fn test_dir(name: &str) -> String {
+        format!("{name}_{}", Utc::now().timestamp_nanos_opt().unwrap_or(0))
+    }
+
+    #[test]
+    fn test_write_sidecar() {
+        let mut sink = MemorySink::new();
+        Redirector::builder()
+            .target("summer/sale")
+            .alias("promo")
+            .base_url("https://example.com")
+            .text_sidecar()
+            .build()
+            .unwrap()
+            .write_redirect_to(&mut sink)
+            .unwrap();
+        let text = sink.read_file(Path::new("s/promo.txt")).unwrap().unwrap();
+        assert_eq!(text, b"https://example.com/summer/sale/\n");
+
+        let mut sink = MemorySink::new();
+        Redirector::builder()
+            .target("winter/sale")
+            .alias("cold")
+            .layout(OutputLayout::Directory)
+            .text_sidecar()
+            .build()
+            .unwrap()
+            .write_redirect_to(&mut sink)
+            .unwrap();
+        let text = sink
+            .read_file(Path::new("s/cold/index.txt"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(text, b"/winter/sale/\n");
+
+        // Without the option, no sidecar is written
+        let mut sink = MemorySink::new();
+        Redirector::builder()
+            .target("summer/sale")
+            .alias("promo")
+            .build()
+            .unwrap()
+            .write_redirect_to(&mut sink)
+            .unwrap();
+        assert_eq!(sink.read_file(Path::new("s/promo.txt")).unwrap(), None);
+    }
+
+    #[test]
+    fn test_sidecar_follows_registry_changes() {
+        let dir = test_dir("test_sidecar_follows_registry_changes");
+        Redirector::builder()
+            .target("a")
+            .out_dir(&dir)
+            .alias("link")
+            .text_sidecar()
+            .build()
+            .unwrap()
+            .write_redirect()
+            .unwrap();
+        let sidecar = Path::new(&dir).join("link.txt");
+        assert_eq!(fs::read_to_string(&sidecar).unwrap(), "/a/\n");
+
+        let mut registry = Registry::load(&dir).unwrap();
+        registry.update_target("link", "b").unwrap();
+        assert_eq!(fs::read_to_string(&sidecar).unwrap(), "/b/\n");
+
+        registry.retire("/b/").unwrap();
+        assert!(!sidecar.exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_write_metadata() {
+        let mut sink = MemorySink::new();
+        Redirector::builder()
+            .target("summer/sale")
+            .alias("promo")
+            .campaign("summer")
+            .tag("flyers")
+            .expires_at("2030-01-01T00:00:00Z".parse().unwrap())
+            .json_sidecar()
+            .build()
+            .unwrap()
+            .write_redirect_to(&mut sink)
+            .unwrap();
+
+        let json = sink.read_file(Path::new("s/promo.json")).unwrap().unwrap();
+        let metadata: serde_json::Value = serde_json::from_slice(&json).unwrap();
+        assert_eq!(metadata["short_name"], "promo");
+        assert_eq!(metadata["target"], "/summer/sale/");
+        assert_eq!(metadata["campaign"], "summer");
+        assert_eq!(metadata["tags"], serde_json::json!(["flyers"]));
+        assert_eq!(metadata["expires_at"], "2030-01-01T00:00:00Z");
+        assert!(metadata["created_at"].is_string());
+        assert!(metadata.get("retired_at").is_none());
+        assert_eq!(sink.read_file(Path::new("s/promo.txt")).unwrap(), None);
+    }
+
+    #[test]
+    fn test_metadata_follows_registry_changes() {
+        let dir = test_dir("test_metadata_follows_registry_changes");
+        Redirector::builder()
+            .target("a")
+            .out_dir(&dir)
+            .alias("link")
+            .json_sidecar()
+            .build()
+            .unwrap()
+            .write_redirect()
+            .unwrap();
+        let json_path = Path::new(&dir).join("link.json");
+        let read = || -> serde_json::Value {
+            serde_json::from_str(&fs::read_to_string(&json_path).unwrap()).unwrap()
+        };
+        assert_eq!(read()["target"], "/a/");
+
+        let mut registry = Registry::load(&dir).unwrap();
+        registry.update_target("link", "b").unwrap();
+        assert_eq!(read()["target"], "/b/");
+
+        registry.retire("/b/").unwrap();
+        assert!(read()["retired_at"].is_string());
+
+        registry.remove("link").unwrap();
+        assert!(!json_path.exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_remove_deletes_sidecar() {
+        let dir = test_dir("test_remove_deletes_sidecar");
+        Redirector::builder()
+            .target("a")
+            .out_dir(&dir)
+            .alias("link")
+            .text_sidecar()
+            .build()
+            .unwrap()
+            .write_redirect()
+            .unwrap();
+
+        let mut registry = Registry::load(&dir).unwrap();
+        registry.remove("link").unwrap();
+        assert!(!Path::new(&dir).join("link.txt").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,130 @@
+//! Short names derived from a keyed hash of the target.
+//!
+//! Generated short names are built from the time and a counter, so anyone who has
+//! seen a few of them can guess others. This module derives short names from an
+//! HMAC-SHA256 of the target under a secret key instead: without the key the names
+//! cannot be predicted or enumerated, and with it anyone holding the key can check
+//! that a short name was issued for its target by the site owner.
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::redirector::Registry;
+
+/// Derives the short name signed for a target with a key.
+///
+/// The name is the first 64 bits of the HMAC-SHA256 of the normalized target,
+/// encoded with base62, so it is at most 11 characters long.
+pub(crate) fn signed_name(target: &str, key: &[u8]) -> String {
+    // HMAC accepts keys of any length, so this cannot fail
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(target.as_bytes());
+    let digest = mac.finalize().into_bytes();
+
+    let mut bytes = [0; 8];
+    bytes.copy_from_slice(&digest[..8]);
+    base62::encode(u64::from_be_bytes(bytes))
+}
+
+impl Registry {
+    /// Reports whether a short name was signed for its target with a key.
+    ///
+    /// Redirects built with
+    /// [`RedirectorBuilder::signing_key()`](crate::RedirectorBuilder::signing_key) are
+    /// named after a keyed hash of their target. This checks that `short_name` is
+    /// registered and matches the name derived from its target with `key`. Aliases,
+    /// and short names chosen by hand or generated without a key, are not signed.
+    /// Requires the `signed-names` feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `short_name` - The short name to check (e.g. `"3cP8kWq1Zx"`)
+    /// * `key` - The key the redirects were signed with
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::{Redirector, Registry};
+    /// use std::fs;
+    ///
+    /// let outcome = Redirector::builder()
+    ///     .target("api/v1/users")
+    ///     .out_dir("doc_test_registry_verify_signed")
+    ///     .signing_key("site secret")
+    ///     .build()
+    ///     .unwrap()
+    ///     .write_redirect()
+    ///     .unwrap();
+    ///
+    /// let registry = Registry::load("doc_test_registry_verify_signed").unwrap();
+    /// assert!(registry.verify_signed(outcome.short_name(), "site secret"));
+    /// assert!(!registry.verify_signed(outcome.short_name(), "another secret"));
+    ///
+    /// fs::remove_dir_all("doc_test_registry_verify_signed").ok();
+    /// ```
+    pub fn verify_signed<K: AsRef<[u8]>>(&self, short_name: &str, key: K) -> bool {
+        self.target_for(short_name)
+            .filter(|target| self.entry(target).map(|entry| entry.short_name()) == Some(short_name))
+            .is_some_and(|target| signed_name(target, key.as_ref()) == short_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Redirector;
+    use chrono::Utc;
+    use std::fs;
+
+    fn test_dir(name: &str) -> String {
+        format!("{name}_{}", Utc::now().timestamp_nanos_opt().unwrap_or(0))
+    }
+
+    #[test]
+    fn test_signed_name() {
+        let name = signed_name("/api/v1/users/", b"key");
+
+        assert_eq!(name, signed_name("/api/v1/users/", b"key"));
+        assert_ne!(name, signed_name("/api/v1/users/", b"other key"));
+        assert_ne!(name, signed_name("/api/v1/groups/", b"key"));
+        assert!(name.len() <= 11);
+        assert!(name.chars().all(|c| c.is_ascii_alphanumeric()));
+    }
+
+    #[test]
+    fn test_verify_signed() {
+        let dir = test_dir("test_verify_signed");
+        let signed = Redirector::builder()
+            .target("docs/install")
+            .out_dir(&dir)
+            .signing_key("secret")
+            .build()
+            .unwrap()
+            .write_redirect()
+            .unwrap();
+        assert_eq!(
+            signed.short_name(),
+            signed_name("/docs/install/", b"secret")
+        );
+
+        // An alias takes precedence over the signing key
+        let aliased = Redirector::builder()
+            .target("docs/usage")
+            .out_dir(&dir)
+            .alias("usage")
+            .signing_key("secret")
+            .build()
+            .unwrap()
+            .write_redirect()
+            .unwrap();
+        assert_eq!(aliased.short_name(), "usage");
+
+        let registry = Registry::load(&dir).unwrap();
+        assert!(registry.verify_signed(signed.short_name(), "secret"));
+        assert!(!registry.verify_signed(signed.short_name(), "guess"));
+        assert!(!registry.verify_signed("usage", "secret"));
+        assert!(!registry.verify_signed("missing", "secret"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,435 @@
+//! Pluggable storage for generated redirect files and registries.
+//!
+//! Writing a redirect reads the registry, writes an HTML page, and writes the updated
+//! registry. This module provides the `OutputSink` trait those operations go through,
+//! with `FsSink` writing to the local filesystem and `MemorySink` keeping every file in
+//! memory. The in-memory sink lets the crate run where `std::fs` is unavailable, such
+//! as `wasm32` targets and serverless functions, with the caller deciding where the
+//! generated files end up, whether an archive, an object store, or a database.
+//! `Redirector::render_to()` covers the simpler case of streaming a single page into
+//! any `io::Write`.
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Cursor, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// A destination for redirect pages and registries.
+///
+/// Implement this trait to store generated files somewhere other than the local
+/// filesystem, then pass the sink to [`Redirector::write_redirect_to()`],
+/// [`Registry::load_from()`], or [`Registry::save_to()`].
+///
+/// [`Redirector::write_redirect_to()`]: crate::Redirector::write_redirect_to
+/// [`Registry::load_from()`]: crate::Registry::load_from
+/// [`Registry::save_to()`]: crate::Registry::save_to
+pub trait OutputSink {
+    /// Reads the contents of a file, returning `Ok(None)` if it does not exist.
+    fn read_file(&mut self, path: &Path) -> io::Result<Option<Vec<u8>>>;
+
+    /// Creates a directory and any missing parents.
+    fn create_dir_all(&mut self, path: &Path) -> io::Result<()>;
+
+    /// Writes a file, replacing any existing contents.
+    fn write_file(&mut self, path: &Path, contents: &[u8]) -> io::Result<()>;
+
+    /// Opens a file for reading, returning `Ok(None)` if it does not exist.
+    ///
+    /// [`Registry::load_from()`] parses `registry.json` from this reader one entry at a
+    /// time. The default implementation reads the whole file with
+    /// [`OutputSink::read_file()`]; sinks that can read incrementally should override
+    /// it so the file is never held in memory in full.
+    ///
+    /// [`Registry::load_from()`]: crate::Registry::load_from
+    fn open_file(&mut self, path: &Path) -> io::Result<Option<Box<dyn Read + '_>>> {
+        Ok(self
+            .read_file(path)?
+            .map(|contents| Box::new(Cursor::new(contents)) as Box<dyn Read>))
+    }
+
+    /// Writes a file by passing a writer to `write`, replacing any existing contents.
+    ///
+    /// Large registries are serialized into this writer as they are produced. The
+    /// default implementation collects the contents in memory and passes them to
+    /// [`OutputSink::write_file()`]; sinks that can write incrementally should override
+    /// it.
+    fn write_file_with(
+        &mut self,
+        path: &Path,
+        write: &mut dyn FnMut(&mut dyn Write) -> io::Result<()>,
+    ) -> io::Result<()> {
+        let mut contents = Vec::new();
+        write(&mut contents)?;
+        self.write_file(path, &contents)
+    }
+
+    /// Writes a new file, failing with [`io::ErrorKind::AlreadyExists`] if one exists.
+    ///
+    /// Redirect pages are created with this method, so a file that link-bridge did not
+    /// write is never overwritten. The default implementation checks for the file with
+    /// [`OutputSink::read_file()`] before calling [`OutputSink::write_file()`].
+    fn create_file(&mut self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        if self.read_file(path)?.is_some() {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                "file already exists",
+            ));
+        }
+        self.write_file(path, contents)
+    }
+}
+
+/// An [`OutputSink`] that reads and writes the local filesystem.
+///
+/// This is the sink used by [`Redirector::write_redirect()`], [`Registry::load()`],
+/// and [`Registry::save()`].
+///
+/// Files are written to a temporary file beside their destination and only moved into
+/// place once complete, so an interrupted run never leaves a half-written page behind
+/// to be deployed.
+///
+/// [`Redirector::write_redirect()`]: crate::Redirector::write_redirect
+/// [`Registry::load()`]: crate::Registry::load
+/// [`Registry::save()`]: crate::Registry::save
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FsSink;
+
+impl OutputSink for FsSink {
+    fn read_file(&mut self, path: &Path) -> io::Result<Option<Vec<u8>>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        fs::read(path).map(Some)
+    }
+
+    fn create_dir_all(&mut self, path: &Path) -> io::Result<()> {
+        fs::create_dir_all(path)
+    }
+
+    fn write_file(&mut self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        self.write_file_with(path, &mut |writer| writer.write_all(contents))
+    }
+
+    fn open_file(&mut self, path: &Path) -> io::Result<Option<Box<dyn Read + '_>>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(Box::new(File::open(path)?)))
+    }
+
+    fn write_file_with(
+        &mut self,
+        path: &Path,
+        write: &mut dyn FnMut(&mut dyn Write) -> io::Result<()>,
+    ) -> io::Result<()> {
+        let temp_path = write_temp_file(path, write)?;
+        fs::rename(&temp_path, path).inspect_err(|_| {
+            fs::remove_file(&temp_path).ok();
+        })
+    }
+
+    fn create_file(&mut self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        let temp_path = write_temp_file(path, &mut |writer| writer.write_all(contents))?;
+        // Linking fails if the destination exists, so an existing file is never replaced
+        let result = match fs::hard_link(&temp_path, path) {
+            // Filesystems without hard links fall back to checking before renaming
+            Err(e) if e.kind() != io::ErrorKind::AlreadyExists && !path.exists() => {
+                fs::rename(&temp_path, path)
+            }
+            result => result,
+        };
+        fs::remove_file(&temp_path).ok();
+        result
+    }
+}
+
+/// Counts the temporary files named by this process.
+static TEMP_FILES: AtomicU64 = AtomicU64::new(0);
+
+/// Returns a temporary file a file can be written to before being moved into place.
+///
+/// The name carries the process id and a per-process counter, such as
+/// `registry.json.4242.7.tmp`, so concurrent writers of the same file, in one process
+/// or several, never share a temporary file.
+pub(crate) fn temp_path(path: &Path) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(format!(
+        ".{}.{}.tmp",
+        std::process::id(),
+        TEMP_FILES.fetch_add(1, Ordering::Relaxed)
+    ));
+    path.with_file_name(file_name)
+}
+
+/// Creates a new temporary file for a file, returning it with its path.
+///
+/// The file is opened with `create_new`, so an existing file, such as one left behind
+/// by an earlier process with the same id, is never truncated; another name is tried
+/// instead.
+fn create_temp_file(path: &Path) -> io::Result<(File, PathBuf)> {
+    loop {
+        let temp_path = temp_path(path);
+        match File::options()
+            .write(true)
+            .create_new(true)
+            .open(&temp_path)
+        {
+            Ok(file) => return Ok((file, temp_path)),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Writes a file's contents to its temporary file and flushes them to disk.
+///
+/// The temporary file is removed if writing fails.
+fn write_temp_file(
+    path: &Path,
+    write: &mut dyn FnMut(&mut dyn Write) -> io::Result<()>,
+) -> io::Result<PathBuf> {
+    let (file, temp_path) = create_temp_file(path)?;
+    let mut writer = BufWriter::new(file);
+    let result = write(&mut writer)
+        .and_then(|()| writer.into_inner().map_err(|e| e.into_error()))
+        .and_then(|file| file.sync_all());
+    match result {
+        Ok(()) => Ok(temp_path),
+        Err(e) => {
+            fs::remove_file(&temp_path).ok();
+            Err(e)
+        }
+    }
+}
+
+/// An [`OutputSink`] that keeps every file in memory.
+///
+/// Files are keyed by the path they would have been written to. Directories are
+/// implicit, so creating one always succeeds.
+///
+/// # Examples
+///
+/// ```rust
+/// use link_bridge::{MemorySink, Redirector};
+///
+/// let redirector = Redirector::builder()
+///     .target("api/v1/users")
+///     .out_dir("s")
+///     .alias("users")
+///     .build()
+///     .unwrap();
+///
+/// let mut sink = MemorySink::new();
+/// let outcome = redirector.write_redirect_to(&mut sink).unwrap();
+///
+/// let html = sink.get_str(outcome.file_path()).unwrap();
+/// assert!(html.contains("url=/api/v1/users/"));
+/// assert!(sink.get_str("s/registry.json").unwrap().contains("users.html"));
+///
+/// // Nothing was written to disk
+/// assert!(!outcome.file_path().exists());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MemorySink {
+    /// The file contents, keyed by path.
+    files: BTreeMap<PathBuf, Vec<u8>>,
+}
+
+impl MemorySink {
+    /// Creates an empty in-memory sink.
+    pub fn new() -> Self {
+        MemorySink::default()
+    }
+
+    /// Returns the contents of a file, if it has been written.
+    pub fn get<P: AsRef<Path>>(&self, path: P) -> Option<&[u8]> {
+        self.files.get(path.as_ref()).map(Vec::as_slice)
+    }
+
+    /// Returns the contents of a file as text, if it has been written and is valid UTF-8.
+    pub fn get_str<P: AsRef<Path>>(&self, path: P) -> Option<&str> {
+        self.get(path)
+            .and_then(|contents| std::str::from_utf8(contents).ok())
+    }
+
+    /// Adds or replaces a file, for example to seed an existing registry.
+    pub fn insert<P: Into<PathBuf>, C: Into<Vec<u8>>>(&mut self, path: P, contents: C) {
+        self.files.insert(path.into(), contents.into());
+    }
+
+    /// Returns an iterator over the stored files and their contents, ordered by path.
+    pub fn files(&self) -> impl Iterator<Item = (&Path, &[u8])> {
+        self.files
+            .iter()
+            .map(|(path, contents)| (path.as_path(), contents.as_slice()))
+    }
+
+    /// Returns the number of stored files.
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Reports whether no files have been stored.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Consumes the sink and returns the stored files, keyed by path.
+    pub fn into_files(self) -> BTreeMap<PathBuf, Vec<u8>> {
+        self.files
+    }
+}
+
+impl OutputSink for MemorySink {
+    fn read_file(&mut self, path: &Path) -> io::Result<Option<Vec<u8>>> {
+        Ok(self.files.get(path).cloned())
+    }
+
+    fn create_dir_all(&mut self, _path: &Path) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn write_file(&mut self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        self.files.insert(path.to_path_buf(), contents.to_vec());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_sink_read_write() {
+        let mut sink = MemorySink::new();
+        assert!(sink.is_empty());
+        assert_eq!(sink.read_file(Path::new("s/a.html")).unwrap(), None);
+
+        sink.write_file(Path::new("s/a.html"), b"hello").unwrap();
+
+        assert_eq!(sink.len(), 1);
+        assert_eq!(sink.get("s/a.html"), Some(&b"hello"[..]));
+        assert_eq!(sink.get_str("s/a.html"), Some("hello"));
+        assert_eq!(
+            sink.read_file(Path::new("s/a.html")).unwrap(),
+            Some(b"hello".to_vec())
+        );
+    }
+
+    #[test]
+    fn test_memory_sink_files_ordered() {
+        let mut sink = MemorySink::new();
+        sink.insert("s/b.html", "b");
+        sink.insert("s/a.html", "a");
+
+        let paths: Vec<&Path> = sink.files().map(|(path, _)| path).collect();
+        assert_eq!(paths, [Path::new("s/a.html"), Path::new("s/b.html")]);
+        assert_eq!(sink.into_files().len(), 2);
+    }
+
+    #[test]
+    fn test_fs_sink_missing_file() {
+        let mut sink = FsSink;
+        let result = sink.read_file(Path::new("test_fs_sink_missing_file/none.json"));
+        assert_eq!(result.unwrap(), None);
+        let result = sink.open_file(Path::new("test_fs_sink_missing_file/none.json"));
+        assert!(result.unwrap().is_none());
+    }
+
+    #[test]
+    fn test_fs_sink_writes_atomically() {
+        let dir = format!(
+            "test_fs_sink_writes_atomically_{}",
+            chrono::Utc::now().timestamp_nanos_opt().unwrap_or(0)
+        );
+        let path = PathBuf::from(&dir).join("a.html");
+        let mut sink = FsSink;
+        sink.create_dir_all(Path::new(&dir)).unwrap();
+
+        sink.create_file(&path, b"first").unwrap();
+        let error = sink.create_file(&path, b"second").unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(fs::read(&path).unwrap(), b"first");
+
+        sink.write_file(&path, b"replaced").unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"replaced");
+
+        // No temporary files are left behind
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_fs_sink_temp_files_are_unique() {
+        let dir = format!(
+            "test_fs_sink_temp_files_are_unique_{}",
+            chrono::Utc::now().timestamp_nanos_opt().unwrap_or(0)
+        );
+        let path = PathBuf::from(&dir).join("a.html");
+        fs::create_dir_all(&dir).unwrap();
+
+        assert_ne!(temp_path(&path), temp_path(&path));
+
+        // An unrelated file named like a temporary file is left alone
+        let unrelated = PathBuf::from(&dir).join("a.html.tmp");
+        fs::write(&unrelated, b"keep").unwrap();
+
+        std::thread::scope(|scope| {
+            for i in 0..8 {
+                let path = &path;
+                scope.spawn(move || FsSink.write_file(path, format!("{i}").as_bytes()).unwrap());
+            }
+        });
+
+        let contents = String::from_utf8(fs::read(&path).unwrap()).unwrap();
+        assert!(contents.parse::<u8>().unwrap() < 8);
+        assert_eq!(fs::read(&unrelated).unwrap(), b"keep");
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_memory_sink_create_file() {
+        let mut sink = MemorySink::new();
+        sink.create_file(Path::new("s/a.html"), b"first").unwrap();
+
+        let error = sink
+            .create_file(Path::new("s/a.html"), b"second")
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(sink.get_str("s/a.html"), Some("first"));
+    }
+
+    #[test]
+    fn test_streaming_read_write() {
+        let dir = format!(
+            "test_streaming_read_write_{}",
+            chrono::Utc::now().timestamp_nanos_opt().unwrap_or(0)
+        );
+        let path = PathBuf::from(&dir).join("a.txt");
+        let mut fs_sink = FsSink;
+        fs_sink.create_dir_all(Path::new(&dir)).unwrap();
+        let mut memory_sink = MemorySink::new();
+
+        for sink in [&mut fs_sink as &mut dyn OutputSink, &mut memory_sink] {
+            sink.write_file_with(&path, &mut |writer| {
+                writer.write_all(b"hello ")?;
+                writer.write_all(b"world")
+            })
+            .unwrap();
+
+            let mut contents = String::new();
+            sink.open_file(&path)
+                .unwrap()
+                .unwrap()
+                .read_to_string(&mut contents)
+                .unwrap();
+            assert_eq!(contents, "hello world");
+        }
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,348 @@
+//! Preview cards for short links shared on social media and in chat.
+//!
+//! A short link pasted into a chat or a social post is shown with a preview card
+//! built from the page's Open Graph tags, and a redirect page has no image to show.
+//! This module provides the `SocialPreview`, which writes an SVG image naming the
+//! short link and the domain it leads to next to each redirect page, and references
+//! it from the page's `og:image` and `twitter:image` tags.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::redirector::bookmarks::escape;
+use crate::redirector::sink::{FsSink, OutputSink};
+use crate::redirector::target::{ExternalUrl, Target};
+use crate::redirector::{OutputLayout, Redirector, RedirectorError};
+
+/// The width of the preview image in pixels, the size Open Graph recommends.
+const WIDTH: u32 = 1200;
+
+/// The height of the preview image in pixels.
+const HEIGHT: u32 = 630;
+
+/// A branded preview image for a redirect's page.
+///
+/// Set with
+/// [`RedirectorBuilder::social_preview()`](crate::RedirectorBuilder::social_preview).
+/// The image is an SVG file written next to the page, as `<name>.svg` or
+/// `<name>/index.svg`, showing the short link, the domain it leads to, and an
+/// optional brand. Open Graph requires an absolute image URL, so the preview is
+/// created with the URL the output directory is served from. Some platforms only
+/// show raster images; convert the SVG files when publishing if they must appear
+/// there.
+///
+/// # Examples
+///
+/// ```rust
+/// use link_bridge::{Redirector, SocialPreview};
+/// use std::fs;
+///
+/// let outcome = Redirector::builder()
+///     .target("summer/sale")
+///     .out_dir("doc_test_social_preview")
+///     .alias("promo")
+///     .base_url("https://example.com")
+///     .social_preview(SocialPreview::new("https://example.com/s").brand("Example Shop"))
+///     .build()
+///     .unwrap()
+///     .write_redirect()
+///     .unwrap();
+///
+/// let page = fs::read_to_string(outcome.file_path()).unwrap();
+/// assert!(page.contains(
+///     r#"<meta property="og:image" content="https://example.com/s/promo.svg">"#
+/// ));
+/// let image = fs::read_to_string("doc_test_social_preview/promo.svg").unwrap();
+/// assert!(image.contains("example.com/s/promo"));
+///
+/// fs::remove_dir_all("doc_test_social_preview").ok();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SocialPreview {
+    /// The URL the output directory is served from, without a trailing slash.
+    site_url: String,
+    /// The name shown at the bottom of the image, if any.
+    brand: Option<String>,
+    /// The background colour of the image.
+    background: String,
+    /// The text colour of the image.
+    foreground: String,
+}
+
+impl SocialPreview {
+    /// Creates a preview for pages served from `site_url`.
+    ///
+    /// The URL is validated when the redirector is built.
+    ///
+    /// # Arguments
+    ///
+    /// * `site_url` - The URL the output directory is served from (e.g.
+    ///   `"https://example.com/s"`)
+    pub fn new<S: ToString>(site_url: S) -> Self {
+        SocialPreview {
+            site_url: site_url.to_string(),
+            brand: None,
+            background: "#1f2937".to_string(),
+            foreground: "#ffffff".to_string(),
+        }
+    }
+
+    /// Shows a name, such as the site's, at the bottom of the image.
+    ///
+    /// # Arguments
+    ///
+    /// * `brand` - The name to show
+    pub fn brand<S: ToString>(mut self, brand: S) -> Self {
+        self.brand = Some(brand.to_string());
+        self
+    }
+
+    /// Sets the colours of the image.
+    ///
+    /// Defaults to white text on dark grey.
+    ///
+    /// # Arguments
+    ///
+    /// * `background` - An SVG colour for the background (e.g. `"#0b3d91"`)
+    /// * `foreground` - An SVG colour for the text (e.g. `"white"`)
+    pub fn colours<B: ToString, F: ToString>(mut self, background: B, foreground: F) -> Self {
+        self.background = background.to_string();
+        self.foreground = foreground.to_string();
+        self
+    }
+
+    /// Validates and normalizes the site URL.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(SocialPreview)` - The preview with its site URL normalized
+    /// * `Err(RedirectorError::InvalidTarget)` - If the site URL is malformed or has a
+    ///   query or fragment
+    pub(crate) fn validate(mut self) -> Result<Self, RedirectorError> {
+        self.site_url = ExternalUrl::new_base(self.site_url)?.to_string();
+        Ok(self)
+    }
+
+    /// Returns the host the site is served from.
+    fn site_host(&self) -> String {
+        ExternalUrl::new(self.site_url.clone())
+            .map(|url| url.host().to_string())
+            .unwrap_or_default()
+    }
+}
+
+/// Returns the path of the preview image written next to a page.
+pub(crate) fn image_path(file_path: &Path) -> PathBuf {
+    file_path.with_extension("svg")
+}
+
+/// Returns the URL of the preview image of a short name relative to the output
+/// directory.
+fn image_file(short_name: &str, layout: OutputLayout) -> String {
+    match layout {
+        OutputLayout::File => format!("{short_name}.svg"),
+        OutputLayout::Directory => format!("{short_name}/index.svg"),
+    }
+}
+
+/// Moves the preview image of a page renamed from `old_page` to `new_page`.
+///
+/// The image shows the short link and the page references the image by name, so
+/// both are rewritten with the new short name. A page without a preview is left
+/// alone.
+pub(crate) fn rename_preview(
+    old_page: &Path,
+    new_page: &Path,
+    layout: OutputLayout,
+    old_short: &str,
+    new_short: &str,
+) -> io::Result<()> {
+    let old_image = image_path(old_page);
+    if !old_image.exists() {
+        return Ok(());
+    }
+    let image = fs::read_to_string(&old_image)?.replace(
+        &format!("/{}</text>", escape(old_short)),
+        &format!("/{}</text>", escape(new_short)),
+    );
+    FsSink.write_file(&image_path(new_page), image.as_bytes())?;
+    fs::remove_file(&old_image)?;
+
+    let page = fs::read_to_string(new_page)?.replace(
+        &format!("/{}\"", escape(&image_file(old_short, layout))),
+        &format!("/{}\"", escape(&image_file(new_short, layout))),
+    );
+    FsSink.write_file(new_page, page.as_bytes())
+}
+
+impl Redirector {
+    /// Renders the tags referencing the preview image in the page's `<head>`, or
+    /// nothing if the redirect has no preview.
+    pub(crate) fn preview_meta(&self) -> String {
+        let Some(preview) = &self.social_preview else {
+            return String::new();
+        };
+        let url = escape(&format!("{}/{}", preview.site_url, self.image_file()));
+
+        format!(
+            r#"
+        <meta property="og:image" content="{url}">
+        <meta property="og:image:type" content="image/svg+xml">
+        <meta property="og:image:width" content="{WIDTH}">
+        <meta property="og:image:height" content="{HEIGHT}">
+        <meta name="twitter:card" content="summary_large_image">
+        <meta name="twitter:image" content="{url}">"#
+        )
+    }
+
+    /// Renders the preview image written next to the page at `file_path`.
+    ///
+    /// Returns `None` if the redirect has no preview.
+    pub(crate) fn preview_image(&self, file_path: &Path) -> Option<(PathBuf, String)> {
+        let preview = self.social_preview.as_ref()?;
+        let site = preview
+            .site_url
+            .split_once("://")
+            .map_or(preview.site_url.as_str(), |(_, rest)| rest);
+        let short_link = format!("{site}/{}", self.short_name());
+        let destination = match &self.target {
+            Target::External(url) => url.host().to_string(),
+            Target::Contact(url) => url.to_string(),
+            Target::Path(_) => match &self.base_url {
+                Some(base_url) => base_url.host().to_string(),
+                None => preview.site_host(),
+            },
+        };
+        let brand = preview.brand.as_deref().map_or(String::new(), |brand| {
+            format!(
+                r#"
+  <text x="600" y="560" text-anchor="middle" font-family="sans-serif" font-size="36" fill="{}" opacity="0.7">{}</text>"#,
+                escape(&preview.foreground),
+                escape(brand)
+            )
+        });
+
+        let image = format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{HEIGHT}" viewBox="0 0 {WIDTH} {HEIGHT}">
+  <rect width="{WIDTH}" height="{HEIGHT}" fill="{background}"/>
+  <text x="600" y="280" text-anchor="middle" font-family="sans-serif" font-size="72" font-weight="bold" fill="{foreground}">{short_link}</text>
+  <text x="600" y="380" text-anchor="middle" font-family="sans-serif" font-size="44" fill="{foreground}">&#8594; {destination}</text>{brand}
+</svg>
+"#,
+            background = escape(&preview.background),
+            foreground = escape(&preview.foreground),
+            short_link = escape(&short_link),
+            destination = escape(&destination),
+        );
+
+        Some((image_path(file_path), image))
+    }
+
+    /// Returns the URL of the preview image relative to the output directory.
+    fn image_file(&self) -> String {
+        image_file(&self.short_name(), self.layout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{HostAllowlist, MemorySink, OutputSink, Registry};
+    use chrono::Utc;
+    use std::fs;
+
+    #[test]
+    fn test_preview_meta() {
+        let redirector = Redirector::builder()
+            .target("summer/sale")
+            .alias("promo")
+            .layout(OutputLayout::Directory)
+            .social_preview(SocialPreview::new("https://example.com/s/"))
+            .build()
+            .unwrap();
+
+        let page = redirector.to_string();
+        assert!(page.contains(
+            r#"<meta property="og:image" content="https://example.com/s/promo/index.svg">"#
+        ));
+        assert!(page.contains(r#"<meta name="twitter:card" content="summary_large_image">"#));
+
+        let plain = Redirector::builder().target("summer/sale").build().unwrap();
+        assert!(!plain.to_string().contains("og:image"));
+    }
+
+    #[test]
+    fn test_preview_image() {
+        let redirector = Redirector::builder()
+            .target("https://docs.example.org/guide")
+            .allow_hosts(HostAllowlist::new(["docs.example.org"]))
+            .alias("guide")
+            .social_preview(
+                SocialPreview::new("https://ex.co")
+                    .brand("Docs & Guides")
+                    .colours("#0b3d91", "white"),
+            )
+            .build()
+            .unwrap();
+
+        let (path, image) = redirector.preview_image(Path::new("s/guide.html")).unwrap();
+        assert_eq!(path, Path::new("s/guide.svg"));
+        assert!(image.contains(">ex.co/guide</text>"));
+        assert!(image.contains("&#8594; docs.example.org</text>"));
+        assert!(image.contains(">Docs &amp; Guides</text>"));
+        assert!(image.contains(r##"fill="#0b3d91""##));
+    }
+
+    #[test]
+    fn test_write_preview_image() {
+        let mut sink = MemorySink::new();
+        Redirector::builder()
+            .target("summer/sale")
+            .alias("promo")
+            .social_preview(SocialPreview::new("https://example.com/s"))
+            .build()
+            .unwrap()
+            .write_redirect_to(&mut sink)
+            .unwrap();
+
+        let image = sink.read_file(Path::new("s/promo.svg")).unwrap().unwrap();
+        let image = String::from_utf8(image).unwrap();
+        assert!(image.contains(">example.com/s/promo</text>"));
+        assert!(image.contains("&#8594; example.com</text>"));
+    }
+
+    #[test]
+    fn test_preview_invalid_site_url() {
+        let result = Redirector::builder()
+            .target("a")
+            .social_preview(SocialPreview::new("example.com/s"))
+            .build();
+        assert!(matches!(result, Err(RedirectorError::InvalidTarget(_))));
+    }
+
+    #[test]
+    fn test_remove_deletes_preview_image() {
+        let dir = format!(
+            "test_remove_preview_image_{}",
+            Utc::now().timestamp_nanos_opt().unwrap_or(0)
+        );
+        Redirector::builder()
+            .target("summer/sale")
+            .out_dir(&dir)
+            .alias("promo")
+            .social_preview(SocialPreview::new("https://example.com/s"))
+            .build()
+            .unwrap()
+            .write_redirect()
+            .unwrap();
+        let image = Path::new(&dir).join("promo.svg");
+        assert!(image.exists());
+
+        let mut registry = Registry::load(&dir).unwrap();
+        registry.remove("promo").unwrap();
+        assert!(!image.exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,51 @@
+//! Redirects split between several weighted targets.
+//!
+//! A simple A/B test of landing pages needs one short link that sends some visitors to
+//! one page and the rest to another. The redirect page cannot decide on the server, so
+//! this module renders the script that picks one of the weighted targets at random on
+//! every visit.
+
+use crate::redirector::beacon::js_string;
+
+/// Renders the script statement that redirects to one of the weighted targets.
+///
+/// Each target is chosen with a probability proportional to its weight. The targets
+/// must not be empty and their weights must not all be zero.
+pub(crate) fn script(targets: &[(String, u32)]) -> String {
+    let targets = targets
+        .iter()
+        .map(|(target, weight)| format!("[{}, {weight}]", js_string(target)))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    format!(
+        r#"window.location.href = (function (targets) {{
+                var pick = Math.random() * targets.reduce(function (total, target) {{
+                    return total + target[1];
+                }}, 0);
+                for (var i = 0; i < targets.length - 1; i++) {{
+                    pick -= targets[i][1];
+                    if (pick < 0) {{
+                        return targets[i][0];
+                    }}
+                }}
+                return targets[targets.length - 1][0];
+            }})([{targets}]);"#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_script() {
+        let script = script(&[
+            ("/landing/a/".to_string(), 3),
+            ("https://example.com/b".to_string(), 1),
+        ]);
+
+        assert!(script.starts_with("window.location.href = (function (targets) {"));
+        assert!(script.ends_with(r#"})([["/landing/a/", 3], ["https://example.com/b", 1]]);"#));
+    }
+}
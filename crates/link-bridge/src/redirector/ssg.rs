@@ -0,0 +1,542 @@
+//! Bridging static site generator aliases into link-bridge redirects.
+//!
+//! Zola and Hugo let a page list old URLs in an `aliases` front-matter entry and emit a
+//! redirect page for each of them. This module provides the `SiteGenerator` type, which
+//! scans a content tree for those entries and turns each one into a `ContentAlias`,
+//! so existing alias definitions can be written as link-bridge redirect pages and
+//! registry entries instead.
+//!
+//! Only the front-matter keys needed to locate a page and its aliases are read
+//! (`aliases`, `slug`, `draft`, and Zola's `path` or Hugo's `url`), so no TOML or YAML
+//! parser is required.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::redirector::alias::validate_alias;
+use crate::redirector::{RedirectOutcome, Redirector, RedirectorError, UrlPath};
+
+/// A static site generator whose content tree can be scanned for aliases.
+///
+/// # Examples
+///
+/// ```rust
+/// use link_bridge::SiteGenerator;
+/// use std::fs;
+///
+/// fs::create_dir_all("doc_test_ssg/content/blog").unwrap();
+/// fs::write(
+///     "doc_test_ssg/content/blog/hello.md",
+///     "+++\ntitle = \"Hello\"\naliases = [\"/hello/\"]\n+++\nHello, world!\n",
+/// )
+/// .unwrap();
+///
+/// let aliases = SiteGenerator::Zola.scan("doc_test_ssg/content").unwrap();
+/// assert_eq!(aliases.len(), 1);
+/// assert_eq!(aliases[0].alias(), "hello");
+/// assert_eq!(aliases[0].page().to_string(), "/blog/hello/");
+///
+/// let outcomes = SiteGenerator::Zola
+///     .write_redirects("doc_test_ssg/content", "doc_test_ssg/static/s")
+///     .unwrap();
+/// assert_eq!(outcomes[0].short_name(), "hello");
+///
+/// fs::remove_dir_all("doc_test_ssg").ok();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SiteGenerator {
+    /// [Zola](https://www.getzola.org): TOML front matter between `+++` lines, with
+    /// aliases relative to the site root.
+    Zola,
+    /// [Hugo](https://gohugo.io): TOML (`+++`) or YAML (`---`) front matter, with
+    /// aliases without a leading slash relative to the page's section.
+    Hugo,
+}
+
+/// An alias declared in the front matter of a content page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContentAlias {
+    /// The content file declaring the alias.
+    source: PathBuf,
+    /// The URL path of the page the alias redirects to.
+    page: UrlPath,
+    /// The short name derived from the alias.
+    alias: String,
+}
+
+impl ContentAlias {
+    /// Returns the content file that declares the alias.
+    pub fn source(&self) -> &Path {
+        &self.source
+    }
+
+    /// Returns the URL path of the page the alias redirects to.
+    pub fn page(&self) -> &UrlPath {
+        &self.page
+    }
+
+    /// Returns the short name derived from the alias.
+    ///
+    /// The alias path is flattened into a single short name by trimming its slashes
+    /// and any `.html` extension and joining its segments with dashes, so
+    /// `/old/post/` becomes `old-post`.
+    pub fn alias(&self) -> &str {
+        &self.alias
+    }
+
+    /// Creates a [`Redirector`] that writes this alias into an output directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `out_dir` - The output directory for the redirect page and registry
+    pub fn redirector<P: Into<PathBuf>>(&self, out_dir: P) -> Redirector {
+        let mut redirector = Redirector::from(self.page.clone());
+        redirector.set_path(out_dir);
+        redirector.short_file_name = format!("{}.html", self.alias).into();
+        redirector
+    }
+}
+
+impl SiteGenerator {
+    /// Scans a content directory for pages that declare aliases.
+    ///
+    /// Every Markdown file below `content_dir` is read, and one [`ContentAlias`] is
+    /// returned for each entry in its `aliases` front matter. Draft pages are skipped.
+    /// Results are ordered by content file, then by their order in the front matter.
+    ///
+    /// # Arguments
+    ///
+    /// * `content_dir` - The site's content directory (usually `content`)
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<ContentAlias>)` - The aliases found
+    /// * `Err(RedirectorError::FailedToReadContent)` - If a directory or file cannot be read
+    /// * `Err(RedirectorError::InvalidUrlPath)` - If a page's URL is not a valid path,
+    ///   including aliases of the home page, whose URL has no path segments
+    /// * `Err(RedirectorError::InvalidAlias)` - If an alias cannot be used as a short name
+    pub fn scan<P: AsRef<Path>>(
+        &self,
+        content_dir: P,
+    ) -> Result<Vec<ContentAlias>, RedirectorError> {
+        let content_dir = content_dir.as_ref();
+        let mut files = Vec::new();
+        collect_markdown_files(content_dir, &mut files)?;
+        files.sort();
+
+        let mut aliases = Vec::new();
+        for file in files {
+            let contents = fs::read_to_string(&file).map_err(|source| {
+                RedirectorError::FailedToReadContent {
+                    path: file.clone(),
+                    source,
+                }
+            })?;
+            let Some(front_matter) = self.front_matter(&contents) else {
+                continue;
+            };
+            if front_matter.draft || front_matter.aliases.is_empty() {
+                continue;
+            }
+
+            let relative = file.strip_prefix(content_dir).unwrap_or(&file);
+            let page = UrlPath::new(self.page_path(relative, &front_matter))?;
+            for alias in &front_matter.aliases {
+                let alias = self.short_name(alias, relative)?;
+                aliases.push(ContentAlias {
+                    source: file.clone(),
+                    page: page.clone(),
+                    alias,
+                });
+            }
+        }
+
+        Ok(aliases)
+    }
+
+    /// Scans a content directory and writes a redirect for every alias.
+    ///
+    /// The aliases are written with [`Redirector::write_all()`], so the registry in
+    /// `out_dir` is updated once and nothing is written if any alias fails. The
+    /// registry holds one short link per target, so when a page declares several
+    /// aliases only the first is written and the rest reuse it.
+    ///
+    /// # Arguments
+    ///
+    /// * `content_dir` - The site's content directory (usually `content`)
+    /// * `out_dir` - The output directory for the redirect pages and registry
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<RedirectOutcome>)` - The outcome for each alias, in scan order
+    /// * `Err(RedirectorError)` - The errors of [`SiteGenerator::scan()`] and
+    ///   [`Redirector::write_all()`]
+    pub fn write_redirects<P: AsRef<Path>, Q: Into<PathBuf>>(
+        &self,
+        content_dir: P,
+        out_dir: Q,
+    ) -> Result<Vec<RedirectOutcome>, RedirectorError> {
+        let out_dir = out_dir.into();
+        let redirectors: Vec<Redirector> = self
+            .scan(content_dir)?
+            .iter()
+            .map(|alias| alias.redirector(&out_dir))
+            .collect();
+
+        Redirector::write_all(&redirectors)
+    }
+
+    /// Extracts the front matter of a content file, if it has any this generator reads.
+    fn front_matter(&self, contents: &str) -> Option<FrontMatter> {
+        let mut lines = contents.lines();
+        let format = match (lines.next()?.trim(), self) {
+            ("+++", _) => Format::Toml,
+            ("---", SiteGenerator::Hugo) => Format::Yaml,
+            _ => return None,
+        };
+        let delimiter = if format == Format::Toml { "+++" } else { "---" };
+
+        let body: Vec<&str> = lines.take_while(|line| line.trim() != delimiter).collect();
+        Some(FrontMatter::parse(format, &body, self))
+    }
+
+    /// Derives the URL path of a page from its location in the content tree.
+    fn page_path(&self, relative: &Path, front_matter: &FrontMatter) -> String {
+        if let Some(path) = &front_matter.path {
+            return path.clone();
+        }
+
+        let mut segments: Vec<String> = relative
+            .parent()
+            .into_iter()
+            .flat_map(Path::components)
+            .map(|component| component.as_os_str().to_string_lossy().into_owned())
+            .collect();
+
+        let stem = relative
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let is_index = stem == "index" || stem == "_index";
+
+        match (&front_matter.slug, is_index) {
+            (Some(slug), true) => {
+                segments.pop();
+                segments.push(slug.clone());
+            }
+            (Some(slug), false) => segments.push(slug.clone()),
+            (None, true) => {}
+            (None, false) => segments.push(stem),
+        }
+
+        segments.join("/")
+    }
+
+    /// Turns an alias path into a short name.
+    fn short_name(&self, alias: &str, relative: &Path) -> Result<String, RedirectorError> {
+        let mut segments: Vec<&str> = Vec::new();
+
+        // Hugo resolves aliases without a leading slash against the page's section
+        let section = relative.parent().and_then(Path::to_str).unwrap_or_default();
+        if *self == SiteGenerator::Hugo && !alias.starts_with('/') {
+            segments.extend(section.split(['/', '\\']));
+        }
+
+        let alias = alias.trim_matches('/');
+        let alias = alias.strip_suffix(".html").unwrap_or(alias);
+        segments.extend(alias.split('/'));
+        segments.retain(|segment| !segment.is_empty());
+
+        let short_name = segments.join("-");
+        validate_alias(&short_name)?;
+        Ok(short_name)
+    }
+}
+
+/// Recursively collects the Markdown files below a directory.
+fn collect_markdown_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), RedirectorError> {
+    let read_error = |source| RedirectorError::FailedToReadContent {
+        path: dir.to_path_buf(),
+        source,
+    };
+
+    for entry in fs::read_dir(dir).map_err(read_error)? {
+        let path = entry.map_err(read_error)?.path();
+        if path.is_dir() {
+            collect_markdown_files(&path, files)?;
+        } else if path.extension().is_some_and(|ext| ext == "md") {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// The front-matter syntax of a content file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    /// TOML between `+++` lines.
+    Toml,
+    /// YAML between `---` lines.
+    Yaml,
+}
+
+/// The front-matter values that determine a page's URL and aliases.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct FrontMatter {
+    /// The alias paths.
+    aliases: Vec<String>,
+    /// The last URL segment, replacing the file name.
+    slug: Option<String>,
+    /// The full URL path, replacing the path derived from the file location.
+    path: Option<String>,
+    /// Whether the page is a draft.
+    draft: bool,
+}
+
+impl FrontMatter {
+    /// Reads the top-level keys of interest from the lines of a front-matter block.
+    fn parse(format: Format, lines: &[&str], generator: &SiteGenerator) -> Self {
+        let separator = if format == Format::Toml { '=' } else { ':' };
+        let path_key = match generator {
+            SiteGenerator::Zola => "path",
+            SiteGenerator::Hugo => "url",
+        };
+
+        let mut front_matter = FrontMatter::default();
+        let mut index = 0;
+        while index < lines.len() {
+            let line = lines[index];
+            index += 1;
+
+            // Only top-level keys are read; TOML tables end the top-level section
+            if format == Format::Toml && line.trim_start().starts_with('[') {
+                break;
+            }
+            if line.starts_with(char::is_whitespace) {
+                continue;
+            }
+            let Some((key, value)) = line.split_once(separator) else {
+                continue;
+            };
+            let value = value.trim();
+
+            match key.trim() {
+                "aliases" if value.starts_with('[') => {
+                    let mut list = value.to_string();
+                    while !list.contains(']') && index < lines.len() {
+                        list.push_str(lines[index]);
+                        index += 1;
+                    }
+                    front_matter.aliases = parse_list(&list);
+                }
+                "aliases" if value.is_empty() && format == Format::Yaml => {
+                    while let Some(item) = lines
+                        .get(index)
+                        .and_then(|line| line.trim_start().strip_prefix('-'))
+                    {
+                        front_matter.aliases.push(unquote(item.trim()).to_string());
+                        index += 1;
+                    }
+                }
+                "slug" => front_matter.slug = Some(unquote(value).to_string()),
+                key if key == path_key => front_matter.path = Some(unquote(value).to_string()),
+                "draft" => front_matter.draft = value == "true",
+                _ => {}
+            }
+        }
+
+        front_matter
+    }
+}
+
+/// Parses a single-line inline list such as `["/a/", "/b/"]`.
+fn parse_list(list: &str) -> Vec<String> {
+    let inner = list.trim().trim_start_matches('[');
+    let inner = inner.split(']').next().unwrap_or_default();
+
+    inner
+        .split(',')
+        .map(|item| unquote(item.trim()))
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Removes matching single or double quotes around a value.
+fn unquote(value: &str) -> &str {
+    ['"', '\'']
+        .iter()
+        .find_map(|quote| value.strip_prefix(*quote)?.strip_suffix(*quote))
+        .unwrap_or(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn content_dir(name: &str) -> PathBuf {
+        PathBuf::from(format!(
+            "{name}_{}",
+            Utc::now().timestamp_nanos_opt().unwrap_or(0)
+        ))
+    }
+
+    fn write(dir: &Path, file: &str, contents: &str) {
+        let path = dir.join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    fn summary(aliases: &[ContentAlias]) -> Vec<(String, String)> {
+        aliases
+            .iter()
+            .map(|alias| (alias.alias().to_string(), alias.page().to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_front_matter_toml() {
+        let front_matter = SiteGenerator::Zola
+            .front_matter(
+                "+++\ntitle = \"Post\"\nslug = \"post\"\naliases = [\n  \"/a/\",\n  '/b/',\n]\n\n[extra]\ndraft = true\n+++\nbody",
+            )
+            .unwrap();
+
+        assert_eq!(front_matter.aliases, ["/a/", "/b/"]);
+        assert_eq!(front_matter.slug.as_deref(), Some("post"));
+        assert!(!front_matter.draft);
+    }
+
+    #[test]
+    fn test_front_matter_yaml() {
+        let front_matter = SiteGenerator::Hugo
+            .front_matter(
+                "---\ntitle: Post\nurl: /custom/\naliases:\n  - /a/\n  - \"b\"\ndraft: true\n---\n",
+            )
+            .unwrap();
+
+        assert_eq!(front_matter.aliases, ["/a/", "b"]);
+        assert_eq!(front_matter.path.as_deref(), Some("/custom/"));
+        assert!(front_matter.draft);
+
+        // Zola only reads TOML front matter
+        assert_eq!(
+            SiteGenerator::Zola.front_matter("---\naliases: [a]\n---\n"),
+            None
+        );
+        assert_eq!(SiteGenerator::Zola.front_matter("no front matter"), None);
+    }
+
+    #[test]
+    fn test_scan_zola() {
+        let dir = content_dir("test_scan_zola");
+        write(&dir, "about.md", "+++\naliases = [\"/about-us/\"]\n+++\n");
+        write(&dir, "blog/_index.md", "+++\naliases = [\"/news\"]\n+++\n");
+        write(
+            &dir,
+            "blog/first.md",
+            "+++\nslug = \"hello\"\naliases = [\"/old/first.html\"]\n+++\n",
+        );
+        write(
+            &dir,
+            "blog/bundle/index.md",
+            "+++\naliases = [\"bundled\"]\n+++\n",
+        );
+        write(
+            &dir,
+            "blog/draft.md",
+            "+++\ndraft = true\naliases = [\"/draft/\"]\n+++\n",
+        );
+        write(
+            &dir,
+            "blog/moved.md",
+            "+++\npath = \"elsewhere/moved\"\naliases = [\"/moved/\"]\n+++\n",
+        );
+        write(&dir, "blog/plain.md", "+++\ntitle = \"No aliases\"\n+++\n");
+
+        let aliases = SiteGenerator::Zola.scan(&dir).unwrap();
+
+        assert_eq!(
+            summary(&aliases),
+            [
+                ("about-us".to_string(), "/about/".to_string()),
+                ("news".to_string(), "/blog/".to_string()),
+                ("bundled".to_string(), "/blog/bundle/".to_string()),
+                ("old-first".to_string(), "/blog/hello/".to_string()),
+                ("moved".to_string(), "/elsewhere/moved/".to_string()),
+            ]
+        );
+        assert_eq!(aliases[0].source(), dir.join("about.md"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_scan_hugo_relative_alias() {
+        let dir = content_dir("test_scan_hugo_relative_alias");
+        write(&dir, "posts/new.md", "---\naliases: [old, /top]\n---\n");
+
+        let aliases = SiteGenerator::Hugo.scan(&dir).unwrap();
+
+        assert_eq!(
+            summary(&aliases),
+            [
+                ("posts-old".to_string(), "/posts/new/".to_string()),
+                ("top".to_string(), "/posts/new/".to_string()),
+            ]
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_scan_errors() {
+        let missing = SiteGenerator::Zola.scan("test_scan_errors_no_such_dir");
+        assert!(matches!(
+            missing,
+            Err(RedirectorError::FailedToReadContent { .. })
+        ));
+
+        let dir = content_dir("test_scan_errors");
+        write(&dir, "page.md", "+++\naliases = [\"/not ok/\"]\n+++\n");
+        assert!(matches!(
+            SiteGenerator::Zola.scan(&dir),
+            Err(RedirectorError::InvalidAlias(_))
+        ));
+
+        write(&dir, "page.md", "+++\n+++\n");
+        write(&dir, "_index.md", "+++\naliases = [\"/home/\"]\n+++\n");
+        assert!(matches!(
+            SiteGenerator::Zola.scan(&dir),
+            Err(RedirectorError::InvalidUrlPath(_))
+        ));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_write_redirects() {
+        let dir = content_dir("test_write_redirects_ssg");
+        let out_dir = dir.join("out");
+        write(
+            &dir,
+            "content/docs/install.md",
+            "+++\naliases = [\"/install/\", \"/setup/\"]\n+++\n",
+        );
+
+        let outcomes = SiteGenerator::Zola
+            .write_redirects(dir.join("content"), &out_dir)
+            .unwrap();
+
+        assert_eq!(outcomes.len(), 2);
+        assert!(outcomes[0].is_created());
+        assert_eq!(outcomes[0].short_name(), "install");
+        assert!(!outcomes[1].is_created());
+        let html = fs::read_to_string(out_dir.join("install.html")).unwrap();
+        assert!(html.contains("url=/docs/install/"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
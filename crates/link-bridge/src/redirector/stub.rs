@@ -0,0 +1,217 @@
+//! Extra short names for a registered redirect.
+//!
+//! A redirect has one short name, but the same page is often shared under several,
+//! such as a campaign name and a shorter one for print. Registering a second
+//! redirect for the target is refused, since the registry holds one entry per
+//! target. This module provides `Registry::add_alias()`, which records an extra
+//! short name on the existing entry and writes a stub page leading to the
+//! redirect's own page.
+
+use std::path::{Path, PathBuf};
+
+use crate::redirector::alias::validate_alias;
+use crate::redirector::sink::{FsSink, OutputSink};
+use crate::redirector::{trace, OutputLayout, RedirectorError, Registry, RegistryEntry};
+
+impl Registry {
+    /// Adds an extra short name for a registered target.
+    ///
+    /// A stub page is written under the alias, with the redirect's layout, leading to
+    /// the redirect's own page, so the alias follows the redirect when it is repointed
+    /// with [`Registry::update_target()`] or retired. No new entry is created: the
+    /// alias is recorded on the target's entry in memory and resolves to the target
+    /// in [`Registry::target_for()`]; call [`Registry::save()`] to persist it.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - The registered target, as normalized (e.g. `"/summer/sale/"`)
+    /// * `alias` - The extra short name (e.g. `"sale"`)
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(PathBuf)` - The path of the stub page
+    /// * `Err(RedirectorError::InvalidAlias)` - If the alias contains forbidden characters
+    /// * `Err(RedirectorError::ReservedName)` - If the alias is reserved by Windows
+    /// * `Err(RedirectorError::ShortLinkNotFound)` - If no redirect has that target
+    /// * `Err(RedirectorError::DuplicateShortName)` - If the alias is already in use
+    /// * `Err(RedirectorError::DirectoryCreationError)` - If the stub's directory
+    ///   cannot be created
+    /// * `Err(RedirectorError::FileWriteError)` - If the stub page cannot be written,
+    ///   or a file already exists at its path
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::{Redirector, Registry};
+    /// use std::fs;
+    ///
+    /// Redirector::builder()
+    ///     .target("summer/sale")
+    ///     .out_dir("doc_test_registry_add_alias")
+    ///     .alias("summer-sale-2025")
+    ///     .build()
+    ///     .unwrap()
+    ///     .write_redirect()
+    ///     .unwrap();
+    ///
+    /// let mut registry = Registry::load("doc_test_registry_add_alias").unwrap();
+    /// let stub = registry.add_alias("/summer/sale/", "sale").unwrap();
+    /// registry.save().unwrap();
+    ///
+    /// assert!(stub.ends_with("sale.html"));
+    /// assert_eq!(registry.len(), 1);
+    /// assert_eq!(registry.target_for("sale"), Some("/summer/sale/"));
+    ///
+    /// fs::remove_dir_all("doc_test_registry_add_alias").ok();
+    /// ```
+    pub fn add_alias(&mut self, target: &str, alias: &str) -> Result<PathBuf, RedirectorError> {
+        validate_alias(alias)?;
+        let entry = self
+            .entry(target)
+            .ok_or(RedirectorError::ShortLinkNotFound)?;
+        if self.contains_short_name(alias) {
+            return Err(RedirectorError::DuplicateShortName(alias.to_string()));
+        }
+
+        let (file_path, page) = stub(self.dir(), entry, alias);
+
+        let dir = file_path.parent().unwrap_or(self.dir());
+        FsSink
+            .create_dir_all(dir)
+            .map_err(|source| RedirectorError::DirectoryCreationError {
+                path: dir.to_path_buf(),
+                source,
+            })?;
+        FsSink
+            .create_file(&file_path, page.as_bytes())
+            .map_err(|source| RedirectorError::FileWriteError {
+                path: file_path.clone(),
+                source,
+            })?;
+        trace::debug!(target = %target, alias, file = %file_path.display(), "wrote alias stub");
+
+        self.insert_alias(target, alias);
+        Ok(file_path)
+    }
+}
+
+/// Returns the path and content of the stub page for an extra short name of a
+/// registered redirect.
+///
+/// The stub uses the layout of the redirect's own page, so it is found and removed
+/// with it.
+pub(crate) fn stub(dir: &Path, entry: &RegistryEntry, alias: &str) -> (PathBuf, String) {
+    let layout = OutputLayout::of(entry.short_file());
+    let href = match layout {
+        OutputLayout::File => entry.short_file().to_string(),
+        OutputLayout::Directory => format!("../{}", entry.short_file()),
+    };
+
+    (layout.file_path(dir, alias), page(&href))
+}
+
+/// Renders the stub page leading to a redirect's page at a relative URL.
+fn page(href: &str) -> String {
+    format!(
+        r#"<!DOCTYPE HTML>
+<html lang="en-US">
+
+<head>
+    <meta charset="UTF-8">
+    <meta http-equiv="refresh" content="0; url={href}">
+    <meta name="robots" content="noindex">
+    <title>Page Redirection</title>
+</head>
+
+<body>
+    If you are not redirected automatically, follow this <a href='{href}'>link to page</a>.
+</body>
+
+</html>
+"#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Redirector;
+    use chrono::Utc;
+    use std::fs;
+
+    fn test_dir(name: &str) -> String {
+        format!("{name}_{}", Utc::now().timestamp_nanos_opt().unwrap_or(0))
+    }
+
+    #[test]
+    fn test_add_alias() {
+        let dir = test_dir("test_add_alias");
+        Redirector::builder()
+            .target("summer/sale")
+            .out_dir(&dir)
+            .alias("promo")
+            .build()
+            .unwrap()
+            .write_redirect()
+            .unwrap();
+
+        let mut registry = Registry::load(&dir).unwrap();
+        let stub = registry.add_alias("/summer/sale/", "sale").unwrap();
+        assert!(fs::read_to_string(&stub)
+            .unwrap()
+            .contains("url=promo.html"));
+        assert!(matches!(
+            registry.add_alias("/summer/sale/", "promo"),
+            Err(RedirectorError::DuplicateShortName(_))
+        ));
+        assert!(matches!(
+            registry.add_alias("/winter/sale/", "winter"),
+            Err(RedirectorError::ShortLinkNotFound)
+        ));
+        assert!(matches!(
+            registry.add_alias("/summer/sale/", "../sale"),
+            Err(RedirectorError::InvalidAlias(_))
+        ));
+        registry.save().unwrap();
+
+        // The alias survives a round trip and moves with the redirect
+        let mut registry = Registry::load(&dir).unwrap();
+        assert_eq!(registry.len(), 1);
+        assert_eq!(registry.entry("/summer/sale/").unwrap().aliases(), ["sale"]);
+        registry.update_target("sale", "winter/sale").unwrap();
+        assert_eq!(registry.target_for("promo"), Some("/winter/sale/"));
+        assert_eq!(registry.target_for("sale"), Some("/winter/sale/"));
+
+        // Removing the alias keeps the redirect, removing the redirect drops both
+        registry.add_alias("/winter/sale/", "deal").unwrap();
+        assert_eq!(registry.remove("sale").unwrap(), "/winter/sale/");
+        assert!(!stub.exists());
+        assert!(registry.contains_short_name("promo"));
+        registry.remove("promo").unwrap();
+        assert!(!registry.contains_short_name("deal"));
+        assert!(!Path::new(&dir).join("deal.html").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_add_alias_directory_layout() {
+        let dir = test_dir("test_add_alias_directory_layout");
+        Redirector::builder()
+            .target("summer/sale")
+            .out_dir(&dir)
+            .alias("promo")
+            .layout(OutputLayout::Directory)
+            .build()
+            .unwrap()
+            .write_redirect()
+            .unwrap();
+
+        let mut registry = Registry::load(&dir).unwrap();
+        let stub = registry.add_alias("/summer/sale/", "sale").unwrap();
+        assert_eq!(stub, Path::new(&dir).join("sale").join("index.html"));
+        assert!(fs::read_to_string(&stub).unwrap().contains("url=../promo/"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
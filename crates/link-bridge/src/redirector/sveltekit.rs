@@ -0,0 +1,117 @@
+//! Exporting the registry as a SvelteKit server hook.
+//!
+//! SvelteKit deployments answer requests from the `handle` hook of
+//! `src/hooks.server.js` before any route runs. This module provides
+//! `Registry::to_sveltekit_hooks()`, which renders a hook module holding the
+//! registry's redirect map, so a SvelteKit server can answer short links with `301`
+//! redirects from the same data as the static pages.
+
+use crate::redirector::json::quote;
+use crate::redirector::Registry;
+
+impl Registry {
+    /// Renders a `hooks.server.js` module whose `handle` hook answers requests for
+    /// short names with `301` redirects.
+    ///
+    /// Each short name, including extra short names added with
+    /// [`Registry::add_alias()`], maps its path below `base_path` to its target, with
+    /// or without the `.html` extension or a trailing slash. Retired redirects are
+    /// left out. Every other request is passed to `resolve`.
+    ///
+    /// To combine the hook with others, import `handle` from the generated module
+    /// into your own hooks file and chain it with `sequence()` from
+    /// `@sveltejs/kit/hooks`.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_path` - The path the short links are served from, such as `"/s"`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::{Redirector, Registry};
+    /// use std::fs;
+    ///
+    /// Redirector::builder()
+    ///     .target("docs/install")
+    ///     .out_dir("doc_test_sveltekit_hooks")
+    ///     .alias("install")
+    ///     .build()
+    ///     .unwrap()
+    ///     .write_redirect()
+    ///     .unwrap();
+    ///
+    /// let registry = Registry::load("doc_test_sveltekit_hooks").unwrap();
+    /// let module = registry.to_sveltekit_hooks("/s");
+    /// assert!(module.contains(r#"["/s/install", "/docs/install/"],"#));
+    /// assert!(module.contains("export async function handle"));
+    ///
+    /// fs::remove_dir_all("doc_test_sveltekit_hooks").ok();
+    /// ```
+    pub fn to_sveltekit_hooks<S: ToString>(&self, base_path: S) -> String {
+        let mut module = String::from(
+            "// Generated by link-bridge from the registry; do not edit.\n\
+             import { redirect } from \"@sveltejs/kit\";\n\
+             \n\
+             const redirects = new Map([\n",
+        );
+        for (source, target) in self.redirect_sources(&base_path.to_string()) {
+            // JSON strings are valid JavaScript string literals
+            module.push_str(&format!("  [{}, {}],\n", quote(&source), quote(target)));
+        }
+        module.push_str(
+            "]);\n\
+             \n\
+             /** @type {import(\"@sveltejs/kit\").Handle} */\n\
+             export async function handle({ event, resolve }) {\n\
+             \x20 const path = event.url.pathname.replace(/\\/$/, \"\").replace(/\\.html$/, \"\");\n\
+             \x20 const location = redirects.get(path);\n\
+             \x20 if (location !== undefined) {\n\
+             \x20   throw redirect(301, location);\n\
+             \x20 }\n\
+             \x20 return resolve(event);\n\
+             }\n",
+        );
+        module
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_to_sveltekit_hooks() {
+        let mut registry = Registry::empty(PathBuf::from("s"));
+        registry.insert("/docs/usage/".to_string(), "s/usage.html".to_string());
+        registry.insert(
+            "https://example.org/?q=\"a\"".to_string(),
+            "s/external.html".to_string(),
+        );
+        registry.insert("/docs/old/".to_string(), "s/old.html".to_string());
+        registry.entry_mut("/docs/old/").unwrap().retire();
+
+        assert_eq!(
+            registry.to_sveltekit_hooks("/s"),
+            r#"// Generated by link-bridge from the registry; do not edit.
+import { redirect } from "@sveltejs/kit";
+
+const redirects = new Map([
+  ["/s/external", "https://example.org/?q=\"a\""],
+  ["/s/usage", "/docs/usage/"],
+]);
+
+/** @type {import("@sveltejs/kit").Handle} */
+export async function handle({ event, resolve }) {
+  const path = event.url.pathname.replace(/\/$/, "").replace(/\.html$/, "");
+  const location = redirects.get(path);
+  if (location !== undefined) {
+    throw redirect(301, location);
+  }
+  return resolve(event);
+}
+"#
+        );
+    }
+}
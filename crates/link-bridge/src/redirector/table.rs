@@ -0,0 +1,122 @@
+//! A plain-text table of the registry.
+//!
+//! `registry.json` is meant for machines; a quick look at what a directory holds
+//! wants one aligned line per redirect. This module renders the registry as a text
+//! table of short names, targets, and creation dates, for terminals and reports.
+
+use std::fmt::{self, Display, Write};
+
+use crate::redirector::time::Timestamp;
+use crate::redirector::Registry;
+
+/// The column headings of the table.
+const HEADINGS: [&str; 3] = ["SHORT NAME", "TARGET", "CREATED"];
+
+impl Registry {
+    /// Renders the registry as an aligned text table.
+    ///
+    /// The table has a heading row followed by one row per redirect, ordered by short
+    /// name, with the short name, the target, and the date the redirect was recorded.
+    /// Redirects whose creation time is unknown, such as those read from registries
+    /// written by earlier versions, show `-`. Columns are padded with spaces to line
+    /// up in a fixed-width font. The [`Display`] implementation renders the same
+    /// table.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::{Redirector, Registry};
+    /// use std::fs;
+    ///
+    /// Redirector::builder()
+    ///     .target("api/v1/users")
+    ///     .out_dir("doc_test_registry_table")
+    ///     .alias("users")
+    ///     .build()
+    ///     .unwrap()
+    ///     .write_redirect()
+    ///     .unwrap();
+    ///
+    /// let table = Registry::load("doc_test_registry_table").unwrap().to_table();
+    /// let mut lines = table.lines();
+    /// assert_eq!(lines.next(), Some("SHORT NAME  TARGET          CREATED"));
+    /// assert!(lines.next().unwrap().starts_with("users       /api/v1/users/  20"));
+    ///
+    /// fs::remove_dir_all("doc_test_registry_table").ok();
+    /// ```
+    pub fn to_table(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl Display for Registry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut rows: Vec<[String; 3]> = self
+            .iter()
+            .map(|(short_name, target, _)| {
+                let created = self
+                    .entry(target)
+                    .and_then(|entry| entry.created_at)
+                    .map_or_else(|| "-".to_string(), Timestamp::date);
+                [short_name.to_string(), target.to_string(), created]
+            })
+            .collect();
+        rows.sort_unstable();
+
+        let mut widths = HEADINGS.map(|heading| heading.chars().count());
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let headings = HEADINGS.map(str::to_string);
+        for row in std::iter::once(&headings).chain(&rows) {
+            let mut line = String::new();
+            for (column, (cell, width)) in row.iter().zip(widths).enumerate() {
+                if column > 0 {
+                    line.push_str("  ");
+                }
+                let _ = write!(line, "{cell:width$}");
+            }
+            writeln!(f, "{}", line.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_to_table() {
+        let registry = Registry::from_reader(
+            PathBuf::from("s"),
+            r#"{
+                "/docs/install/": {"path": "s/install.html", "created_at": "2025-06-01T12:00:00Z"},
+                "/a/": "s/zz.html",
+                "/café/": {"path": "s/cafe.html", "created_at": "2025-07-15T08:30:00Z"}
+            }"#
+            .as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            registry.to_table(),
+            "SHORT NAME  TARGET          CREATED\n\
+             cafe        /café/          2025-07-15\n\
+             install     /docs/install/  2025-06-01\n\
+             zz          /a/             -\n"
+        );
+    }
+
+    #[test]
+    fn test_to_table_empty() {
+        assert_eq!(
+            Registry::default().to_table(),
+            "SHORT NAME  TARGET  CREATED\n"
+        );
+    }
+}
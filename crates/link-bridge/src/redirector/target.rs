@@ -0,0 +1,675 @@
+//! Redirect target parsing and host allowlisting.
+//!
+//! This module provides the `Target` type describing where a redirect points. A target
+//! is either a site-relative URL path (validated by `UrlPath`) or, when explicitly
+//! enabled, an absolute `http`/`https` URL on a host permitted by a `HostAllowlist`, or
+//! a `mailto:` or `tel:` contact link. Requiring an allowlist for external targets
+//! prevents the redirect generator from being used to point short links at arbitrary
+//! third-party domains.
+
+use std::fmt::Display;
+
+use thiserror::Error;
+
+use crate::redirector::path_policy::{PathPolicy, TrailingSlash};
+use crate::redirector::url_path::UrlPath;
+use crate::redirector::RedirectorError;
+
+/// Errors that can occur when parsing an external redirect target.
+#[derive(Debug, Error)]
+pub enum TargetError {
+    /// The provided target is not a valid absolute URL.
+    ///
+    /// External targets must use the `http` or `https` scheme, name a host made up of
+    /// letters, digits, dots, and dashes, and must not contain user information,
+    /// whitespace, quotes, angle brackets, or backslashes. `mailto:` targets must name
+    /// an address, and `tel:` targets a number made up of digits, an optional leading
+    /// `+`, and the separators `-`, `.`, `(`, and `)`.
+    #[error("Invalid target URL: {0}")]
+    InvalidUrl(String),
+
+    /// The provided target uses a scheme that can run code in the visitor's browser.
+    ///
+    /// Targets using `javascript:`, `data:`, `vbscript:`, or a similar scheme are
+    /// rejected outright, so following a generated redirect cannot run a script
+    /// supplied as its target. The offending scheme is included, in lowercase.
+    #[error("Dangerous target URL scheme: {0}")]
+    DangerousScheme(String),
+}
+
+/// URL schemes that browsers execute or render in place of navigating.
+const DANGEROUS_SCHEMES: [&str; 8] = [
+    "blob",
+    "data",
+    "file",
+    "filesystem",
+    "javascript",
+    "livescript",
+    "mocha",
+    "vbscript",
+];
+
+/// A set of hosts that external redirect targets are permitted to point at.
+///
+/// Entries are matched case-insensitively against the host of the target URL. An entry
+/// of the form `*.example.com` matches any subdomain of `example.com` (but not
+/// `example.com` itself); any other entry must match the host exactly.
+///
+/// # Examples
+///
+/// ```rust
+/// use link_bridge::HostAllowlist;
+///
+/// let allowlist = HostAllowlist::new(["example.com", "*.example.org"]);
+///
+/// assert!(allowlist.permits("example.com"));
+/// assert!(allowlist.permits("docs.example.org"));
+/// assert!(!allowlist.permits("example.org"));
+/// assert!(!allowlist.permits("evil.com"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct HostAllowlist {
+    /// The permitted host patterns, stored in lowercase.
+    hosts: Vec<String>,
+}
+
+impl HostAllowlist {
+    /// Creates a new allowlist from the given host patterns.
+    ///
+    /// # Arguments
+    ///
+    /// * `hosts` - The hosts to permit, e.g. `"example.com"` or `"*.example.com"`
+    pub fn new<I, S>(hosts: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: ToString,
+    {
+        HostAllowlist {
+            hosts: hosts
+                .into_iter()
+                .map(|host| host.to_string().to_ascii_lowercase())
+                .collect(),
+        }
+    }
+
+    /// Adds a host pattern to the allowlist.
+    ///
+    /// # Arguments
+    ///
+    /// * `host` - The host to permit, e.g. `"example.com"` or `"*.example.com"`
+    pub fn allow<S: ToString>(&mut self, host: S) {
+        self.hosts.push(host.to_string().to_ascii_lowercase());
+    }
+
+    /// Reports whether the given host is permitted by the allowlist.
+    ///
+    /// # Arguments
+    ///
+    /// * `host` - The host name to check (without scheme or port)
+    pub fn permits(&self, host: &str) -> bool {
+        let host = host.to_ascii_lowercase();
+        self.hosts
+            .iter()
+            .any(|pattern| match pattern.strip_prefix("*.") {
+                Some(domain) => host
+                    .strip_suffix(domain)
+                    .is_some_and(|prefix| prefix.len() > 1 && prefix.ends_with('.')),
+                None => *pattern == host,
+            })
+    }
+}
+
+/// A validated absolute `http` or `https` URL used as an external redirect target.
+///
+/// The scheme and host are normalized to lowercase; the remainder of the URL is kept
+/// as provided.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub(crate) struct ExternalUrl {
+    /// The full normalized URL.
+    url: String,
+    /// The host component of the URL.
+    host: String,
+}
+
+impl ExternalUrl {
+    /// Parses and validates an absolute URL.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The absolute URL to validate (e.g. `"https://example.com/docs"`)
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ExternalUrl)` - If the URL is a well-formed `http` or `https` URL
+    /// * `Err(TargetError::DangerousScheme)` - If the URL uses a scheme such as
+    ///   `javascript:`
+    /// * `Err(TargetError::InvalidUrl)` - If the URL is malformed or uses another scheme
+    pub(crate) fn new(url: String) -> Result<Self, TargetError> {
+        check_scheme(&url)?;
+
+        let Some((scheme, host, port, rest)) = split_url(&url) else {
+            return Err(TargetError::InvalidUrl(url));
+        };
+        let scheme = scheme.to_ascii_lowercase();
+        let host = host.to_ascii_lowercase();
+
+        Ok(ExternalUrl {
+            url: format!("{scheme}://{host}{port}{rest}"),
+            host,
+        })
+    }
+
+    /// Parses and validates a site base URL.
+    ///
+    /// A base URL is an absolute `http` or `https` URL, optionally with a path prefix
+    /// (e.g. `"https://example.com/blog"`). Any trailing slash is removed so that path
+    /// targets can be appended directly. Query strings and fragments are not permitted.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ExternalUrl)` - If the base URL is valid
+    /// * `Err(TargetError::InvalidUrl)` - If the URL is malformed or has a query or fragment
+    pub(crate) fn new_base(url: String) -> Result<Self, TargetError> {
+        if url.contains(['?', '#']) {
+            return Err(TargetError::InvalidUrl(url));
+        }
+
+        let mut base = ExternalUrl::new(url)?;
+        while base.url.ends_with('/') {
+            base.url.pop();
+        }
+
+        Ok(base)
+    }
+
+    /// Returns the host component of the URL.
+    pub(crate) fn host(&self) -> &str {
+        &self.host
+    }
+}
+
+impl Display for ExternalUrl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.url)
+    }
+}
+
+/// Splits an absolute `http` or `https` URL into its scheme, host, port (with its
+/// colon), and the remaining path, query, and fragment.
+///
+/// The host must start and end with a letter or digit and otherwise hold only letters,
+/// digits, dots, and dashes; the port holds one to five digits; and the remainder
+/// starts with `/`, `?`, or `#` and holds no whitespace, quotes, angle brackets, or
+/// backslashes. Returns `None` if the URL does not have this shape.
+fn split_url(url: &str) -> Option<(&str, &str, &str, &str)> {
+    let (scheme, rest) = url.split_once("://")?;
+    if !scheme.eq_ignore_ascii_case("http") && !scheme.eq_ignore_ascii_case("https") {
+        return None;
+    }
+
+    let host_end = rest
+        .find(|c: char| !c.is_ascii_alphanumeric() && c != '.' && c != '-')
+        .unwrap_or(rest.len());
+    let (host, rest) = rest.split_at(host_end);
+    if !host.starts_with(|c: char| c.is_ascii_alphanumeric())
+        || !host.ends_with(|c: char| c.is_ascii_alphanumeric())
+    {
+        return None;
+    }
+
+    let port_end = match rest.strip_prefix(':') {
+        Some(digits) => {
+            let len = digits
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(digits.len());
+            if !(1..=5).contains(&len) {
+                return None;
+            }
+            len + 1
+        }
+        None => 0,
+    };
+    let (port, rest) = rest.split_at(port_end);
+
+    let valid_rest = rest.is_empty()
+        || (rest.starts_with(['/', '?', '#'])
+            && !rest.contains(|c: char| c.is_whitespace() || "\"'<>\\".contains(c)));
+
+    valid_rest.then_some((scheme, host, port, rest))
+}
+
+/// A validated `mailto:` or `tel:` URL used as a contact redirect target.
+///
+/// The scheme is normalized to lowercase. Characters of a `mailto:` URL that are not
+/// safe in an HTML attribute or a script string are percent-encoded, and spaces are
+/// removed from a `tel:` number, so the URL can be written into the page as it is.
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) struct ContactUrl {
+    /// The full normalized URL.
+    url: String,
+}
+
+impl ContactUrl {
+    /// Parses and validates a `mailto:` or `tel:` URL.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The contact URL (e.g. `"mailto:support@example.com"` or
+    ///   `"tel:+353 1 234 567"`)
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ContactUrl)` - If the URL is a well-formed contact URL
+    /// * `Err(TargetError::InvalidUrl)` - If the URL uses another scheme, has no
+    ///   address, or is not a phone number
+    pub(crate) fn new(url: String) -> Result<Self, TargetError> {
+        let Some((scheme, rest)) = url.split_once(':') else {
+            return Err(TargetError::InvalidUrl(url));
+        };
+        let scheme = scheme.to_ascii_lowercase();
+
+        let rest = match scheme.as_str() {
+            "mailto" => {
+                let address = rest.split('?').next().unwrap_or_default();
+                if !address.contains('@') {
+                    return Err(TargetError::InvalidUrl(url));
+                }
+                percent_encode(rest)
+            }
+            "tel" => {
+                let number: String = rest.chars().filter(|c| *c != ' ').collect();
+                let digits = number.strip_prefix('+').unwrap_or(&number);
+                let valid = digits.chars().any(|c| c.is_ascii_digit())
+                    && digits
+                        .chars()
+                        .all(|c| c.is_ascii_digit() || matches!(c, '-' | '.' | '(' | ')'));
+                if !valid {
+                    return Err(TargetError::InvalidUrl(url));
+                }
+                number
+            }
+            _ => return Err(TargetError::InvalidUrl(url)),
+        };
+
+        Ok(ContactUrl {
+            url: format!("{scheme}:{rest}"),
+        })
+    }
+}
+
+impl Display for ContactUrl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.url)
+    }
+}
+
+/// Percent-encodes every byte of a URL that is not safe to write unescaped into an
+/// HTML attribute or a script string, other than `&`, which separates the fields of
+/// a `mailto:` URL. Existing percent-escapes are kept.
+fn percent_encode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut encoded = String::with_capacity(text.len());
+    for (index, &byte) in bytes.iter().enumerate() {
+        let escaped = byte == b'%'
+            && bytes.get(index + 1).is_some_and(u8::is_ascii_hexdigit)
+            && bytes.get(index + 2).is_some_and(u8::is_ascii_hexdigit);
+        if escaped || byte.is_ascii_alphanumeric() || b"-._~!$&()*+,;=:@/?".contains(&byte) {
+            encoded.push(char::from(byte));
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
+/// The destination of a redirect.
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) enum Target {
+    /// A path on the same site, e.g. `/api/v1/`.
+    Path(UrlPath),
+    /// An absolute URL on an allowlisted host.
+    External(ExternalUrl),
+    /// A `mailto:` or `tel:` contact link.
+    Contact(ContactUrl),
+}
+
+impl Target {
+    /// Reconstructs a target from its registry key.
+    ///
+    /// Registry keys are targets that were validated and normalized when the redirect
+    /// was written, so they are parsed without re-applying normalization: absolute
+    /// URLs become external targets, contact URLs contact targets, and paths keep
+    /// their trailing slash as stored.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The normalized target recorded in the registry
+    pub(crate) fn from_registry_key(key: &str) -> Result<Self, RedirectorError> {
+        if is_absolute_url(key) {
+            return Ok(Target::External(ExternalUrl::new(key.to_string())?));
+        }
+        if is_contact_url(key) {
+            return Ok(Target::Contact(ContactUrl::new(key.to_string())?));
+        }
+
+        let policy = PathPolicy::default().trailing_slash(TrailingSlash::Preserve);
+        Ok(Target::Path(UrlPath::with_policy(key, &policy)?))
+    }
+
+    /// Resolves the target to the URL emitted in the generated redirect page.
+    ///
+    /// Path targets are prefixed with the base URL when one is configured; external
+    /// and contact targets are already absolute and are returned unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_url` - The optional site base URL (without trailing slash)
+    pub(crate) fn resolve(&self, base_url: Option<&ExternalUrl>) -> String {
+        match (self, base_url) {
+            (Target::Path(path), Some(base)) => format!("{base}{path}"),
+            _ => self.to_string(),
+        }
+    }
+}
+
+impl Default for Target {
+    fn default() -> Self {
+        Target::Path(UrlPath::default())
+    }
+}
+
+impl Display for Target {
+    /// Formats the target as it appears in the generated redirect page.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Target::Path(path) => path.fmt(f),
+            Target::External(url) => url.fmt(f),
+            Target::Contact(url) => url.fmt(f),
+        }
+    }
+}
+
+/// Rejects a target whose scheme is one of [`DANGEROUS_SCHEMES`].
+///
+/// Browsers ignore leading whitespace and control characters, and tabs and newlines
+/// anywhere in the scheme, so they are ignored here too; `" Java\tScript:"` is
+/// rejected like `"javascript:"`.
+pub(crate) fn check_scheme(target: &str) -> Result<(), TargetError> {
+    let Some((scheme, _)) = target.split_once(':') else {
+        return Ok(());
+    };
+    let scheme: String = scheme
+        .trim_start_matches(|c: char| c <= ' ')
+        .chars()
+        .filter(|c| !matches!(c, '\t' | '\n' | '\r'))
+        .collect::<String>()
+        .to_ascii_lowercase();
+
+    if DANGEROUS_SCHEMES.contains(&scheme.as_str()) {
+        return Err(TargetError::DangerousScheme(scheme));
+    }
+    Ok(())
+}
+
+/// Reports whether a target string looks like an absolute `http` or `https` URL.
+pub(crate) fn is_absolute_url(target: &str) -> bool {
+    let lower = target.get(..8).unwrap_or(target).to_ascii_lowercase();
+    lower.starts_with("http://") || lower.starts_with("https://")
+}
+
+/// Reports whether a target string looks like a `mailto:` or `tel:` URL.
+pub(crate) fn is_contact_url(target: &str) -> bool {
+    let lower = target.get(..7).unwrap_or(target).to_ascii_lowercase();
+    lower.starts_with("mailto:") || lower.starts_with("tel:")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_external_url_valid_https() {
+        let url = ExternalUrl::new("https://example.com/docs".to_string()).unwrap();
+        assert_eq!(url.to_string(), "https://example.com/docs");
+        assert_eq!(url.host(), "example.com");
+    }
+
+    #[test]
+    fn test_external_url_normalizes_scheme_and_host() {
+        let url = ExternalUrl::new("HTTPS://Docs.Example.COM/Path".to_string()).unwrap();
+        assert_eq!(url.to_string(), "https://docs.example.com/Path");
+        assert_eq!(url.host(), "docs.example.com");
+    }
+
+    #[test]
+    fn test_external_url_with_port_and_query() {
+        let url = ExternalUrl::new("http://example.com:8080/a?b=c#d".to_string()).unwrap();
+        assert_eq!(url.to_string(), "http://example.com:8080/a?b=c#d");
+        assert_eq!(url.host(), "example.com");
+    }
+
+    #[test]
+    fn test_external_url_host_only() {
+        let url = ExternalUrl::new("https://example.com".to_string()).unwrap();
+        assert_eq!(url.to_string(), "https://example.com");
+    }
+
+    #[test]
+    fn test_external_url_invalid_scheme() {
+        assert!(matches!(
+            ExternalUrl::new("ftp://example.com/file".to_string()),
+            Err(TargetError::InvalidUrl(_))
+        ));
+        assert!(matches!(
+            ExternalUrl::new("javascript:alert(1)".to_string()),
+            Err(TargetError::DangerousScheme(scheme)) if scheme == "javascript"
+        ));
+    }
+
+    #[test]
+    fn test_check_scheme() {
+        for target in [
+            "data:text/html,<script>alert(1)</script>",
+            "VBScript:msgbox(1)",
+            " \u{1}java\tscript:alert(1)",
+            "jav\nascript:alert(1)",
+        ] {
+            assert!(
+                matches!(check_scheme(target), Err(TargetError::DangerousScheme(_))),
+                "{target:?} was accepted"
+            );
+        }
+        assert!(check_scheme("https://example.com/a:b").is_ok());
+        assert!(check_scheme("docs/install").is_ok());
+        assert!(check_scheme("docs/data:set").is_ok());
+    }
+
+    #[test]
+    fn test_contact_url() {
+        let url = ContactUrl::new("MailTo:support@example.com".to_string()).unwrap();
+        assert_eq!(url.to_string(), "mailto:support@example.com");
+
+        let url = ContactUrl::new(
+            "mailto:sales@example.com?subject=Poster enquiry&body=\"Hi\" <'team'>".to_string(),
+        )
+        .unwrap();
+        assert_eq!(
+            url.to_string(),
+            "mailto:sales@example.com?subject=Poster%20enquiry&body=%22Hi%22%20%3C%27team%27%3E"
+        );
+        let url = ContactUrl::new("mailto:caf%C3%A9@example.com?subject=100%".to_string()).unwrap();
+        assert_eq!(
+            url.to_string(),
+            "mailto:caf%C3%A9@example.com?subject=100%25"
+        );
+
+        let url = ContactUrl::new("tel:+353 1 234-567".to_string()).unwrap();
+        assert_eq!(url.to_string(), "tel:+3531234-567");
+
+        for invalid in [
+            "mailto:",
+            "mailto:?subject=Hi",
+            "tel:",
+            "tel:+",
+            "tel:call-us",
+            "tel:123;ext=4",
+            "sms:+3531234567",
+            "support@example.com",
+        ] {
+            assert!(
+                matches!(
+                    ContactUrl::new(invalid.to_string()),
+                    Err(TargetError::InvalidUrl(_))
+                ),
+                "{invalid:?} was accepted"
+            );
+        }
+    }
+
+    #[test]
+    fn test_is_contact_url() {
+        assert!(is_contact_url("mailto:a@example.com"));
+        assert!(is_contact_url("TEL:123"));
+        assert!(!is_contact_url("docs/mailto"));
+        assert!(!is_contact_url("tel"));
+    }
+
+    #[test]
+    fn test_external_url_invalid_userinfo() {
+        let result = ExternalUrl::new("https://example.com@evil.com/".to_string());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_external_url_invalid_characters() {
+        assert!(ExternalUrl::new("https://example.com/\"onload".to_string()).is_err());
+        assert!(ExternalUrl::new("https://example.com/a'onmouseover='x".to_string()).is_err());
+        assert!(ExternalUrl::new("https://example.com/<script>".to_string()).is_err());
+        assert!(ExternalUrl::new("https://example.com/a b".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_external_url_invalid_host_and_port() {
+        for url in [
+            "https://",
+            "https://-example.com/",
+            "https://example.com./",
+            "https://exa_mple.com/",
+            "https://example.com:/",
+            "https://example.com:123456/",
+            "https://example.com:80x/",
+            "https:/example.com/",
+            "https://example.com\\evil.com",
+        ] {
+            assert!(ExternalUrl::new(url.to_string()).is_err(), "{url}");
+        }
+        let url = ExternalUrl::new("HTTP://A-1.Example.COM:65535?q".to_string()).unwrap();
+        assert_eq!(url.to_string(), "http://a-1.example.com:65535?q");
+    }
+
+    #[test]
+    fn test_external_url_relative_path_rejected() {
+        assert!(ExternalUrl::new("/api/v1".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_external_url_new_base() {
+        let base = ExternalUrl::new_base("https://Example.com/".to_string()).unwrap();
+        assert_eq!(base.to_string(), "https://example.com");
+
+        let base = ExternalUrl::new_base("https://example.com/blog//".to_string()).unwrap();
+        assert_eq!(base.to_string(), "https://example.com/blog");
+    }
+
+    #[test]
+    fn test_external_url_new_base_rejects_query_and_fragment() {
+        assert!(ExternalUrl::new_base("https://example.com/?a=b".to_string()).is_err());
+        assert!(ExternalUrl::new_base("https://example.com/#top".to_string()).is_err());
+        assert!(ExternalUrl::new_base("example.com".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_target_resolve() {
+        let base = ExternalUrl::new_base("https://example.com/blog/".to_string()).unwrap();
+        let path = Target::Path(UrlPath::new("api/v1".to_string()).unwrap());
+        let url = Target::External(ExternalUrl::new("https://other.com/x".to_string()).unwrap());
+
+        assert_eq!(path.resolve(None), "/api/v1/");
+        assert_eq!(
+            path.resolve(Some(&base)),
+            "https://example.com/blog/api/v1/"
+        );
+        assert_eq!(url.resolve(Some(&base)), "https://other.com/x");
+    }
+
+    #[test]
+    fn test_host_allowlist_exact_match() {
+        let allowlist = HostAllowlist::new(["example.com"]);
+        assert!(allowlist.permits("example.com"));
+        assert!(allowlist.permits("EXAMPLE.com"));
+        assert!(!allowlist.permits("docs.example.com"));
+        assert!(!allowlist.permits("notexample.com"));
+    }
+
+    #[test]
+    fn test_host_allowlist_wildcard_match() {
+        let allowlist = HostAllowlist::new(["*.example.com"]);
+        assert!(allowlist.permits("docs.example.com"));
+        assert!(allowlist.permits("a.b.example.com"));
+        assert!(!allowlist.permits("example.com"));
+        assert!(!allowlist.permits("evilexample.com"));
+    }
+
+    #[test]
+    fn test_host_allowlist_allow() {
+        let mut allowlist = HostAllowlist::default();
+        assert!(!allowlist.permits("example.com"));
+
+        allowlist.allow("Example.com");
+        assert!(allowlist.permits("example.com"));
+    }
+
+    #[test]
+    fn test_target_display() {
+        let path = Target::Path(UrlPath::new("api/v1".to_string()).unwrap());
+        assert_eq!(path.to_string(), "/api/v1/");
+
+        let url = Target::External(ExternalUrl::new("https://example.com/".to_string()).unwrap());
+        assert_eq!(url.to_string(), "https://example.com/");
+    }
+
+    #[test]
+    fn test_target_from_registry_key() {
+        let target = Target::from_registry_key("/docs/install").unwrap();
+        assert_eq!(target.to_string(), "/docs/install");
+
+        let target = Target::from_registry_key("/docs/install/").unwrap();
+        assert_eq!(target.to_string(), "/docs/install/");
+
+        let target = Target::from_registry_key("https://example.com/x").unwrap();
+        assert!(matches!(target, Target::External(_)));
+        assert_eq!(target.to_string(), "https://example.com/x");
+
+        assert!(Target::from_registry_key("bad?key").is_err());
+    }
+
+    #[test]
+    fn test_is_absolute_url() {
+        assert!(is_absolute_url("https://example.com"));
+        assert!(is_absolute_url("HTTP://example.com"));
+        assert!(!is_absolute_url("api/v1"));
+        assert!(!is_absolute_url("http"));
+        assert!(!is_absolute_url("ftp://example.com"));
+    }
+
+    #[test]
+    fn test_target_default() {
+        assert_eq!(Target::default(), Target::Path(UrlPath::default()));
+    }
+
+    #[test]
+    fn test_target_error_display() {
+        let error = TargetError::InvalidUrl("ftp://example.com".to_string());
+        assert_eq!(format!("{error}"), "Invalid target URL: ftp://example.com");
+    }
+}
@@ -0,0 +1,353 @@
+//! Timestamps without a date library.
+//!
+//! Registries record when redirects were created, replaced, retired, and when they
+//! expire, and short names are generated from the current time. This module provides
+//! the `Timestamp` type those are kept as, read from the system clock and written in
+//! RFC 3339 format, so the crate builds without `chrono`. With the `chrono` feature,
+//! timestamps convert to and from `DateTime<Utc>` at the public API.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Utc};
+
+/// Seconds in a day.
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// A point in time in UTC, with nanosecond precision.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) struct Timestamp {
+    /// Seconds since the Unix epoch.
+    secs: i64,
+    /// Nanoseconds past `secs`, below one billion.
+    nanos: u32,
+}
+
+impl Timestamp {
+    /// The latest representable time, the same as `chrono`'s `DateTime::<Utc>::MAX_UTC`
+    /// (the end of the year 262143).
+    pub(crate) const MAX: Timestamp = Timestamp {
+        secs: days_from_civil(262_143, 12, 31) * SECONDS_PER_DAY + SECONDS_PER_DAY - 1,
+        nanos: 999_999_999,
+    };
+
+    /// Returns the current system time.
+    pub(crate) fn now() -> Self {
+        Timestamp::from(SystemTime::now())
+    }
+
+    /// Returns the time as milliseconds since the Unix epoch.
+    pub(crate) fn unix_millis(self) -> i64 {
+        self.secs * 1000 + i64::from(self.nanos / 1_000_000)
+    }
+
+    /// Returns the time with the fraction of a second dropped.
+    pub(crate) fn trunc_subsecs(self) -> Self {
+        Timestamp {
+            secs: self.secs,
+            nanos: 0,
+        }
+    }
+
+    /// Returns the time `duration` later, or `None` past [`Timestamp::MAX`].
+    pub(crate) fn checked_add(self, duration: Duration) -> Option<Self> {
+        let nanos = self.nanos + duration.subsec_nanos();
+        let secs = i64::try_from(duration.as_secs())
+            .ok()
+            .and_then(|secs| self.secs.checked_add(secs))?
+            .checked_add(i64::from(nanos / 1_000_000_000))?;
+        let time = Timestamp {
+            secs,
+            nanos: nanos % 1_000_000_000,
+        };
+        (time <= Timestamp::MAX).then_some(time)
+    }
+
+    /// Returns the UTC day the time falls on, as days since the Unix epoch.
+    pub(crate) fn day(self) -> i64 {
+        self.secs.div_euclid(SECONDS_PER_DAY)
+    }
+
+    /// Formats the UTC date the time falls on, such as `2024-01-02`.
+    ///
+    /// Years outside `0000`-`9999` are written with an explicit sign, as `chrono`
+    /// writes them.
+    pub(crate) fn date(self) -> String {
+        let (year, month, day) = civil_from_days(self.day());
+        if (0..=9999).contains(&year) {
+            format!("{year:04}-{month:02}-{day:02}")
+        } else {
+            format!("{year:+05}-{month:02}-{day:02}")
+        }
+    }
+
+    /// Formats the time in RFC 3339 format to the second, such as
+    /// `2024-01-02T03:04:05Z`.
+    pub(crate) fn to_rfc3339(self) -> String {
+        let seconds = self.secs.rem_euclid(SECONDS_PER_DAY);
+        format!(
+            "{}T{:02}:{:02}:{:02}Z",
+            self.date(),
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60
+        )
+    }
+
+    /// Parses a time in RFC 3339 format, such as `2024-01-02T03:04:05Z` or
+    /// `2024-01-02T04:04:05.250+01:00`.
+    ///
+    /// Returns `None` if the text is not a valid RFC 3339 date and time.
+    pub(crate) fn parse_rfc3339(text: &str) -> Option<Self> {
+        let bytes = text.as_bytes();
+        let number = |range: std::ops::Range<usize>| -> Option<i64> {
+            let digits = bytes.get(range)?;
+            digits.iter().all(u8::is_ascii_digit).then(|| {
+                digits
+                    .iter()
+                    .fold(0, |value, digit| value * 10 + i64::from(digit - b'0'))
+            })
+        };
+        let separator = |index: usize, allowed: &[u8]| {
+            bytes.get(index).is_some_and(|byte| allowed.contains(byte))
+        };
+
+        let (year, month, day) = (number(0..4)?, number(5..7)?, number(8..10)?);
+        let (hour, minute, second) = (number(11..13)?, number(14..16)?, number(17..19)?);
+        if !(separator(4, b"-")
+            && separator(7, b"-")
+            && separator(10, b"Tt ")
+            && separator(13, b":")
+            && separator(16, b":"))
+        {
+            return None;
+        }
+        if !(1..=12).contains(&month)
+            || !(1..=days_in_month(year, month)).contains(&day)
+            || hour > 23
+            || minute > 59
+            || second > 59
+        {
+            return None;
+        }
+
+        let mut rest = &text[19..];
+        let mut nanos = 0;
+        if let Some(fraction) = rest.strip_prefix('.') {
+            let digits = fraction.bytes().take_while(u8::is_ascii_digit).count();
+            if digits == 0 {
+                return None;
+            }
+            nanos = fraction[..digits]
+                .bytes()
+                .chain(std::iter::repeat(b'0'))
+                .take(9)
+                .fold(0, |value, digit| value * 10 + u32::from(digit - b'0'));
+            rest = &fraction[digits..];
+        }
+
+        let offset = match rest.as_bytes() {
+            [b'Z' | b'z'] => 0,
+            [sign @ (b'+' | b'-'), h1, h2, b':', m1, m2]
+                if [h1, h2, m1, m2].iter().all(|digit| digit.is_ascii_digit()) =>
+            {
+                let digit = |digit: &u8| i64::from(digit - b'0');
+                let (hours, minutes) = (digit(h1) * 10 + digit(h2), digit(m1) * 10 + digit(m2));
+                if hours > 23 || minutes > 59 {
+                    return None;
+                }
+                let offset = hours * 3600 + minutes * 60;
+                if *sign == b'-' {
+                    -offset
+                } else {
+                    offset
+                }
+            }
+            _ => return None,
+        };
+
+        let secs = days_from_civil(year, month, day) * SECONDS_PER_DAY
+            + hour * 3600
+            + minute * 60
+            + second
+            - offset;
+        Some(Timestamp { secs, nanos })
+    }
+}
+
+impl From<SystemTime> for Timestamp {
+    fn from(time: SystemTime) -> Self {
+        match time.duration_since(UNIX_EPOCH) {
+            Ok(since) => Timestamp {
+                secs: i64::try_from(since.as_secs()).unwrap_or(i64::MAX),
+                nanos: since.subsec_nanos(),
+            },
+            Err(e) => {
+                let before = e.duration();
+                let secs = -i64::try_from(before.as_secs()).unwrap_or(i64::MAX);
+                match before.subsec_nanos() {
+                    0 => Timestamp { secs, nanos: 0 },
+                    nanos => Timestamp {
+                        secs: secs - 1,
+                        nanos: 1_000_000_000 - nanos,
+                    },
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<DateTime<Utc>> for Timestamp {
+    fn from(time: DateTime<Utc>) -> Self {
+        Timestamp {
+            secs: time.timestamp(),
+            // A leap second is counted as the last nanosecond of the second before it
+            nanos: time.timestamp_subsec_nanos().min(999_999_999),
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<Timestamp> for DateTime<Utc> {
+    fn from(time: Timestamp) -> Self {
+        DateTime::from_timestamp(time.secs, time.nanos).unwrap_or(DateTime::<Utc>::MAX_UTC)
+    }
+}
+
+/// Returns the days since the Unix epoch of a date in the proleptic Gregorian
+/// calendar.
+const fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = if year >= 0 { year } else { year - 399 } / 400;
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Returns the year, month, and day of a day counted from the Unix epoch.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = if days >= 0 { days } else { days - 146_096 } / 146_097;
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Returns the number of days in a month of a year.
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rfc3339_round_trip() {
+        for text in [
+            "1970-01-01T00:00:00Z",
+            "2000-02-29T12:34:56Z",
+            "2024-12-31T23:59:59Z",
+            "1969-07-20T20:17:40Z",
+            "0001-01-01T00:00:00Z",
+        ] {
+            let time = Timestamp::parse_rfc3339(text).unwrap();
+            assert_eq!(time.to_rfc3339(), text);
+        }
+        assert_eq!(
+            Timestamp::parse_rfc3339("1970-01-02T00:00:00Z").unwrap(),
+            Timestamp {
+                secs: 86_400,
+                nanos: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_rfc3339_offsets_and_fractions() {
+        let time = Timestamp::parse_rfc3339("2024-01-02T04:04:05.25+01:00").unwrap();
+        assert_eq!(time.to_rfc3339(), "2024-01-02T03:04:05Z");
+        assert_eq!(time.date(), "2024-01-02");
+        assert_eq!(time.nanos, 250_000_000);
+
+        let time = Timestamp::parse_rfc3339("2024-01-01t22:30:00-02:30").unwrap();
+        assert_eq!(time.to_rfc3339(), "2024-01-02T01:00:00Z");
+    }
+
+    #[test]
+    fn test_parse_rfc3339_invalid() {
+        for text in [
+            "",
+            "yesterday",
+            "2024-01-02",
+            "2024-01-02T03:04:05",
+            "2024-13-02T03:04:05Z",
+            "2023-02-29T03:04:05Z",
+            "2024-01-02T24:00:00Z",
+            "2024-01-02T03:04:05.Z",
+            "2024-01-02T03:04:05+0100",
+            "2024-01-02T03:04:05Z trailing",
+        ] {
+            assert!(Timestamp::parse_rfc3339(text).is_none(), "{text}");
+        }
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let time = Timestamp::parse_rfc3339("2024-01-02T03:04:05.5Z").unwrap();
+        assert_eq!(time.unix_millis(), 1_704_164_645_500);
+        assert_eq!(time.trunc_subsecs().unix_millis(), 1_704_164_645_000);
+        assert_eq!(
+            time.checked_add(Duration::from_millis(600))
+                .unwrap()
+                .unix_millis(),
+            1_704_164_646_100
+        );
+        assert_eq!(time.checked_add(Duration::MAX), None);
+        assert_eq!(Timestamp::MAX.checked_add(Duration::from_nanos(1)), None);
+        assert_eq!(time.day(), 19_724);
+    }
+
+    #[test]
+    fn test_max_and_before_epoch() {
+        assert_eq!(Timestamp::MAX.to_rfc3339(), "+262143-12-31T23:59:59Z");
+
+        let before = UNIX_EPOCH - Duration::from_millis(1500);
+        let time = Timestamp::from(before);
+        assert_eq!(time.unix_millis(), -1500);
+        assert_eq!(time.to_rfc3339(), "1969-12-31T23:59:58Z");
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_chrono_conversion() {
+        let now = Utc::now();
+        let time = Timestamp::from(now);
+        assert_eq!(DateTime::<Utc>::from(time), now);
+        assert_eq!(
+            time.to_rfc3339(),
+            now.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+        );
+        assert_eq!(
+            DateTime::<Utc>::from(Timestamp::MAX),
+            DateTime::<Utc>::MAX_UTC
+        );
+    }
+}
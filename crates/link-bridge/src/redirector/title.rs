@@ -0,0 +1,265 @@
+//! Titles of the pages redirects lead to.
+//!
+//! Every redirect page is titled "Page Redirection", so a short link pasted into a
+//! chat or a social post is previewed under that name rather than the name of the
+//! page it leads to. This module provides `RedirectorBuilder::fetch_title()`, which
+//! requests the target when the redirect is built and uses the `<title>` of the page
+//! it answers with as the redirect page's title and Open Graph title.
+
+use std::io::Read;
+use std::time::Duration;
+
+use crate::redirector::bookmarks::escape;
+use crate::redirector::target::Target;
+use crate::redirector::{trace, Redirector};
+
+/// How long fetching a target's title may take before it is abandoned.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The most of a target page read while looking for its title, in bytes.
+const MAX_READ: u64 = 64 * 1024;
+
+impl Redirector {
+    /// Fetches the title of the page the redirect leads to.
+    ///
+    /// Returns `None` for contact targets, for path targets without a base URL, and
+    /// if the page cannot be fetched or has no title.
+    pub(crate) fn fetch_title(&self) -> Option<String> {
+        match (&self.target, &self.base_url) {
+            (Target::Contact(_), _) | (Target::Path(_), None) => return None,
+            _ => {}
+        }
+        let url = self.target.resolve(self.base_url.as_ref());
+
+        let agent: ureq::Agent = ureq::Agent::config_builder()
+            .timeout_global(Some(TIMEOUT))
+            .build()
+            .into();
+        let mut html = Vec::new();
+        let fetched = agent
+            .get(&url)
+            .call()
+            .map_err(|e| e.to_string())
+            .and_then(|mut response| {
+                response
+                    .body_mut()
+                    .as_reader()
+                    .take(MAX_READ)
+                    .read_to_end(&mut html)
+                    .map_err(|e| e.to_string())
+            });
+        if let Err(_e) = fetched {
+            trace::warn!(url = %url, error = %_e, "failed to fetch target title");
+            return None;
+        }
+
+        let title = parse_title(&String::from_utf8_lossy(&html));
+        trace::debug!(url = %url, title = ?title, "fetched target title");
+        title
+    }
+
+    /// Renders the Open Graph title tag in the page's `<head>`, or nothing if no
+    /// title was fetched.
+    pub(crate) fn title_meta(&self) -> String {
+        match &self.title {
+            Some(title) => format!(
+                r#"
+        <meta property="og:title" content="{}">"#,
+                escape(title)
+            ),
+            None => String::new(),
+        }
+    }
+}
+
+/// Finds the text of the first `<title>` element of an HTML page, with entities
+/// decoded and whitespace collapsed.
+fn parse_title(html: &str) -> Option<String> {
+    // ASCII lowercasing keeps every byte where it was, so offsets carry over
+    let lower = html.to_ascii_lowercase();
+    let start = lower.find("<title")?;
+    let text_start = start + lower[start..].find('>')? + 1;
+    let text_end = text_start + lower[text_start..].find("</title")?;
+
+    let title = unescape(&html[text_start..text_end])
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    (!title.is_empty()).then_some(title)
+}
+
+/// Decodes the character references of HTML text.
+///
+/// Numeric references and the common named ones are decoded; any other `&` is kept
+/// as written.
+fn unescape(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        decoded.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let reference = rest[1..]
+            .find(';')
+            .map(|semi| &rest[1..=semi])
+            .and_then(|name| Some((name, decode_reference(name)?)));
+        match reference {
+            Some((name, c)) => {
+                decoded.push(c);
+                rest = &rest[name.len() + 2..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+/// Decodes a character reference, given without its `&` and `;`.
+fn decode_reference(name: &str) -> Option<char> {
+    let code = if let Some(hex) = name.strip_prefix("#x").or_else(|| name.strip_prefix("#X")) {
+        u32::from_str_radix(hex, 16).ok()?
+    } else if let Some(decimal) = name.strip_prefix('#') {
+        decimal.parse().ok()?
+    } else {
+        return match name {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            "ndash" => Some('–'),
+            "mdash" => Some('—'),
+            "hellip" => Some('…'),
+            "rsquo" => Some('’'),
+            "lsquo" => Some('‘'),
+            "rdquo" => Some('”'),
+            "ldquo" => Some('“'),
+            "middot" => Some('·'),
+            "copy" => Some('©'),
+            "reg" => Some('®'),
+            "trade" => Some('™'),
+            _ => None,
+        };
+    };
+    char::from_u32(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{HostAllowlist, MemorySink, OutputSink};
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::path::Path;
+    use std::thread;
+
+    /// Answers one HTTP request with an HTML page.
+    fn serve(
+        listener: TcpListener,
+        status: &'static str,
+        html: &'static str,
+    ) -> thread::JoinHandle<()> {
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim_end().is_empty() {
+                    break;
+                }
+            }
+            write!(
+                reader.get_mut(),
+                "HTTP/1.1 {status}\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{html}",
+                html.len()
+            )
+            .unwrap();
+        })
+    }
+
+    #[test]
+    fn test_parse_title() {
+        assert_eq!(
+            parse_title(
+                "<html><head><TITLE lang=\"en\">\n  Summer Sale\n  &amp; More </TITLE></head>"
+            ),
+            Some("Summer Sale & More".to_string())
+        );
+        assert_eq!(
+            parse_title("<title>Caf&#233; &#x2014; Men&uacute; &lt;2025&gt;</title>"),
+            Some("Café — Men&uacute; <2025>".to_string())
+        );
+        assert_eq!(parse_title("<title>  </title>"), None);
+        assert_eq!(parse_title("<title>Unclosed"), None);
+        assert_eq!(parse_title("<p>No title</p>"), None);
+    }
+
+    #[test]
+    fn test_unescape() {
+        assert_eq!(unescape("Fish & Chips"), "Fish & Chips");
+        assert_eq!(unescape("a &amp;amp; b"), "a &amp; b");
+        assert_eq!(unescape("&#xZZ; &; a & b;"), "&#xZZ; &; a & b;");
+    }
+
+    #[test]
+    fn test_fetch_title() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let server = serve(
+            listener,
+            "200 OK",
+            "<!DOCTYPE html><html><head><title>Summer \"Sale\" &amp; Offers</title></head></html>",
+        );
+
+        let mut sink = MemorySink::new();
+        Redirector::builder()
+            .target("summer/sale")
+            .alias("promo")
+            .base_url(&base_url)
+            .fetch_title()
+            .build()
+            .unwrap()
+            .write_redirect_to(&mut sink)
+            .unwrap();
+        server.join().unwrap();
+
+        let page = sink.read_file(Path::new("s/promo.html")).unwrap().unwrap();
+        let page = String::from_utf8(page).unwrap();
+        assert!(page.contains("<title>Summer &quot;Sale&quot; &amp; Offers</title>"));
+        assert!(page.contains(
+            r#"<meta property="og:title" content="Summer &quot;Sale&quot; &amp; Offers">"#
+        ));
+    }
+
+    #[test]
+    fn test_fetch_title_falls_back() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = serve(listener, "404 Not Found", "<title>Not Found</title>");
+
+        let redirector = Redirector::builder()
+            .target(format!("http://{addr}/gone"))
+            .allow_hosts(HostAllowlist::new(["127.0.0.1"]))
+            .fetch_title()
+            .build()
+            .unwrap();
+        server.join().unwrap();
+        assert_eq!(redirector.title, None);
+        let page = redirector.to_string();
+        assert!(page.contains("<title>Page Redirection</title>"));
+        assert!(!page.contains("og:title"));
+
+        // Path targets cannot be fetched without a base URL
+        let redirector = Redirector::builder()
+            .target("summer/sale")
+            .fetch_title()
+            .build()
+            .unwrap();
+        assert_eq!(redirector.title, None);
+    }
+}
@@ -0,0 +1,88 @@
+//! Optional `tracing` instrumentation.
+//!
+//! With the `tracing` feature enabled these macros forward to the macros of the same
+//! name in the `tracing` crate; without it they expand to nothing, so instrumented
+//! code compiles unchanged and costs nothing when tracing is not wanted.
+
+/// Emits a `tracing` event at the `DEBUG` level when the `tracing` feature is enabled.
+macro_rules! debug {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "tracing")]
+        {
+            ::tracing::debug!($($arg)*);
+        }
+    }};
+}
+
+/// Emits a `tracing` event at the `TRACE` level when the `tracing` feature is enabled.
+macro_rules! trace {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "tracing")]
+        {
+            ::tracing::trace!($($arg)*);
+        }
+    }};
+}
+
+/// Emits a `tracing` event at the `WARN` level when the `tracing` feature is enabled.
+macro_rules! warn_event {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "tracing")]
+        {
+            ::tracing::warn!($($arg)*);
+        }
+    }};
+}
+
+// `warn` is also the name of a built-in attribute, so it is exported under an alias.
+pub(crate) use {debug, trace, warn_event as warn};
+
+#[cfg(all(test, feature = "tracing"))]
+mod tests {
+    use std::io;
+    use std::sync::{Arc, Mutex};
+
+    use crate::{MemorySink, Redirector};
+
+    /// A log writer that collects formatted events in a shared buffer.
+    #[derive(Clone, Default)]
+    struct Capture(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for Capture {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_write_redirect_emits_events() {
+        let capture = Capture::default();
+        let writer = capture.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::TRACE)
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+
+        tracing::subscriber::with_default(subscriber, || {
+            let redirector = Redirector::new("some/path").unwrap();
+            let mut sink = MemorySink::new();
+            redirector.write_redirect_to(&mut sink).unwrap();
+            redirector.write_redirect_to(&mut sink).unwrap();
+        });
+
+        let logs = String::from_utf8(capture.0.lock().unwrap().clone()).unwrap();
+        assert!(logs.contains("validated URL path"));
+        assert!(logs.contains("generated short name"));
+        assert!(logs.contains("loaded registry"));
+        assert!(logs.contains("wrote redirect file"));
+        assert!(logs.contains("writing registry"));
+        assert!(logs.contains("reusing existing redirect"));
+        assert!(logs.contains("write_redirect{target=/some/path/"));
+    }
+}
@@ -2,39 +2,78 @@
 //!
 //! This module provides the `UrlPath` type for validating and normalizing URL paths
 //! used in the redirect system. It ensures paths contain only valid characters and
-//! are properly formatted with leading and trailing slashes.
+//! are properly formatted with a leading slash and, by default, a trailing slash.
+//!
+//! `UrlPath` is part of the public API so applications can validate paths up-front and
+//! reuse the same normalization rules outside of `Redirector`.
 
 use std::fmt::Display;
+use std::str::FromStr;
 
-use once_cell::sync::Lazy;
-use regex::Regex;
 use thiserror::Error;
 
+use crate::redirector::path_policy::{self, PathPolicy};
+use crate::redirector::trace;
+
 /// Errors that can occur when working with URL paths.
 #[derive(Debug, Error)]
 pub enum UrlPathError {
     /// The provided path is not a valid URL path.
     ///
-    /// Valid URL paths must consist of letters, digits, and dashes, separated by forward slashes.
-    /// They cannot contain query parameters (?), fragment identifiers (#), or semicolons (;).
+    /// Valid URL paths consist of non-empty segments separated by forward slashes, where
+    /// each segment satisfies the active `PathPolicy`. By default segments cannot contain
+    /// query parameters (?), fragment identifiers (#), or semicolons (;).
     #[error("Invalid URL path: {0}")]
     InvalidPath(String),
+
+    /// The provided path is longer than the `PathPolicy` permits.
+    #[error("URL path is {length} bytes long, exceeding the maximum of {max}")]
+    TooLong {
+        /// The length of the rejected path, in bytes.
+        length: usize,
+        /// The maximum length permitted by the policy.
+        max: usize,
+    },
+
+    /// The provided path has more segments than the `PathPolicy` permits.
+    #[error("URL path has {depth} segments, exceeding the maximum of {max}")]
+    TooDeep {
+        /// The number of segments in the rejected path.
+        depth: usize,
+        /// The maximum number of segments permitted by the policy.
+        max: usize,
+    },
 }
 
 /// A validated and normalized URL path.
 ///
 /// This struct represents a URL path that has been validated to ensure it contains
-/// only valid characters and is properly normalized with leading and trailing slashes.
-/// The path is automatically normalized to include leading and trailing forward slashes.
-#[derive(Debug, Default, PartialEq, Clone)]
-pub(crate) struct UrlPath(String);
+/// only valid characters and is properly normalized with a leading slash. The trailing
+/// slash is appended by default, or handled as configured by the `PathPolicy`.
+///
+/// # Examples
+///
+/// ```rust
+/// use link_bridge::{Redirector, UrlPath};
+///
+/// let path: UrlPath = "docs/getting-started".parse().unwrap();
+/// assert_eq!(path.as_str(), "/docs/getting-started/");
+/// assert_eq!(path.segments().collect::<Vec<_>>(), ["docs", "getting-started"]);
+///
+/// // A validated path can be handed straight to a redirector
+/// let redirector = Redirector::from(path);
+/// assert!(redirector.to_string().contains("/docs/getting-started/"));
+///
+/// assert!("docs?page=2".parse::<UrlPath>().is_err());
+/// ```
+#[derive(Debug, Default, PartialEq, Eq, Hash, Clone)]
+pub struct UrlPath(String);
 
 impl UrlPath {
     /// Creates a new `UrlPath` from a string, validating and normalizing it.
     ///
-    /// This method validates that the provided path contains only valid URL path characters
-    /// (letters, digits, hyphens, and forward slashes) and normalizes it by ensuring it
-    /// starts and ends with forward slashes.
+    /// This method validates the path against the default [`PathPolicy`] and normalizes
+    /// it by ensuring it starts and ends with forward slashes.
     ///
     /// # Arguments
     ///
@@ -50,6 +89,7 @@ impl UrlPath {
     /// - `"api/v1"` → normalized to `"/api/v1/"`
     /// - `"/api/v1/"` → remains `"/api/v1/"`
     /// - `"user-data/profile"` → normalized to `"/user-data/profile/"`
+    /// - `"download/report.pdf"` → normalized to `"/download/report.pdf"`
     ///
     /// # Invalid Paths
     ///
@@ -57,36 +97,118 @@ impl UrlPath {
     /// - `"api;session=123"` (contains semicolon)
     /// - `""` (empty string)
     /// - `"/"` (root only)
-    pub(crate) fn new(path: String) -> Result<Self, UrlPathError> {
-        static RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^/?[^/;#?]+(?:/[^/;#?]+)*/?$").unwrap());
-
-        if !RE.is_match(&path) {
-            return Err(UrlPathError::InvalidPath(path.clone()));
-        }
+    pub fn new<S: ToString>(path: S) -> Result<Self, UrlPathError> {
+        UrlPath::with_policy(path, &PathPolicy::default())
+    }
 
-        let mut path = path;
-        if !path.starts_with('/') {
-            path.insert(0, '/');
+    /// Creates a new `UrlPath` from a string, validating it against the given policy.
+    ///
+    /// Behaves like [`UrlPath::new()`] but uses the supplied [`PathPolicy`] to decide
+    /// which path segments are acceptable and how the trailing slash is normalized.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The URL path string to validate and normalize
+    /// * `policy` - The validation policy to apply
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(UrlPath)` - If the path satisfies the policy and has been normalized
+    /// * `Err(UrlPathError::InvalidPath)` - If the path is rejected by the policy
+    /// * `Err(UrlPathError::TooLong)` - If the path exceeds the policy's maximum length
+    /// * `Err(UrlPathError::TooDeep)` - If the path exceeds the policy's maximum depth
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::{PathPolicy, TrailingSlash, UrlPath};
+    ///
+    /// let policy = PathPolicy::strict().trailing_slash(TrailingSlash::Strip);
+    /// let path = UrlPath::with_policy("/docs/install/", &policy).unwrap();
+    /// assert_eq!(path.as_str(), "/docs/install");
+    /// ```
+    pub fn with_policy<S: ToString>(path: S, policy: &PathPolicy) -> Result<Self, UrlPathError> {
+        let path = path.to_string();
+        if let Err(e) = policy.check_limits(&path) {
+            trace::debug!(path = %path, error = %e, "rejected URL path");
+            return Err(e);
         }
 
-        if !path.ends_with('/') {
-            path.push('/');
+        if !policy.is_valid_path(&path) {
+            trace::debug!(path = %path, "rejected URL path with invalid segment");
+            return Err(UrlPathError::InvalidPath(path));
         }
 
-        Ok(UrlPath(path))
+        let normalized = policy.normalize(path);
+        trace::trace!(path = %normalized, "validated URL path");
+        Ok(UrlPath(normalized))
     }
 
-    /// Encodes the URL path as UTF-16.
+    /// Returns the normalized path as a string slice.
     ///
-    /// This method converts the internal path string to a vector of UTF-16 code units,
-    /// which can be useful for generating hash values or other operations that require
-    /// numeric representation of the path.
+    /// The returned path always starts with a forward slash.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns an iterator over the non-empty segments of the path.
     ///
-    /// # Returns
+    /// For example, `/api/v1/` yields `"api"` and `"v1"`.
+    pub fn segments(&self) -> impl Iterator<Item = &str> {
+        self.0.split('/').filter(|segment| !segment.is_empty())
+    }
+
+    /// Returns the file extension of the final segment, if the path names a file.
     ///
-    /// A vector of UTF-16 code units representing the path string.
-    pub(crate) fn encode_utf16(&self) -> Vec<u16> {
-        self.0.encode_utf16().collect()
+    /// For example, `/download/report.pdf` returns `Some("pdf")`, while `/api/v1/`
+    /// returns `None`.
+    pub fn file_extension(&self) -> Option<&str> {
+        if self.0.ends_with('/') {
+            return None;
+        }
+        self.segments().last().and_then(path_policy::file_extension)
+    }
+
+    /// Consumes the `UrlPath`, returning the normalized path string.
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl FromStr for UrlPath {
+    type Err = UrlPathError;
+
+    /// Parses a `UrlPath` using the default [`PathPolicy`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        UrlPath::new(s)
+    }
+}
+
+impl TryFrom<String> for UrlPath {
+    type Error = UrlPathError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        UrlPath::new(value)
+    }
+}
+
+impl TryFrom<&str> for UrlPath {
+    type Error = UrlPathError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        UrlPath::new(value)
+    }
+}
+
+impl AsRef<str> for UrlPath {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl From<UrlPath> for String {
+    fn from(path: UrlPath) -> Self {
+        path.into_string()
     }
 }
 
@@ -103,6 +225,7 @@ impl Display for UrlPath {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::redirector::path_policy::TrailingSlash;
 
     #[test]
     fn test_url_path_new_valid_basic() {
@@ -178,6 +301,13 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_url_path_new_invalid_backslash() {
+        assert!(UrlPath::new("\\\\evil.com".to_string()).is_err());
+        assert!(UrlPath::new("/\\evil.com".to_string()).is_err());
+        assert!(crate::Redirector::new("\\evil.com").is_err());
+    }
+
     #[test]
     fn test_url_path_new_invalid_double_slash() {
         let result = UrlPath::new("api//v1".to_string());
@@ -185,19 +315,45 @@ mod tests {
     }
 
     #[test]
-    fn test_url_path_encode_utf16() {
-        let path = UrlPath::new("api/v1".to_string()).unwrap();
-        let encoded = path.encode_utf16();
-        let expected: Vec<u16> = "/api/v1/".encode_utf16().collect();
-        assert_eq!(encoded, expected);
+    fn test_url_path_with_policy_strict() {
+        let policy = PathPolicy::strict();
+        let path = UrlPath::with_policy("api/v1".to_string(), &policy).unwrap();
+        assert_eq!(path.0, "/api/v1/");
+
+        let result = UrlPath::with_policy("café/müsli".to_string(), &policy);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_url_path_with_policy_custom() {
+        let policy = PathPolicy::custom(|segment| segment.starts_with('v'));
+        assert!(UrlPath::with_policy("v1/v2".to_string(), &policy).is_ok());
+        assert!(UrlPath::with_policy("api/v1".to_string(), &policy).is_err());
     }
 
     #[test]
-    fn test_url_path_encode_utf16_unicode() {
-        let path = UrlPath::new("café/müsli".to_string()).unwrap();
-        let encoded = path.encode_utf16();
-        let expected: Vec<u16> = "/café/müsli/".encode_utf16().collect();
-        assert_eq!(encoded, expected);
+    fn test_url_path_with_policy_trailing_slash() {
+        let strip = PathPolicy::default().trailing_slash(TrailingSlash::Strip);
+        let path = UrlPath::with_policy("/docs/install/".to_string(), &strip).unwrap();
+        assert_eq!(path.0, "/docs/install");
+
+        let preserve = PathPolicy::default().trailing_slash(TrailingSlash::Preserve);
+        let path = UrlPath::with_policy("download/report.pdf".to_string(), &preserve).unwrap();
+        assert_eq!(path.0, "/download/report.pdf");
+        let path = UrlPath::with_policy("docs/".to_string(), &preserve).unwrap();
+        assert_eq!(path.0, "/docs/");
+    }
+
+    #[test]
+    fn test_url_path_new_file_extension_targets() {
+        let path = UrlPath::new("download/report.pdf".to_string()).unwrap();
+        assert_eq!(path.0, "/download/report.pdf");
+
+        let path = UrlPath::new("/releases/app-1.2.zip".to_string()).unwrap();
+        assert_eq!(path.0, "/releases/app-1.2.zip");
+
+        let path = UrlPath::new("images/banner.png".to_string()).unwrap();
+        assert_eq!(path.0, "/images/banner.png");
     }
 
     #[test]
@@ -236,6 +392,30 @@ mod tests {
         assert_eq!(error_message, "Invalid URL path: invalid-path");
     }
 
+    #[test]
+    fn test_url_path_new_rejects_oversized_path() {
+        let result = UrlPath::new("a".repeat(1024 * 1024));
+        assert!(matches!(result, Err(UrlPathError::TooLong { .. })));
+
+        let result = UrlPath::new("a/".repeat(100));
+        assert!(matches!(result, Err(UrlPathError::TooDeep { .. })));
+    }
+
+    #[test]
+    fn test_url_path_limit_error_display() {
+        let error = UrlPathError::TooLong { length: 10, max: 5 };
+        assert_eq!(
+            format!("{error}"),
+            "URL path is 10 bytes long, exceeding the maximum of 5"
+        );
+
+        let error = UrlPathError::TooDeep { depth: 4, max: 3 };
+        assert_eq!(
+            format!("{error}"),
+            "URL path has 4 segments, exceeding the maximum of 3"
+        );
+    }
+
     #[test]
     fn test_url_path_error_debug() {
         let error = UrlPathError::InvalidPath("invalid-path".to_string());
@@ -244,6 +424,70 @@ mod tests {
         assert!(debug_output.contains("invalid-path"));
     }
 
+    #[test]
+    fn test_url_path_as_str() {
+        let path = UrlPath::new("api/v1").unwrap();
+        assert_eq!(path.as_str(), "/api/v1/");
+        assert_eq!(path.as_ref(), "/api/v1/");
+    }
+
+    #[test]
+    fn test_url_path_segments() {
+        let path = UrlPath::new("/api/v2/users/123").unwrap();
+        let segments: Vec<&str> = path.segments().collect();
+        assert_eq!(segments, vec!["api", "v2", "users", "123"]);
+
+        assert_eq!(UrlPath::default().segments().count(), 0);
+    }
+
+    #[test]
+    fn test_url_path_file_extension() {
+        let path = UrlPath::new("download/report.pdf").unwrap();
+        assert_eq!(path.file_extension(), Some("pdf"));
+
+        let path = UrlPath::new("api/v1").unwrap();
+        assert_eq!(path.file_extension(), None);
+
+        let path = UrlPath::new("release/v1.0").unwrap();
+        assert_eq!(path.file_extension(), None);
+    }
+
+    #[test]
+    fn test_url_path_into_string() {
+        let path = UrlPath::new("api/v1").unwrap();
+        assert_eq!(path.clone().into_string(), "/api/v1/");
+        assert_eq!(String::from(path), "/api/v1/");
+    }
+
+    #[test]
+    fn test_url_path_from_str() {
+        let path: UrlPath = "api/v1".parse().unwrap();
+        assert_eq!(path.0, "/api/v1/");
+
+        let result = "api?x=1".parse::<UrlPath>();
+        assert!(matches!(result, Err(UrlPathError::InvalidPath(_))));
+    }
+
+    #[test]
+    fn test_url_path_try_from() {
+        let path = UrlPath::try_from("api/v1").unwrap();
+        assert_eq!(path.0, "/api/v1/");
+
+        let path = UrlPath::try_from("api/v1".to_string()).unwrap();
+        assert_eq!(path.0, "/api/v1/");
+
+        assert!(UrlPath::try_from("").is_err());
+    }
+
+    #[test]
+    fn test_url_path_hash() {
+        use std::collections::HashSet;
+
+        let mut set = HashSet::new();
+        set.insert(UrlPath::new("api/v1").unwrap());
+        assert!(set.contains(&UrlPath::new("/api/v1/").unwrap()));
+    }
+
     #[test]
     fn test_url_path_display() {
         let path = UrlPath::new("api/v1".to_string()).unwrap();
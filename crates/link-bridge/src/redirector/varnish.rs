@@ -0,0 +1,163 @@
+//! Exporting the registry as Varnish configuration.
+//!
+//! Sites fronted by Varnish can answer redirects from the cache layer with a
+//! synthetic response, without the request reaching the origin. This module provides
+//! `Registry::to_varnish_vcl()`, which renders the registry as a VCL snippet holding
+//! a redirect table, to be included from a site's main VCL.
+
+use std::fmt::Write;
+
+use crate::redirector::Registry;
+
+impl Registry {
+    /// Renders the registry as a VCL snippet answering short links with `301`
+    /// redirects.
+    ///
+    /// The snippet defines two subroutines. `link_bridge_recv` looks the request path
+    /// up in a table mapping each short name, including extra short names added with
+    /// [`Registry::add_alias()`], to its target, ignoring a `.html` extension, a
+    /// trailing slash, and the query string. On a match it answers with a synthetic
+    /// `301`, whose `Location` header `link_bridge_synth` sets. A client cannot
+    /// forge the lookup, as the subroutine drops the headers it uses from incoming
+    /// requests. Retired redirects are left out.
+    ///
+    /// Include the snippet and call both subroutines from the main VCL:
+    ///
+    /// ```text
+    /// include "short-links.vcl";
+    ///
+    /// sub vcl_recv {
+    ///     call link_bridge_recv;
+    /// }
+    ///
+    /// sub vcl_synth {
+    ///     call link_bridge_synth;
+    /// }
+    /// ```
+    ///
+    /// # Arguments
+    ///
+    /// * `base_path` - The path the short links are served from, such as `"/s"`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use link_bridge::{Redirector, Registry};
+    /// use std::fs;
+    ///
+    /// Redirector::builder()
+    ///     .target("docs/install")
+    ///     .out_dir("doc_test_varnish_vcl")
+    ///     .alias("install")
+    ///     .build()
+    ///     .unwrap()
+    ///     .write_redirect()
+    ///     .unwrap();
+    ///
+    /// let registry = Registry::load("doc_test_varnish_vcl").unwrap();
+    /// let vcl = registry.to_varnish_vcl("/s");
+    /// assert!(vcl.contains(r#"if (req.http.X-Link-Bridge-Path == "/s/install") {"#));
+    /// assert!(vcl.contains(r#"set req.http.X-Link-Bridge-Location = "/docs/install/";"#));
+    ///
+    /// fs::remove_dir_all("doc_test_varnish_vcl").ok();
+    /// ```
+    pub fn to_varnish_vcl<S: ToString>(&self, base_path: S) -> String {
+        let mut vcl = String::from(
+            "# Generated by link-bridge from the registry; do not edit.\n\
+             \n\
+             sub link_bridge_recv {\n\
+             \x20   unset req.http.X-Link-Bridge-Location;\n\
+             \x20   set req.http.X-Link-Bridge-Path = regsub(req.url, \"(\\.html)?/?(\\?.*)?$\", \"\");\n",
+        );
+        for (index, (source, target)) in self
+            .redirect_sources(&base_path.to_string())
+            .into_iter()
+            .enumerate()
+        {
+            let keyword = if index == 0 { "    if" } else { " elsif" };
+            let _ = write!(
+                vcl,
+                "{keyword} (req.http.X-Link-Bridge-Path == {}) {{\n\
+                 \x20       set req.http.X-Link-Bridge-Location = {};\n\
+                 \x20   }}",
+                string(&source),
+                string(target)
+            );
+        }
+        if vcl.ends_with('}') {
+            vcl.push('\n');
+        }
+        vcl.push_str(
+            "    unset req.http.X-Link-Bridge-Path;\n\
+             \x20   if (req.http.X-Link-Bridge-Location) {\n\
+             \x20       return (synth(301));\n\
+             \x20   }\n\
+             }\n\
+             \n\
+             sub link_bridge_synth {\n\
+             \x20   if (resp.status == 301 && req.http.X-Link-Bridge-Location) {\n\
+             \x20       set resp.http.Location = req.http.X-Link-Bridge-Location;\n\
+             \x20       return (deliver);\n\
+             \x20   }\n\
+             }\n",
+        );
+        vcl
+    }
+}
+
+/// Renders a VCL string literal, using the long form when the text holds a quote.
+fn string(text: &str) -> String {
+    if text.contains('"') {
+        format!("{{\"{text}\"}}")
+    } else {
+        format!("\"{text}\"")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_to_varnish_vcl() {
+        let mut registry = Registry::empty(PathBuf::from("s"));
+        registry.insert("/docs/usage/".to_string(), "s/usage.html".to_string());
+        registry.insert(
+            "https://example.org/?q=\"a\"".to_string(),
+            "s/external.html".to_string(),
+        );
+        registry.insert("/docs/old/".to_string(), "s/old.html".to_string());
+        registry.entry_mut("/docs/old/").unwrap().retire();
+
+        assert_eq!(
+            registry.to_varnish_vcl("/s"),
+            r#"# Generated by link-bridge from the registry; do not edit.
+
+sub link_bridge_recv {
+    unset req.http.X-Link-Bridge-Location;
+    set req.http.X-Link-Bridge-Path = regsub(req.url, "(\.html)?/?(\?.*)?$", "");
+    if (req.http.X-Link-Bridge-Path == "/s/external") {
+        set req.http.X-Link-Bridge-Location = {"https://example.org/?q="a""};
+    } elsif (req.http.X-Link-Bridge-Path == "/s/usage") {
+        set req.http.X-Link-Bridge-Location = "/docs/usage/";
+    }
+    unset req.http.X-Link-Bridge-Path;
+    if (req.http.X-Link-Bridge-Location) {
+        return (synth(301));
+    }
+}
+
+sub link_bridge_synth {
+    if (resp.status == 301 && req.http.X-Link-Bridge-Location) {
+        set resp.http.Location = req.http.X-Link-Bridge-Location;
+        return (deliver);
+    }
+}
+"#
+        );
+        assert!(!Registry::empty(PathBuf::from("s"))
+            .to_varnish_vcl("/s")
+            .contains(" if (req.http.X-Link-Bridge-Path"));
+    }
+}
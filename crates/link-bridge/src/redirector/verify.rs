@@ -0,0 +1,323 @@
+//! Checking written redirect pages before they are deployed.
+//!
+//! A redirect page leads to its target three ways: the meta refresh, the script, and
+//! the fallback link. A mistake in the page template or in escaping could make them
+//! disagree, so a visitor with JavaScript ends up somewhere else than one without, and
+//! nothing fails until someone notices. This module provides the check run by
+//! `RedirectorBuilder::verify_output()`, which reads each page back after it is
+//! written and confirms all three lead to the target.
+
+use std::path::Path;
+
+use crate::redirector::gate::Gate;
+use crate::redirector::json::parse_prefix;
+use crate::redirector::sink::OutputSink;
+use crate::redirector::time::Timestamp;
+use crate::redirector::{Redirector, RedirectorError};
+
+/// What a script that redirects to a single target starts with.
+const REDIRECT_SCRIPT: &str = "window.location.href = ";
+
+impl Redirector {
+    /// Reads the page written at `file_path` back from the sink and checks that it
+    /// leads to the target, if the redirector was built with
+    /// [`verify_output()`](crate::RedirectorBuilder::verify_output).
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the page is consistent, or is not checked
+    /// * `Err(RedirectorError::FileWriteError)` - If the page cannot be read back
+    /// * `Err(RedirectorError::InconsistentPage)` - If the page is missing, or a way
+    ///   of leading to the target is missing or leads elsewhere
+    pub(crate) fn verify_written<S: OutputSink + ?Sized>(
+        &self,
+        sink: &mut S,
+        file_path: &Path,
+    ) -> Result<(), RedirectorError> {
+        if !self.verify_output {
+            return Ok(());
+        }
+        let page = sink
+            .read_file(file_path)
+            .map_err(|source| RedirectorError::FileWriteError {
+                path: file_path.to_path_buf(),
+                source,
+            })?
+            .ok_or_else(|| RedirectorError::InconsistentPage {
+                path: file_path.to_path_buf(),
+                reason: "page is missing".to_string(),
+            })?;
+        self.verify_page(file_path, &page)
+    }
+
+    /// Checks that a page written at `file_path` leads to the target, if the
+    /// redirector was built with
+    /// [`verify_output()`](crate::RedirectorBuilder::verify_output).
+    ///
+    /// Expired pages, which do not redirect, are not checked.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the page is consistent, or is not checked
+    /// * `Err(RedirectorError::InconsistentPage)` - If a way of leading to the target
+    ///   is missing or leads elsewhere
+    pub(crate) fn verify_page(&self, file_path: &Path, page: &[u8]) -> Result<(), RedirectorError> {
+        if !self.verify_output
+            || self
+                .expires_at
+                .is_some_and(|expires_at| expires_at <= Timestamp::now())
+        {
+            return Ok(());
+        }
+
+        let target = self.target.resolve(self.base_url.as_ref());
+        let page = String::from_utf8_lossy(page);
+        let checked = if self.amp && self.gate == Gate::Open {
+            check_amp_page(&page, &target)
+        } else {
+            check_page(&page, &target, &self.gate)
+        };
+        checked.map_err(|reason| RedirectorError::InconsistentPage {
+            path: file_path.to_path_buf(),
+            reason,
+        })
+    }
+}
+
+/// Checks that every way a page leads to its target leads to `target`.
+///
+/// The script must always redirect; the meta refresh is only expected on ungated
+/// pages, and the fallback link on pages without a passphrase.
+fn check_page(page: &str, target: &str, gate: &Gate) -> Result<(), String> {
+    let found = [
+        (
+            "meta refresh",
+            refresh_url(page),
+            matches!(gate, Gate::Open),
+        ),
+        ("script", script_url(page), true),
+        (
+            "fallback link",
+            link_url(page),
+            matches!(gate, Gate::Open | Gate::Confirm),
+        ),
+    ];
+
+    for (name, url, expected) in found {
+        match url {
+            Some(url) if url != target => {
+                return Err(format!("{name} leads to {url} instead of {target}"))
+            }
+            None if expected => return Err(format!("{name} is missing")),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Checks that the canonical link and the fallback link of an AMP page both lead to
+/// `target`.
+fn check_amp_page(page: &str, target: &str) -> Result<(), String> {
+    for (name, url) in [
+        ("canonical link", canonical_url(page)),
+        ("fallback link", link_url(page)),
+    ] {
+        match url {
+            Some(url) if url != target => {
+                return Err(format!("{name} leads to {url} instead of {target}"))
+            }
+            None => return Err(format!("{name} is missing")),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Returns the URL of the page's meta refresh.
+fn refresh_url(page: &str) -> Option<String> {
+    let (_, rest) = page.split_once(r#"http-equiv="refresh" content=""#)?;
+    let (content, _) = rest.split_once('"')?;
+    let (_, url) = content.split_once("url=")?;
+    Some(unescape(url))
+}
+
+/// Returns the URL the page's script redirects to; for a split redirect, its first
+/// target.
+fn script_url(page: &str) -> Option<String> {
+    let (_, rest) = page.split_once(REDIRECT_SCRIPT)?;
+    if rest.starts_with('"') {
+        return parse_prefix(rest)?.as_str().map(str::to_string);
+    }
+
+    // A split redirect passes its weighted targets to a function picking one
+    let (_, targets) = rest.split_once("})(")?;
+    let targets = parse_prefix(targets)?;
+    targets.get(0)?.get(0)?.as_str().map(str::to_string)
+}
+
+/// Returns the URL of the page's canonical link.
+fn canonical_url(page: &str) -> Option<String> {
+    let (_, rest) = page.split_once(r#"<link rel="canonical" href=""#)?;
+    let (href, _) = rest.split_once('"')?;
+    Some(unescape(href))
+}
+
+/// Returns the URL of the page's first link.
+fn link_url(page: &str) -> Option<String> {
+    let (_, rest) = page.split_once("<a href='")?;
+    let (href, _) = rest.split_once('\'')?;
+    Some(unescape(href))
+}
+
+/// Reverses the escaping of text written into an HTML attribute.
+fn unescape(text: &str) -> String {
+    text.replace("&#39;", "'")
+        .replace("&quot;", "\"")
+        .replace("&gt;", ">")
+        .replace("&lt;", "<")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{HostAllowlist, MemorySink};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_check_page() {
+        let redirector = Redirector::builder()
+            .target("https://example.com/a?b=1&c=2")
+            .allow_hosts(HostAllowlist::new(["example.com"]))
+            .build()
+            .unwrap();
+        let target = redirector.target.to_string();
+        let page = redirector.to_string();
+        assert_eq!(check_page(&page, &target, &Gate::Open), Ok(()));
+
+        // Escaping the target twice changes it
+        let broken = page.replacen("&amp;", "&amp;amp;", 1);
+        assert_eq!(
+            check_page(&broken, &target, &Gate::Open),
+            Err(
+                "meta refresh leads to https://example.com/a?b=1&amp;c=2 instead of \
+                 https://example.com/a?b=1&c=2"
+                    .to_string()
+            )
+        );
+        let broken = page.replacen("url=https", "url=http", 1);
+        assert!(check_page(&broken, &target, &Gate::Open)
+            .unwrap_err()
+            .starts_with("meta refresh leads to http://example.com/a"));
+        let broken = page.replacen("<a href='https", "<a href='http", 1);
+        assert!(check_page(&broken, &target, &Gate::Open)
+            .unwrap_err()
+            .starts_with("fallback link leads to"));
+        let broken = page.replacen(REDIRECT_SCRIPT, "", 1);
+        assert_eq!(
+            check_page(&broken, &target, &Gate::Open),
+            Err("script is missing".to_string())
+        );
+    }
+
+    #[test]
+    fn test_check_split_and_gated_pages() {
+        let redirector = Redirector::builder()
+            .target("landing/a")
+            .variant("landing/b", 1)
+            .build()
+            .unwrap();
+        let page = redirector.to_string();
+        assert_eq!(script_url(&page).as_deref(), Some("/landing/a/"));
+        assert_eq!(check_page(&page, "/landing/a/", &Gate::Open), Ok(()));
+
+        let redirector = Redirector::builder()
+            .target("internal/wiki")
+            .confirm()
+            .build()
+            .unwrap();
+        let page = redirector.to_string();
+        assert_eq!(refresh_url(&page), None);
+        assert_eq!(check_page(&page, "/internal/wiki/", &Gate::Confirm), Ok(()));
+        assert!(check_page(&page, "/internal/wiki/", &Gate::Open).is_err());
+
+        let page = Redirector::builder()
+            .target("news/launch")
+            .amp()
+            .build()
+            .unwrap()
+            .to_string();
+        assert_eq!(check_amp_page(&page, "/news/launch/"), Ok(()));
+        assert_eq!(
+            check_amp_page(&page.replacen("canonical", "alternate", 1), "/news/launch/"),
+            Err("canonical link is missing".to_string())
+        );
+        assert!(check_page(&page, "/news/launch/", &Gate::Open).is_err());
+    }
+
+    #[test]
+    fn test_write_verified_page() {
+        let mut sink = MemorySink::new();
+        let outcome = Redirector::builder()
+            .target("summer/sale")
+            .alias("promo")
+            .base_url("https://example.com")
+            .verify_output()
+            .build()
+            .unwrap()
+            .write_redirect_to(&mut sink)
+            .unwrap();
+        assert!(outcome.is_created());
+        assert!(sink.get("s/promo.html").is_some());
+
+        for builder in [
+            Redirector::builder().delay(3).beacon("/hits"),
+            Redirector::builder().confirm(),
+            Redirector::builder().variant("winter/sale", 2),
+            Redirector::builder().amp(),
+        ] {
+            builder
+                .target("summer/sale")
+                .verify_output()
+                .build()
+                .unwrap()
+                .write_redirect_to(&mut MemorySink::new())
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_verify_page_reports_inconsistency() {
+        let redirector = Redirector::builder()
+            .target("summer/sale")
+            .verify_output()
+            .build()
+            .unwrap();
+        let page = redirector
+            .to_string()
+            .replacen("url=/summer", "url=/winter", 1);
+
+        match redirector.verify_page(Path::new("s/page.html"), page.as_bytes()) {
+            Err(RedirectorError::InconsistentPage { path, reason }) => {
+                assert_eq!(path, PathBuf::from("s/page.html"));
+                assert_eq!(
+                    reason,
+                    "meta refresh leads to /winter/sale/ instead of /summer/sale/"
+                );
+            }
+            other => panic!("expected InconsistentPage, got {other:?}"),
+        }
+
+        // Without verify_output() nothing is checked
+        let unchecked = Redirector::builder().target("summer/sale").build().unwrap();
+        assert!(unchecked
+            .verify_page(Path::new("s/page.html"), page.as_bytes())
+            .is_ok());
+
+        // A page the sink did not keep is reported
+        assert!(matches!(
+            redirector.verify_written(&mut MemorySink::new(), Path::new("s/x.html")),
+            Err(RedirectorError::InconsistentPage { reason, .. }) if reason == "page is missing"
+        ));
+    }
+}
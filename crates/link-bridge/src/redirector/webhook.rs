@@ -0,0 +1,356 @@
+//! Webhook notifications for new redirects.
+//!
+//! Teams often want to know when a short link is published, whether in a chat
+//! channel or an audit log. This module provides the `Webhook` type, a
+//! `RedirectHooks` implementation that POSTs a small JSON payload to a configured URL
+//! whenever a redirect is created, and the `WebhookFormat` of that payload, which can
+//! instead be a message for a Slack or Discord incoming webhook.
+
+use std::time::Duration;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::redirector::target::ExternalUrl;
+use crate::redirector::time::Timestamp;
+use crate::redirector::{trace, RedirectHooks, RedirectOutcome, RedirectorError};
+
+/// How long a webhook request may take before it is abandoned.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The payload a [`Webhook`] posts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+#[non_exhaustive]
+pub enum WebhookFormat {
+    /// A JSON object naming the short name, the target, and the time of creation.
+    #[default]
+    Json,
+    /// A message for a Slack incoming webhook.
+    Slack,
+    /// A message for a Discord webhook.
+    Discord,
+}
+
+/// Posts a notification to a URL whenever a redirect is created.
+///
+/// By default the payload is JSON naming the short name, the target, and the time of
+/// creation as an RFC 3339 timestamp, with the short URL added when a site URL is set:
+///
+/// ```json
+/// {"short_name": "install", "target": "/docs/install/", "timestamp": "2025-01-01T12:00:00Z"}
+/// ```
+///
+/// [`Webhook::slack()`] and [`Webhook::discord()`] instead post a chat message
+/// announcing the short URL and the target, so a batch run of new redirects is
+/// reported in a channel without a relay in between. Without a site URL the message
+/// names the short link by its file.
+///
+/// Reused redirects and failed writes are not reported. The request is sent from the
+/// write call itself and abandoned after ten seconds. A failed notification never
+/// fails the write; with the `tracing` feature enabled it is logged as a warning.
+/// Requires the `webhook` feature.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use link_bridge::{Redirector, Webhook};
+///
+/// let webhook = Webhook::new("https://hooks.example.com/link-bridge").unwrap();
+/// let redirector = Redirector::builder()
+///     .target("api/v1/users")
+///     .hooks(webhook)
+///     .build()
+///     .unwrap();
+///
+/// redirector.write_redirect().unwrap(); // notifies the webhook
+/// ```
+///
+/// Announcing new short links in a Slack channel:
+///
+/// ```rust
+/// use link_bridge::Webhook;
+///
+/// let webhook = Webhook::slack("https://hooks.slack.com/services/T000/B000/XXXX")
+///     .unwrap()
+///     .site_url("https://example.com/s");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Webhook {
+    /// The URL notifications are posted to.
+    url: ExternalUrl,
+    /// The payload posted.
+    format: WebhookFormat,
+    /// The URL the output directory is served from, used to name short URLs.
+    site_url: Option<String>,
+    /// The HTTP client used to post notifications.
+    agent: ureq::Agent,
+}
+
+impl Webhook {
+    /// Creates a webhook that posts to the given URL.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - An absolute `http` or `https` URL
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Webhook)` - The webhook
+    /// * `Err(RedirectorError::InvalidTarget)` - If the URL is malformed
+    pub fn new<S: ToString>(url: S) -> Result<Self, RedirectorError> {
+        let url = ExternalUrl::new(url.to_string())?;
+        let agent = ureq::Agent::config_builder()
+            .timeout_global(Some(TIMEOUT))
+            .build()
+            .into();
+
+        Ok(Webhook {
+            url,
+            format: WebhookFormat::default(),
+            site_url: None,
+            agent,
+        })
+    }
+
+    /// Creates a webhook that posts messages to a Slack incoming webhook URL.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Webhook)` - The webhook
+    /// * `Err(RedirectorError::InvalidTarget)` - If the URL is malformed
+    pub fn slack<S: ToString>(url: S) -> Result<Self, RedirectorError> {
+        Ok(Webhook::new(url)?.format(WebhookFormat::Slack))
+    }
+
+    /// Creates a webhook that posts messages to a Discord webhook URL.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Webhook)` - The webhook
+    /// * `Err(RedirectorError::InvalidTarget)` - If the URL is malformed
+    pub fn discord<S: ToString>(url: S) -> Result<Self, RedirectorError> {
+        Ok(Webhook::new(url)?.format(WebhookFormat::Discord))
+    }
+
+    /// Sets the payload posted.
+    pub fn format(mut self, format: WebhookFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Sets the URL the output directory is served from, such as
+    /// `https://example.com/s`, so notifications name the short URL.
+    pub fn site_url<S: ToString>(mut self, site_url: S) -> Self {
+        self.site_url = Some(site_url.to_string());
+        self
+    }
+
+    /// Returns the URL notifications are posted to.
+    pub fn url(&self) -> String {
+        self.url.to_string()
+    }
+
+    /// Builds the payload announcing a new redirect.
+    fn payload(&self, outcome: &RedirectOutcome) -> String {
+        let short_url = self.site_url.as_deref().map(|url| outcome.short_url(url));
+        let payload = match self.format {
+            WebhookFormat::Json => {
+                let mut payload = serde_json::json!({
+                    "short_name": outcome.short_name(),
+                    "target": outcome.target(),
+                    "timestamp": Timestamp::now().to_rfc3339(),
+                });
+                if let Some(short_url) = short_url {
+                    payload["short_url"] = short_url.into();
+                }
+                payload
+            }
+            WebhookFormat::Slack => {
+                // Slack reads `&`, `<`, and `>` as markup, and links are written `<url|text>`
+                let escape = |text: &str| {
+                    text.replace('&', "&amp;")
+                        .replace('<', "&lt;")
+                        .replace('>', "&gt;")
+                };
+                let short = match short_url {
+                    Some(url) => format!("<{}|{}>", escape(&url), escape(outcome.short_name())),
+                    None => format!("`{}`", escape(outcome.short_file())),
+                };
+                serde_json::json!({
+                    "text": format!("New short link {short} \u{2192} {}", escape(outcome.target())),
+                })
+            }
+            WebhookFormat::Discord => {
+                // Angle brackets keep Discord from embedding a preview of each link
+                let short = match short_url {
+                    Some(url) => format!("<{url}>"),
+                    None => format!("`{}`", outcome.short_file()),
+                };
+                let target = if outcome.target().contains("://") {
+                    format!("<{}>", outcome.target())
+                } else {
+                    format!("`{}`", outcome.target())
+                };
+                serde_json::json!({
+                    "content": format!("New short link {short} \u{2192} {target}"),
+                    "allowed_mentions": { "parse": [] },
+                })
+            }
+        };
+        payload.to_string()
+    }
+}
+
+impl RedirectHooks for Webhook {
+    fn on_created(&self, outcome: &RedirectOutcome) {
+        let result = self
+            .agent
+            .post(self.url.to_string())
+            .header("Content-Type", "application/json")
+            .send(self.payload(outcome));
+
+        if let Err(_e) = result {
+            trace::warn!(url = %self.url, error = %_e, "webhook notification failed");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MemorySink, Redirector};
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// Accepts one HTTP request, answers `204 No Content`, and returns the request body.
+    fn receive_one(listener: TcpListener) -> thread::JoinHandle<String> {
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let line = line.trim_end();
+                if line.is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+            }
+
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            reader
+                .get_mut()
+                .write_all(b"HTTP/1.1 204 No Content\r\nConnection: close\r\n\r\n")
+                .unwrap();
+
+            String::from_utf8(body).unwrap()
+        })
+    }
+
+    #[test]
+    fn test_webhook_posts_on_create() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let received = receive_one(listener);
+
+        let redirector = Redirector::builder()
+            .target("docs/install")
+            .alias("install")
+            .hooks(Webhook::new(&url).unwrap())
+            .build()
+            .unwrap();
+        let mut sink = MemorySink::new();
+        redirector.write_redirect_to(&mut sink).unwrap();
+
+        let payload: serde_json::Value = serde_json::from_str(&received.join().unwrap()).unwrap();
+        assert_eq!(payload["short_name"], "install");
+        assert_eq!(payload["target"], "/docs/install/");
+        assert!(payload["timestamp"].as_str().unwrap().ends_with('Z'));
+
+        // Reusing the redirect sends nothing, so no listener is needed
+        redirector.write_redirect_to(&mut sink).unwrap();
+    }
+
+    #[test]
+    fn test_chat_payloads() {
+        let outcome = Redirector::builder()
+            .target("https://docs.example.com/a?b=1&c=2")
+            .allow_hosts(crate::HostAllowlist::new(["docs.example.com"]))
+            .alias("docs")
+            .build()
+            .unwrap()
+            .write_redirect_to(&mut MemorySink::new())
+            .unwrap();
+        let payload = |webhook: Webhook| -> serde_json::Value {
+            serde_json::from_str(&webhook.payload(&outcome)).unwrap()
+        };
+        let url = "https://hooks.example.com/hook";
+
+        let slack = Webhook::slack(url).unwrap();
+        assert_eq!(
+            payload(slack.clone())["text"],
+            "New short link `docs.html` \u{2192} https://docs.example.com/a?b=1&amp;c=2"
+        );
+        assert_eq!(
+            payload(slack.site_url("https://example.com/s/"))["text"],
+            "New short link <https://example.com/s/docs.html|docs> \u{2192} \
+             https://docs.example.com/a?b=1&amp;c=2"
+        );
+
+        let discord = Webhook::discord(url)
+            .unwrap()
+            .site_url("https://example.com/s");
+        let discord = payload(discord);
+        assert_eq!(
+            discord["content"],
+            "New short link <https://example.com/s/docs.html> \u{2192} \
+             <https://docs.example.com/a?b=1&c=2>"
+        );
+        assert_eq!(discord["allowed_mentions"]["parse"], serde_json::json!([]));
+
+        let json = payload(Webhook::new(url).unwrap().site_url("https://example.com/s"));
+        assert_eq!(json["short_url"], "https://example.com/s/docs.html");
+        assert_eq!(json["short_name"], "docs");
+    }
+
+    #[test]
+    fn test_webhook_failure_does_not_fail_write() {
+        // Bind and drop a listener to find a port nothing is listening on
+        let addr = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let webhook = Webhook::new(format!("http://{addr}/hook")).unwrap();
+
+        let outcome = Redirector::builder()
+            .target("docs/usage")
+            .hooks(webhook)
+            .build()
+            .unwrap()
+            .write_redirect_to(&mut MemorySink::new())
+            .unwrap();
+        assert!(outcome.is_created());
+    }
+
+    #[test]
+    fn test_webhook_invalid_url() {
+        assert!(matches!(
+            Webhook::new("ftp://example.com/hook"),
+            Err(RedirectorError::InvalidTarget(_))
+        ));
+        assert_eq!(
+            Webhook::new("HTTPS://Example.com/hook").unwrap().url(),
+            "https://example.com/hook"
+        );
+    }
+}